@@ -16,12 +16,14 @@ use super::{
 	Loader, MidnightDataProvider, Offer, OutputMode, PUBLIC_PARAMS, ProofKind,
 	PureGeneratorPedersen, Resolver, SerdeTransaction, SignatureKind, Storable, SyntheticCost,
 	Tagged, Timestamp, Transaction, TransactionContext, TransactionResult, Utxo,
-	VerifiedTransaction, Wallet, WalletAddress, WalletSeed, WellFormedStrictness, default_storage,
-	mn_ledger_serialize as serialize, mn_ledger_storage as storage, types::StorableSyntheticCost,
+	VerifiedTransaction, ViewingKeyWallet, Wallet, WalletAddress, WalletSeed, WellFormedStrictness,
+	default_storage, mn_ledger_serialize as serialize, mn_ledger_storage as storage,
+	persistent_hash, types::StorableSyntheticCost,
 };
 use derive_where::derive_where;
 use hex::encode as hex_encode;
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 use std::{
 	collections::{HashMap, HashSet},
 	sync::Mutex,
@@ -48,9 +50,43 @@ pub struct LedgerContext<D: DB + Clone> {
 	pub ledger_state: Mutex<LedgerState<D>>,
 	pub latest_block_context: Mutex<Option<BlockContext>>,
 	pub wallets: Mutex<HashMap<WalletSeed, Wallet<D>>>,
+	pub viewing_keys: Mutex<Vec<ViewingKeyWallet>>,
 	pub resolver: MutexTokio<&'static Resolver>,
 }
 
+/// Serialized `LedgerContext`, to resume following a chain without replaying its blocks.
+/// It doesn't contain the wallet keys, which must be passed again to restore it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerContextSnapshot {
+	pub ledger_state: Vec<u8>,
+	pub latest_block_context: Option<BlockContext>,
+	pub wallets: Vec<WalletSnapshot>,
+	pub viewing_keys: Vec<ViewingKeySnapshot>,
+}
+
+/// Serialized local state of a wallet of a `LedgerContext`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletSnapshot {
+	/// `key_fingerprint` of the root seed
+	pub fingerprint: String,
+	pub shielded_state: Vec<u8>,
+	pub dust_local_state: Option<Vec<u8>>,
+}
+
+/// Serialized coins received by a viewing key of a `LedgerContext`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewingKeySnapshot {
+	/// `key_fingerprint` of the viewing key
+	pub fingerprint: String,
+	/// Hex encoded commitments and serialized coins
+	pub received_coins: Vec<(String, Vec<u8>)>,
+}
+
+/// Hex encoded hash identifying a wallet key without revealing it
+pub fn key_fingerprint(key: &[u8]) -> String {
+	hex_encode(persistent_hash(key).0)
+}
+
 #[derive(Debug, Storable)]
 #[derive_where(Clone)]
 #[storable(db = D)]
@@ -80,6 +116,7 @@ impl<D: DB + Clone> LedgerContext<D> {
 		Self {
 			ledger_state: Mutex::new(LedgerState::new(network_id)),
 			wallets: Mutex::new(HashMap::new()),
+			viewing_keys: Mutex::new(Vec::new()),
 			resolver: MutexTokio::new(&DEFAULT_RESOLVER),
 			latest_block_context: Mutex::new(None),
 		}
@@ -106,11 +143,120 @@ impl<D: DB + Clone> LedgerContext<D> {
 		Self {
 			ledger_state: Mutex::new(ledger_state),
 			wallets,
+			viewing_keys: Mutex::new(Vec::new()),
 			resolver,
 			latest_block_context: Mutex::new(None),
 		}
 	}
 
+	/// Restores a context from a snapshot taken with `LedgerContext::snapshot`, for the same
+	/// wallet seeds and viewing keys
+	pub fn from_snapshot(
+		snapshot: &LedgerContextSnapshot,
+		wallet_seeds: &[WalletSeed],
+		viewing_keys: &[ViewingKeyWallet],
+	) -> Result<Self, std::io::Error> {
+		let missing = |fingerprint: &str| {
+			std::io::Error::new(
+				std::io::ErrorKind::InvalidData,
+				format!("no wallet with fingerprint {fingerprint} in the snapshot"),
+			)
+		};
+		let ledger_state: LedgerState<D> = super::deserialize(&snapshot.ledger_state[..])?;
+
+		let mut wallets = HashMap::new();
+		for seed in wallet_seeds {
+			let fingerprint = key_fingerprint(seed.as_bytes());
+			let wallet_snapshot = snapshot
+				.wallets
+				.iter()
+				.find(|w| w.fingerprint == fingerprint)
+				.ok_or_else(|| missing(&fingerprint))?;
+			let mut wallet = Wallet::default(*seed, &ledger_state);
+			wallet.shielded.state = super::deserialize(&wallet_snapshot.shielded_state[..])?;
+			wallet.dust.dust_local_state = wallet_snapshot
+				.dust_local_state
+				.as_ref()
+				.map(|state| super::deserialize(&state[..]))
+				.transpose()?;
+			wallets.insert(*seed, wallet);
+		}
+
+		let mut viewing_key_wallets = Vec::new();
+		for viewing_key in viewing_keys {
+			let fingerprint = key_fingerprint(viewing_key.viewing_key.as_bytes());
+			let viewing_key_snapshot = snapshot
+				.viewing_keys
+				.iter()
+				.find(|v| v.fingerprint == fingerprint)
+				.ok_or_else(|| missing(&fingerprint))?;
+			let mut viewing_key = viewing_key.clone();
+			for (commitment, coin) in &viewing_key_snapshot.received_coins {
+				viewing_key
+					.received_coins
+					.insert(commitment.clone(), super::deserialize(&coin[..])?);
+			}
+			viewing_key_wallets.push(viewing_key);
+		}
+
+		Ok(Self {
+			ledger_state: Mutex::new(ledger_state),
+			wallets: Mutex::new(wallets),
+			viewing_keys: Mutex::new(viewing_key_wallets),
+			resolver: MutexTokio::new(&*DEFAULT_RESOLVER),
+			latest_block_context: Mutex::new(snapshot.latest_block_context.clone()),
+		})
+	}
+
+	/// Serializes the ledger state and the local state of every wallet, without their keys
+	pub fn snapshot(&self) -> Result<LedgerContextSnapshot, std::io::Error> {
+		let ledger_state = self.with_ledger_state(|ledger_state| super::serialize(ledger_state))?;
+		let latest_block_context = self
+			.latest_block_context
+			.lock()
+			.expect("error locking latest_block_context")
+			.clone();
+
+		let wallets = self
+			.wallets
+			.lock()
+			.expect("Error locking `LedgerContext` wallets")
+			.iter()
+			.map(|(seed, wallet)| {
+				Ok(WalletSnapshot {
+					fingerprint: key_fingerprint(seed.as_bytes()),
+					shielded_state: super::serialize(&wallet.shielded.state)?,
+					dust_local_state: wallet
+						.dust
+						.dust_local_state
+						.as_ref()
+						.map(super::serialize)
+						.transpose()?,
+				})
+			})
+			.collect::<Result<_, std::io::Error>>()?;
+
+		let viewing_keys = self
+			.viewing_keys
+			.lock()
+			.expect("Error locking `LedgerContext` viewing keys")
+			.iter()
+			.map(|viewing_key| {
+				let received_coins = viewing_key
+					.received_coins
+					.iter()
+					.map(|(commitment, coin)| Ok((commitment.clone(), super::serialize(coin)?)))
+					.collect::<Result<_, std::io::Error>>()?;
+				Ok(ViewingKeySnapshot {
+					fingerprint: key_fingerprint(viewing_key.viewing_key.as_bytes()),
+					received_coins,
+				})
+			})
+			.collect::<Result<_, std::io::Error>>()?;
+
+		Ok(LedgerContextSnapshot { ledger_state, latest_block_context, wallets, viewing_keys })
+	}
+
 	pub fn update_from_block<S: SignatureKind<D>, P: ProofKind<D> + std::fmt::Debug>(
 		&self,
 		txs: Vec<SerdeTransaction<S, P, D>>,
@@ -257,6 +403,14 @@ impl<D: DB + Clone> LedgerContext<D> {
 		{
 			wallet.update_state_from_offers(&offers);
 		}
+		for viewing_key in self
+			.viewing_keys
+			.lock()
+			.expect("Error locking `LedgerContext` viewing keys")
+			.iter_mut()
+		{
+			viewing_key.update_from_offers(&offers);
+		}
 
 		*self.ledger_state.lock().expect("Error locking `LedgerContext` ledger_state") =
 			new_ledger_state;
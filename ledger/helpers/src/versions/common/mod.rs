@@ -23,7 +23,7 @@ pub use super::{
 	},
 	coin_structure::{
 		coin::{
-			Info as CoinInfo, NIGHT, Nonce, PublicAddress, PublicKey as CoinPublicKey,
+			Commitment, Info as CoinInfo, NIGHT, Nonce, PublicAddress, PublicKey as CoinPublicKey,
 			QualifiedInfo, ShieldedTokenType, TokenType, UnshieldedTokenType, UserAddress,
 		},
 		contract::ContractAddress,
@@ -83,9 +83,9 @@ pub use super::{
 	transient_crypto::{
 		commitment::{Pedersen, PedersenRandomness, PureGeneratorPedersen},
 		curve::Fr,
-		encryption::PublicKey as EncryptionPublicKey,
+		encryption::{PublicKey as EncryptionPublicKey, SecretKey as EncryptionSecretKey},
 		fab::ValueReprAlignedValue,
-		merkle_tree::{MerklePath, MerkleTree, leaf_hash},
+		merkle_tree::{MerklePath, MerkleTree, MerkleTreeDigest, leaf_hash},
 		proofs::{
 			KeyLocation, ParamsProver, ParamsProverProvider, ProofPreimage, ProverKey,
 			ProvingKeyMaterial, Resolver as ResolverTrait, VerifierKey,
@@ -133,6 +133,7 @@ mod utxo_spend;
 pub mod wallet;
 
 // Module declarations without can-panic feature
+mod proofs;
 mod proving;
 pub mod types;
 
@@ -144,6 +145,7 @@ pub use {
 };
 
 // Re-exports without can-panic feature
+pub use proofs::*;
 pub use types::*;
 
 /// Serializes a mn_ledger::serialize-able type into bytes
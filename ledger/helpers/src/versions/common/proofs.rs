@@ -0,0 +1,265 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::super::{
+	ArenaKey, Commitment, ContractAddress, ContractState, DB, DefaultDB, Deserializable,
+	HashOutput, LedgerState, MerklePath, MerkleTreeDigest, PERSISTENT_HASH_BYTES,
+	ledger_storage::{Storage, arena::TypedArenaKey, db::InMemoryDB},
+	mn_ledger_serialize::tagged_deserialize,
+	serialize_untagged,
+};
+use sha2::Digest;
+
+type Hasher = <DefaultDB as DB>::Hasher;
+
+/// A storage arena node of an inclusion proof
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArenaNode {
+	/// Binary representation of the node
+	pub data: Vec<u8>,
+	/// Serialized arena keys of the node's children
+	pub children: Vec<Vec<u8>>,
+}
+
+/// A storage arena node on the path of an inclusion proof
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArenaPathStep {
+	/// Binary representation of the node
+	pub data: Vec<u8>,
+	/// Serialized arena keys of the node's children
+	pub children: Vec<Vec<u8>>,
+	/// Index in `children` of the next node on the path
+	pub child: usize,
+}
+
+/// Checks a zswap coin commitment inclusion proof, as returned by the
+/// `midnight_zswapCommitmentProof` RPC, without access to the ledger state.
+///
+/// `state_key` is the key of the ledger state the proof was generated against, `tree_path` the
+/// arena nodes from the ledger state root down to `tree_node`, the top node of the commitment
+/// tree, which records its root. `root` and `proof` are the serialized tree root and merkle
+/// path, and `commitment` the raw bytes of the coin commitment.
+pub fn verify_zswap_commitment_proof(
+	state_key: &[u8],
+	tree_path: &[ArenaPathStep],
+	tree_node: &ArenaNode,
+	root: &[u8],
+	commitment: &[u8],
+	proof: &[u8],
+) -> bool {
+	let Ok(commitment) = <[u8; PERSISTENT_HASH_BYTES]>::try_from(commitment) else {
+		return false;
+	};
+	let Ok(tree_root) = MerkleTreeDigest::deserialize(&mut &root[..], 0) else {
+		return false;
+	};
+	let Ok(path) = MerklePath::<Commitment>::deserialize(&mut &proof[..], 0) else {
+		return false;
+	};
+	if path.leaf != Commitment(HashOutput(commitment)) || path.root() != tree_root {
+		return false;
+	}
+
+	let records_root = tree_node.data.windows(root.len()).any(|window| window == root);
+	records_root
+		&& leads_to_state_key(state_key, node_key(&tree_node.data, &tree_node.children), tree_path)
+}
+
+/// Checks a contract state inclusion proof, as returned by the `midnight_contractStateProof`
+/// RPC, without access to the ledger state.
+///
+/// `state_key` is the key of the ledger state the proof was generated against,
+/// `contract_address` the serialized contract address, `contract_state` the contract state as
+/// returned by the `midnight_contractState` RPC, and `path` the arena nodes from the ledger
+/// state root down to the map entry holding the contract.
+pub fn verify_contract_state_proof(
+	state_key: &[u8],
+	contract_address: &[u8],
+	contract_state: &[u8],
+	path: &[ArenaPathStep],
+) -> bool {
+	let Ok(address) = ContractAddress::deserialize(&mut &contract_address[..], 0) else {
+		return false;
+	};
+	let Ok(contract_state) = tagged_deserialize::<ContractState<InMemoryDB>>(contract_state) else {
+		return false;
+	};
+
+	// Arena keys only depend on the content of the nodes, so the entry is keyed in a throwaway
+	// arena rather than in the one holding the ledger state
+	let storage = Storage::<InMemoryDB>::new(0, InMemoryDB::default());
+	let entry: ArenaKey<Hasher> = storage.arena.alloc((address, contract_state)).hash().into();
+	let Ok(key) = serialize_untagged(&entry) else {
+		return false;
+	};
+
+	leads_to_state_key(state_key, key, path)
+}
+
+/// Arena keys hash the binary representation of a node followed by the keys of its children
+fn node_key(data: &[u8], children: &[Vec<u8>]) -> Vec<u8> {
+	let mut hasher = Hasher::new();
+	hasher.update(data);
+	for child in children {
+		hasher.update(child);
+	}
+	hasher.finalize().to_vec()
+}
+
+/// Checks that `path` walks from the ledger state root at `state_key` down to the parent of the
+/// node with the serialized arena key `key`
+fn leads_to_state_key(state_key: &[u8], mut key: Vec<u8>, path: &[ArenaPathStep]) -> bool {
+	let Ok(root) = tagged_deserialize::<TypedArenaKey<LedgerState<DefaultDB>, Hasher>>(state_key)
+	else {
+		return false;
+	};
+
+	for step in path.iter().rev() {
+		if step.children.get(step.child) != Some(&key) {
+			return false;
+		}
+		key = node_key(&step.data, &step.children);
+	}
+
+	serialize_untagged(&ArenaKey::from(root)).is_ok_and(|root| root == key)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::super::{
+		ChargedState, Commitment, ContractAddress, ContractMaintenanceAuthority, ContractState,
+		DefaultDB, Deserializable, HashMapStorage, HashOutput, LedgerState, MerkleTree, StateValue,
+		ledger_storage::arena::TypedArenaKey, serialize, serialize_untagged,
+	};
+	use super::{
+		ArenaKey, ArenaNode, ArenaPathStep, Hasher, InMemoryDB, Storage, node_key,
+		verify_contract_state_proof, verify_zswap_commitment_proof,
+	};
+
+	/// A ledger state root node with the node at `key` as its second child, and its state key
+	fn state_root_over(key: Vec<u8>) -> (ArenaPathStep, Vec<u8>) {
+		let step = ArenaPathStep {
+			data: b"ledger state".to_vec(),
+			children: vec![vec![0u8; 32], key],
+			child: 1,
+		};
+		let root = node_key(&step.data, &step.children);
+		let root = TypedArenaKey::<LedgerState<DefaultDB>, Hasher>::deserialize(&mut &root[..], 0)
+			.expect("Arena keys should deserialize from their hash");
+		(step, serialize(&root).expect("State keys should serialize"))
+	}
+
+	#[test]
+	fn zswap_commitment_proofs_verify_against_the_state_key() {
+		let commitment = [3u8; 32];
+		let tree = MerkleTree::<()>::blank(32).update_hash(0, HashOutput(commitment), ()).rehash();
+		let root = serialize_untagged(&tree.root().unwrap()).unwrap();
+		let proof =
+			serialize_untagged(&tree.path_for_leaf(0, Commitment(HashOutput(commitment))).unwrap())
+				.unwrap();
+
+		let tree_node = ArenaNode {
+			data: [&[1u8][..], &root, &[32u8][..]].concat(),
+			children: vec![vec![4u8; 32], vec![5u8; 32]],
+		};
+		let (step, state_key) = state_root_over(node_key(&tree_node.data, &tree_node.children));
+		let tree_path = [step];
+		assert!(verify_zswap_commitment_proof(
+			&state_key,
+			&tree_path,
+			&tree_node,
+			&root,
+			&commitment,
+			&proof
+		));
+
+		// Another root than the one recorded in the tree
+		let other_tree =
+			MerkleTree::<()>::blank(32).update_hash(1, HashOutput(commitment), ()).rehash();
+		let other_root = serialize_untagged(&other_tree.root().unwrap()).unwrap();
+		let other_proof = serialize_untagged(
+			&other_tree.path_for_leaf(1, Commitment(HashOutput(commitment))).unwrap(),
+		)
+		.unwrap();
+		assert!(!verify_zswap_commitment_proof(
+			&state_key,
+			&tree_path,
+			&tree_node,
+			&other_root,
+			&commitment,
+			&other_proof
+		));
+		// A tree that isn't in the ledger state
+		let (_, other_state_key) = state_root_over(vec![6u8; 32]);
+		assert!(!verify_zswap_commitment_proof(
+			&other_state_key,
+			&tree_path,
+			&tree_node,
+			&root,
+			&commitment,
+			&proof
+		));
+		// A commitment that isn't in the tree
+		assert!(!verify_zswap_commitment_proof(
+			&state_key, &tree_path, &tree_node, &root, &[4u8; 32], &proof
+		));
+	}
+
+	#[test]
+	fn contract_state_proofs_verify_against_the_state_key() {
+		let address = ContractAddress(HashOutput([7u8; 32]));
+		let contract_state = ContractState::<InMemoryDB> {
+			data: ChargedState::new(StateValue::Null),
+			operations: HashMapStorage::new(),
+			maintenance_authority: ContractMaintenanceAuthority {
+				committee: vec![],
+				threshold: 0,
+				counter: 0,
+			},
+			balance: HashMapStorage::new(),
+		};
+		let entry: ArenaKey<Hasher> = Storage::<InMemoryDB>::new(0, InMemoryDB::default())
+			.arena
+			.alloc((address, contract_state.clone()))
+			.hash()
+			.into();
+		let (step, state_key) = state_root_over(serialize_untagged(&entry).unwrap());
+		let path = [step];
+		let address = serialize_untagged(&address).unwrap();
+		let contract_state = serialize(&contract_state).unwrap();
+		assert!(verify_contract_state_proof(&state_key, &address, &contract_state, &path));
+
+		let (_, other_state_key) = state_root_over(vec![6u8; 32]);
+		assert!(!verify_contract_state_proof(&other_state_key, &address, &contract_state, &path));
+		let other_address = serialize_untagged(&ContractAddress(HashOutput([8u8; 32]))).unwrap();
+		assert!(!verify_contract_state_proof(&state_key, &other_address, &contract_state, &path));
+	}
+
+	#[test]
+	fn should_reject_malformed_proofs() {
+		let tree_node = ArenaNode { data: vec![0u8; 32], children: vec![] };
+		assert!(!verify_zswap_commitment_proof(&[], &[], &tree_node, &[0u8; 32], &[0u8; 32], &[]));
+		assert!(!verify_zswap_commitment_proof(
+			&[],
+			&[],
+			&tree_node,
+			&[0u8; 32],
+			&[1, 2, 3],
+			&[0u8; 64]
+		));
+
+		let step = ArenaPathStep { data: vec![], children: vec![vec![0u8; 32]], child: 1 };
+		assert!(!verify_contract_state_proof(&[0u8; 32], &[0u8; 32], &[], &[]));
+		assert!(!verify_contract_state_proof(&[0u8; 32], &[0u8; 32], &[0u8; 32], &[step]));
+	}
+}
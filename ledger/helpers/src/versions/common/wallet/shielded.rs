@@ -14,13 +14,14 @@
 #![cfg(feature = "can-panic")]
 
 use super::super::{
-	CoinPublicKey, DB, DerivationPath, DeriveSeed, Deserializable, EncryptionPublicKey,
-	HRP_CONSTANT, HRP_CREDENTIAL_SHIELDED, HRP_CREDENTIAL_SHIELDED_ESK, HashOutput,
-	IntoWalletAddress, Role, SecretKeys, Seed, Serializable, WalletAddress, WalletSeed,
-	WalletState,
+	CoinInfo, CoinPublicKey, DB, DerivationPath, DeriveSeed, Deserializable, EncryptionPublicKey,
+	EncryptionSecretKey, HRP_CONSTANT, HRP_CREDENTIAL_SHIELDED, HRP_CREDENTIAL_SHIELDED_ESK,
+	HashOutput, IntoWalletAddress, Offer, Role, SecretKeys, Seed, Serializable, Storable,
+	WalletAddress, WalletSeed, WalletState,
 };
 use bech32::{Bech32m, Hrp};
 use derive_where::derive_where;
+use std::collections::BTreeMap;
 
 #[derive(Debug)]
 #[derive_where(Clone)]
@@ -149,3 +150,58 @@ impl<D: DB + Clone> TryFrom<&WalletAddress> for ShieldedWallet<D> {
 		Ok(Self::from_pub_keys(coin_public_key, enc_public_key))
 	}
 }
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ViewingKeyParseError {
+	DecodeError(bech32::DecodeError),
+	NotAViewingKey,
+	InvalidKey,
+}
+
+/// Shielded wallet known only by its viewing key, i.e. its encryption secret key.
+/// It can decrypt the coins sent to the wallet, but without the coin secret key it can't tell
+/// when they are spent, so received coins are never removed.
+#[derive(Clone)]
+pub struct ViewingKeyWallet {
+	pub viewing_key: String,
+	encryption_secret_key: EncryptionSecretKey,
+	/// Received coins by hex encoded commitment
+	pub received_coins: BTreeMap<String, CoinInfo>,
+}
+
+impl ViewingKeyWallet {
+	/// Parses a Bech32m-encoded viewing key, as returned by `ShieldedWallet::viewing_key`
+	pub fn from_viewing_key(viewing_key: &str) -> Result<Self, ViewingKeyParseError> {
+		let (hrp, data) = bech32::decode(viewing_key).map_err(ViewingKeyParseError::DecodeError)?;
+		let prefix = format!("{HRP_CONSTANT}_{HRP_CREDENTIAL_SHIELDED_ESK}");
+		if hrp.as_str() != prefix && !hrp.as_str().starts_with(&format!("{prefix}_")) {
+			return Err(ViewingKeyParseError::NotAViewingKey);
+		}
+		let encryption_secret_key = Deserializable::deserialize(&mut &data[..], 0)
+			.map_err(|_| ViewingKeyParseError::InvalidKey)?;
+
+		Ok(Self {
+			viewing_key: viewing_key.to_string(),
+			encryption_secret_key,
+			received_coins: BTreeMap::new(),
+		})
+	}
+
+	/// Records the outputs and transients of the offers that can be decrypted with the viewing key
+	pub fn update_from_offers<D: DB, P: Storable<D>>(&mut self, offers: &[Offer<P, D>]) {
+		for offer in offers {
+			let outputs = offer.outputs.iter().map(|o| (o.coin_com, o.ciphertext.clone()));
+			let transients = offer.transient.iter().map(|t| (t.coin_com, t.ciphertext.clone()));
+			for (commitment, ciphertext) in outputs.chain(transients) {
+				let Some(ciphertext) = ciphertext else {
+					continue;
+				};
+				if let Some(coin) =
+					self.encryption_secret_key.decrypt::<CoinInfo>(&(*ciphertext).clone().into())
+				{
+					self.received_coins.insert(hex::encode(commitment.0.0), coin);
+				}
+			}
+		}
+	}
+}
@@ -11,7 +11,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use parity_scale_codec::{Decode, DecodeWithMemTracking, Encode};
+use parity_scale_codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
 use scale_info::prelude::string::String;
 use scale_info_derive::TypeInfo;
 use sp_runtime::Vec;
@@ -56,6 +56,70 @@ pub struct TransactionAppliedStateRoot {
 	pub unshielded_utxos_spent: Vec<UtxoInfo>,
 }
 
+/// A segment which failed while applying a transaction, together with the
+/// `u8` error code of the reason it failed.
+#[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, Clone, Eq, PartialEq, Debug)]
+pub struct FailedSegment {
+	pub segment_id: SegmentId,
+	pub error_code: u8,
+}
+
+/// The would-be outcome of applying a transaction, as returned by a dry-run.
+/// Mirrors `TransactionAppliedStateRoot`, but nothing is persisted.
+#[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, Clone, PartialEq, Debug)]
+pub struct TransactionSimulationResult {
+	pub tx_hash: Hash,
+	pub all_applied: bool,
+	pub failed_segments: Vec<FailedSegment>,
+	pub call_addresses: Vec<Vec<u8>>,
+	pub deploy_addresses: Vec<Vec<u8>>,
+	pub maintain_addresses: Vec<Vec<u8>>,
+	pub claim_rewards: Vec<u128>,
+	pub unshielded_utxos_created: Vec<UtxoInfo>,
+	pub unshielded_utxos_spent: Vec<UtxoInfo>,
+	pub fees: u128,
+}
+
+/// Result of a single segment of an applied transaction. `error_code` is the
+/// `u8` code of the reason the segment failed, or `None` if it succeeded.
+#[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, Clone, Eq, PartialEq, Debug)]
+pub struct SegmentResult {
+	pub segment_id: SegmentId,
+	pub error_code: Option<u8>,
+}
+
+/// Fee accounting and per-segment results of an applied transaction.
+/// Returned alongside `TransactionAppliedStateRoot`.
+#[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, Clone, Default, Eq, PartialEq, Debug)]
+pub struct TransactionOutcome {
+	/// DUST paid in fees, i.e. spent by the DUST spends of the transaction
+	pub fees_paid: u128,
+	/// Empty if all segments were applied
+	pub segment_results: Vec<SegmentResult>,
+	pub dust_spends: u32,
+	pub guaranteed_shielded_outputs: u32,
+	pub fallible_shielded_outputs: u32,
+	/// Serialized zswap merkle tree root after the transaction was applied
+	pub zswap_state_root: Vec<u8>,
+	/// Weight components of the transaction, without the gas of the fallible transcripts of
+	/// failed segments
+	pub applied_weight_components: TransactionWeightComponents,
+}
+
+/// State change of a contract called by the applied segments of a transaction.
+/// The hashes are the serialized arena hashes of the contract state.
+#[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, Clone, Eq, PartialEq, Debug)]
+pub struct ContractStateChange {
+	/// Serialized address of the called contract
+	pub contract_address: Vec<u8>,
+	/// Names of the called entry points, in call order
+	pub entry_points: Vec<Vec<u8>>,
+	/// State hash before the transaction, empty if the contract wasn't deployed yet
+	pub old_state_hash: Vec<u8>,
+	/// State hash after the transaction
+	pub new_state_hash: Vec<u8>,
+}
+
 #[derive(Encode, Decode, DecodeWithMemTracking)]
 pub struct SystemTransactionAppliedStateRoot {
 	pub state_root: Vec<u8>,
@@ -80,6 +144,68 @@ pub struct Tx {
 	pub has_guaranteed_coins: bool,
 }
 
+/// A coin or UTXO spent by a transaction. Transactions spending the same one conflict.
+#[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, Clone, Eq, PartialEq, Debug)]
+pub enum TransactionSpend {
+	ShieldedNullifier(Hash),
+	/// Serialized `DustNullifier`
+	DustNullifier(Vec<u8>),
+	UnshieldedUtxo {
+		intent_hash: Hash,
+		output_no: u32,
+	},
+}
+
+/// What the transaction pool orders and deduplicates Midnight transactions by
+#[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, Clone, Eq, PartialEq, Debug)]
+pub struct TransactionPoolInfo {
+	pub tx_hash: Hash,
+	/// Sum of the `v_fee` of the DUST spends of the transaction
+	pub fee: u128,
+	pub spends: Vec<TransactionSpend>,
+	pub weight_components: TransactionWeightComponents,
+}
+
+/// What a ledger state is checked against across runtime upgrades
+#[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, Clone, Eq, PartialEq, Debug)]
+pub struct LedgerStateSummary {
+	pub zswap_state_root: Vec<u8>,
+	/// Number of unshielded UTXOs
+	pub utxo_count: u64,
+	/// Number of deployed contracts
+	pub contract_count: u64,
+}
+
+/// Outcome of walking a ledger state in the storage, see `check_ledger_state`
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub struct LedgerStateCheck {
+	/// Number of arena nodes found in the storage
+	pub nodes: u64,
+	/// Serialized arena keys of the nodes missing from the storage
+	pub missing: Vec<Vec<u8>>,
+	/// Error loading the ledger state although none of its nodes is missing, i.e. a node
+	/// can't be decoded
+	pub corrupt: Option<String>,
+}
+
+impl LedgerStateCheck {
+	pub fn is_complete(&self) -> bool {
+		self.missing.is_empty() && self.corrupt.is_none()
+	}
+}
+
+/// Size of a transaction along the components of `send_mn_transaction`'s weight.
+#[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, Clone, Default, Eq, PartialEq, Debug)]
+pub struct TransactionWeightComponents {
+	pub intents: u32,
+	/// Shielded offer inputs, including transients
+	pub inputs: u32,
+	/// Shielded offer outputs, including transients
+	pub outputs: u32,
+	/// Read and compute time declared by the transcripts of the contract calls, in picoseconds
+	pub gas: GasCost,
+}
+
 /// A scale friendly version of mn_ledger::onchain_runtime::context::BlockContext
 /// that can be used to pass across the host interface.
 #[derive(Encode, Decode, DecodeWithMemTracking, Clone, Debug, TypeInfo, Eq, PartialEq)]
@@ -293,3 +419,242 @@ pub struct UtxoInfo {
 	pub value: u128,
 	pub output_no: u32,
 }
+
+/// DUST generation of a cNight UTXO, see `get_dust_generation_info`
+#[derive(Encode, Decode, DecodeWithMemTracking, Debug, Clone, PartialEq, Eq, TypeInfo)]
+pub struct DustGenerationDetails {
+	/// Value of the generating cNight UTXO
+	pub value: u128,
+	/// Serialized DUST public key the DUST is generated for
+	pub owner: Vec<u8>,
+	/// Initial nonce of the generated DUST
+	pub nonce: Hash,
+	/// Time the generation stops, in seconds since the epoch
+	pub dtime: u64,
+}
+
+/// Inclusion proof of a coin commitment in the zswap commitment tree, see
+/// `get_zswap_commitment_proof`
+#[derive(Encode, Decode, DecodeWithMemTracking, Debug, Clone, PartialEq, Eq, TypeInfo)]
+pub struct ZswapCommitmentProof {
+	/// State key of the ledger state the proof was generated against
+	pub state_key: Vec<u8>,
+	/// Nodes from the ledger state root down to the parent of the top node of the commitment
+	/// tree
+	pub tree_path: Vec<ArenaPathStep>,
+	/// Top node of the commitment tree, which records the tree root
+	pub tree_node: ArenaNode,
+	/// Serialized root of the commitment tree
+	pub root: Vec<u8>,
+	/// Serialized merkle path from the root to the commitment
+	pub path: Vec<u8>,
+}
+
+/// A storage arena node, see `ArenaPathStep`
+#[derive(Encode, Decode, DecodeWithMemTracking, Debug, Clone, PartialEq, Eq, TypeInfo)]
+pub struct ArenaNode {
+	/// Binary representation of the node
+	pub data: Vec<u8>,
+	/// Serialized arena keys of the node's children
+	pub children: Vec<Vec<u8>>,
+}
+
+/// A storage arena node on the path from a ledger state root to one of its descendants
+#[derive(Encode, Decode, DecodeWithMemTracking, Debug, Clone, PartialEq, Eq, TypeInfo)]
+pub struct ArenaPathStep {
+	/// Binary representation of the node
+	pub data: Vec<u8>,
+	/// Serialized arena keys of the node's children
+	pub children: Vec<Vec<u8>>,
+	/// Index in `children` of the next node on the path
+	pub child: u32,
+}
+
+/// Inclusion proof of a contract in a ledger state, see `get_contract_state_proof`
+#[derive(Encode, Decode, DecodeWithMemTracking, Debug, Clone, PartialEq, Eq, TypeInfo)]
+pub struct ContractStateProof {
+	/// State key of the ledger state the proof was generated against
+	pub state_key: Vec<u8>,
+	/// Nodes from the ledger state root down to the parent of the map entry holding the
+	/// contract address and state
+	pub path: Vec<ArenaPathStep>,
+}
+
+/// Upper bound of the fee prices accepted by `LedgerParametersUpdate`
+pub const MAX_FEE_PRICE: u64 = 1_000_000;
+
+/// A `numerator / denominator` fixed point value
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	Clone,
+	Copy,
+	Eq,
+	PartialEq,
+	Debug,
+	MaxEncodedLen,
+)]
+pub struct FixedPointRatio {
+	pub numerator: u64,
+	pub denominator: u64,
+}
+
+/// Per-block limits of the ledger cost model
+#[derive(
+	Encode, Decode, DecodeWithMemTracking, TypeInfo, Clone, Eq, PartialEq, Debug, MaxEncodedLen,
+)]
+pub struct BlockLimits {
+	pub read_time_picoseconds: u64,
+	pub compute_time_picoseconds: u64,
+	pub block_usage: u64,
+	pub bytes_written: u64,
+	pub bytes_churned: u64,
+}
+
+/// Changes to the `LedgerParameters` of the ledger state.
+/// Parameters left as `None` keep their current value.
+#[derive(
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	Clone,
+	Default,
+	Eq,
+	PartialEq,
+	Debug,
+	MaxEncodedLen,
+)]
+pub struct LedgerParametersUpdate {
+	pub read_price: Option<FixedPointRatio>,
+	pub compute_price: Option<FixedPointRatio>,
+	pub block_usage_price: Option<FixedPointRatio>,
+	pub write_price: Option<FixedPointRatio>,
+	/// DUST generated per NIGHT, in specks per STAR
+	pub night_dust_ratio: Option<u64>,
+	/// Rate at which DUST generation decays, in specks per second
+	pub generation_decay_rate: Option<u32>,
+	pub dust_grace_period_secs: Option<u64>,
+	pub transaction_byte_limit: Option<u64>,
+	pub block_limits: Option<BlockLimits>,
+}
+
+#[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, Clone, Copy, Eq, PartialEq, Debug)]
+pub enum LedgerParametersUpdateError {
+	/// No parameter is changed
+	Empty,
+	/// A fee price has a zero denominator or exceeds `MAX_FEE_PRICE`
+	InvalidFeePrice,
+	/// A DUST parameter is zero, or the grace period does not fit a ledger `Duration`
+	InvalidDustParameter,
+	/// A limit is zero, or the transaction byte limit exceeds the block limits
+	InvalidLimit,
+}
+
+impl LedgerParametersUpdate {
+	/// Checks the ranges of the changed parameters
+	pub fn validate(&self) -> Result<(), LedgerParametersUpdateError> {
+		if *self == Self::default() {
+			return Err(LedgerParametersUpdateError::Empty);
+		}
+
+		let prices =
+			[&self.read_price, &self.compute_price, &self.block_usage_price, &self.write_price];
+		for price in prices.into_iter().flatten() {
+			if price.denominator == 0 || price.numerator / price.denominator >= MAX_FEE_PRICE {
+				return Err(LedgerParametersUpdateError::InvalidFeePrice);
+			}
+		}
+
+		if self.night_dust_ratio == Some(0)
+			|| self.generation_decay_rate == Some(0)
+			|| self
+				.dust_grace_period_secs
+				.is_some_and(|secs| secs == 0 || secs > i64::MAX as u64)
+		{
+			return Err(LedgerParametersUpdateError::InvalidDustParameter);
+		}
+
+		if self.transaction_byte_limit == Some(0) {
+			return Err(LedgerParametersUpdateError::InvalidLimit);
+		}
+		if let Some(limits) = &self.block_limits {
+			let BlockLimits {
+				read_time_picoseconds,
+				compute_time_picoseconds,
+				block_usage,
+				bytes_written,
+				bytes_churned,
+			} = *limits;
+			if [
+				read_time_picoseconds,
+				compute_time_picoseconds,
+				block_usage,
+				bytes_written,
+				bytes_churned,
+			]
+			.contains(&0)
+			{
+				return Err(LedgerParametersUpdateError::InvalidLimit);
+			}
+			if self.transaction_byte_limit.is_some_and(|limit| limit > block_usage) {
+				return Err(LedgerParametersUpdateError::InvalidLimit);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_validate_ledger_parameters_update() {
+		assert_eq!(
+			LedgerParametersUpdate::default().validate(),
+			Err(LedgerParametersUpdateError::Empty)
+		);
+
+		let update = LedgerParametersUpdate {
+			read_price: Some(FixedPointRatio { numerator: 1, denominator: 10 }),
+			night_dust_ratio: Some(5_000_000_000),
+			transaction_byte_limit: Some(1024),
+			..Default::default()
+		};
+		assert_eq!(update.validate(), Ok(()));
+
+		let zero_denominator = LedgerParametersUpdate {
+			write_price: Some(FixedPointRatio { numerator: 1, denominator: 0 }),
+			..Default::default()
+		};
+		assert_eq!(zero_denominator.validate(), Err(LedgerParametersUpdateError::InvalidFeePrice));
+
+		let zero_decay =
+			LedgerParametersUpdate { generation_decay_rate: Some(0), ..Default::default() };
+		assert_eq!(zero_decay.validate(), Err(LedgerParametersUpdateError::InvalidDustParameter));
+
+		let grace_period_out_of_range =
+			LedgerParametersUpdate { dust_grace_period_secs: Some(u64::MAX), ..Default::default() };
+		assert_eq!(
+			grace_period_out_of_range.validate(),
+			Err(LedgerParametersUpdateError::InvalidDustParameter)
+		);
+
+		let tx_over_block_limit = LedgerParametersUpdate {
+			transaction_byte_limit: Some(1024),
+			block_limits: Some(BlockLimits {
+				read_time_picoseconds: 1,
+				compute_time_picoseconds: 1,
+				block_usage: 512,
+				bytes_written: 1,
+				bytes_churned: 1,
+			}),
+			..Default::default()
+		};
+		assert_eq!(tx_over_block_limit.validate(), Err(LedgerParametersUpdateError::InvalidLimit));
+	}
+}
@@ -0,0 +1,312 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Switch from the `latest` ledger version to the `hard_fork_test` one
+//!
+//! Both versions share the same ParityDB, which can only be opened once, so the `latest`
+//! default storage has to be dropped before the `hard_fork_test` one is set. The nodes of the
+//! ledger state stay in the ParityDB across the switch, only the key of its root is carried.
+//! The state is checked with the `latest` version before the switch and with the
+//! `hard_fork_test` one after it. If the latter fails, the ParityDB is handed back to the
+//! `latest` storage, which still holds the state.
+
+use crate::{common::types::LedgerStateSummary, hard_fork_test, latest};
+use sp_externalities::Externalities;
+use sp_std::vec::Vec;
+
+type Database = ledger_storage::db::ParityDb;
+type DatabaseHF = ledger_storage_hf::db::ParityDb;
+type Signature = base_crypto::signatures::Signature;
+type SignatureHF = base_crypto_hf::signatures::Signature;
+
+/// Drops the `latest` ledger default storage, releasing the ParityDB for the hard-fork one
+pub(crate) fn drop_latest_default_storage() {
+	use ledger_storage::storage::{try_get_default_storage, unsafe_drop_default_storage};
+	unsafe_drop_default_storage::<Database>();
+
+	match try_get_default_storage::<Database>() {
+		Some(_) => {
+			log::error!(
+				target: hard_fork_test::LOG_TARGET,
+				"Pre Hard-fork Default Storage wasn't successfully dropped, still exists",
+			);
+		},
+		None => {
+			log::info!(
+				target: hard_fork_test::LOG_TARGET,
+				"Pre Hard-fork Default Storage was successfully dropped",
+			);
+		},
+	};
+}
+
+/// Drops the `hard_fork_test` ledger default storage, handing the ParityDB back to the
+/// `latest` one after a failed translation
+fn drop_hard_fork_default_storage() {
+	use ledger_storage_hf::storage::{try_get_default_storage, unsafe_drop_default_storage};
+	unsafe_drop_default_storage::<DatabaseHF>();
+
+	if try_get_default_storage::<DatabaseHF>().is_some() {
+		log::error!(
+			target: hard_fork_test::LOG_TARGET,
+			"Hard-fork Default Storage wasn't successfully dropped, still exists",
+		);
+	}
+}
+
+/// Translates the `latest` ledger state at `state_key` into the `hard_fork_test` ledger
+/// version, switching the default storage, and returns the state key of the translated state.
+///
+/// On error, the default storage is left to the `latest` version.
+pub(crate) fn translate_ledger_state(
+	externalities: &mut dyn Externalities,
+	state_key: &[u8],
+) -> Result<Vec<u8>, hard_fork_test::types::LedgerApiError> {
+	use hard_fork_test::types::{LedgerApiError, SerializationError};
+
+	let previous_state_key =
+		latest::Bridge::<Signature, Database>::export_untagged_ledger_state_key(state_key)
+			.map_err(|e| {
+				log::error!(
+					target: hard_fork_test::LOG_TARGET,
+					"Invalid pre Hard-fork Ledger State key: {e:?}"
+				);
+				LedgerApiError::Serialization(SerializationError::LedgerState)
+			})?;
+
+	// Nothing is switched unless the whole state can be read before the hard fork
+	let check =
+		latest::Bridge::<Signature, Database>::check_ledger_state(state_key).map_err(|e| {
+			log::error!(
+				target: hard_fork_test::LOG_TARGET,
+				"Unable to check the pre Hard-fork Ledger State: {e:?}"
+			);
+			LedgerApiError::NoLedgerState
+		})?;
+	if !check.is_complete() {
+		log::error!(
+			target: hard_fork_test::LOG_TARGET,
+			"Incomplete pre Hard-fork Ledger State, {} nodes missing: {:?}",
+			check.missing.len(),
+			check.corrupt
+		);
+		return Err(LedgerApiError::NoLedgerState);
+	}
+
+	// Everything written by the pre hard-fork storage has to be in the ParityDB before it is
+	// dropped
+	latest::Bridge::<Signature, Database>::flush_storage(externalities);
+	drop_latest_default_storage();
+	hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::set_default_storage(externalities);
+
+	match import_checked_ledger_state_key(&previous_state_key) {
+		Ok(new_state_key) => {
+			log::info!(
+				target: hard_fork_test::LOG_TARGET,
+				"Ledger State translated to the Hard-fork version: {}",
+				hex::encode(&new_state_key)
+			);
+			Ok(new_state_key)
+		},
+		Err(e) => {
+			drop_hard_fork_default_storage();
+			latest::Bridge::<Signature, Database>::set_default_storage(externalities);
+			Err(e)
+		},
+	}
+}
+
+/// Reads the pre hard-fork ledger state from the `hard_fork_test` storage, checking that all
+/// of it can be read with this version
+fn import_checked_ledger_state_key(
+	previous_state_key: &[u8],
+) -> Result<Vec<u8>, hard_fork_test::types::LedgerApiError> {
+	type HardFork = hard_fork_test::Bridge<SignatureHF, DatabaseHF>;
+
+	let new_state_key = HardFork::import_retagged_ledger_state_key(previous_state_key)?;
+	let check = HardFork::check_ledger_state(&new_state_key)?;
+	if !check.is_complete() {
+		log::error!(
+			target: hard_fork_test::LOG_TARGET,
+			"Unable to read the Ledger State with the Hard-fork version, {} nodes missing: {:?}",
+			check.missing.len(),
+			check.corrupt
+		);
+		return Err(hard_fork_test::types::LedgerApiError::NoLedgerState);
+	}
+	Ok(new_state_key)
+}
+
+/// Summary of the `latest` ledger state at `state_key`, before it is translated
+pub(crate) fn get_previous_ledger_state_summary(
+	state_key: &[u8],
+) -> Result<LedgerStateSummary, hard_fork_test::types::LedgerApiError> {
+	latest::Bridge::<Signature, Database>::get_ledger_state_summary(state_key).map_err(|e| {
+		log::error!(
+			target: hard_fork_test::LOG_TARGET,
+			"Unable to read the pre Hard-fork Ledger State: {e:?}"
+		);
+		hard_fork_test::types::LedgerApiError::NoLedgerState
+	})
+}
+
+// grcov-excl-start
+#[cfg(test)]
+mod tests {
+	use crate::{
+		common::types::BlockContext,
+		hard_fork_test,
+		latest::{
+			self,
+			api::{Transaction, ledger::Ledger},
+			helpers_local::extract_info_from_tx_with_context,
+		},
+	};
+	use base_crypto::time::Timestamp;
+	use ledger_storage::{DefaultDB, arena::Sp, storage::try_get_default_storage};
+	use ledger_storage_hf::DefaultDB as DefaultDBHF;
+	use midnight_node_res::{
+		networks::{MidnightNetwork, UndeployedNetwork},
+		undeployed::transactions::{CONTRACT_ADDR, DEPLOY_TX, STORE_TX},
+	};
+	use midnight_primitives_ledger::{LedgerStorage, LedgerStorageExt};
+	use midnight_serialize::{tagged_deserialize, tagged_serialize};
+	use mn_ledger::structure::{LedgerState, SystemTransaction};
+	use sp_io::TestExternalities;
+
+	type Latest = latest::Bridge<base_crypto::signatures::Signature, DefaultDB>;
+	type HardFork = hard_fork_test::Bridge<base_crypto_hf::signatures::Signature, DefaultDBHF>;
+	type LatestParityDb = latest::Bridge<super::Signature, super::Database>;
+	type HardForkParityDb = hard_fork_test::Bridge<super::SignatureHF, super::DatabaseHF>;
+
+	/// Initial nonce of the cNight UTXO generating DUST in `prepare_latest_ledger_state`
+	const DUST_NONCE: [u8; 32] = [7; 32];
+
+	/// Persists the genesis ledger state with a deployed and called contract and a cNight UTXO
+	/// generating DUST, returning its key and the address owning its first unshielded UTXO
+	fn prepare_latest_ledger_state() -> (Vec<u8>, Vec<u8>) {
+		sp_tracing::try_init_simple();
+
+		let state: LedgerState<DefaultDB> = tagged_deserialize(UndeployedNetwork.genesis_state())
+			.unwrap_or_else(|err| panic!("Can't deserialize ledger from genesis: {err}"));
+		let mut ledger = Sp::new(Ledger::new(state));
+
+		let api = latest::api::new();
+		let mut tblock = 0;
+		for tx_with_context in [DEPLOY_TX, STORE_TX] {
+			let (tx, block_context) = extract_info_from_tx_with_context(tx_with_context);
+			let tx = api
+				.tagged_deserialize::<Transaction<base_crypto::signatures::Signature, DefaultDB>>(
+					&tx,
+				)
+				.expect("Can't deserialize transaction");
+			let block_context: BlockContext = block_context.into();
+			tblock = block_context.tblock;
+			let tx_ctx = ledger.get_transaction_context(block_context.clone());
+			let (applied, _) = Ledger::apply_transaction(ledger, &api, &tx, &tx_ctx, false)
+				.unwrap_or_else(|err| panic!("Can't apply transaction: {err}"));
+			ledger = Ledger::post_block_update(applied, block_context)
+				.expect("Post block update failed");
+		}
+
+		let owner = ledger.state.utxo.utxos.iter().next().expect("No unshielded UTXO").0.owner.0.0;
+
+		let mut dust_public_key = [0; 32];
+		dust_public_key[0] = 1;
+		let event = Latest::construct_cnight_generates_dust_event(
+			1_000_000,
+			&dust_public_key,
+			tblock,
+			0,
+			DUST_NONCE,
+		)
+		.expect("Can't construct the DUST event");
+		let sys_tx = Latest::construct_cnight_generates_dust_system_tx(vec![event])
+			.expect("Can't construct the DUST system transaction");
+		let sys_tx = api
+			.tagged_deserialize::<SystemTransaction>(&sys_tx)
+			.expect("Can't deserialize system transaction");
+		ledger = Ledger::apply_system_tx(ledger, &sys_tx, Timestamp::from_secs(tblock))
+			.expect("Can't apply system transaction");
+
+		let mut bytes = vec![];
+		tagged_serialize(&*ledger, &mut bytes).expect("Can't serialize ledger");
+		let state_key = Latest::import_ledger_state(&bytes).expect("Can't persist ledger");
+		(state_key, owner.to_vec())
+	}
+
+	#[test]
+	fn translate_ledger_state_switches_the_storage_and_keeps_the_state() {
+		let dir = tempfile::tempdir().unwrap();
+		let cache_size = 1024 * 1024;
+		let (state_key, owner) = prepare_latest_ledger_state();
+		let ledger_state = Latest::export_ledger_state(&state_key).unwrap();
+
+		crate::open_storage_paritydb(dir.path(), cache_size);
+		let state_key = crate::import_ledger_state(&ledger_state).unwrap();
+		let summary = LatestParityDb::get_ledger_state_summary(&state_key).unwrap();
+		let contract_address = hex::decode(CONTRACT_ADDR).unwrap();
+		let utxos = LatestParityDb::get_unshielded_utxos(&state_key, &owner).unwrap();
+		let contract_state =
+			LatestParityDb::get_contract_state(&state_key, &contract_address).unwrap();
+		let dust_generation =
+			LatestParityDb::get_dust_generation_info(&state_key, &DUST_NONCE).unwrap();
+		assert!(!utxos.is_empty());
+		assert!(!contract_state.is_empty());
+		assert!(dust_generation.is_some());
+
+		let mut externalities = TestExternalities::default();
+		externalities.register_extension(LedgerStorageExt::new(LedgerStorage::new(
+			dir.path().to_path_buf(),
+			cache_size,
+		)));
+
+		// A state missing from the storage is rejected before the switch
+		let mut missing_state_key = state_key.clone();
+		*missing_state_key.last_mut().unwrap() ^= 1;
+		assert!(
+			super::translate_ledger_state(&mut externalities.ext(), &missing_state_key).is_err()
+		);
+		assert!(try_get_default_storage::<super::Database>().is_some());
+
+		let new_state_key =
+			super::translate_ledger_state(&mut externalities.ext(), &state_key).unwrap();
+
+		// The pre hard-fork storage was released, the hard-fork one reads the same ParityDB
+		assert!(try_get_default_storage::<super::Database>().is_none());
+		assert_eq!(HardForkParityDb::get_ledger_state_summary(&new_state_key).unwrap(), summary);
+		assert!(HardForkParityDb::check_ledger_state(&new_state_key).unwrap().is_complete());
+
+		// The UTXOs, contracts and DUST generations are read the same with the hard-fork version
+		assert_eq!(HardForkParityDb::get_unshielded_utxos(&new_state_key, &owner).unwrap(), utxos);
+		assert_eq!(
+			HardForkParityDb::get_contract_state(&new_state_key, &contract_address).unwrap(),
+			contract_state
+		);
+		assert_eq!(
+			HardForkParityDb::get_dust_generation_info(&new_state_key, &DUST_NONCE).unwrap(),
+			dust_generation
+		);
+	}
+
+	#[test]
+	fn untranslatable_ledger_state_is_rejected() {
+		assert!(HardFork::import_retagged_ledger_state_key(&[1, 2, 3]).is_err());
+
+		// A state missing from the storage of the hard-fork version
+		let (state_key, _) = prepare_latest_ledger_state();
+		let previous_state_key = Latest::export_untagged_ledger_state_key(&state_key).unwrap();
+		assert!(HardFork::import_retagged_ledger_state_key(&previous_state_key).is_err());
+	}
+}
+// grcov-excl-stop
@@ -13,8 +13,11 @@
 
 use crate::{
 	common::types::{
-		BlockContext, GasCost, Hash, StorageCost, SystemTransactionAppliedStateRoot,
-		TransactionAppliedStateRoot, TransactionDetails, Tx,
+		BlockContext, ContractStateChange, ContractStateProof, DustGenerationDetails, GasCost,
+		Hash, LedgerParametersUpdate, LedgerStateSummary, StorageCost,
+		SystemTransactionAppliedStateRoot, TransactionAppliedStateRoot, TransactionDetails,
+		TransactionOutcome, TransactionPoolInfo, TransactionSimulationResult,
+		TransactionWeightComponents, Tx, UtxoInfo, ZswapCommitmentProof,
 	},
 	hard_fork_test, latest,
 };
@@ -49,6 +52,14 @@ pub trait LedgerBridge {
 		// hard-fork and no hard-fork versions.
 	}
 
+	fn translate_ledger_state(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<Result<Vec<u8>, latest::types::LedgerApiError>> {
+		// Nothing to translate. No ledger version exists prior this version.
+		Ok(state_key.to_vec())
+	}
+
 	fn flush_storage(&mut self) {
 		latest::Bridge::<Signature, Database>::flush_storage(*self)
 	}
@@ -111,6 +122,60 @@ pub trait LedgerBridge {
 		)
 	}
 
+	#[version(3)]
+	fn apply_transaction(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		tx: PassFatPointerAndRead<&[u8]>,
+		block_context: PassFatPointerAndDecode<BlockContext>,
+		_runtime_version: u32,
+		pre_verified: bool,
+	) -> AllocateAndReturnByCodec<
+		Result<
+			(TransactionAppliedStateRoot, TransactionOutcome, Vec<ContractStateChange>),
+			latest::types::LedgerApiError,
+		>,
+	> {
+		latest::Bridge::<Signature, Database>::apply_transaction_with_outcome(
+			*self,
+			state_key,
+			tx,
+			block_context,
+			true,
+			pre_verified,
+		)
+	}
+
+	/*
+	 * Verifies the proofs and signatures of a block's transactions in parallel, ahead of
+	 * `apply_transaction`. Returns whether each transaction was verified.
+	 */
+	fn pre_verify_transactions(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		txs: PassFatPointerAndDecode<Vec<Vec<u8>>>,
+		block_context: PassFatPointerAndDecode<BlockContext>,
+	) -> AllocateAndReturnByCodec<Result<Vec<bool>, latest::types::LedgerApiError>> {
+		latest::Bridge::<Signature, Database>::pre_verify_transactions(
+			state_key,
+			txs,
+			block_context,
+		)
+	}
+
+	/*
+	 * Applies a transaction without persisting the resulting ledger state
+	 */
+	fn simulate_transaction(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		tx: PassFatPointerAndRead<&[u8]>,
+		block_context: PassFatPointerAndDecode<BlockContext>,
+	) -> AllocateAndReturnByCodec<Result<TransactionSimulationResult, latest::types::LedgerApiError>>
+	{
+		latest::Bridge::<Signature, Database>::simulate_transaction(state_key, tx, block_context)
+	}
+
 	fn apply_system_transaction(
 		&mut self,
 		state_key: PassFatPointerAndRead<&[u8]>,
@@ -171,6 +236,38 @@ pub trait LedgerBridge {
 		latest::Bridge::<Signature, Database>::get_decoded_transaction(transaction_bytes)
 	}
 
+	/*
+	 * Validates a transaction and returns its fee, spends and weight components, to order the
+	 * transaction pool
+	 */
+	// Current Enabled Version
+	fn get_transaction_pool_info(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		tx: PassFatPointerAndRead<&[u8]>,
+		block_context: PassFatPointerAndDecode<BlockContext>,
+		runtime_version: u32,
+	) -> AllocateAndReturnByCodec<Result<TransactionPoolInfo, latest::types::LedgerApiError>> {
+		latest::Bridge::<Signature, Database>::get_transaction_pool_info(
+			*self,
+			state_key,
+			tx,
+			block_context,
+			runtime_version,
+		)
+	}
+
+	/*
+	 * Returns the components of `send_mn_transaction`'s weight
+	 */
+	// Current Enabled Version
+	fn get_transaction_weight_components(
+		transaction_bytes: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<Result<TransactionWeightComponents, latest::types::LedgerApiError>>
+	{
+		latest::Bridge::<Signature, Database>::get_transaction_weight_components(transaction_bytes)
+	}
+
 	/*
 	 * get_zswap_chain_state()
 	 */
@@ -225,6 +322,59 @@ pub trait LedgerBridge {
 		latest::Bridge::<Signature, Database>::get_ledger_parameters(state_key)
 	}
 
+	/*
+	 * Returns the unshielded UTXOs owned by the provided address
+	 */
+	// Current Enabled Version
+	fn get_unshielded_utxos(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		address: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<Result<Vec<UtxoInfo>, latest::types::LedgerApiError>> {
+		latest::Bridge::<Signature, Database>::get_unshielded_utxos(state_key, address)
+	}
+
+	/*
+	 * Returns the merkle path from the zswap state root to the provided coin commitment,
+	 * together with the root
+	 */
+	// Current Enabled Version
+	fn get_zswap_commitment_proof(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		commitment: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<Result<Option<ZswapCommitmentProof>, latest::types::LedgerApiError>>
+	{
+		latest::Bridge::<Signature, Database>::get_zswap_commitment_proof(state_key, commitment)
+	}
+
+	/*
+	 * Returns the storage arena path from the ledger state root to the provided contract
+	 */
+	// Current Enabled Version
+	fn get_contract_state_proof(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		contract_address: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<Result<Option<ContractStateProof>, latest::types::LedgerApiError>>
+	{
+		latest::Bridge::<Signature, Database>::get_contract_state_proof(state_key, contract_address)
+	}
+
+	/*
+	 * Returns the DUST generation of the cNight UTXO with the provided initial nonce
+	 */
+	// Current Enabled Version
+	fn get_dust_generation_info(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		nonce: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<
+		Result<Option<DustGenerationDetails>, latest::types::LedgerApiError>,
+	> {
+		latest::Bridge::<Signature, Database>::get_dust_generation_info(state_key, nonce)
+	}
+
 	/*
 	 * Returns the expected fee to pay for a submitting a transaction
 	 */
@@ -248,6 +398,28 @@ pub trait LedgerBridge {
 		latest::Bridge::<Signature, Database>::get_zswap_state_root(state_key)
 	}
 
+	/*
+	 * Returns what the ledger state is checked against across runtime upgrades
+	 */
+	// Current Enabled Version
+	fn get_ledger_state_summary(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<Result<LedgerStateSummary, latest::types::LedgerApiError>> {
+		latest::Bridge::<Signature, Database>::get_ledger_state_summary(state_key)
+	}
+
+	/*
+	 * Returns the summary of a ledger state not yet translated by `translate_ledger_state`
+	 */
+	fn get_previous_ledger_state_summary(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<Result<LedgerStateSummary, latest::types::LedgerApiError>> {
+		// No ledger version exists prior this version, its states are never translated.
+		latest::Bridge::<Signature, Database>::get_ledger_state_summary(state_key)
+	}
+
 	fn construct_cnight_generates_dust_event(
 		value: PassFatPointerAndDecode<u128>,
 		owner: PassFatPointerAndRead<&[u8]>,
@@ -265,6 +437,21 @@ pub trait LedgerBridge {
 	) -> AllocateAndReturnByCodec<Result<Vec<u8>, latest::types::LedgerApiError>> {
 		latest::Bridge::<Signature, Database>::construct_cnight_generates_dust_system_tx(events)
 	}
+
+	/*
+	 * Returns the serialized `OverwriteParameters` system transaction applying the update
+	 * to the current Ledger Parameters
+	 */
+	// Current Enabled Version
+	fn construct_update_parameters_system_tx(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		update: PassFatPointerAndDecode<LedgerParametersUpdate>,
+	) -> AllocateAndReturnByCodec<Result<Vec<u8>, latest::types::LedgerApiError>> {
+		latest::Bridge::<Signature, Database>::construct_update_parameters_system_tx(
+			state_key, update,
+		)
+	}
 }
 
 #[runtime_interface]
@@ -274,26 +461,18 @@ pub trait LedgerBridgeHf {
 	}
 
 	fn drop_default_storage(&mut self) {
-		use ledger_storage::{
-			db::ParityDb,
-			storage::{try_get_default_storage, unsafe_drop_default_storage},
-		};
-		unsafe_drop_default_storage::<ParityDb>();
-
-		match try_get_default_storage::<ParityDb>() {
-			Some(_) => {
-				log::error!(
-					target: hard_fork_test::LOG_TARGET,
-					"Pre Hard-fork Default Storage wasn't successfully dropped, still exists",
-				);
-			},
-			None => {
-				log::info!(
-					target: hard_fork_test::LOG_TARGET,
-					"Pre Hard-fork Default Storage was successfully dropped",
-				);
-			},
-		};
+		crate::hard_fork::drop_latest_default_storage()
+	}
+
+	/*
+	 * Translates the pre hard-fork ledger state at `state_key` into this ledger version,
+	 * switching the default storage. Returns the state key of the translated state
+	 */
+	fn translate_ledger_state(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<Result<Vec<u8>, hard_fork_test::types::LedgerApiError>> {
+		crate::hard_fork::translate_ledger_state(*self, state_key)
 	}
 
 	fn flush_storage(&mut self) {
@@ -343,6 +522,61 @@ pub trait LedgerBridgeHf {
 		)
 	}
 
+	// Hard-fork Version
+	#[version(2)]
+	fn apply_transaction(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		tx: PassFatPointerAndRead<&[u8]>,
+		block_context: PassFatPointerAndDecode<BlockContext>,
+		_runtime_version: u32,
+		pre_verified: bool,
+	) -> AllocateAndReturnByCodec<
+		Result<
+			(TransactionAppliedStateRoot, TransactionOutcome, Vec<ContractStateChange>),
+			hard_fork_test::types::LedgerApiError,
+		>,
+	> {
+		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::apply_transaction_with_outcome(
+			*self,
+			state_key,
+			tx,
+			block_context,
+			true,
+			pre_verified,
+		)
+	}
+
+	// Hard-fork Version
+	fn pre_verify_transactions(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		txs: PassFatPointerAndDecode<Vec<Vec<u8>>>,
+		block_context: PassFatPointerAndDecode<BlockContext>,
+	) -> AllocateAndReturnByCodec<Result<Vec<bool>, hard_fork_test::types::LedgerApiError>> {
+		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::pre_verify_transactions(
+			state_key,
+			txs,
+			block_context,
+		)
+	}
+
+	// Hard-fork Version
+	fn simulate_transaction(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		tx: PassFatPointerAndRead<&[u8]>,
+		block_context: PassFatPointerAndDecode<BlockContext>,
+	) -> AllocateAndReturnByCodec<
+		Result<TransactionSimulationResult, hard_fork_test::types::LedgerApiError>,
+	> {
+		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::simulate_transaction(
+			state_key,
+			tx,
+			block_context,
+		)
+	}
+
 	fn apply_system_transaction(
 		&mut self,
 		state_key: PassFatPointerAndRead<&[u8]>,
@@ -400,6 +634,35 @@ pub trait LedgerBridgeHf {
 		)
 	}
 
+	// Hard-fork Version
+	fn get_transaction_pool_info(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		tx: PassFatPointerAndRead<&[u8]>,
+		block_context: PassFatPointerAndDecode<BlockContext>,
+		runtime_version: u32,
+	) -> AllocateAndReturnByCodec<Result<TransactionPoolInfo, hard_fork_test::types::LedgerApiError>>
+	{
+		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::get_transaction_pool_info(
+			*self,
+			state_key,
+			tx,
+			block_context,
+			runtime_version,
+		)
+	}
+
+	// Hard-fork Version
+	fn get_transaction_weight_components(
+		transaction_bytes: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<
+		Result<TransactionWeightComponents, hard_fork_test::types::LedgerApiError>,
+	> {
+		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::get_transaction_weight_components(
+			transaction_bytes,
+		)
+	}
+
 	// Hard-fork Version
 	fn get_zswap_chain_state(
 		&mut self,
@@ -432,6 +695,17 @@ pub trait LedgerBridgeHf {
 		)
 	}
 
+	// Hard-fork Version
+	fn construct_update_parameters_system_tx(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		update: PassFatPointerAndDecode<LedgerParametersUpdate>,
+	) -> AllocateAndReturnByCodec<Result<Vec<u8>, hard_fork_test::types::LedgerApiError>> {
+		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::construct_update_parameters_system_tx(
+			state_key, update,
+		)
+	}
+
 	// Hard-fork Version
 	fn mint_coins(
 		&mut self,
@@ -468,6 +742,55 @@ pub trait LedgerBridgeHf {
 		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::get_ledger_parameters(state_key)
 	}
 
+	// Hard-fork Version
+	fn get_unshielded_utxos(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		address: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<Result<Vec<UtxoInfo>, hard_fork_test::types::LedgerApiError>> {
+		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::get_unshielded_utxos(state_key, address)
+	}
+
+	// Hard-fork Version
+	fn get_zswap_commitment_proof(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		commitment: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<
+		Result<Option<ZswapCommitmentProof>, hard_fork_test::types::LedgerApiError>,
+	> {
+		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::get_zswap_commitment_proof(
+			state_key, commitment,
+		)
+	}
+
+	// Hard-fork Version
+	fn get_contract_state_proof(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		contract_address: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<
+		Result<Option<ContractStateProof>, hard_fork_test::types::LedgerApiError>,
+	> {
+		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::get_contract_state_proof(
+			state_key,
+			contract_address,
+		)
+	}
+
+	// Hard-fork Version
+	fn get_dust_generation_info(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+		nonce: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<
+		Result<Option<DustGenerationDetails>, hard_fork_test::types::LedgerApiError>,
+	> {
+		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::get_dust_generation_info(
+			state_key, nonce,
+		)
+	}
+
 	// Hard-fork Version
 	fn get_transaction_cost(
 		&mut self,
@@ -491,4 +814,22 @@ pub trait LedgerBridgeHf {
 	) -> AllocateAndReturnByCodec<Result<Vec<u8>, hard_fork_test::types::LedgerApiError>> {
 		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::get_zswap_state_root(state_key)
 	}
+
+	// Hard-fork Version
+	fn get_ledger_state_summary(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<Result<LedgerStateSummary, hard_fork_test::types::LedgerApiError>>
+	{
+		hard_fork_test::Bridge::<SignatureHF, DatabaseHF>::get_ledger_state_summary(state_key)
+	}
+
+	// Hard-fork Version
+	fn get_previous_ledger_state_summary(
+		&mut self,
+		state_key: PassFatPointerAndRead<&[u8]>,
+	) -> AllocateAndReturnByCodec<Result<LedgerStateSummary, hard_fork_test::types::LedgerApiError>>
+	{
+		crate::hard_fork::get_previous_ledger_state_summary(state_key)
+	}
 }
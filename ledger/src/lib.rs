@@ -22,6 +22,10 @@
 #[cfg(feature = "std")]
 pub mod json;
 
+#[cfg(feature = "std")]
+mod hard_fork;
+#[cfg(feature = "std")]
+pub mod rejections;
 #[cfg(feature = "std")]
 mod storage;
 #[cfg(feature = "std")]
@@ -0,0 +1,70 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Host-side record of why transactions were rejected by the ledger.
+//!
+//! `InvalidTransaction::Custom(u8)` only carries an error code, so the details
+//! of each rejection (failing segment, offending contract address, nullifier...)
+//! are kept here, keyed by the Midnight transaction hash, for the node to serve over RPC.
+
+use crate::common::types::{Hash, SegmentId};
+use lazy_static::lazy_static;
+use moka::sync::Cache;
+
+const MAX_REJECTIONS: u64 = 1000;
+
+lazy_static! {
+	static ref REJECTIONS: Cache<Hash, TransactionRejection> = Cache::new(MAX_REJECTIONS);
+}
+
+/// Details of why the ledger rejected a transaction
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TransactionRejection {
+	/// Error code, as returned in `InvalidTransaction::Custom`
+	pub error_code: u8,
+	/// Human readable `LedgerApiError`
+	pub error: String,
+	/// Segment which failed, if known
+	pub segment_id: Option<SegmentId>,
+	/// Tagged serialized address of the offending contract, if any
+	pub contract_address: Option<Vec<u8>>,
+	/// Offending zswap nullifier, if any
+	pub nullifier: Option<Vec<u8>>,
+	/// Offending zswap coin commitment, if any
+	pub commitment: Option<Vec<u8>>,
+	/// Debug representation of the error returned by the ledger
+	pub details: String,
+}
+
+impl TransactionRejection {
+	pub(crate) fn new<E: core::fmt::Display + Clone + Into<u8>>(
+		error: &E,
+		details: String,
+	) -> Self {
+		Self {
+			error_code: error.clone().into(),
+			error: error.to_string(),
+			details,
+			..Default::default()
+		}
+	}
+}
+
+pub(crate) fn record_rejection(tx_hash: Hash, rejection: TransactionRejection) {
+	REJECTIONS.insert(tx_hash, rejection);
+}
+
+/// Returns the most recent rejection recorded for the given Midnight transaction hash
+pub fn last_rejection(tx_hash: &Hash) -> Option<TransactionRejection> {
+	REJECTIONS.get(tx_hash)
+}
@@ -16,6 +16,7 @@ use {
 	ledger_storage::db::DB,
 	midnight_serialize::{self, Tagged},
 	mn_ledger::structure::{ProofMarker, SignatureKind, Transaction},
+	parity_scale_codec::{Decode, Encode},
 	transient_crypto::commitment::PureGeneratorPedersen,
 };
 
@@ -61,6 +62,15 @@ pub fn init_storage_paritydb(
 	cache_size: usize,
 ) -> Vec<u8> {
 	use base_crypto::signatures::Signature;
+	use ledger_storage::db::ParityDb;
+
+	open_storage_paritydb(dir, cache_size);
+	alloc_with_initial_state::<Signature, ParityDb>(genesis_state)
+}
+
+/// Sets the ParityDB at `dir` as the default ledger storage, without allocating any state
+#[cfg(feature = "std")]
+pub fn open_storage_paritydb(dir: &std::path::Path, cache_size: usize) {
 	use ledger_storage::{Storage, db::ParityDb, storage::set_default_storage};
 
 	let res = set_default_storage(|| {
@@ -73,8 +83,268 @@ pub fn init_storage_paritydb(
 	if res.is_err() {
 		log::warn!("Warning: Failed to set default storage: {res:?}");
 	}
+}
 
-	alloc_with_initial_state::<Signature, ParityDb>(genesis_state)
+/// Default maximum number of cached transaction validations
+pub const DEFAULT_TX_VALIDATION_CACHE_SIZE: u64 = 1000;
+
+#[cfg(feature = "std")]
+static TX_VALIDATION_CACHE_SIZE: std::sync::atomic::AtomicU64 =
+	std::sync::atomic::AtomicU64::new(DEFAULT_TX_VALIDATION_CACHE_SIZE);
+
+/// Sets the maximum number of cached transaction validations, per ledger version.
+/// Has to be called before the first transaction is validated to take effect.
+#[cfg(feature = "std")]
+pub fn set_tx_validation_cache_size(size: u64) {
+	TX_VALIDATION_CACHE_SIZE.store(size, std::sync::atomic::Ordering::Relaxed);
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn tx_validation_cache_size() -> u64 {
+	TX_VALIDATION_CACHE_SIZE.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Serializes the ledger state reachable from `state_key` in the default ParityDB storage
+#[cfg(feature = "std")]
+pub fn export_ledger_state(
+	state_key: &[u8],
+) -> Result<Vec<u8>, super::latest::types::LedgerApiError> {
+	use base_crypto::signatures::Signature;
+	use ledger_storage::db::ParityDb;
+
+	super::latest::Bridge::<Signature, ParityDb>::export_ledger_state(state_key)
+}
+
+/// Restores a ledger state exported by `export_ledger_state` into the default ParityDB
+/// storage, returning its state key
+#[cfg(feature = "std")]
+pub fn import_ledger_state(
+	ledger_state: &[u8],
+) -> Result<Vec<u8>, super::latest::types::LedgerApiError> {
+	use base_crypto::signatures::Signature;
+	use ledger_storage::db::ParityDb;
+
+	super::latest::Bridge::<Signature, ParityDb>::import_ledger_state(ledger_state)
+}
+
+/// Writes the serialization of `export_ledger_state` to `writer` as it goes
+#[cfg(feature = "std")]
+pub fn write_ledger_state(
+	state_key: &[u8],
+	writer: impl std::io::Write,
+) -> Result<(), super::latest::types::LedgerApiError> {
+	use base_crypto::signatures::Signature;
+	use ledger_storage::db::ParityDb;
+
+	super::latest::Bridge::<Signature, ParityDb>::write_ledger_state(state_key, writer)
+}
+
+/// Reads a ledger state written by `write_ledger_state` from `reader` and restores it into
+/// the default ParityDB storage if it has the key `state_key`. Returns the key of the ledger
+/// state read.
+#[cfg(feature = "std")]
+pub fn read_ledger_state(
+	reader: impl std::io::Read,
+	state_key: &[u8],
+) -> Result<Vec<u8>, super::latest::types::LedgerApiError> {
+	use base_crypto::signatures::Signature;
+	use ledger_storage::db::ParityDb;
+
+	super::latest::Bridge::<Signature, ParityDb>::read_ledger_state(reader, state_key)
+}
+
+/// Ledger version a ledger state is read and written with
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum LedgerVersion {
+	Latest,
+	HardForkTest,
+}
+
+#[cfg(feature = "std")]
+impl LedgerVersion {
+	/// The ledger version reporting `version` from `get_version`, i.e. the runtime's
+	/// `MidnightRuntimeApi::get_ledger_version`
+	pub fn from_version(version: &[u8]) -> Option<Self> {
+		use base_crypto::signatures::Signature;
+		use ledger_storage::db::ParityDb;
+
+		if version == super::latest::Bridge::<Signature, ParityDb>::get_version() {
+			Some(Self::Latest)
+		} else if version == hard_fork_test_version() {
+			Some(Self::HardForkTest)
+		} else {
+			None
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+fn hard_fork_test_version() -> Vec<u8> {
+	use base_crypto_hf::signatures::Signature;
+	use ledger_storage_hf::db::ParityDb;
+
+	super::hard_fork_test::Bridge::<Signature, ParityDb>::get_version()
+}
+
+/// Switches the default ledger storage from the `latest` ledger version to the
+/// `hard_fork_test` one, reopening the ParityDB at `dir`
+#[cfg(feature = "std")]
+pub fn switch_storage_paritydb_to_hard_fork(dir: &std::path::Path, cache_size: usize) {
+	use ledger_storage_hf::{Storage, db::ParityDb, storage::set_default_storage};
+
+	super::hard_fork::drop_latest_default_storage();
+	let res = set_default_storage(|| {
+		let db = ParityDb::<sha2::Sha256>::open(dir);
+		Storage::new(cache_size, db)
+	});
+	if res.is_err() {
+		log::warn!("Warning: Failed to set default storage: {res:?}");
+	}
+}
+
+/// `write_ledger_state` for the `hard_fork_test` ledger version, once the default storage
+/// is switched with `switch_storage_paritydb_to_hard_fork`
+#[cfg(feature = "std")]
+pub fn write_ledger_state_hf(
+	state_key: &[u8],
+	writer: impl std::io::Write,
+) -> Result<(), super::hard_fork_test::types::LedgerApiError> {
+	use base_crypto_hf::signatures::Signature;
+	use ledger_storage_hf::db::ParityDb;
+
+	super::hard_fork_test::Bridge::<Signature, ParityDb>::write_ledger_state(state_key, writer)
+}
+
+/// `read_ledger_state` for the `hard_fork_test` ledger version, once the default storage
+/// is switched with `switch_storage_paritydb_to_hard_fork`
+#[cfg(feature = "std")]
+pub fn read_ledger_state_hf(
+	reader: impl std::io::Read,
+	state_key: &[u8],
+) -> Result<Vec<u8>, super::hard_fork_test::types::LedgerApiError> {
+	use base_crypto_hf::signatures::Signature;
+	use ledger_storage_hf::db::ParityDb;
+
+	super::hard_fork_test::Bridge::<Signature, ParityDb>::read_ledger_state(reader, state_key)
+}
+
+/// Walks the ledger state at `state_key` in the default ParityDB storage, reporting its
+/// missing or undecodable nodes
+#[cfg(feature = "std")]
+pub fn check_ledger_state(
+	state_key: &[u8],
+) -> Result<crate::common::types::LedgerStateCheck, super::latest::types::LedgerApiError> {
+	use base_crypto::signatures::Signature;
+	use ledger_storage::db::ParityDb;
+
+	super::latest::Bridge::<Signature, ParityDb>::check_ledger_state(state_key)
+}
+
+/// Whether the default ParityDB storage is still the `latest` ledger version one, rather than
+/// the `hard_fork_test` one set by `switch_storage_paritydb_to_hard_fork`
+#[cfg(feature = "std")]
+fn latest_storage_is_set() -> bool {
+	ledger_storage::storage::try_get_default_storage::<ledger_storage::db::ParityDb>().is_some()
+}
+
+/// Releases the persisted root of the ledger state at `state_key` in the default ParityDB
+/// storage of either ledger version. Its nodes are reclaimed by the next `gc_ledger_storage`.
+///
+/// Once the storage is switched to the `hard_fork_test` version, the roots persisted by the
+/// `latest` version before the switch are released through their translated key.
+#[cfg(feature = "std")]
+pub fn unpersist_ledger_state(state_key: &[u8]) -> Result<(), String> {
+	use super::{hard_fork_test, latest};
+
+	type Latest = latest::Bridge<base_crypto::signatures::Signature, ledger_storage::db::ParityDb>;
+	type HardFork = hard_fork_test::Bridge<
+		base_crypto_hf::signatures::Signature,
+		ledger_storage_hf::db::ParityDb,
+	>;
+
+	if latest_storage_is_set() {
+		return Latest::unpersist_ledger_state(state_key).map_err(|e| e.to_string());
+	}
+	if HardFork::unpersist_ledger_state(state_key).is_ok() {
+		return Ok(());
+	}
+	let previous_state_key =
+		Latest::export_untagged_ledger_state_key(state_key).map_err(|e| e.to_string())?;
+	let state_key = HardFork::import_retagged_ledger_state_key(&previous_state_key)
+		.map_err(|e| e.to_string())?;
+	HardFork::unpersist_ledger_state(&state_key).map_err(|e| e.to_string())
+}
+
+/// Ledger state roots persisted while executing blocks, by parent block hash
+#[cfg(feature = "std")]
+pub type PersistedRoots = std::collections::BTreeMap<Vec<u8>, Vec<Vec<u8>>>;
+
+#[cfg(feature = "std")]
+type PersistedRootsSink = Box<dyn Fn(Vec<u8>) + Send + Sync>;
+
+#[cfg(feature = "std")]
+lazy_static::lazy_static! {
+	/// Ledger state roots persisted while executing blocks, and where to write them down once
+	/// they are recorded
+	static ref PERSISTED_ROOTS: std::sync::Mutex<Option<(PersistedRoots, PersistedRootsSink)>> =
+		Default::default();
+}
+
+/// Starts recording the ledger state roots persisted by block execution, to be released
+/// with `unpersist_ledger_state` once the block is pruned. Unset on archive nodes, which
+/// never take the recorded roots.
+///
+/// The recording starts from `roots`, as written down by `sink`: it is given the SCALE
+/// encoded `PersistedRoots` after every change, so that a restart doesn't lose the roots of
+/// the blocks which aren't finalized yet.
+#[cfg(feature = "std")]
+pub fn record_persisted_roots(
+	roots: PersistedRoots,
+	sink: impl Fn(Vec<u8>) + Send + Sync + 'static,
+) {
+	*PERSISTED_ROOTS.lock().unwrap_or_else(|e| e.into_inner()) = Some((roots, Box::new(sink)));
+}
+
+/// Records `state_root` as persisted by the execution of a child block of
+/// `parent_block_hash`
+#[cfg(feature = "std")]
+pub(crate) fn record_persisted_root(parent_block_hash: &[u8], state_root: &[u8]) {
+	let mut recorded = PERSISTED_ROOTS.lock().unwrap_or_else(|e| e.into_inner());
+	if let Some((roots, sink)) = recorded.as_mut() {
+		roots.entry(parent_block_hash.to_vec()).or_default().push(state_root.to_vec());
+		sink(roots.encode());
+	}
+}
+
+/// Takes the ledger state roots persisted by the execution of the child blocks of
+/// `parent_block_hash`, once per call to `persist`
+#[cfg(feature = "std")]
+pub fn take_persisted_roots(parent_block_hash: &[u8]) -> Vec<Vec<u8>> {
+	let mut recorded = PERSISTED_ROOTS.lock().unwrap_or_else(|e| e.into_inner());
+	let Some((roots, sink)) = recorded.as_mut() else {
+		return Vec::new();
+	};
+	match roots.remove(parent_block_hash) {
+		Some(taken) => {
+			sink(roots.encode());
+			taken
+		},
+		None => Vec::new(),
+	}
+}
+
+/// Garbage collects the default ParityDB storage of either ledger version
+#[cfg(feature = "std")]
+pub fn gc_ledger_storage() {
+	if latest_storage_is_set() {
+		super::latest::Bridge::<base_crypto::signatures::Signature, ledger_storage::db::ParityDb>::gc_storage()
+	} else {
+		super::hard_fork_test::Bridge::<
+			base_crypto_hf::signatures::Signature,
+			ledger_storage_hf::db::ParityDb,
+		>::gc_storage()
+	}
 }
 
 #[cfg(test)]
@@ -91,6 +361,34 @@ mod tests {
 	};
 	use std::path::PathBuf;
 
+	#[test]
+	fn persisted_roots_are_written_down() {
+		use super::{
+			PersistedRoots, record_persisted_root, record_persisted_roots, take_persisted_roots,
+		};
+		use parity_scale_codec::Decode;
+		use std::sync::{Arc, Mutex};
+
+		let parent = b"persisted_roots_are_written_down".to_vec();
+		let written = Arc::new(Mutex::new(Vec::new()));
+		let sink = written.clone();
+		record_persisted_roots([(parent.clone(), vec![vec![1]])].into(), move |encoded| {
+			*sink.lock().unwrap() = encoded
+		});
+		let written_roots = || {
+			PersistedRoots::decode(&mut &written.lock().unwrap()[..])
+				.unwrap()
+				.remove(&parent)
+		};
+
+		record_persisted_root(&parent, &[2]);
+		assert_eq!(written_roots(), Some(vec![vec![1], vec![2]]));
+
+		assert_eq!(take_persisted_roots(&parent), vec![vec![1], vec![2]]);
+		assert_eq!(written_roots(), None);
+		assert!(take_persisted_roots(&parent).is_empty());
+	}
+
 	#[test]
 	fn set_and_drop_default_storage() {
 		let mut db_path: PathBuf = std::env::temp_dir();
@@ -32,6 +32,7 @@ use midnight_serialize_local::{self as serialize, Tagged};
 use mn_ledger_local::{
 	semantics::{TransactionContext, TransactionResult},
 	structure::{LedgerParameters, LedgerState, SignatureKind},
+	verify::WellFormedStrictness,
 };
 use onchain_runtime_local::context::BlockContext as LedgerBlockContext;
 use std::{borrow::Borrow, collections::HashMap};
@@ -44,6 +45,7 @@ use super::{
 	types::{DeserializationError, LedgerApiError, SerializationError, TransactionError},
 };
 
+use super::super::conversions::{RejectedTransaction, reject_invalid, reject_malformed};
 use crate::common::types::BlockContext;
 
 #[derive(Debug)]
@@ -134,17 +136,20 @@ impl<D: DB> Ledger<D> {
 		api: &Api,
 		tx: &Transaction<S, D>,
 		ctx: &TransactionContext<D>,
-	) -> Result<(Sp<Self, D>, AppliedStage<D>), LedgerApiError> {
+		pre_verified: bool,
+	) -> Result<(Sp<Self, D>, AppliedStage<D>), RejectedTransaction> {
 		let tx_cost =
 			tx.0.cost(&sp.state.parameters, true)
 				.map_err(|_| LedgerApiError::FeeCalculationError)?;
+		let mut strictness = WellFormedStrictness::default();
+		if pre_verified {
+			// Already verified by `Bridge::pre_verify_transactions`
+			strictness.verify_native_proofs = false;
+			strictness.verify_signatures = false;
+		}
 		let valid_tx =
-			tx.0.well_formed(
-				&ctx.ref_state,
-				mn_ledger_local::verify::WellFormedStrictness::default(),
-				ctx.block_context.tblock,
-			)
-			.map_err(|e| LedgerApiError::Transaction(TransactionError::Malformed(e.into())))?;
+			tx.0.well_formed(&ctx.ref_state, strictness, ctx.block_context.tblock)
+				.map_err(reject_malformed)?;
 		let (next_state, result) = sp.state.apply(&valid_tx, ctx);
 		let next_block_fullness = tx_cost + sp.block_fullness.clone().into();
 		let new_sp = default_storage::<D>()
@@ -164,7 +169,8 @@ impl<D: DB> Ledger<D> {
 			},
 			TransactionResult::Failure(reason) => {
 				log::warn!(target: LOG_TARGET, "Error applying Transaction: {reason:?}");
-				Err(LedgerApiError::Transaction(TransactionError::Invalid(reason.into())))
+				// The ledger doesn't report which segment of the guaranteed part failed
+				Err(reject_invalid(None, reason))
 			},
 		}
 	}
@@ -188,10 +194,26 @@ impl<D: DB> Ledger<D> {
 		&self,
 		tx: &Transaction<S, D>,
 		block_context: &BlockContext,
-	) -> Result<(), LedgerApiError> {
+	) -> Result<(), RejectedTransaction> {
 		tx.validate(self, block_context)
 	}
 
+	/// Checks the transaction is well-formed against this state, including its proofs and
+	/// signatures, without logging it as validated
+	pub(crate) fn pre_verify_transaction<S: SignatureKind<D>>(
+		&self,
+		tx: &Transaction<S, D>,
+		block_context: &BlockContext,
+	) -> Result<(), LedgerApiError> {
+		tx.0.well_formed(
+			&self.state,
+			WellFormedStrictness::default(),
+			Timestamp::from_secs(block_context.tblock),
+		)
+		.map(|_| ())
+		.map_err(|e| reject_malformed(e).into())
+	}
+
 	pub(crate) fn apply_system_tx(
 		sp: Sp<Self, D>,
 		tx: &SystemTransaction,
@@ -281,9 +303,14 @@ mod tests {
 		let tx = api.tagged_deserialize::<Transaction<Signature, DefaultDB>>(bytes);
 		assert!(tx.is_ok(), "Can't deserialize transaction: {}", tx.unwrap_err());
 		let tx_ctx = ledger.get_transaction_context(block_context.clone());
-		let (mut new_ledger_state, _applied_stage) =
-			Ledger::<DefaultDB>::apply_transaction(ledger.clone(), api, &tx.unwrap(), &tx_ctx)
-				.unwrap_or_else(|err| panic!("Can't apply transaction: {err}"));
+		let (mut new_ledger_state, _applied_stage) = Ledger::<DefaultDB>::apply_transaction(
+			ledger.clone(),
+			api,
+			&tx.unwrap(),
+			&tx_ctx,
+			false,
+		)
+		.unwrap_or_else(|err| panic!("Can't apply transaction: {err}"));
 
 		new_ledger_state =
 			Ledger::<DefaultDB>::post_block_update(new_ledger_state, block_context.clone())
@@ -22,7 +22,11 @@ pub use super::types::{self, DeserializationError, LedgerApiError, Serialization
 
 use base_crypto_local::hash::HashOutput;
 use coin_structure_local::coin::UserAddress as UserAddressLedger;
-use ledger_storage_local::{WellBehavedHasher, arena::TypedArenaKey, db::DB};
+use ledger_storage_local::{
+	WellBehavedHasher,
+	arena::{ArenaKey, TypedArenaKey},
+	db::DB,
+};
 use midnight_serialize_local::{Deserializable, Tagged};
 
 pub mod ledger;
@@ -34,6 +38,7 @@ pub(crate) type ContractState<D> = onchain_runtime_local::state::ContractState<D
 pub(crate) type ZswapState<D> = zswap_local::ledger::State<D>;
 pub(crate) type ContractAddress = coin_structure_local::contract::ContractAddress;
 pub(crate) type DustPublicKey = mn_ledger_local::dust::DustPublicKey;
+pub(crate) type MerklePath<T> = transient_crypto_local::merkle_tree::MerklePath<T>;
 pub(crate) type UserAddress = coin_structure_local::coin::UserAddress;
 pub(crate) type OutputInstructionUnshielded =
 	mn_ledger_local::structure::OutputInstructionUnshielded;
@@ -71,6 +76,12 @@ impl DeserializableError for DustPublicKey {
 	}
 }
 
+impl SerializableError for DustPublicKey {
+	fn error() -> SerializationError {
+		SerializationError::DustPublicKey
+	}
+}
+
 impl<T, H: WellBehavedHasher> SerializableError for TypedArenaKey<T, H> {
 	fn error() -> SerializationError {
 		SerializationError::TypedArenaKey
@@ -101,6 +112,18 @@ impl<D: DB> SerializableError for ZswapState<D> {
 	}
 }
 
+impl<T> SerializableError for MerklePath<T> {
+	fn error() -> SerializationError {
+		SerializationError::MerklePath
+	}
+}
+
+impl<H: WellBehavedHasher> SerializableError for ArenaKey<H> {
+	fn error() -> SerializationError {
+		SerializationError::ArenaKey
+	}
+}
+
 impl SerializableError for SystemTransaction {
 	fn error() -> SerializationError {
 		SerializationError::SystemTransaction
@@ -154,6 +177,23 @@ impl Api {
 		})
 	}
 
+	/// `tagged_deserialize` reading from `reader` as needed rather than from a buffer
+	pub fn tagged_deserialize_from<T>(
+		&self,
+		reader: impl std::io::Read,
+	) -> Result<T, LedgerApiError>
+	where
+		T: Deserializable + DeserializableError + Tagged + 'static,
+	{
+		let kind = core::any::type_name::<T>();
+		let error = LedgerApiError::Deserialization(<T as DeserializableError>::error());
+
+		midnight_serialize_local::tagged_deserialize(reader).map_err(|e| {
+			log::error!(target: LOG_TARGET, "Error deserializing: {kind:?}: {e:?}");
+			error
+		})
+	}
+
 	pub fn deserialize<T>(&self, mut bytes: &[u8]) -> Result<T, LedgerApiError>
 	where
 		T: Deserializable + DeserializableError + 'static,
@@ -183,6 +223,23 @@ impl Api {
 		Ok(bytes)
 	}
 
+	/// `tagged_serialize` writing to `writer` as it goes rather than to a buffer
+	pub fn tagged_serialize_into<T>(
+		&self,
+		value: &T,
+		mut writer: impl std::io::Write,
+	) -> Result<(), LedgerApiError>
+	where
+		T: midnight_serialize_local::Serializable + SerializableError + Tagged + 'static,
+	{
+		let error = LedgerApiError::Serialization(<T as SerializableError>::error());
+
+		midnight_serialize_local::tagged_serialize(value, &mut writer).map_err(|e| {
+			log::error!(target: LOG_TARGET, "Error serializing: {error:?}: {e:?}");
+			error
+		})
+	}
+
 	pub fn serialize<T>(&self, value: &T) -> Result<Vec<u8>, LedgerApiError>
 	where
 		T: midnight_serialize_local::Serializable + SerializableError + 'static,
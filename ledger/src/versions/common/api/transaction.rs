@@ -34,6 +34,7 @@ use mn_ledger_local::{
 };
 use std::borrow::Borrow;
 
+use super::super::conversions::{RejectedTransaction, reject_malformed};
 use super::{
 	ContractAddress, DeserializableError, LOG_TARGET, Ledger, LedgerParameters, SerializableError,
 	TransactionIdentifier, TransactionInvalid,
@@ -223,7 +224,7 @@ impl<S: SignatureKind<D>, D: DB> Transaction<S, D> {
 		&self,
 		ledger: &Ledger<D>,
 		block_context: &BlockContext,
-	) -> Result<(), LedgerApiError> {
+	) -> Result<(), RejectedTransaction> {
 		self.0
 			.well_formed(
 				<Ledger<D> as Borrow<LedgerState<D>>>::borrow(ledger),
@@ -232,7 +233,7 @@ impl<S: SignatureKind<D>, D: DB> Transaction<S, D> {
 			)
 			.map_err(|e| {
 				log::error!(target: LOG_TARGET, "Error validating Transaction: {e:?}");
-				LedgerApiError::Transaction(TransactionError::Malformed(e.into()))
+				reject_malformed(e)
 			})?;
 
 		log::info!(
@@ -311,7 +312,6 @@ impl<S: SignatureKind<D>, D: DB> Transaction<S, D> {
 		}
 	}
 
-	#[allow(dead_code)]
 	pub(crate) fn fee(&self, params: &LedgerParameters) -> Result<u128, LedgerApiError> {
 		self.0.fees(params, false).map_err(|e| {
 			log::error!(target: LOG_TARGET, "Error getting the transaction fee: {e:?}");
@@ -321,6 +321,33 @@ impl<S: SignatureKind<D>, D: DB> Transaction<S, D> {
 		})
 	}
 
+	/// Returns the number of DUST spends in the transaction and the total DUST they pay in fees
+	pub(crate) fn dust_spends(&self) -> (u32, u128) {
+		let mut count = 0u32;
+		let mut total = 0u128;
+		if let Tx::Standard(tx) = &self.0 {
+			for intent in tx.intents.values() {
+				if let Some(dust_actions) = &intent.dust_actions {
+					for spend in dust_actions.spends.iter() {
+						count = count.saturating_add(1);
+						total = total.saturating_add(spend.v_fee);
+					}
+				}
+			}
+		}
+		(count, total)
+	}
+
+	/// Returns the number of guaranteed and fallible shielded outputs in the transaction
+	pub(crate) fn shielded_outputs(&self) -> (u32, u32) {
+		match &self.0 {
+			Tx::Standard(tx) => {
+				(tx.guaranteed_outputs().count() as u32, tx.fallible_outputs().count() as u32)
+			},
+			Tx::ClaimRewards(_) => (0, 0),
+		}
+	}
+
 	pub(crate) fn unshielded_utxos(&self) -> UnshieldedUtxos {
 		let mut outputs: HashMap<u16, Vec<UtxoInfo>> = HashMap::new();
 		let mut inputs: HashMap<u16, Vec<UtxoInfo>> = HashMap::new();
@@ -463,6 +490,48 @@ mod tests {
 		assert!(result.is_ok(), "Transaction is invalid: {}", result.unwrap_err());
 	}
 
+	#[test]
+	fn should_record_rejection_details() {
+		if CRATE_NAME != crate::latest::CRATE_NAME {
+			println!("This test should only be run with ledger latest");
+			return;
+		}
+		let api = api::new();
+		let (tx, block_context) =
+			prepare_transaction(&api, midnight_node_res::undeployed::transactions::STORE_TX);
+		let ledger = prepare_ledger();
+
+		let rejected = tx
+			.validate(&ledger, &block_context)
+			.expect_err("Calling a non deployed contract should be rejected");
+		let error = rejected.record(tx.hash());
+
+		let rejection = crate::rejections::last_rejection(&tx.hash())
+			.expect("Rejection should have been recorded");
+		assert_eq!(rejection.error_code, u8::from(error));
+		assert!(rejection.contract_address.is_some());
+	}
+
+	#[test]
+	fn should_not_record_dropped_rejection_details() {
+		if CRATE_NAME != crate::latest::CRATE_NAME {
+			println!("This test should only be run with ledger latest");
+			return;
+		}
+		let api = api::new();
+		let (tx, block_context) =
+			prepare_transaction(&api, midnight_node_res::undeployed::transactions::CHECK_TX);
+		let ledger = prepare_ledger();
+
+		let error: LedgerApiError = tx
+			.validate(&ledger, &block_context)
+			.expect_err("Calling a non deployed contract should be rejected")
+			.into();
+
+		assert!(matches!(error, LedgerApiError::Transaction(TransactionError::Malformed(_))));
+		assert_eq!(crate::rejections::last_rejection(&tx.hash()), None);
+	}
+
 	#[test]
 	#[should_panic]
 	fn should_fail_to_deserialize_transaction() {
@@ -14,8 +14,16 @@
 #[cfg(all(feature = "std", feature = "test-utils"))]
 use super::super::common::BlockContext;
 use super::{
+	api::Api,
 	ledger_storage_local, mn_ledger_local,
-	types::{InvalidError, MalformedError, SystemTransactionError},
+	types::{
+		InvalidError, LedgerApiError, MalformedError, SystemTransactionError, TransactionError,
+	},
+	zswap_local,
+};
+use crate::{
+	common::types::{Hash, SegmentId},
+	rejections::{TransactionRejection, record_rejection},
 };
 
 use ledger_storage_local::db::DB;
@@ -23,6 +31,7 @@ use mn_ledger_local::error::{
 	MalformedTransaction, SystemTransactionError as LedgerSystemTransactionError,
 	TransactionInvalid,
 };
+use zswap_local::error::TransactionInvalid as ZswapTransactionInvalid;
 
 impl<D: DB> From<TransactionInvalid<D>> for InvalidError {
 	fn from(error: TransactionInvalid<D>) -> Self {
@@ -99,6 +108,93 @@ impl<D: DB> From<MalformedTransaction<D>> for MalformedError {
 	}
 }
 
+/// A transaction rejected by the ledger, together with the details of the rejection.
+///
+/// The details are only recorded, with `record`, on behalf of the node: when validating a
+/// transaction for the pool and when applying it in a block. Simulations and pre-verifications
+/// drop them, converting into the `LedgerApiError`.
+#[derive(Debug)]
+pub(crate) struct RejectedTransaction {
+	pub(crate) error: LedgerApiError,
+	rejection: Option<TransactionRejection>,
+}
+
+impl RejectedTransaction {
+	/// Records the details of the rejection, returning the `LedgerApiError` it maps to
+	pub(crate) fn record(self, tx_hash: Hash) -> LedgerApiError {
+		if let Some(rejection) = self.rejection {
+			record_rejection(tx_hash, rejection);
+		}
+		self.error
+	}
+}
+
+impl From<LedgerApiError> for RejectedTransaction {
+	fn from(error: LedgerApiError) -> Self {
+		Self { error, rejection: None }
+	}
+}
+
+impl From<RejectedTransaction> for LedgerApiError {
+	fn from(rejected: RejectedTransaction) -> Self {
+		rejected.error
+	}
+}
+
+impl core::fmt::Display for RejectedTransaction {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		self.error.fmt(f)
+	}
+}
+
+/// Rejection of a transaction which is malformed
+pub(crate) fn reject_malformed<D: DB>(error: MalformedTransaction<D>) -> RejectedTransaction {
+	let details = format!("{error:?}");
+	let contract_address = match &error {
+		MalformedTransaction::ContractNotPresent(address) => {
+			Api::new().tagged_serialize(address).ok()
+		},
+		_ => None,
+	};
+
+	let error = LedgerApiError::Transaction(TransactionError::Malformed(error.into()));
+	let rejection =
+		TransactionRejection { contract_address, ..TransactionRejection::new(&error, details) };
+
+	RejectedTransaction { error, rejection: Some(rejection) }
+}
+
+/// Rejection of a transaction which failed to apply, in `segment_id` if the ledger reports it
+pub(crate) fn reject_invalid<D: DB>(
+	segment_id: Option<SegmentId>,
+	error: TransactionInvalid<D>,
+) -> RejectedTransaction {
+	use TransactionInvalid as Ti;
+	use ZswapTransactionInvalid as Zti;
+
+	let details = format!("{error:?}");
+	let (mut contract_address, mut nullifier, mut commitment) = (None, None, None);
+	match &error {
+		Ti::ContractAlreadyDeployed(address) | Ti::ContractNotPresent(address) => {
+			contract_address = Api::new().tagged_serialize(address).ok();
+		},
+		Ti::Zswap(Zti::NullifierAlreadyPresent(n)) => nullifier = Some(n.0.0.to_vec()),
+		Ti::Zswap(Zti::CommitmentAlreadyPresent(c)) => commitment = Some(c.0.0.to_vec()),
+		_ => {},
+	}
+
+	let error = LedgerApiError::Transaction(TransactionError::Invalid(error.into()));
+	let rejection = TransactionRejection {
+		segment_id,
+		contract_address,
+		nullifier,
+		commitment,
+		..TransactionRejection::new(&error, details)
+	};
+
+	RejectedTransaction { error, rejection: Some(rejection) }
+}
+
 #[cfg(all(feature = "std", feature = "test-utils"))]
 impl From<super::super::helpers_local::BlockContext> for BlockContext {
 	fn from(value: super::super::helpers_local::BlockContext) -> Self {
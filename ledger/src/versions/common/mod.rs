@@ -23,26 +23,32 @@ use midnight_serialize_local::Tagged;
 #[cfg(feature = "std")]
 use transient_crypto_local::commitment::PureGeneratorPedersen;
 
-use frame_support::{StorageHasher, Twox128};
+use frame_support::{Blake2_256, StorageHasher, Twox128};
 use sp_externalities::{Externalities, ExternalitiesExt};
 use sp_std::vec::Vec;
 
 pub mod types;
-use types::LedgerApiError;
+use types::{LedgerApiError, TransactionError};
 
 #[cfg(feature = "std")]
 pub mod api;
 
 #[cfg(feature = "std")]
 pub mod conversions;
+#[cfg(feature = "std")]
+use conversions::reject_invalid;
 
 #[cfg(feature = "std")]
 use {
 	api::{
 		ContractAddress, ContractState, Ledger, LedgerParameters, SystemTransaction, Transaction,
-		TransactionAppliedStage, TransactionOperation,
+		TransactionAppliedStage, TransactionInvalid, TransactionOperation,
+	},
+	base_crypto_local::{
+		cost_model::{CostDuration, FixedPoint, SyntheticCost},
+		hash::HashOutput,
+		time::{Duration, Timestamp},
 	},
-	base_crypto_local::{hash::HashOutput, time::Timestamp},
 	coin_structure_local::coin::Commitment,
 	coin_structure_local::coin::Nonce,
 	coin_structure_local::coin::UnshieldedTokenType,
@@ -63,28 +69,75 @@ use {
 		},
 	},
 	onchain_runtime_local::cost_model::CostModel,
-	std::time::Instant,
+	parity_scale_codec::Encode,
+	std::{
+		collections::{HashMap, HashSet},
+		time::Instant,
+	},
 	transient_crypto_local::proofs::Proof as BaseProof,
 	zswap_local::Offer,
 };
 
 use crate::common::types::{
-	BlockContext, ContractCallsDetails, FallibleCoinsDetails, GasCost, GuaranteedCoinsDetails,
-	Hash, Op, StorageCost, SystemTransactionAppliedStateRoot, TransactionAppliedStateRoot,
-	TransactionDetails, TransactionValidationWasCached, Tx, WrappedHash,
+	ArenaNode, ArenaPathStep, BlockContext, ContractCallsDetails, ContractStateChange,
+	ContractStateProof, DustGenerationDetails, FailedSegment, FallibleCoinsDetails,
+	FixedPointRatio, GasCost, GuaranteedCoinsDetails, Hash, LedgerParametersUpdate,
+	LedgerStateCheck, LedgerStateSummary, Op, SegmentId, SegmentResult, StorageCost,
+	SystemTransactionAppliedStateRoot, TransactionAppliedStateRoot, TransactionDetails,
+	TransactionOutcome, TransactionPoolInfo, TransactionSimulationResult, TransactionSpend,
+	TransactionValidationWasCached, TransactionWeightComponents, Tx, UtxoInfo, WrappedHash,
+	ZswapCommitmentProof,
 };
 
 #[cfg(feature = "std")]
-use {lazy_static::lazy_static, moka::sync::Cache};
+use {
+	lazy_static::lazy_static,
+	moka::{notification::RemovalCause, sync::Cache},
+	std::sync::{
+		Arc,
+		atomic::{AtomicU64, AtomicUsize, Ordering},
+	},
+};
 
 pub const LOG_TARGET: &str = "midnight::ledger_v2";
 pub const MINT_COINS_DOMAIN_SEPARATOR: &[u8; 10] = b"mint_coins";
 
 #[cfg(feature = "std")]
 lazy_static! {
-	static ref TX_VALIDATION_CACHE: Cache<Hash, Result<(), LedgerApiError>> = Cache::new(1000);
+	static ref TX_VALIDATION_CACHE: Cache<Hash, Result<(), LedgerApiError>> = Cache::builder()
+		.max_capacity(crate::storage::tx_validation_cache_size())
+		.eviction_listener(|_, _, cause: RemovalCause| {
+			if cause.was_evicted() {
+				TX_VALIDATION_CACHE_EVICTIONS.fetch_add(1, Ordering::Relaxed);
+			}
+		})
+		.build();
+}
+
+/// Evictions from `TX_VALIDATION_CACHE` not yet reported to the metrics
+#[cfg(feature = "std")]
+static TX_VALIDATION_CACHE_EVICTIONS: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(feature = "std")]
+lazy_static! {
+	/// Unshielded UTXOs of the recently queried ledger states, indexed by owner and keyed by
+	/// state key, for `Bridge::get_unshielded_utxos`
+	static ref UNSHIELDED_UTXOS_BY_OWNER: Cache<Vec<u8>, Arc<HashMap<Hash, Vec<UtxoInfo>>>> =
+		Cache::new(LEDGER_STATE_INDEX_CAPACITY);
+	/// Leaf indices of the zswap commitments of the recently queried ledger states, keyed by
+	/// state key, for `Bridge::get_zswap_commitment_proof`
+	static ref ZSWAP_COMMITMENT_INDICES: Cache<Vec<u8>, Arc<HashMap<Hash, u64>>> =
+		Cache::new(LEDGER_STATE_INDEX_CAPACITY);
+	/// Storage arena paths to the contracts of the recently queried ledger states, keyed by
+	/// state key, for `Bridge::get_contract_state_proof`
+	static ref CONTRACT_STATE_PATHS: Cache<Vec<u8>, Arc<HashMap<Vec<u8>, Vec<ArenaPathStep>>>> =
+		Cache::new(LEDGER_STATE_INDEX_CAPACITY);
 }
 
+/// The best block and a few of its ancestors
+#[cfg(feature = "std")]
+const LEDGER_STATE_INDEX_CAPACITY: u64 = 8;
+
 #[cfg(feature = "std")]
 pub struct Bridge<S: SignatureKind<D>, D: DB> {
 	_phantom: core::marker::PhantomData<(S, D)>,
@@ -150,6 +203,7 @@ where
 	) -> Result<Vec<u8>, LedgerApiError> {
 		let api = api::new();
 		let ledger = Self::get_ledger(&api, state_key)?;
+		let parent_block_hash = block_context.parent_block_hash.clone();
 
 		let ledger = Ledger::post_block_update(ledger, block_context).map_err(|e| {
 			log::error!(
@@ -163,21 +217,218 @@ where
 
 		// Only update state after no errors
 		ledger.persist();
+		crate::storage::record_persisted_root(&parent_block_hash, &state_root);
 
 		Ok(state_root)
 	}
 
+	/// Serializes the whole ledger state reachable from `state_key`, so that it can be
+	/// restored into another ledger storage with `import_ledger_state`
+	pub fn export_ledger_state(state_key: &[u8]) -> Result<Vec<u8>, LedgerApiError> {
+		let api = api::new();
+		let ledger = Self::get_ledger(&api, state_key)?;
+		api.tagged_serialize(&*ledger)
+	}
+
+	/// Allocates and persists a ledger state serialized by `export_ledger_state`,
+	/// returning its state key
+	pub fn import_ledger_state(ledger_state: &[u8]) -> Result<Vec<u8>, LedgerApiError> {
+		let api = api::new();
+		let ledger: Ledger<D> = api.tagged_deserialize(ledger_state)?;
+
+		Self::persist_imported_ledger(&api, ledger)
+	}
+
+	/// `export_ledger_state` writing the serialized ledger state to `writer` as it goes, so
+	/// that it is never held in memory as a whole
+	pub fn write_ledger_state(
+		state_key: &[u8],
+		writer: impl std::io::Write,
+	) -> Result<(), LedgerApiError> {
+		let api = api::new();
+		let ledger = Self::get_ledger(&api, state_key)?;
+		api.tagged_serialize_into(&*ledger, writer)
+	}
+
+	/// Reads a ledger state written by `write_ledger_state` from `reader`, and persists it
+	/// only if it has the key `state_key`. Returns the key of the ledger state read.
+	pub fn read_ledger_state(
+		reader: impl std::io::Read,
+		state_key: &[u8],
+	) -> Result<Vec<u8>, LedgerApiError> {
+		let api = api::new();
+		let ledger: Ledger<D> = api.tagged_deserialize_from(reader)?;
+
+		let ledger = default_storage::<D>().arena.alloc(ledger);
+		let computed = api.tagged_serialize(&ledger.hash())?;
+		if computed == state_key {
+			ledger.persist();
+			default_storage::<D>().with_backend(|backend| backend.flush_all_changes_to_db());
+		}
+		Ok(computed)
+	}
+
+	/// Returns the state key `import_ledger_state` would return for a ledger state serialized
+	/// by `export_ledger_state`, without persisting it
+	pub fn ledger_state_key(ledger_state: &[u8]) -> Result<Vec<u8>, LedgerApiError> {
+		let api = api::new();
+		let ledger: Ledger<D> = api.tagged_deserialize(ledger_state)?;
+
+		api.tagged_serialize(&default_storage::<D>().arena.alloc(ledger).hash())
+	}
+
+	/// The key of the ledger state at `state_key` without its version tag, so that the next
+	/// ledger version can read the same state with `import_retagged_ledger_state_key`
+	pub fn export_untagged_ledger_state_key(state_key: &[u8]) -> Result<Vec<u8>, LedgerApiError> {
+		let api = api::new();
+		let key: TypedArenaKey<Ledger<D>, D::Hasher> = api.tagged_deserialize(state_key)?;
+		api.serialize(&key)
+	}
+
+	/// Tags the key of a ledger state left in the storage by the previous ledger version, as
+	/// exported by `export_untagged_ledger_state_key`, with this version, and checks that its
+	/// root can be loaded.
+	///
+	/// No ledger type is converted: the state is read in place, so this only holds while both
+	/// versions share the layout of the ledger state. A hard fork changing the layout has to
+	/// convert the state into a new one instead.
+	pub fn import_retagged_ledger_state_key(
+		previous_state_key: &[u8],
+	) -> Result<Vec<u8>, LedgerApiError> {
+		let api = api::new();
+		let key: TypedArenaKey<Ledger<D>, D::Hasher> = api.deserialize(previous_state_key)?;
+		let state_key = api.tagged_serialize(&key)?;
+
+		Self::get_ledger(&api, &state_key)?;
+		Ok(state_key)
+	}
+
+	fn persist_imported_ledger(
+		api: &api::Api,
+		ledger: Ledger<D>,
+	) -> Result<Vec<u8>, LedgerApiError> {
+		let ledger = default_storage::<D>().arena.alloc(ledger);
+		ledger.persist();
+		default_storage::<D>().with_backend(|backend| backend.flush_all_changes_to_db());
+
+		api.tagged_serialize(&ledger.hash())
+	}
+
+	/// Walks every node of the ledger state at `state_key` in the storage, reporting all the
+	/// missing ones. Once none is missing, also checks that the ledger state can be loaded.
+	pub fn check_ledger_state(state_key: &[u8]) -> Result<LedgerStateCheck, LedgerApiError> {
+		let api = api::new();
+		let key: TypedArenaKey<Ledger<D>, D::Hasher> = api.tagged_deserialize(state_key)?;
+
+		let mut check = LedgerStateCheck::default();
+		let mut visited = HashSet::new();
+		let mut to_visit: Vec<ArenaKey<D::Hasher>> = vec![key.clone().into()];
+		while let Some(node_key) = to_visit.pop() {
+			if !visited.insert(node_key.clone()) {
+				continue;
+			}
+			let children = default_storage::<D>()
+				.with_backend(|backend| backend.get(&node_key).map(|node| node.children.clone()));
+			match children {
+				Some(children) => {
+					check.nodes += 1;
+					to_visit.extend(children);
+				},
+				None => check.missing.push(api.serialize(&node_key)?),
+			}
+		}
+
+		if check.missing.is_empty()
+			&& let Err(e) = default_storage::<D>().arena.get(&key)
+		{
+			check.corrupt = Some(format!("{e:?}"));
+		}
+		Ok(check)
+	}
+
+	/// Releases the persisted root of the ledger state at `state_key`. Its nodes are removed
+	/// from the storage by the next `gc_storage` unless reachable from another persisted root
+	pub fn unpersist_ledger_state(state_key: &[u8]) -> Result<(), LedgerApiError> {
+		let api = api::new();
+		let ledger = Self::get_ledger(&api, state_key)?;
+		ledger.unpersist();
+		Ok(())
+	}
+
+	/// Removes the nodes no longer reachable from any persisted root from the storage
+	pub fn gc_storage() {
+		default_storage::<D>().with_backend(|backend| {
+			backend.gc();
+			backend.flush_all_changes_to_db();
+		});
+	}
+
 	pub fn get_version() -> Vec<u8> {
 		crate::utils::find_crate_version(super::CRATE_NAME).unwrap_or(b"unknown".into())
 	}
 
 	pub fn apply_transaction(
-		mut externalities: &mut dyn Externalities,
+		externalities: &mut dyn Externalities,
 		state_key: &[u8],
 		tx_serialized: &[u8],
 		block_context: BlockContext,
 		should_skip_failed_segments: bool,
 	) -> Result<TransactionAppliedStateRoot, LedgerApiError> {
+		Self::do_apply_transaction(
+			externalities,
+			state_key,
+			tx_serialized,
+			block_context,
+			should_skip_failed_segments,
+			false,
+			false,
+		)
+		.map(|(event, _)| event)
+	}
+
+	/// Same as `apply_transaction`, also returning the `TransactionOutcome` of the transaction
+	/// and the state changes of the contracts it called.
+	///
+	/// `pre_verified` skips the native proofs and signatures verification of a transaction
+	/// reported as verified by `pre_verify_transactions`.
+	pub fn apply_transaction_with_outcome(
+		externalities: &mut dyn Externalities,
+		state_key: &[u8],
+		tx_serialized: &[u8],
+		block_context: BlockContext,
+		should_skip_failed_segments: bool,
+		pre_verified: bool,
+	) -> Result<
+		(TransactionAppliedStateRoot, TransactionOutcome, Vec<ContractStateChange>),
+		LedgerApiError,
+	> {
+		let (event, outcome) = Self::do_apply_transaction(
+			externalities,
+			state_key,
+			tx_serialized,
+			block_context,
+			should_skip_failed_segments,
+			true,
+			pre_verified,
+		)?;
+		let (outcome, contract_state_changes) = outcome.unwrap_or_default();
+		Ok((event, outcome, contract_state_changes))
+	}
+
+	/// The outcome is only computed when `with_outcome` is set: the released versions of the
+	/// `apply_transaction` host function return no outcome, and can't fail computing it
+	fn do_apply_transaction(
+		mut externalities: &mut dyn Externalities,
+		state_key: &[u8],
+		tx_serialized: &[u8],
+		block_context: BlockContext,
+		should_skip_failed_segments: bool,
+		with_outcome: bool,
+		pre_verified: bool,
+	) -> Result<
+		(TransactionAppliedStateRoot, Option<(TransactionOutcome, Vec<ContractStateChange>)>),
+		LedgerApiError,
+	> {
 		// Gather metrics for Prometheus
 		let start_tx_processing_time = Instant::now();
 		let tx_size = tx_serialized.len();
@@ -192,22 +443,36 @@ where
 		let ledger = Self::get_ledger(&api, state_key)?;
 		let initial_utxos_size = ledger.state.utxo.utxos.size();
 
+		let prev_ledger = ledger.clone();
 		let tx_ctx = ledger.get_transaction_context(block_context.clone());
-		let (ledger, applied_stage) = Ledger::apply_transaction(ledger, &api, &tx, &tx_ctx)?;
+		let (ledger, applied_stage) =
+			Ledger::apply_transaction(ledger, &api, &tx, &tx_ctx, pre_verified)
+				.map_err(|rejected| rejected.record(tx_hash))?;
 
 		let all_applied = matches!(applied_stage, TransactionAppliedStage::AllApplied);
 
 		let mut utxos = tx.unshielded_utxos();
 
-		let failed_segments =
+		let (failed_segments, segment_results) =
 			if let TransactionAppliedStage::PartialSuccess(segments) = applied_stage {
 				// Remove from `utxos` the `segments` that failed
 				utxos.remove_failed_segments(&segments);
-				Some(segments.keys().copied().collect())
+				let failed_segments = segments.keys().copied().collect();
+				(Some(failed_segments), Self::segment_results(tx_hash, segments))
 			} else {
-				None
+				(None, vec![])
 			};
 
+		let applied_weight_components =
+			Self::weight_components(&tx, failed_segments.as_deref().unwrap_or_default());
+
+		// The contract state changes never include the calls of failed segments
+		let contract_state_changes = if with_outcome {
+			Self::contract_state_changes(&api, &tx, failed_segments.clone(), &prev_ledger, &ledger)?
+		} else {
+			vec![]
+		};
+
 		let operations =
 			tx.calls_and_deploys(should_skip_failed_segments.then_some(failed_segments).flatten());
 
@@ -243,8 +508,26 @@ where
 			}
 		}
 
+		let outcome = if with_outcome {
+			let (dust_spends, fees_paid) = tx.dust_spends();
+			let (guaranteed_shielded_outputs, fallible_shielded_outputs) = tx.shielded_outputs();
+			let outcome = TransactionOutcome {
+				fees_paid,
+				segment_results,
+				dust_spends,
+				guaranteed_shielded_outputs,
+				fallible_shielded_outputs,
+				zswap_state_root: api.serialize(&ledger.get_zswap_state_root())?,
+				applied_weight_components,
+			};
+			Some((outcome, contract_state_changes))
+		} else {
+			None
+		};
+
 		// Only update state after no errors
 		ledger.persist();
+		crate::storage::record_persisted_root(&block_context.parent_block_hash, &event.state_root);
 
 		// Write Prometheus metrics
 		let maybe_metrics = externalities.extension::<LedgerMetricsExt>();
@@ -256,7 +539,242 @@ where
 			metrics.observe_txs_size(tx_size as f64, tx_type);
 		}
 
-		Ok(event)
+		Ok((event, outcome))
+	}
+
+	/// One state change per contract called by the segments of `tx` not in `failed_segments`,
+	/// in the order of their first call
+	fn contract_state_changes(
+		api: &api::Api,
+		tx: &Transaction<S, D>,
+		failed_segments: Option<Vec<u16>>,
+		prev_ledger: &Ledger<D>,
+		ledger: &Ledger<D>,
+	) -> Result<Vec<ContractStateChange>, LedgerApiError> {
+		let mut calls: Vec<(ContractAddress, Vec<Vec<u8>>)> = vec![];
+		for op in tx.calls_and_deploys(failed_segments) {
+			if let TransactionOperation::Call { address, entry_point } = op {
+				match calls.iter_mut().find(|(called, _)| *called == address) {
+					Some((_, entry_points)) => entry_points.push(entry_point),
+					None => calls.push((address, vec![entry_point])),
+				}
+			}
+		}
+
+		calls
+			.into_iter()
+			.map(|(address, entry_points)| {
+				Ok(ContractStateChange {
+					contract_address: api.tagged_serialize(&address)?,
+					entry_points,
+					old_state_hash: Self::contract_state_hash(api, prev_ledger, address)?,
+					new_state_hash: Self::contract_state_hash(api, ledger, address)?,
+				})
+			})
+			.collect()
+	}
+
+	/// Serialized arena hash of the state of the contract at `address`, empty if the contract
+	/// isn't deployed. The arena reuses the hashes of the unchanged nodes of the state.
+	fn contract_state_hash(
+		api: &api::Api,
+		ledger: &Ledger<D>,
+		address: ContractAddress,
+	) -> Result<Vec<u8>, LedgerApiError> {
+		match ledger.get_contract_state(address) {
+			Some(contract_state) => {
+				api.serialize(&default_storage::<D>().arena.alloc(contract_state).hash())
+			},
+			None => Ok(vec![]),
+		}
+	}
+
+	/// Maps the per-segment results of a partially applied transaction to their error codes,
+	/// sorted by segment id, recording the rejection of the first failed segment
+	fn segment_results(
+		tx_hash: Hash,
+		segments: HashMap<SegmentId, Result<(), TransactionInvalid<D>>>,
+	) -> Vec<SegmentResult> {
+		let mut segments: Vec<_> = segments.into_iter().collect();
+		segments.sort_by_key(|(segment_id, _)| *segment_id);
+
+		let mut first_failure = None;
+		let results = segments
+			.into_iter()
+			.map(|(segment_id, result)| SegmentResult {
+				segment_id,
+				error_code: result.err().map(|e| {
+					let rejected = reject_invalid(Some(segment_id), e);
+					let error_code = rejected.error.clone().into();
+					first_failure.get_or_insert(rejected);
+					error_code
+				}),
+			})
+			.collect();
+
+		if let Some(rejected) = first_failure {
+			rejected.record(tx_hash);
+		}
+		results
+	}
+
+	/// Verifies the proofs and signatures of the transactions of a block in parallel, against the
+	/// ledger state at `state_key`, before they are applied one by one. Returns, for each
+	/// transaction, whether it was verified.
+	///
+	/// The result only depends on the arguments, so that every node skips the verification of the
+	/// same transactions when passing it to `apply_transaction_with_outcome`. Contract proofs are
+	/// still verified when applying, as their verifier keys may be changed by an earlier
+	/// transaction of the block. A transaction failing here is simply verified in full when
+	/// applied.
+	pub fn pre_verify_transactions(
+		state_key: &[u8],
+		txs_serialized: Vec<Vec<u8>>,
+		block_context: BlockContext,
+	) -> Result<Vec<bool>, LedgerApiError> {
+		let api = api::new();
+		let ledger = Self::get_ledger(&api, state_key)?;
+
+		let workers = std::thread::available_parallelism()
+			.map_or(1, |workers| workers.get())
+			.min(txs_serialized.len());
+		let next_tx = AtomicUsize::new(0);
+
+		let verified = std::thread::scope(|scope| {
+			let workers: Vec<_> = (0..workers)
+				.map(|_| {
+					scope.spawn(|| {
+						let mut verified = Vec::new();
+						loop {
+							let index = next_tx.fetch_add(1, Ordering::Relaxed);
+							let Some(tx_serialized) = txs_serialized.get(index) else {
+								break verified;
+							};
+							let is_verified = Self::pre_verify_transaction(
+								&api,
+								&ledger,
+								tx_serialized,
+								&block_context,
+							);
+							verified.push((index, is_verified));
+						}
+					})
+				})
+				.collect();
+			// A worker which panicked leaves its transactions to be verified in full
+			workers
+				.into_iter()
+				.flat_map(|worker| worker.join().unwrap_or_default())
+				.collect::<Vec<_>>()
+		});
+
+		let mut results = vec![false; txs_serialized.len()];
+		for (index, is_verified) in verified {
+			results[index] = is_verified;
+		}
+		Ok(results)
+	}
+
+	fn pre_verify_transaction(
+		api: &api::Api,
+		ledger: &Ledger<D>,
+		tx_serialized: &[u8],
+		block_context: &BlockContext,
+	) -> bool {
+		let verified = api
+			.tagged_deserialize::<Transaction<S, D>>(tx_serialized)
+			.and_then(|tx| ledger.pre_verify_transaction(&tx, block_context));
+		if let Err(e) = &verified {
+			log::debug!(
+				target: LOG_TARGET,
+				"Transaction not pre-verified, it will be verified when applied: {e:?}"
+			);
+		}
+		verified.is_ok()
+	}
+
+	/// Applies a transaction on top of the ledger state at `state_key` without persisting
+	/// the resulting state, returning everything `apply_transaction` would have reported
+	/// together with the fees paid and the reason each failed segment failed.
+	pub fn simulate_transaction(
+		state_key: &[u8],
+		tx_serialized: &[u8],
+		block_context: BlockContext,
+	) -> Result<TransactionSimulationResult, LedgerApiError> {
+		let api = api::new();
+		let tx = api.tagged_deserialize::<Transaction<S, D>>(tx_serialized)?;
+		let tx_hash = tx.hash();
+		let ledger = Self::get_ledger(&api, state_key)?;
+		let initial_utxos_size = ledger.state.utxo.utxos.size();
+		let fees = tx.fee(&ledger.state.parameters)?;
+
+		let tx_ctx = ledger.get_transaction_context(block_context);
+		// Rejections are returned to the caller of a simulation rather than recorded
+		let (ledger, applied_stage) = Ledger::apply_transaction(ledger, &api, &tx, &tx_ctx, false)?;
+
+		let all_applied = matches!(applied_stage, TransactionAppliedStage::AllApplied);
+
+		let mut utxos = tx.unshielded_utxos();
+
+		let failed_segments = match applied_stage {
+			TransactionAppliedStage::AllApplied => vec![],
+			TransactionAppliedStage::PartialSuccess(segments) => {
+				utxos.remove_failed_segments(&segments);
+				let mut failed: Vec<FailedSegment> = segments
+					.into_iter()
+					.filter_map(|(segment_id, result)| {
+						result.err().map(|e| FailedSegment {
+							segment_id,
+							error_code: LedgerApiError::Transaction(TransactionError::Invalid(
+								e.into(),
+							))
+							.into(),
+						})
+					})
+					.collect();
+				failed.sort_by_key(|segment| segment.segment_id);
+				failed
+			},
+		};
+
+		let failed_segment_ids = failed_segments.iter().map(|segment| segment.segment_id).collect();
+		let operations = tx.calls_and_deploys(Some(failed_segment_ids));
+
+		let (utxo_outputs, utxo_inputs) =
+			utxos.check_utxos_response_integrity(initial_utxos_size, &ledger)?;
+
+		let mut result = TransactionSimulationResult {
+			tx_hash,
+			all_applied,
+			failed_segments,
+			call_addresses: vec![],
+			deploy_addresses: vec![],
+			maintain_addresses: vec![],
+			claim_rewards: vec![],
+			unshielded_utxos_created: utxo_outputs,
+			unshielded_utxos_spent: utxo_inputs,
+			fees,
+		};
+
+		for op in operations {
+			match op {
+				TransactionOperation::Call { address, .. } => {
+					result.call_addresses.push(api.tagged_serialize(&address)?);
+				},
+				TransactionOperation::Deploy { address } => {
+					result.deploy_addresses.push(api.tagged_serialize(&address)?);
+				},
+				TransactionOperation::Maintain { address } => {
+					result.maintain_addresses.push(api.tagged_serialize(&address)?);
+				},
+				TransactionOperation::ClaimRewards { value, .. } => {
+					result.claim_rewards.push(value);
+				},
+			}
+		}
+
+		// `ledger` is intentionally dropped here without calling `persist()`
+		Ok(result)
 	}
 
 	pub fn apply_system_transaction(
@@ -290,6 +808,7 @@ where
 
 		// Only update state after no errors
 		ledger.persist();
+		crate::storage::record_persisted_root(&block_context.parent_block_hash, &event.state_root);
 
 		// Write Prometheus metrics
 		let maybe_metrics = externalities.extension::<LedgerMetricsExt>();
@@ -304,7 +823,7 @@ where
 	}
 
 	pub fn validate_transaction(
-		mut externalities: &mut dyn Externalities,
+		externalities: &mut dyn Externalities,
 		state_key: &[u8],
 		tx_serialized: &[u8],
 		block_context: BlockContext,
@@ -317,26 +836,61 @@ where
 		let tx = api.tagged_deserialize::<Transaction<S, D>>(tx_serialized)?;
 		let ledger = Self::get_ledger(&api, state_key)?;
 
-		let wrapped_cache_key = Self::tx_validation_cache_key(runtime_version, tx_serialized);
+		let tx_hash = Self::validate_deserialized_transaction(
+			externalities,
+			start_tx_validation_time,
+			&ledger,
+			&tx,
+			state_key,
+			tx_serialized,
+			&block_context,
+			runtime_version,
+		)?;
+		let tx_details = Self::get_transaction_details(&tx, &ledger)?;
 
-		let was_cached =
-			Self::do_validate_transaction(&ledger, &tx, &block_context, &wrapped_cache_key)?;
+		Ok((tx_hash, tx_details))
+	}
 
-		let tx_details = Self::get_transaction_details(&tx, &ledger)?;
+	#[allow(clippy::too_many_arguments)]
+	fn validate_deserialized_transaction(
+		mut externalities: &mut dyn Externalities,
+		start_tx_validation_time: Instant,
+		ledger: &Ledger<D>,
+		tx: &Transaction<S, D>,
+		state_key: &[u8],
+		tx_serialized: &[u8],
+		block_context: &BlockContext,
+		runtime_version: u32,
+	) -> Result<Hash, LedgerApiError> {
+		let tx_hash = Self::tx_hash(runtime_version, tx_serialized);
+		let cache_key =
+			Self::tx_validation_cache_key(runtime_version, state_key, block_context, tx_serialized);
 
-		// We only want to record the metric once
-		if let TransactionValidationWasCached::No = was_cached {
-			// Write Prometheus metrics
-			let maybe_metrics = externalities.extension::<LedgerMetricsExt>();
-			if let Some(metrics) = maybe_metrics {
-				let tx_type = Self::get_tx_type(&tx);
-				let elapsed_time = start_tx_validation_time.elapsed().as_secs_f64();
+		let (was_cached, validation) =
+			Self::do_validate_transaction(ledger, tx, block_context, &cache_key);
 
-				metrics.observe_txs_validating_time(elapsed_time, tx_type);
+		// Write Prometheus metrics
+		if let Some(metrics) = externalities.extension::<LedgerMetricsExt>() {
+			match was_cached {
+				TransactionValidationWasCached::Yes => metrics.inc_tx_validation_cache_hits(),
+				TransactionValidationWasCached::No => {
+					metrics.inc_tx_validation_cache_misses();
+
+					// Only actual validations are timed
+					let tx_type = Self::get_tx_type(tx);
+					let elapsed_time = start_tx_validation_time.elapsed().as_secs_f64();
+					metrics.observe_txs_validating_time(elapsed_time, tx_type);
+				},
+			}
+
+			let evictions = TX_VALIDATION_CACHE_EVICTIONS.swap(0, Ordering::Relaxed);
+			if evictions > 0 {
+				metrics.inc_tx_validation_cache_evictions(evictions);
 			}
 		}
 
-		Ok((wrapped_cache_key.0, tx_details))
+		validation?;
+		Ok(tx_hash.0)
 	}
 
 	pub fn get_decoded_transaction(transaction_bytes: &[u8]) -> Result<Tx, LedgerApiError> {
@@ -374,6 +928,127 @@ where
 		})
 	}
 
+	/// Validates a transaction for the transaction pool, and returns the DUST fee it pays, the
+	/// coins it spends and its weight components, for the pool to prioritise it and to detect
+	/// conflicting transactions. The transaction is only deserialized once.
+	pub fn get_transaction_pool_info(
+		externalities: &mut dyn Externalities,
+		state_key: &[u8],
+		tx_serialized: &[u8],
+		block_context: BlockContext,
+		runtime_version: u32,
+	) -> Result<TransactionPoolInfo, LedgerApiError> {
+		let start_tx_validation_time = Instant::now();
+
+		let api = api::new();
+		let tx = api.tagged_deserialize::<Transaction<S, D>>(tx_serialized)?;
+		let ledger = Self::get_ledger(&api, state_key)?;
+
+		let tx_hash = Self::validate_deserialized_transaction(
+			externalities,
+			start_tx_validation_time,
+			&ledger,
+			&tx,
+			state_key,
+			tx_serialized,
+			&block_context,
+			runtime_version,
+		)?;
+		let (_, fee) = tx.dust_spends();
+
+		let mut spends = Vec::new();
+		if let LedgerTransaction::Standard(standard) = &tx.0 {
+			for input in standard.guaranteed_inputs().chain(standard.fallible_inputs()) {
+				spends.push(TransactionSpend::ShieldedNullifier(input.nullifier.0.0));
+			}
+			for transient in standard.guaranteed_transients().chain(standard.fallible_transients())
+			{
+				spends.push(TransactionSpend::ShieldedNullifier(transient.nullifier.0.0));
+			}
+			for intent in standard.intents.values() {
+				if let Some(dust_actions) = &intent.dust_actions {
+					for spend in dust_actions.spends.iter() {
+						let nullifier = api.serialize(&spend.old_nullifier)?;
+						spends.push(TransactionSpend::DustNullifier(nullifier));
+					}
+				}
+			}
+		}
+		for utxo in tx.unshielded_utxos().inputs.into_values().flatten() {
+			spends.push(TransactionSpend::UnshieldedUtxo {
+				intent_hash: utxo.intent_hash,
+				output_no: utxo.output_no,
+			});
+		}
+
+		Ok(TransactionPoolInfo {
+			tx_hash,
+			fee,
+			spends,
+			weight_components: Self::weight_components(&tx, &[]),
+		})
+	}
+
+	pub fn get_transaction_weight_components(
+		transaction_bytes: &[u8],
+	) -> Result<TransactionWeightComponents, LedgerApiError> {
+		let api = api::new();
+		let tx = api.tagged_deserialize::<Transaction<S, D>>(transaction_bytes)?;
+
+		Ok(Self::weight_components(&tx, &[]))
+	}
+
+	/// Weight components of `tx`, not counting the gas of the fallible transcripts of
+	/// `failed_segments`
+	fn weight_components(
+		tx: &Transaction<S, D>,
+		failed_segments: &[u16],
+	) -> TransactionWeightComponents {
+		match &tx.0 {
+			LedgerTransaction::Standard(tx) => {
+				let transients =
+					tx.guaranteed_transients().count() + tx.fallible_transients().count();
+				let inputs =
+					tx.guaranteed_inputs().count() + tx.fallible_inputs().count() + transients;
+				let outputs =
+					tx.guaranteed_outputs().count() + tx.fallible_outputs().count() + transients;
+				// The ledger charges the gas declared by the transcripts, so it doesn't depend on
+				// the state the transaction applies to
+				let gas = tx
+					.actions()
+					.filter_map(|(segment, action)| match action {
+						ContractAction::Call(call) => Some((segment, call)),
+						_ => None,
+					})
+					.flat_map(|(segment, call)| {
+						// The fallible transcript of a failed segment isn't run
+						let fallible_transcript = call
+							.fallible_transcript
+							.clone()
+							.filter(|_| !failed_segments.contains(&segment));
+						[call.guaranteed_transcript.clone(), fallible_transcript]
+					})
+					.flatten()
+					.map(|transcript| {
+						transcript
+							.gas
+							.read_time
+							.into_picoseconds()
+							.saturating_add(transcript.gas.compute_time.into_picoseconds())
+					})
+					.fold(0, GasCost::saturating_add);
+
+				TransactionWeightComponents {
+					intents: tx.intents.values().count() as u32,
+					inputs: inputs as u32,
+					outputs: outputs as u32,
+					gas,
+				}
+			},
+			LedgerTransaction::ClaimRewards(_) => TransactionWeightComponents::default(),
+		}
+	}
+
 	fn do_get_contract_state<F>(
 		api: &api::Api,
 		state_key: &[u8],
@@ -418,6 +1093,19 @@ where
 		api.serialize(&ledger.get_zswap_state_root())
 	}
 
+	pub fn get_ledger_state_summary(
+		state_key: &[u8],
+	) -> Result<LedgerStateSummary, LedgerApiError> {
+		let api = api::new();
+		let ledger = Self::get_ledger(&api, state_key)?;
+
+		Ok(LedgerStateSummary {
+			zswap_state_root: api.serialize(&ledger.get_zswap_state_root())?,
+			utxo_count: ledger.state.utxo.utxos.size() as u64,
+			contract_count: ledger.state.contract.size() as u64,
+		})
+	}
+
 	pub fn mint_coins(
 		state_key: &[u8],
 		amount: u128,
@@ -437,9 +1125,13 @@ where
 		let ledger =
 			Ledger::apply_system_tx(ledger, &sys_tx, Timestamp::from_secs(block_context.tblock))?;
 
+		let state_root = api.tagged_serialize(&ledger.hash())?;
+
 		// Only update state after no errors
 		ledger.persist();
-		api.tagged_serialize(&ledger.hash())
+		crate::storage::record_persisted_root(&block_context.parent_block_hash, &state_root);
+
+		Ok(state_root)
 	}
 
 	pub fn get_unclaimed_amount(
@@ -461,6 +1153,274 @@ where
 		api.tagged_serialize(&ledger_parameters)
 	}
 
+	/// Returns the unshielded UTXOs owned by `address` in the ledger state at `state_key`
+	pub fn get_unshielded_utxos(
+		state_key: &[u8],
+		address: &[u8],
+	) -> Result<Vec<UtxoInfo>, LedgerApiError> {
+		let api = api::new();
+
+		let owner = api.night_address(address)?;
+		let utxos_by_owner = UNSHIELDED_UTXOS_BY_OWNER
+			.try_get_with(state_key.to_vec(), || Self::index_unshielded_utxos(&api, state_key))
+			.map_err(|e| (*e).clone())?;
+
+		Ok(utxos_by_owner.get(&owner.0.0).cloned().unwrap_or_default())
+	}
+
+	/// Indexes the unshielded UTXOs of the ledger state at `state_key` by owner. The ledger
+	/// keys its UTXO set by the whole UTXO, so this is the only scan of the set for that state.
+	fn index_unshielded_utxos(
+		api: &api::Api,
+		state_key: &[u8],
+	) -> Result<Arc<HashMap<Hash, Vec<UtxoInfo>>>, LedgerApiError> {
+		let ledger = Self::get_ledger(api, state_key)?;
+
+		let mut utxos_by_owner: HashMap<Hash, Vec<UtxoInfo>> = HashMap::new();
+		for utxo in ledger.state.utxo.utxos.iter() {
+			utxos_by_owner.entry(utxo.0.owner.0.0).or_default().push(UtxoInfo {
+				address: utxo.0.owner.0.0,
+				token_type: utxo.0.type_.0.0,
+				intent_hash: utxo.0.intent_hash.0.0,
+				value: utxo.0.value,
+				output_no: utxo.0.output_no,
+			});
+		}
+		Ok(Arc::new(utxos_by_owner))
+	}
+
+	/// Returns the inclusion proof of `commitment` in the zswap commitment tree of the ledger
+	/// state at `state_key`, or `None` if the commitment is not in the tree.
+	///
+	/// Besides the merkle path from the tree root to the commitment, the proof holds the path
+	/// of storage arena nodes from the ledger state root to the top node of the tree, which
+	/// records the root, see `verify_zswap_commitment_proof` in `midnight_node_ledger_helpers`.
+	pub fn get_zswap_commitment_proof(
+		state_key: &[u8],
+		commitment: &[u8],
+	) -> Result<Option<ZswapCommitmentProof>, LedgerApiError> {
+		let api = api::new();
+
+		let commitment: Hash = commitment
+			.try_into()
+			.map_err(|_| LedgerApiError::Deserialization(api::DeserializationError::Commitment))?;
+		let commitment_indices = ZSWAP_COMMITMENT_INDICES
+			.try_get_with(state_key.to_vec(), || Self::index_zswap_commitments(&api, state_key))
+			.map_err(|e| (*e).clone())?;
+		let Some(index) = commitment_indices.get(&commitment) else {
+			return Ok(None);
+		};
+
+		let ledger = Self::get_ledger(&api, state_key)?;
+		// The ledger rehashes the tree after every update to record its root in the top node,
+		// which is what ties the root to the state key
+		let coin_coms = &ledger.state.zswap.coin_coms;
+		let root = coin_coms.root().ok_or_else(|| {
+			log::error!(target: LOG_TARGET, "Zswap commitment tree without a recorded root");
+			LedgerApiError::NoLedgerState
+		})?;
+		let path =
+			coin_coms
+				.path_for_leaf(*index, Commitment(HashOutput(commitment)))
+				.map_err(|e| {
+					log::error!(target: LOG_TARGET, "Error building zswap commitment proof: {e:?}");
+					LedgerApiError::NoLedgerState
+				})?;
+
+		// Allocating the tree again only references its existing node
+		let tree_key: ArenaKey<D::Hasher> =
+			default_storage::<D>().arena.alloc(coin_coms.clone()).hash().into();
+		// Subtrees of the ledger state that hold no zswap state
+		let mut visited: HashSet<ArenaKey<D::Hasher>> = [
+			ledger.state.parameters.hash().into(),
+			ledger.state.utxo.hash().into(),
+			ledger.state.dust.hash().into(),
+		]
+		.into_iter()
+		.collect();
+		let mut tree_paths = HashMap::new();
+		Self::collect_arena_paths(
+			&api,
+			ledger.hash().into(),
+			&[(tree_key.clone(), Vec::new())].into_iter().collect(),
+			&mut visited,
+			&mut Vec::new(),
+			&mut tree_paths,
+		)?;
+		let tree_path = tree_paths.remove(&Vec::new()).ok_or_else(|| {
+			log::error!(target: LOG_TARGET, "Zswap commitment tree not in the Ledger State");
+			LedgerApiError::NoLedgerState
+		})?;
+		let (data, children) = Self::get_arena_node(&tree_key)?;
+		let children = children
+			.iter()
+			.map(|child| api.serialize(child))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(Some(ZswapCommitmentProof {
+			state_key: state_key.to_vec(),
+			tree_path,
+			tree_node: ArenaNode { data, children },
+			root: api.serialize(&root)?,
+			path: api.serialize(&path)?,
+		}))
+	}
+
+	/// Indexes the leaves of the zswap commitment tree of the ledger state at `state_key` by
+	/// commitment, so that proofs don't have to search the tree
+	fn index_zswap_commitments(
+		api: &api::Api,
+		state_key: &[u8],
+	) -> Result<Arc<HashMap<Hash, u64>>, LedgerApiError> {
+		let ledger = Self::get_ledger(api, state_key)?;
+		let zswap = &ledger.state.zswap;
+
+		let commitment_indices = (0..zswap.first_free)
+			.filter_map(|index| zswap.coin_coms.index(index).map(|(leaf, _)| (leaf.0, index)))
+			.collect();
+		Ok(Arc::new(commitment_indices))
+	}
+
+	/// Returns the inclusion proof of the contract at `contract_address` in the ledger state
+	/// at `state_key`, or `None` if there is no such contract.
+	///
+	/// The proof is the path of storage arena nodes from the ledger state root to the map entry
+	/// holding the contract address and state, see `verify_contract_state_proof` in
+	/// `midnight_node_ledger_helpers`.
+	pub fn get_contract_state_proof(
+		state_key: &[u8],
+		contract_address: &[u8],
+	) -> Result<Option<ContractStateProof>, LedgerApiError> {
+		let api = api::new();
+
+		let address = api.deserialize::<ContractAddress>(contract_address)?;
+		let contract_paths = CONTRACT_STATE_PATHS
+			.try_get_with(state_key.to_vec(), || Self::index_contract_state_paths(&api, state_key))
+			.map_err(|e| (*e).clone())?;
+
+		Ok(contract_paths
+			.get(&api.serialize(&address)?)
+			.map(|path| ContractStateProof { state_key: state_key.to_vec(), path: path.clone() }))
+	}
+
+	/// Walks the storage arena from the root of the ledger state at `state_key` down to the
+	/// entries of its contract map, returning the path to each of them keyed by the serialized
+	/// contract address
+	fn index_contract_state_paths(
+		api: &api::Api,
+		state_key: &[u8],
+	) -> Result<Arc<HashMap<Vec<u8>, Vec<ArenaPathStep>>>, LedgerApiError> {
+		let ledger = Self::get_ledger(api, state_key)?;
+
+		let mut entries = HashMap::new();
+		for entry in ledger.state.contract.iter() {
+			let key: ArenaKey<D::Hasher> = entry.hash().into();
+			entries.insert(key, api.serialize(&entry.0)?);
+		}
+		// Subtrees of the ledger state that hold no contract
+		let mut visited: HashSet<ArenaKey<D::Hasher>> = [
+			ledger.state.parameters.hash().into(),
+			ledger.state.zswap.hash().into(),
+			ledger.state.utxo.hash().into(),
+			ledger.state.dust.hash().into(),
+		]
+		.into_iter()
+		.collect();
+
+		let mut contract_paths = HashMap::new();
+		Self::collect_arena_paths(
+			api,
+			ledger.hash().into(),
+			&entries,
+			&mut visited,
+			&mut Vec::new(),
+			&mut contract_paths,
+		)?;
+		Ok(Arc::new(contract_paths))
+	}
+
+	/// Depth first search of the `targets` reachable from `key`, recording the path to each of
+	/// them in `paths`. Nodes shared between subtrees are walked once.
+	fn collect_arena_paths(
+		api: &api::Api,
+		key: ArenaKey<D::Hasher>,
+		targets: &HashMap<ArenaKey<D::Hasher>, Vec<u8>>,
+		visited: &mut HashSet<ArenaKey<D::Hasher>>,
+		path: &mut Vec<ArenaPathStep>,
+		paths: &mut HashMap<Vec<u8>, Vec<ArenaPathStep>>,
+	) -> Result<(), LedgerApiError> {
+		if let Some(target) = targets.get(&key) {
+			paths.insert(target.clone(), path.clone());
+			return Ok(());
+		}
+		if paths.len() == targets.len() || !visited.insert(key.clone()) {
+			return Ok(());
+		}
+
+		let (data, children) = Self::get_arena_node(&key)?;
+		let serialized_children = children
+			.iter()
+			.map(|child| api.serialize(child))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		for (index, child) in children.into_iter().enumerate() {
+			path.push(ArenaPathStep {
+				data: data.clone(),
+				children: serialized_children.clone(),
+				child: index as u32,
+			});
+			Self::collect_arena_paths(api, child, targets, visited, path, paths)?;
+			path.pop();
+		}
+		Ok(())
+	}
+
+	/// Returns the binary representation and the children keys of the storage arena node at
+	/// `key`
+	fn get_arena_node(
+		key: &ArenaKey<D::Hasher>,
+	) -> Result<(Vec<u8>, Vec<ArenaKey<D::Hasher>>), LedgerApiError> {
+		default_storage::<D>()
+			.with_backend(|backend| {
+				backend.get(key).map(|node| (node.data.clone(), node.children.clone()))
+			})
+			.ok_or_else(|| {
+				log::error!(target: LOG_TARGET, "Missing Ledger State node: {key:?}");
+				LedgerApiError::NoLedgerState
+			})
+	}
+
+	/// Returns the DUST generation of the cNight UTXO with the initial nonce `nonce` in the
+	/// ledger state at `state_key`, or `None` if it doesn't generate DUST
+	pub fn get_dust_generation_info(
+		state_key: &[u8],
+		nonce: &[u8],
+	) -> Result<Option<DustGenerationDetails>, LedgerApiError> {
+		let api = api::new();
+
+		let nonce: Hash = nonce.try_into().map_err(|_| {
+			LedgerApiError::Deserialization(api::DeserializationError::InitialNonce)
+		})?;
+		let ledger = Self::get_ledger(&api, state_key)?;
+		let generation = &ledger.state.dust.generation;
+
+		let Some(index) = generation.night_indices.get(&InitialNonce(HashOutput(nonce))) else {
+			return Ok(None);
+		};
+		generation
+			.generating_tree
+			.index(*index)
+			.map(|(_, info)| {
+				Ok(DustGenerationDetails {
+					value: info.value,
+					owner: api.serialize(&info.owner)?,
+					nonce: info.nonce.0.0,
+					dtime: info.dtime.to_secs(),
+				})
+			})
+			.transpose()
+	}
+
 	// TODO COST MODEL: Needs to be redone with the new ledger cost model
 	#[allow(unused_variables)]
 	pub fn get_transaction_cost(
@@ -581,13 +1541,27 @@ where
 		}
 	}
 
-	/// Calculate tx hash to be used in the `TX_VALIDATION_CACHE`
+	/// Calculate the tx hash returned by `validate_transaction`
 	/// `runtime_version` is prepended to differentiate tx validity between versions
-	fn tx_validation_cache_key(runtime_version: u32, tx_serialized: &[u8]) -> WrappedHash {
+	fn tx_hash(runtime_version: u32, tx_serialized: &[u8]) -> WrappedHash {
 		let to_hash = [&runtime_version.to_le_bytes(), tx_serialized].concat();
 		Twox128::hash(&to_hash).into()
 	}
 
+	/// The validation of a transaction depends on the ledger state and the block context it is
+	/// validated against, so both are part of the key: a new state root is a cache miss.
+	fn tx_validation_cache_key(
+		runtime_version: u32,
+		state_key: &[u8],
+		block_context: &BlockContext,
+		tx_serialized: &[u8],
+	) -> WrappedHash {
+		let to_hash =
+			[&runtime_version.to_le_bytes(), state_key, &block_context.encode(), tx_serialized]
+				.concat();
+		WrappedHash(Blake2_256::hash(&to_hash))
+	}
+
 	fn get_tx_type(tx: &Transaction<S, D>) -> &'static str {
 		match tx.0 {
 			mn_ledger_local::structure::Transaction::Standard(_) => "standard",
@@ -615,20 +1589,17 @@ where
 		ledger: &Ledger<D>,
 		tx: &Transaction<S, D>,
 		block_context: &BlockContext,
-		tx_hash: &WrappedHash,
-	) -> Result<TransactionValidationWasCached, LedgerApiError> {
-		// We always revalidate the transaction, whether it's in the cache or not.
-		let validation = ledger.validate_transaction(tx, block_context);
-
-		// Caching remains helpful as it prevent us from recording validation metrics multiple times
-		// Tx is cached: map `Ok` to `TransactionValidationWasCached::Yes`
-		if TX_VALIDATION_CACHE.get(&tx_hash.0).is_some() {
-			validation.map(|_| TransactionValidationWasCached::Yes)
-		// Tx is not cached: insert the validation and map `Ok` to `TransactionValidationWasCached::No` afterwards
-		} else {
-			TX_VALIDATION_CACHE.insert(tx_hash.0, validation.clone());
-			validation.map(|_| TransactionValidationWasCached::No)
+		cache_key: &WrappedHash,
+	) -> (TransactionValidationWasCached, Result<(), LedgerApiError>) {
+		if let Some(validation) = TX_VALIDATION_CACHE.get(&cache_key.0) {
+			return (TransactionValidationWasCached::Yes, validation);
 		}
+
+		let validation = ledger
+			.validate_transaction(tx, block_context)
+			.map_err(|rejected| rejected.record(tx.hash()));
+		TX_VALIDATION_CACHE.insert(cache_key.0, validation.clone());
+		(TransactionValidationWasCached::No, validation)
 	}
 
 	pub fn construct_cnight_generates_dust_event(
@@ -664,6 +1635,61 @@ where
 		let system_tx = SystemTransaction::CNightGeneratesDustUpdate { events: events? };
 		api.tagged_serialize(&system_tx)
 	}
+
+	/// Builds an `OverwriteParameters` system transaction applying `update` to the
+	/// current ledger parameters
+	pub fn construct_update_parameters_system_tx(
+		state_key: &[u8],
+		update: LedgerParametersUpdate,
+	) -> Result<Vec<u8>, LedgerApiError> {
+		update.validate().map_err(|_| LedgerApiError::InvalidParametersUpdate)?;
+
+		let api = api::new();
+		let ledger = Self::get_ledger(&api, state_key)?;
+		let mut parameters = Self::get_deserialized_ledger_parameters(&ledger);
+
+		let fixed_point =
+			|ratio: FixedPointRatio| FixedPoint::from_u64_div(ratio.numerator, ratio.denominator);
+		if let Some(price) = update.read_price {
+			parameters.fee_prices.read_price = fixed_point(price);
+		}
+		if let Some(price) = update.compute_price {
+			parameters.fee_prices.compute_price = fixed_point(price);
+		}
+		if let Some(price) = update.block_usage_price {
+			parameters.fee_prices.block_usage_price = fixed_point(price);
+		}
+		if let Some(price) = update.write_price {
+			parameters.fee_prices.write_price = fixed_point(price);
+		}
+
+		if let Some(ratio) = update.night_dust_ratio {
+			parameters.dust.night_dust_ratio = ratio;
+		}
+		if let Some(rate) = update.generation_decay_rate {
+			parameters.dust.generation_decay_rate = rate;
+		}
+		if let Some(secs) = update.dust_grace_period_secs {
+			let secs = secs.try_into().map_err(|_| LedgerApiError::InvalidParametersUpdate)?;
+			parameters.dust.dust_grace_period = Duration::from_secs(secs);
+		}
+
+		if let Some(limit) = update.transaction_byte_limit {
+			parameters.limits.transaction_byte_limit = limit;
+		}
+		if let Some(limits) = update.block_limits {
+			parameters.limits.block_limits = SyntheticCost {
+				read_time: CostDuration::from_picoseconds(limits.read_time_picoseconds),
+				compute_time: CostDuration::from_picoseconds(limits.compute_time_picoseconds),
+				block_usage: limits.block_usage,
+				bytes_written: limits.bytes_written,
+				bytes_churned: limits.bytes_churned,
+			};
+		}
+
+		let system_tx = SystemTransaction::OverwriteParameters(parameters);
+		api.tagged_serialize(&system_tx)
+	}
 }
 
 /// Creates a Nonce using BlakeTwo256; similar Hashing type set in the Runtime.
@@ -682,3 +1708,236 @@ fn create_nonce(separator: &[u8], block_hash: &[u8], output_number: u8) -> Nonce
 
 	Nonce(HashOutput(h256.0))
 }
+
+// grcov-excl-start
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use base_crypto_local::signatures::Signature;
+	use ledger_storage_local::DefaultDB;
+	use midnight_node_res::networks::{MidnightNetwork, UndeployedNetwork};
+	use mn_ledger_local::structure::LedgerState;
+
+	type TestBridge = Bridge<Signature, DefaultDB>;
+
+	/// Persists the genesis ledger state, returning its key
+	fn genesis_state_key() -> Vec<u8> {
+		sp_tracing::try_init_simple();
+
+		let state: LedgerState<DefaultDB> =
+			midnight_serialize_local::tagged_deserialize(UndeployedNetwork.genesis_state())
+				.unwrap_or_else(|err| panic!("Can't deserialize ledger from genesis: {err}"));
+		let ledger = api::new()
+			.tagged_serialize(&Ledger::new(state))
+			.expect("Can't serialize ledger");
+		TestBridge::import_ledger_state(&ledger).expect("Can't persist ledger")
+	}
+
+	#[test]
+	fn unshielded_utxos_are_looked_up_by_owner() {
+		if super::super::CRATE_NAME != crate::latest::CRATE_NAME {
+			println!("This test should only be run with ledger latest");
+			return;
+		}
+		let state_key = genesis_state_key();
+		let api = api::new();
+		let ledger = TestBridge::get_ledger(&api, &state_key).unwrap();
+		let owner = ledger.state.utxo.utxos.iter().next().expect("Genesis has no UTXO").0.owner;
+		let owned = ledger.state.utxo.utxos.iter().filter(|utxo| utxo.0.owner == owner).count();
+
+		let utxos = TestBridge::get_unshielded_utxos(&state_key, &owner.0.0).unwrap();
+		assert_eq!(utxos.len(), owned);
+		assert!(utxos.iter().all(|utxo| utxo.address == owner.0.0));
+
+		// Served from the index of the state
+		assert!(UNSHIELDED_UTXOS_BY_OWNER.contains_key(&state_key));
+		assert_eq!(TestBridge::get_unshielded_utxos(&state_key, &owner.0.0).unwrap(), utxos);
+		assert_eq!(TestBridge::get_unshielded_utxos(&state_key, &[0; 32]).unwrap(), vec![]);
+	}
+
+	#[test]
+	fn dust_generation_info_is_looked_up_by_nonce() {
+		let state_key = genesis_state_key();
+
+		assert_eq!(TestBridge::get_dust_generation_info(&state_key, &[0; 32]).unwrap(), None);
+		assert!(matches!(
+			TestBridge::get_dust_generation_info(&state_key, &[1, 2, 3]),
+			Err(LedgerApiError::Deserialization(types::DeserializationError::InitialNonce))
+		));
+	}
+
+	#[test]
+	fn ledger_state_key_is_known_before_import() {
+		if super::super::CRATE_NAME != crate::latest::CRATE_NAME {
+			println!("This test should only be run with ledger latest");
+			return;
+		}
+		let state_key = genesis_state_key();
+		let ledger_state = TestBridge::export_ledger_state(&state_key).unwrap();
+
+		assert_eq!(TestBridge::ledger_state_key(&ledger_state).unwrap(), state_key);
+		assert_eq!(TestBridge::import_ledger_state(&ledger_state).unwrap(), state_key);
+		assert!(TestBridge::ledger_state_key(&[1, 2, 3]).is_err());
+	}
+
+	#[test]
+	fn read_ledger_state_checks_the_state_key() {
+		if super::super::CRATE_NAME != crate::latest::CRATE_NAME {
+			println!("This test should only be run with ledger latest");
+			return;
+		}
+		let state_key = genesis_state_key();
+		let mut ledger_state = Vec::new();
+		TestBridge::write_ledger_state(&state_key, &mut ledger_state).unwrap();
+		assert_eq!(ledger_state, TestBridge::export_ledger_state(&state_key).unwrap());
+
+		let mut other_state_key = state_key.clone();
+		*other_state_key.last_mut().unwrap() ^= 1;
+		assert_eq!(
+			TestBridge::read_ledger_state(&ledger_state[..], &other_state_key).unwrap(),
+			state_key
+		);
+		assert_eq!(
+			TestBridge::read_ledger_state(&ledger_state[..], &state_key).unwrap(),
+			state_key
+		);
+		assert!(TestBridge::read_ledger_state(&[1u8, 2, 3][..], &state_key).is_err());
+	}
+
+	#[test]
+	fn check_ledger_state_reports_missing_nodes() {
+		if super::super::CRATE_NAME != crate::latest::CRATE_NAME {
+			println!("This test should only be run with ledger latest");
+			return;
+		}
+		let state_key = genesis_state_key();
+		let check = TestBridge::check_ledger_state(&state_key).unwrap();
+		assert!(check.is_complete());
+		assert!(check.nodes > 1);
+
+		// A state that was never written to the storage is missing from its root
+		let mut missing_key = state_key.clone();
+		*missing_key.last_mut().unwrap() ^= 1;
+		let api = api::new();
+		let root: TypedArenaKey<Ledger<DefaultDB>, <DefaultDB as DB>::Hasher> =
+			api.tagged_deserialize(&missing_key).unwrap();
+		let root: ArenaKey<<DefaultDB as DB>::Hasher> = root.into();
+
+		let check = TestBridge::check_ledger_state(&missing_key).unwrap();
+		assert!(!check.is_complete());
+		assert_eq!(check.nodes, 0);
+		assert_eq!(check.missing, vec![api.serialize(&root).unwrap()]);
+		assert!(TestBridge::check_ledger_state(&[1, 2, 3]).is_err());
+	}
+
+	#[test]
+	fn tx_validations_are_cached_per_state_and_block_context() {
+		if super::super::CRATE_NAME != crate::latest::CRATE_NAME {
+			println!("This test should only be run with ledger latest");
+			return;
+		}
+		let state_key = genesis_state_key();
+		let api = api::new();
+		let ledger = TestBridge::get_ledger(&api, &state_key).unwrap();
+		let tx_serialized: &[u8] =
+			include_bytes!("../../../../res/test-tx-deserialize/serialized_tx_no_context.mn");
+		let tx = api
+			.tagged_deserialize::<Transaction<Signature, DefaultDB>>(tx_serialized)
+			.unwrap();
+		// Not a runtime version, so that no other test shares the cache entries
+		let runtime_version = u32::MAX;
+
+		let validate = |state_key: &[u8], block_context: &BlockContext| {
+			let cache_key = TestBridge::tx_validation_cache_key(
+				runtime_version,
+				state_key,
+				block_context,
+				tx_serialized,
+			);
+			let (was_cached, validation) =
+				TestBridge::do_validate_transaction(&ledger, &tx, block_context, &cache_key);
+			(matches!(was_cached, TransactionValidationWasCached::Yes), validation)
+		};
+
+		let block_context = BlockContext { tblock: 1_763_577_105, ..Default::default() };
+		let (was_cached, validation) = validate(&state_key, &block_context);
+		assert!(!was_cached);
+
+		// Same state and block context: a hit, with the same outcome
+		let (was_cached, cached_validation) = validate(&state_key, &block_context);
+		assert!(was_cached);
+		assert_eq!(cached_validation.encode(), validation.encode());
+
+		// Another state root: a miss
+		let mut other_state_key = state_key.clone();
+		*other_state_key.last_mut().unwrap() ^= 1;
+		assert!(!validate(&other_state_key, &block_context).0);
+
+		// Another block context: a miss
+		let next_block_context =
+			BlockContext { tblock: block_context.tblock + 6, ..block_context.clone() };
+		assert!(!validate(&state_key, &next_block_context).0);
+		let other_parent_context =
+			BlockContext { parent_block_hash: vec![1; 32], ..block_context.clone() };
+		assert!(!validate(&state_key, &other_parent_context).0);
+
+		// The original entry is still cached
+		assert!(validate(&state_key, &block_context).0);
+	}
+
+	#[test]
+	fn zswap_commitment_proofs_verify_against_the_state_key() {
+		if super::super::CRATE_NAME != crate::latest::CRATE_NAME {
+			println!("This test should only be run with ledger latest");
+			return;
+		}
+		let state_key = genesis_state_key();
+		let ledger = TestBridge::get_ledger(&api::new(), &state_key).unwrap();
+		let (leaf, _) =
+			ledger.state.zswap.coin_coms.index(0).expect("Genesis has no zswap commitment");
+		let commitment: Hash = leaf.0;
+
+		let proof = TestBridge::get_zswap_commitment_proof(&state_key, &commitment)
+			.unwrap()
+			.expect("The commitment should be in the tree");
+		assert!(ZSWAP_COMMITMENT_INDICES.contains_key(&state_key));
+		assert_eq!(proof.state_key, state_key);
+		assert_eq!(proof.root, TestBridge::get_zswap_state_root(&state_key).unwrap());
+		let tree_path: Vec<_> = proof
+			.tree_path
+			.iter()
+			.map(|step| helpers_local::ArenaPathStep {
+				data: step.data.clone(),
+				children: step.children.clone(),
+				child: step.child as usize,
+			})
+			.collect();
+		let tree_node = helpers_local::ArenaNode {
+			data: proof.tree_node.data.clone(),
+			children: proof.tree_node.children.clone(),
+		};
+		let verify = |state_key: &[u8], commitment: &[u8]| {
+			helpers_local::verify_zswap_commitment_proof(
+				state_key,
+				&tree_path,
+				&tree_node,
+				&proof.root,
+				commitment,
+				&proof.path,
+			)
+		};
+		assert!(verify(&state_key, &commitment));
+
+		let mut other_state_key = state_key.clone();
+		*other_state_key.last_mut().unwrap() ^= 1;
+		assert!(!verify(&other_state_key, &commitment));
+		let mut other_commitment = commitment;
+		other_commitment[0] ^= 1;
+		assert!(!verify(&state_key, &other_commitment));
+		assert!(matches!(
+			TestBridge::get_zswap_commitment_proof(&state_key, &other_commitment),
+			Ok(None)
+		));
+	}
+}
+// grcov-excl-stop
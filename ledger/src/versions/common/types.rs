@@ -104,6 +104,8 @@ pub enum DeserializationError {
 	DustPublicKey,
 	CNightGeneratesDustActionType,
 	CNightGeneratesDustEvent,
+	Commitment,
+	InitialNonce,
 }
 
 #[derive(RuntimeDebug, Encode, Decode, DecodeWithMemTracking, Clone, TypeInfo, PalletError)]
@@ -121,6 +123,9 @@ pub enum SerializationError {
 	VersionedArenaKey,
 	CNightGeneratesDustEvent,
 	SystemTransaction,
+	DustPublicKey,
+	MerklePath,
+	ArenaKey,
 }
 
 #[derive(RuntimeDebug, Encode, Decode, DecodeWithMemTracking, Clone, TypeInfo, PalletError)]
@@ -141,6 +146,7 @@ pub enum LedgerApiError {
 	ContractCallCostError,
 	BlockLimitExceededError,
 	FeeCalculationError,
+	InvalidParametersUpdate,
 	HostApiError,
 }
 
@@ -174,6 +180,12 @@ impl core::fmt::Display for LedgerApiError {
 				DeserializationError::CNightGeneratesDustEvent => {
 					write!(f, "Error deserializing: CNightGeneratesDustEvent")
 				},
+				DeserializationError::Commitment => {
+					write!(f, "Error deserializing: Commitment")
+				},
+				DeserializationError::InitialNonce => {
+					write!(f, "Error deserializing: InitialNonce")
+				},
 			},
 			LedgerApiError::Serialization(error) => match error {
 				TransactionIdentifier => write!(f, "Error serializing: TransactionIdentifier"),
@@ -197,6 +209,15 @@ impl core::fmt::Display for LedgerApiError {
 				SerializationError::SystemTransaction => {
 					write!(f, "Error serializing: SystemTransaction")
 				},
+				SerializationError::DustPublicKey => {
+					write!(f, "Error serializing: DustPublicKey")
+				},
+				SerializationError::MerklePath => {
+					write!(f, "Error serializing: MerklePath")
+				},
+				SerializationError::ArenaKey => {
+					write!(f, "Error serializing: ArenaKey")
+				},
 			},
 			LedgerApiError::Transaction(error) => match error {
 				Invalid(e) => write!(f, "Transaction Error: Invalid({e:?})"),
@@ -221,6 +242,9 @@ impl core::fmt::Display for LedgerApiError {
 			LedgerApiError::FeeCalculationError => {
 				write!(f, "Error, exceeded block limit during transaction application")
 			},
+			LedgerApiError::InvalidParametersUpdate => {
+				write!(f, "Error, the ledger parameters update is out of range")
+			},
 			LedgerApiError::HostApiError => {
 				write!(f, "Error while processing the transaction in the host API")
 			},
@@ -245,6 +269,8 @@ impl From<LedgerApiError> for u8 {
 				DeserializationError::DustPublicKey => 9,
 				DeserializationError::CNightGeneratesDustActionType => 10,
 				DeserializationError::CNightGeneratesDustEvent => 11,
+				DeserializationError::Commitment => 12,
+				DeserializationError::InitialNonce => 13,
 			},
 			// Reserved from [50-100)
 			LedgerApiError::Serialization(error) => match error {
@@ -261,6 +287,9 @@ impl From<LedgerApiError> for u8 {
 				SerializationError::TypedArenaKey => 60,
 				SerializationError::CNightGeneratesDustEvent => 61,
 				SerializationError::SystemTransaction => 62,
+				SerializationError::DustPublicKey => 63,
+				SerializationError::MerklePath => 64,
+				SerializationError::ArenaKey => 65,
 			},
 			// Reserved from [100-150)
 			LedgerApiError::Transaction(error) => match error {
@@ -309,7 +338,6 @@ impl From<LedgerApiError> for u8 {
 					MalformedError::UnknownError => 139,
 				},
 				SystemTransaction(e) => match e {
-					SystemTransactionError::IllegalPayout => 139,
 					SystemTransactionError::InsufficientTreasuryFunds => 140,
 					SystemTransactionError::CommitmentAlreadyPresent => 141,
 					SystemTransactionError::UnknownError => 142,
@@ -318,6 +346,8 @@ impl From<LedgerApiError> for u8 {
 					SystemTransactionError::GenerationInfoAlreadyPresent => 145,
 					SystemTransactionError::InvalidBasisPoints => 146,
 					SystemTransactionError::InvariantViolation => 147,
+					// Used to share 139 with `MalformedError::UnknownError`
+					SystemTransactionError::IllegalPayout => 148,
 				},
 			},
 			// Reserved from [150-255) for future Errors
@@ -327,6 +357,7 @@ impl From<LedgerApiError> for u8 {
 			LedgerApiError::ContractCallCostError => 153,
 			LedgerApiError::BlockLimitExceededError => 154,
 			LedgerApiError::FeeCalculationError => 155,
+			LedgerApiError::InvalidParametersUpdate => 156,
 			// Error in the Host API, not coming from Ledger
 			LedgerApiError::HostApiError => 255,
 		}
@@ -336,3 +367,117 @@ impl From<LedgerApiError> for u8 {
 // Implement the `std::error::Error` trait only when `std` is enabled.
 #[cfg(feature = "std")]
 impl std::error::Error for LedgerApiError {}
+
+impl InvalidError {
+	pub const ALL: [InvalidError; 10] = [
+		InvalidError::EffectsMismatch,
+		InvalidError::ContractAlreadyDeployed,
+		InvalidError::ContractNotPresent,
+		InvalidError::Zswap,
+		InvalidError::Transcript,
+		InvalidError::InsufficientClaimable,
+		InvalidError::VerifierKeyNotFound,
+		InvalidError::VerifierKeyAlreadyPresent,
+		InvalidError::ReplayCounterMismatch,
+		InvalidError::UnknownError,
+	];
+}
+
+impl MalformedError {
+	pub const ALL: [MalformedError; 30] = [
+		MalformedError::VerifierKeyNotSet,
+		MalformedError::TransactionTooLarge,
+		MalformedError::VerifierKeyTooLarge,
+		MalformedError::VerifierKeyNotPresent,
+		MalformedError::ContractNotPresent,
+		MalformedError::InvalidProof,
+		MalformedError::BindingCommitmentOpeningInvalid,
+		MalformedError::NotNormalized,
+		MalformedError::FallibleWithoutCheckpoint,
+		MalformedError::ClaimReceiveFailed,
+		MalformedError::ClaimSpendFailed,
+		MalformedError::ClaimNullifierFailed,
+		MalformedError::ClaimCallFailed,
+		MalformedError::InvalidSchnorrProof,
+		MalformedError::UnclaimedCoinCom,
+		MalformedError::UnclaimedNullifier,
+		MalformedError::Unbalanced,
+		MalformedError::Zswap,
+		MalformedError::BuiltinDecode,
+		MalformedError::GuaranteedLimit,
+		MalformedError::MergingContracts,
+		MalformedError::CantMergeTypes,
+		MalformedError::ClaimOverflow,
+		MalformedError::ClaimCoinMismatch,
+		MalformedError::KeyNotInCommittee,
+		MalformedError::InvalidCommitteeSignature,
+		MalformedError::ThresholdMissed,
+		MalformedError::TooManyZswapEntries,
+		MalformedError::BalanceCheckOverspend,
+		MalformedError::UnknownError,
+	];
+}
+
+impl SystemTransactionError {
+	pub const ALL: [SystemTransactionError; 9] = [
+		SystemTransactionError::InsufficientTreasuryFunds,
+		SystemTransactionError::CommitmentAlreadyPresent,
+		SystemTransactionError::UnknownError,
+		SystemTransactionError::ReplayProtectionFailure,
+		SystemTransactionError::IllegalReserveDistribution,
+		SystemTransactionError::GenerationInfoAlreadyPresent,
+		SystemTransactionError::InvalidBasisPoints,
+		SystemTransactionError::InvariantViolation,
+		SystemTransactionError::IllegalPayout,
+	];
+}
+
+impl TransactionError {
+	/// Every `TransactionError`, in error code order
+	pub fn all() -> impl Iterator<Item = TransactionError> {
+		InvalidError::ALL
+			.into_iter()
+			.map(Invalid)
+			.chain(MalformedError::ALL.into_iter().map(Malformed))
+			.chain(SystemTransactionError::ALL.into_iter().map(SystemTransaction))
+	}
+}
+
+/// The error code table for `TransactionError`, as reported in
+/// `InvalidTransaction::Custom` when a transaction is rejected by the pool.
+///
+/// Each entry is the `u8` error code and the error name, e.g. `(102, "Invalid(ContractNotPresent)")`.
+#[cfg(feature = "std")]
+pub fn transaction_error_codes() -> Vec<(u8, String)> {
+	TransactionError::all()
+		.map(|e| {
+			let name = format!("{e:?}");
+			(LedgerApiError::Transaction(e).into(), name)
+		})
+		.collect()
+}
+
+// grcov-excl-start
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn transaction_error_codes_should_be_unique() {
+		let codes = transaction_error_codes();
+		let unique: std::collections::BTreeSet<u8> = codes.iter().map(|(code, _)| *code).collect();
+
+		assert_eq!(codes.len(), unique.len(), "duplicated error codes: {codes:?}");
+		assert!(codes.iter().all(|(code, _)| (100..150).contains(code)));
+	}
+
+	#[test]
+	fn system_transaction_error_codes_should_be_stable() {
+		let code = |e| u8::from(LedgerApiError::Transaction(SystemTransaction(e)));
+
+		assert_eq!(code(SystemTransactionError::InsufficientTreasuryFunds), 140);
+		assert_eq!(code(SystemTransactionError::InvariantViolation), 147);
+		assert_eq!(code(SystemTransactionError::IllegalPayout), 148);
+	}
+}
+// grcov-excl-stop
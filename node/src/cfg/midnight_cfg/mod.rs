@@ -12,6 +12,7 @@
 // limitations under the License.
 
 use documented::{Documented, DocumentedFields as _};
+use midnight_primitives_mainchain_follower::chain_sync::Point;
 use serde::{Deserialize, Serialize};
 use serde_valid::{Validate, validation};
 use sidechain_domain::mainchain_epoch::MainchainEpochConfig;
@@ -24,6 +25,7 @@ use super::{CfgHelp, HelpField, cfg_help, error::CfgError, util::get_keys};
 /// Parameters specific to Midnight
 pub struct MidnightCfg {
 	/// On start-up, wipe the chain
+	/// An incomplete ledger storage can instead be repaired with `check-ledger-storage --repair`
 	pub wipe_chain_state: bool,
 
 	/// Path to file containing a secret string to use as the AURA seed (32 bytes)
@@ -47,6 +49,17 @@ pub struct MidnightCfg {
 	/// Used in the sidechains library
 	#[validate(custom = |s| maybe(s, path_exists))]
 	pub mock_registrations_file: Option<String>,
+	/// cNight observation fixture to replay, a JSON lines file of recorded data source responses
+	/// Only if use_main_chain_follower_mock is true: cNight observations are replayed from it
+	/// instead of being randomly generated
+	#[validate(custom = |s| maybe(s, path_exists))]
+	pub replay_cnight_observation_file: Option<String>,
+	/// cNight observation fixture to record the db-sync or Ogmios data source responses to
+	/// Only if use_main_chain_follower_mock is false. Not meant for production nodes
+	pub record_cnight_observation_file: Option<String>,
+	/// Maximum number of responses in the recorded cNight observation fixture, after which
+	/// recording stops
+	pub record_cnight_observation_max_responses: u64,
 
 	/// see partner-chains EpochConfig
 	#[serde(rename = "mc__first_epoch_timestamp_millis")]
@@ -68,6 +81,23 @@ pub struct MidnightCfg {
 	#[doc_tag(secret)]
 	pub db_sync_postgres_connection_string: Option<String>,
 
+	/// Observe cNight and the federated authority with Ogmios chain-sync instead of db-sync
+	/// The other main chain data sources still read from db-sync
+	pub use_ogmios_observation: bool,
+	/// Ogmios websocket URL, required if use_ogmios_observation is true
+	pub ogmios_url: Option<String>,
+	/// Directory of the Cardano UTXO index, required if use_ogmios_observation is true
+	pub ogmios_index_path: Option<String>,
+	/// Cardano point to index from, `origin` or `<slot>.<block hash>`. Defaults to `origin`
+	/// Must precede the deployment of the observed contracts
+	#[validate(custom = |s| maybe(s, valid_cardano_point))]
+	pub ogmios_start_point: Option<String>,
+	/// Path to the cNight addresses file of the network, as used by generate-cnight-genesis
+	/// Required if use_ogmios_observation is true, with federated_authority_config_file, so the
+	/// index watches the observed outputs from the start
+	#[validate(custom = |s| maybe(s, path_exists))]
+	pub cnight_addresses_file: Option<String>,
+
 	/// see partner-chains CandidateDataSourceCacheConfig and DbSyncBlockDataSourceConfig
 	pub cardano_security_parameter: Option<u32>,
 
@@ -83,6 +113,9 @@ pub struct MidnightCfg {
 
 	/// Size of ledger storage cache (number of nodes)
 	pub storage_cache_size: usize,
+
+	/// Maximum number of cached transaction validations, per ledger version
+	pub tx_validation_cache_size: u64,
 }
 
 fn main_chain_follower_vars(cfg: &MidnightCfg) -> Result<(), validation::Error> {
@@ -99,7 +132,19 @@ fn main_chain_follower_vars(cfg: &MidnightCfg) -> Result<(), validation::Error>
 					.to_string(),
 			));
 		}
+		if cfg.record_cnight_observation_file.is_some() {
+			return Err(validation::Error::Custom(
+				"record_cnight_observation_file can't be defined if use_main_chain_follower_mock is true."
+					.to_string(),
+			));
+		}
 	} else {
+		if cfg.replay_cnight_observation_file.is_some() {
+			return Err(validation::Error::Custom(
+				"replay_cnight_observation_file can only be defined if use_main_chain_follower_mock is true."
+					.to_string(),
+			));
+		}
 		if cfg.db_sync_postgres_connection_string.is_none() {
 			return Err(missing("db_sync_postgres_connection_string"));
 		}
@@ -112,10 +157,39 @@ fn main_chain_follower_vars(cfg: &MidnightCfg) -> Result<(), validation::Error>
 		if cfg.block_stability_margin.is_none() {
 			return Err(missing("block_stability_margin"));
 		}
+		if cfg.use_ogmios_observation {
+			if cfg.ogmios_url.is_none() {
+				return Err(validation::Error::Custom(
+					"ogmios_url must be defined if use_ogmios_observation is true.".to_string(),
+				));
+			}
+			if cfg.ogmios_index_path.is_none() {
+				return Err(validation::Error::Custom(
+					"ogmios_index_path must be defined if use_ogmios_observation is true."
+						.to_string(),
+				));
+			}
+			if cfg.cnight_addresses_file.is_none() {
+				return Err(validation::Error::Custom(
+					"cnight_addresses_file must be defined if use_ogmios_observation is true."
+						.to_string(),
+				));
+			}
+			if cfg.federated_authority_config_file.is_none() {
+				return Err(validation::Error::Custom(
+					"federated_authority_config_file must be defined if use_ogmios_observation is true."
+						.to_string(),
+				));
+			}
+		}
 	}
 	Ok(())
 }
 
+fn valid_cardano_point(point: &String) -> Result<(), validation::Error> {
+	point.parse::<Point>().map(|_| ()).map_err(validation::Error::Custom)
+}
+
 impl CfgHelp for MidnightCfg {
 	fn help(cur_cfg: Option<&config::Config>) -> Result<Vec<HelpField>, CfgError> {
 		cfg_help!(cur_cfg, Self)
@@ -186,6 +186,7 @@ pub fn get_chainspec_extrinsics(
 			CNightObservationCall::process_tokens {
 				utxos: observed_utxos_cnight.utxos.clone(),
 				next_cardano_position: observed_utxos_cnight.end.clone(),
+				cardano_tip_lag: observed_utxos_cnight.tip_lag(),
 			},
 		));
 		extrinsics.push(hex::encode(cnight_extrinsic.encode()));
@@ -0,0 +1,271 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integrity check of the ledger storage against the on-chain `pallet_midnight::StateKey`.
+//!
+//! A node crashing between persisting a ledger state and flushing the storage may be left
+//! with a `StateKey` whose arena nodes are missing from the ledger storage. The missing nodes
+//! can be rebuilt by re-executing the blocks since the last complete ledger state.
+
+use clap::Parser;
+use frame_support::storage::storage_prefix;
+use midnight_node_ledger::types::LedgerStateCheck;
+use midnight_node_runtime::Block;
+use parity_scale_codec::Decode;
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::{Backend, BlockBackend, StorageProvider};
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::StorageKey;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT},
+};
+use std::sync::Arc;
+
+type Hash = <Block as BlockT>::Hash;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CheckLedgerStorageError {
+	#[error("Ledger state of block {0} is incomplete, run again with `--repair` to rebuild it")]
+	Incomplete(String),
+
+	#[error("No complete ledger state found in the ancestry of block {0}")]
+	NoCompleteAncestor(String),
+
+	#[error("Ledger state of block {0} is still incomplete after re-executing the blocks")]
+	RepairFailed(String),
+
+	#[error("Failed to re-execute block {0}: {1}")]
+	Execution(String, String),
+
+	#[error("Block {0} not found")]
+	BlockNotFound(String),
+
+	#[error("No ledger state key at block {0}")]
+	StateKeyNotFound(String),
+
+	#[error("Failed to check the ledger state of block {0}: {1}")]
+	Ledger(String, String),
+
+	#[error("Failed to read the ledger state key: {0}")]
+	Storage(#[from] sp_blockchain::Error),
+
+	#[error("Failed to decode ledger state key: {0}")]
+	Decode(#[from] parity_scale_codec::Error),
+}
+
+impl From<CheckLedgerStorageError> for sc_cli::Error {
+	fn from(error: CheckLedgerStorageError) -> Self {
+		sc_cli::Error::Application(Box::new(error))
+	}
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct CheckLedgerStorageCmd {
+	/// Block hash or number whose ledger state is checked. Defaults to the best block
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Rebuild an incomplete ledger state by re-executing the blocks since the last
+	/// complete one
+	#[arg(long)]
+	pub repair: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl CheckLedgerStorageCmd {
+	pub async fn run<BE, C>(&self, client: Arc<C>) -> sc_cli::Result<()>
+	where
+		BE: Backend<Block>,
+		C: StorageProvider<Block, BE>
+			+ HeaderBackend<Block>
+			+ BlockBackend<Block>
+			+ ProvideRuntimeApi<Block>,
+		C::Api: Core<Block>,
+	{
+		let hash = match &self.at {
+			Some(at) => match at.parse::<Block>().map_err(sc_cli::Error::Input)? {
+				BlockId::Hash(hash) => hash,
+				BlockId::Number(number) => client
+					.hash(number)?
+					.ok_or_else(|| CheckLedgerStorageError::BlockNotFound(number.to_string()))?,
+			},
+			None => client.info().best_hash,
+		};
+
+		if is_complete(client.as_ref(), hash)? {
+			log::info!("Ledger state of block {hash:?} is complete");
+			return Ok(());
+		}
+		if !self.repair {
+			return Err(CheckLedgerStorageError::Incomplete(hash.to_string()).into());
+		}
+
+		let to_execute = blocks_to_reexecute(
+			hash,
+			client.info().genesis_hash,
+			|hash| is_complete(client.as_ref(), hash),
+			|hash| parent_hash(client.as_ref(), hash),
+		)?;
+		log::info!("Re-executing {} blocks since the last complete ledger state", to_execute.len());
+
+		// Re-executing a block persists and flushes the ledger states it produces
+		for block_hash in to_execute {
+			let block = client
+				.block(block_hash)?
+				.ok_or_else(|| CheckLedgerStorageError::BlockNotFound(block_hash.to_string()))?
+				.block;
+			let parent = *block.header().parent_hash();
+			client.runtime_api().execute_block(parent, block).map_err(|e| {
+				CheckLedgerStorageError::Execution(block_hash.to_string(), e.to_string())
+			})?;
+		}
+
+		if !is_complete(client.as_ref(), hash)? {
+			return Err(CheckLedgerStorageError::RepairFailed(hash.to_string()).into());
+		}
+		log::info!("Repaired the ledger state of block {hash:?}");
+		Ok(())
+	}
+}
+
+impl CliConfiguration for CheckLedgerStorageCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// Checks the ledger state of block `hash`, logging each of its missing or undecodable nodes
+fn is_complete<BE, C>(client: &C, hash: Hash) -> Result<bool, CheckLedgerStorageError>
+where
+	BE: Backend<Block>,
+	C: StorageProvider<Block, BE>,
+{
+	let key = StorageKey(storage_prefix(b"Midnight", b"StateKey").to_vec());
+	let state_key = client
+		.storage(hash, &key)?
+		.ok_or_else(|| CheckLedgerStorageError::StateKeyNotFound(hash.to_string()))?;
+	let state_key = Vec::<u8>::decode(&mut &state_key.0[..])?;
+
+	let check = midnight_node_ledger::check_ledger_state(&state_key)
+		.map_err(|e| CheckLedgerStorageError::Ledger(hash.to_string(), e.to_string()))?;
+	report(hash, &check);
+	Ok(check.is_complete())
+}
+
+fn report(hash: Hash, check: &LedgerStateCheck) {
+	for node in &check.missing {
+		log::warn!("Ledger state of block {hash:?} is missing node 0x{}", hex::encode(node));
+	}
+	if let Some(error) = &check.corrupt {
+		log::warn!("Ledger state of block {hash:?} can't be loaded: {error}");
+	}
+	if !check.is_complete() {
+		log::warn!(
+			"Ledger state of block {hash:?}: {} nodes found, {} missing",
+			check.nodes,
+			check.missing.len()
+		);
+	}
+}
+
+/// Walks back from `hash` to the last block with a complete ledger state, returning the
+/// blocks to re-execute on top of it, oldest first
+fn blocks_to_reexecute(
+	hash: Hash,
+	genesis_hash: Hash,
+	mut is_complete: impl FnMut(Hash) -> Result<bool, CheckLedgerStorageError>,
+	mut parent_hash: impl FnMut(Hash) -> Result<Hash, CheckLedgerStorageError>,
+) -> Result<Vec<Hash>, CheckLedgerStorageError> {
+	let mut to_execute = vec![hash];
+	let mut parent = parent_hash(hash)?;
+	while !is_complete(parent)? {
+		if parent == genesis_hash {
+			return Err(CheckLedgerStorageError::NoCompleteAncestor(hash.to_string()));
+		}
+		to_execute.push(parent);
+		parent = parent_hash(parent)?;
+	}
+	to_execute.reverse();
+	Ok(to_execute)
+}
+
+fn parent_hash<C>(client: &C, hash: Hash) -> Result<Hash, CheckLedgerStorageError>
+where
+	C: HeaderBackend<Block>,
+{
+	client
+		.header(hash)
+		.ok()
+		.flatten()
+		.map(|header| *header.parent_hash())
+		.ok_or_else(|| CheckLedgerStorageError::BlockNotFound(hash.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+
+	fn hash(n: u8) -> Hash {
+		Hash::repeat_byte(n)
+	}
+
+	/// A chain 0 <- 1 <- .. <- 5 where the blocks in `complete` have a complete ledger state
+	fn walk_back(complete: &[u8]) -> Result<Vec<Hash>, CheckLedgerStorageError> {
+		let parents: HashMap<Hash, Hash> = (1..=5).map(|n| (hash(n), hash(n - 1))).collect();
+		blocks_to_reexecute(
+			hash(5),
+			hash(0),
+			|h| Ok(complete.iter().any(|n| hash(*n) == h)),
+			|h| {
+				parents
+					.get(&h)
+					.copied()
+					.ok_or_else(|| CheckLedgerStorageError::BlockNotFound(h.to_string()))
+			},
+		)
+	}
+
+	#[test]
+	fn should_reexecute_blocks_since_last_complete_state() {
+		assert_eq!(walk_back(&[0, 1, 2]).unwrap(), vec![hash(3), hash(4), hash(5)]);
+		assert_eq!(walk_back(&[0, 4]).unwrap(), vec![hash(5)]);
+		assert_eq!(walk_back(&[0]).unwrap(), vec![hash(1), hash(2), hash(3), hash(4), hash(5)]);
+	}
+
+	#[test]
+	fn should_fail_without_complete_ancestor() {
+		assert!(matches!(walk_back(&[]), Err(CheckLedgerStorageError::NoCompleteAncestor(_))));
+	}
+}
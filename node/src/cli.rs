@@ -16,6 +16,8 @@ use std::str::FromStr;
 use clap::Parser;
 
 use crate::cfg::Cfg;
+use crate::check_ledger_storage_cmd::CheckLedgerStorageCmd;
+use crate::ledger_snapshot_cmd::{ExportLedgerSnapshotCmd, ImportLedgerSnapshotCmd};
 use midnight_node_runtime::opaque::SessionKeys;
 use parity_scale_codec::Encode;
 use partner_chains_cli::{AURA, CROSS_CHAIN, CreateChainSpecConfig, GRANDPA, KeyDefinition};
@@ -75,6 +77,16 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export the ledger state of a given block into a portable, checksummed snapshot file.
+	ExportLedgerSnapshot(ExportLedgerSnapshotCmd),
+
+	/// Import a ledger snapshot into the ledger storage.
+	ImportLedgerSnapshot(ImportLedgerSnapshotCmd),
+
+	/// Check that the ledger state of a given block is complete in the ledger storage,
+	/// optionally rebuilding it.
+	CheckLedgerStorage(CheckLedgerStorageCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
@@ -21,6 +21,8 @@ use serde_json;
 use tokio::{fs::File, io::AsyncWriteExt};
 
 const UTXO_CAPACITY: usize = 1000;
+// The genesis observes everything up to the tip, only bounded by `UTXO_CAPACITY`
+const BLOCK_WINDOW: u32 = u32::MAX;
 
 #[derive(Debug, thiserror::Error)]
 pub enum CNightGenesisError {
@@ -37,12 +39,13 @@ pub enum CNightGenesisError {
 fn create_inherent(
 	utxos: Vec<ObservedUtxo>,
 	next_cardano_position: CardanoPosition,
+	cardano_tip_lag: u32,
 ) -> InherentData {
 	let mut inherent_data = InherentData::new();
 	inherent_data
 		.put_data(
 			INHERENT_IDENTIFIER,
-			&MidnightObservationTokenMovement { utxos, next_cardano_position },
+			&MidnightObservationTokenMovement { utxos, next_cardano_position, cardano_tip_lag },
 		)
 		.expect("inherent data insertion should not fail");
 	inherent_data
@@ -57,7 +60,8 @@ struct PalletExecResult {
 
 fn exec_pallet(utxos: &ObservedUtxos) -> PalletExecResult {
 	mock::new_test_ext().execute_with(|| {
-		let inherent_data = create_inherent(utxos.utxos.clone(), utxos.end.clone());
+		let inherent_data =
+			create_inherent(utxos.utxos.clone(), utxos.end.clone(), utxos.tip_lag());
 		let call = mock::CNightObservation::create_inherent(&inherent_data)
 			.expect("Expected to create inherent call");
 		let call = mock::RuntimeCall::CNightObservation(call);
@@ -88,6 +92,7 @@ pub async fn generate_cnight_genesis(
 	};
 
 	let mut all_utxos = Vec::new();
+	let mut tip_block_number = 0;
 
 	loop {
 		let observed = cnight_observation_data_source
@@ -96,11 +101,13 @@ pub async fn generate_cnight_genesis(
 				&current_position,
 				cardano_tip.clone(),
 				UTXO_CAPACITY,
+				BLOCK_WINDOW,
 			)
 			.await
 			.map_err(CNightGenesisError::UtxoQueryError)?;
 
 		current_position = observed.end;
+		tip_block_number = observed.tip_block_number;
 		log::info!(
 			"Fetched {} cNight utxos. Current tip: {current_position:?}",
 			observed.utxos.len(),
@@ -117,6 +124,7 @@ pub async fn generate_cnight_genesis(
 		start: CardanoPosition::default(),
 		end: current_position,
 		utxos: all_utxos,
+		tip_block_number,
 	};
 
 	let PalletExecResult { mappings, utxo_owners, next_cardano_position, system_tx } =
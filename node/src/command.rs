@@ -126,8 +126,11 @@ fn run_node(cfg: Cfg) -> sc_cli::Result<()> {
 	let properties = chain_spec.properties();
 	let genesis_state_hex = properties.get("genesis_state").unwrap().as_str().unwrap();
 	let genesis_state = hex::decode(genesis_state_hex).unwrap();
-	let storage_config =
-		StorageInit { genesis_state, cache_size: cfg.midnight_cfg.storage_cache_size };
+	let storage_config = StorageInit {
+		genesis_state,
+		cache_size: cfg.midnight_cfg.storage_cache_size,
+		tx_validation_cache_size: cfg.midnight_cfg.tx_validation_cache_size,
+	};
 
 	let keystore: KeystorePtr = {
 		let res = run_cmd.keystore_params().unwrap().keystore_config(&config_dir)?;
@@ -212,6 +215,7 @@ fn run_subcommand(subcommand: Subcommand, cfg: Cfg) -> sc_cli::Result<()> {
 	let storage_config = StorageInit {
 		genesis_state: midnight_node_res::networks::UndeployedNetwork.genesis_state().to_vec(),
 		cache_size: cfg.midnight_cfg.storage_cache_size,
+		tx_validation_cache_size: cfg.midnight_cfg.tx_validation_cache_size,
 	};
 
 	match subcommand {
@@ -282,6 +286,53 @@ fn run_subcommand(subcommand: Subcommand, cfg: Cfg) -> sc_cli::Result<()> {
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		},
+		Subcommand::ExportLedgerSnapshot(ref cmd) => {
+			let runner = cfg.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let data_sources = config.tokio_handle.block_on(
+					crate::main_chain_follower::create_cached_main_chain_follower_data_sources(
+						cfg.midnight_cfg.clone(),
+						None,
+					),
+				)?;
+				let ledger_storage_path = config.base_path.path().join("ledger_storage");
+				let cache_size = storage_config.cache_size;
+				let PartialComponents { client, task_manager, .. } =
+					service::new_partial(&config, epoch_config, data_sources, storage_config)?;
+				Ok((cmd.run(client, ledger_storage_path, cache_size), task_manager))
+			})
+		},
+		Subcommand::ImportLedgerSnapshot(ref cmd) => {
+			let runner = cfg.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let data_sources = config.tokio_handle.block_on(
+					crate::main_chain_follower::create_cached_main_chain_follower_data_sources(
+						cfg.midnight_cfg.clone(),
+						None,
+					),
+				)?;
+				let ledger_storage_path = config.base_path.path().join("ledger_storage");
+				let cache_size = storage_config.cache_size;
+				// The GRANDPA block import records the authority set of the snapshot block
+				let PartialComponents { task_manager, other: (block_import, ..), .. } =
+					service::new_partial(&config, epoch_config, data_sources, storage_config)?;
+				Ok((cmd.run(block_import, ledger_storage_path, cache_size), task_manager))
+			})
+		},
+		Subcommand::CheckLedgerStorage(ref cmd) => {
+			let runner = cfg.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let data_sources = config.tokio_handle.block_on(
+					crate::main_chain_follower::create_cached_main_chain_follower_data_sources(
+						cfg.midnight_cfg.clone(),
+						None,
+					),
+				)?;
+				let PartialComponents { client, task_manager, .. } =
+					service::new_partial(&config, epoch_config, data_sources, storage_config)?;
+				Ok((cmd.run(client), task_manager))
+			})
+		},
 		Subcommand::ImportBlocks(ref cmd) => {
 			let runner = cfg.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -0,0 +1,268 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pruning of the ledger storage for non-archive nodes.
+//!
+//! The ledger host functions record every ledger state root persisted while executing a block,
+//! i.e. one per applied transaction plus the post block update. Once a block is finalized, the
+//! roots persisted by it and by its stale siblings join the pruning window, and the roots
+//! persisted by stale blocks' children are released right away. When a block falls out of the
+//! `--state-pruning` window, every root it persisted is released and the ledger storage is
+//! garbage collected periodically.
+//!
+//! Pruning runs as a finality action of the client: it is serialized with block import and the
+//! window is written to the aux storage atomically with the finalization, so it survives
+//! restarts. The roots recorded for blocks which aren't finalized yet are written to the aux
+//! storage as they are recorded, and the garbage collection runs on its own blocking task.
+//!
+//! Both ledger versions are pruned: once the ledger storage is switched to the hard-fork
+//! version, the roots persisted before the switch are released through the new version.
+
+use midnight_node_runtime::opaque::Block;
+use midnight_primitives_ledger::{LedgerMetrics, LedgerMetricsExt};
+use parity_scale_codec::{Decode, Encode};
+use sc_client_api::{AuxStore, FinalityNotification, PreCommitActions};
+use sc_service::{PruningMode, SpawnTaskHandle};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto};
+use std::{
+	collections::VecDeque,
+	sync::{
+		Arc, Mutex,
+		mpsc::{Receiver, SyncSender, TrySendError, sync_channel},
+	},
+	time::Instant,
+};
+
+const LOG_TARGET: &str = "ledger-pruning";
+
+/// Aux storage key of the pruning window
+const PRUNING_WINDOW_KEY: &[u8] = b"midnight_ledger_pruning_window";
+
+/// Aux storage key of the ledger state roots recorded for blocks which aren't finalized yet
+const PERSISTED_ROOTS_KEY: &[u8] = b"midnight_ledger_persisted_roots";
+
+/// Number of released ledger state roots between two garbage collections of the ledger storage
+const GC_INTERVAL: u32 = 32;
+
+type Hash = <Block as BlockT>::Hash;
+
+/// Number of finalized blocks whose ledger states are kept, if the node prunes its state.
+/// `None` is Substrate's default pruning, not an archive node.
+pub fn blocks_to_keep(state_pruning: &Option<PruningMode>) -> Option<u32> {
+	match state_pruning.clone().unwrap_or_default() {
+		PruningMode::Constrained(constraints) => constraints.max_blocks,
+		_ => None,
+	}
+}
+
+/// Finalized blocks whose persisted ledger state roots are still held, oldest first
+#[derive(Debug, Default, Encode, Decode)]
+struct PruningWindow {
+	/// Last finalized block whose child blocks' roots joined the window
+	last_finalized: Option<Hash>,
+	blocks: VecDeque<(u32, Vec<Vec<u8>>)>,
+	/// Roots released since the last garbage collection
+	released_since_gc: u32,
+}
+
+impl PruningWindow {
+	/// Records the roots persisted by a newly finalized block and its stale siblings, and
+	/// returns the roots of the blocks which fell out of the window. Roots are reference
+	/// counted by the ledger storage, so each of them is released once per `persist`.
+	fn finalize(&mut self, blocks_to_keep: u32, number: u32, roots: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
+		self.blocks.push_back((number, roots));
+
+		let mut released = Vec::new();
+		while let Some((oldest, _)) = self.blocks.front() {
+			if oldest.saturating_add(blocks_to_keep) >= number {
+				break;
+			}
+			if let Some((_, roots)) = self.blocks.pop_front() {
+				released.extend(roots);
+			}
+		}
+		released
+	}
+}
+
+/// Registers ledger pruning as a finality action of `client`, starts recording the roots
+/// persisted by block execution into the aux storage of `backend`, and spawns the garbage
+/// collection of the ledger storage
+pub fn register<C, B>(
+	client: &C,
+	backend: Arc<B>,
+	spawn_handle: SpawnTaskHandle,
+	blocks_to_keep: u32,
+	metrics: Arc<Mutex<Option<LedgerMetrics>>>,
+) -> sp_blockchain::Result<()>
+where
+	C: AuxStore + HeaderBackend<Block> + PreCommitActions<Block>,
+	B: AuxStore + Send + Sync + 'static,
+{
+	log::info!(target: LOG_TARGET, "Pruning ledger states older than {blocks_to_keep} finalized blocks");
+
+	let mut window = match client.get_aux(PRUNING_WINDOW_KEY)? {
+		Some(encoded) => PruningWindow::decode(&mut &encoded[..])
+			.map_err(|e| sp_blockchain::Error::Backend(e.to_string()))?,
+		None => PruningWindow::default(),
+	};
+	// Blocks finalized before the roots are recorded have nothing to release
+	if window.last_finalized.is_none() {
+		window.last_finalized = Some(client.info().finalized_hash);
+	}
+
+	let roots = match backend.get_aux(PERSISTED_ROOTS_KEY)? {
+		Some(encoded) => midnight_node_ledger::PersistedRoots::decode(&mut &encoded[..])
+			.map_err(|e| sp_blockchain::Error::Backend(e.to_string()))?,
+		None => Default::default(),
+	};
+	// Written straight to the backend, without the import lock held by the block import
+	// executing the block
+	midnight_node_ledger::record_persisted_roots(roots, move |encoded| {
+		if let Err(e) = backend.insert_aux(&[(PERSISTED_ROOTS_KEY, &encoded[..])], &[]) {
+			log::warn!(target: LOG_TARGET, "Failed to write the persisted ledger state roots: {e}");
+		}
+	});
+
+	let (gc_trigger, gc_requests) = sync_channel(1);
+	let gc_metrics = LedgerMetricsExt::new(metrics.clone());
+	spawn_handle.spawn_blocking("ledger-storage-gc", Some("ledger-pruning"), async move {
+		run_gc(gc_requests, gc_metrics)
+	});
+
+	let window = Mutex::new(window);
+	let metrics = Mutex::new(LedgerMetricsExt::new(metrics));
+	client.register_finality_action(Box::new(move |notification| {
+		let mut window = window.lock().unwrap_or_else(|e| e.into_inner());
+		let mut metrics = metrics.lock().unwrap_or_else(|e| e.into_inner());
+		on_finality(&mut window, blocks_to_keep, notification, &mut metrics, &gc_trigger);
+		vec![(PRUNING_WINDOW_KEY.to_vec(), Some(window.encode()))]
+	}));
+	Ok(())
+}
+
+fn on_finality(
+	window: &mut PruningWindow,
+	blocks_to_keep: u32,
+	notification: &FinalityNotification<Block>,
+	metrics: &mut LedgerMetricsExt,
+	gc_trigger: &SyncSender<u32>,
+) {
+	let finalized: Vec<Hash> = notification
+		.tree_route
+		.iter()
+		.copied()
+		.chain(std::iter::once(notification.hash))
+		.collect();
+	let finalized_number: u32 = (*notification.header.number()).unique_saturated_into();
+	let first_number = finalized_number.saturating_sub(notification.tree_route.len() as u32);
+
+	let mut released = Vec::new();
+	let mut parent = window.last_finalized.unwrap_or(*notification.header.parent_hash());
+	for (number, hash) in (first_number..).zip(finalized) {
+		let roots = midnight_node_ledger::take_persisted_roots(parent.as_ref());
+		released.extend(window.finalize(blocks_to_keep, number, roots));
+		parent = hash;
+	}
+	window.last_finalized = Some(parent);
+
+	// Children of stale blocks can never be finalized
+	for stale in notification.stale_blocks.iter() {
+		released.extend(midnight_node_ledger::take_persisted_roots(stale.hash.as_ref()));
+	}
+
+	for state_root in released {
+		match midnight_node_ledger::unpersist_ledger_state(&state_root) {
+			Ok(()) => {
+				window.released_since_gc += 1;
+				metrics.inc_pruned_states(1);
+			},
+			Err(e) => log::warn!(
+				target: LOG_TARGET,
+				"Failed to prune ledger state 0x{}: {e}",
+				hex::encode(&state_root)
+			),
+		}
+	}
+
+	if window.released_since_gc >= GC_INTERVAL {
+		// A collection still pending covers these roots too
+		match gc_trigger.try_send(window.released_since_gc) {
+			Ok(()) => window.released_since_gc = 0,
+			Err(TrySendError::Full(_)) => {},
+			Err(TrySendError::Disconnected(_)) => {
+				log::warn!(target: LOG_TARGET, "Ledger storage garbage collection stopped")
+			},
+		}
+	}
+}
+
+/// Garbage collects the ledger storage on every request, each carrying the number of ledger
+/// states released since the previous collection
+fn run_gc(requests: Receiver<u32>, mut metrics: LedgerMetricsExt) {
+	for released in requests {
+		let start = Instant::now();
+		midnight_node_ledger::gc_ledger_storage();
+		let elapsed = start.elapsed().as_secs_f64();
+
+		log::debug!(
+			target: LOG_TARGET,
+			"Garbage collected {released} ledger states in {elapsed:.3}s"
+		);
+		metrics.observe_storage_gc_time(elapsed, "ledger_state");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_keep_states_inside_window() {
+		let mut window = PruningWindow::default();
+
+		assert!(window.finalize(2, 1, vec![vec![1]]).is_empty());
+		assert!(window.finalize(2, 2, vec![vec![2]]).is_empty());
+		assert!(window.finalize(2, 3, vec![vec![3]]).is_empty());
+		assert_eq!(window.finalize(2, 4, vec![vec![4]]), vec![vec![1]]);
+		assert_eq!(window.finalize(2, 6, vec![vec![6]]), vec![vec![2], vec![3]]);
+	}
+
+	#[test]
+	fn should_release_every_root_a_block_persisted() {
+		let mut window = PruningWindow::default();
+
+		// One root per transaction and the post block update, of the block and a stale sibling
+		assert!(window.finalize(1, 1, vec![vec![1], vec![2], vec![1], vec![3]]).is_empty());
+		// Empty block, the ledger state is unchanged but persisted again
+		assert!(window.finalize(1, 2, vec![vec![3]]).is_empty());
+		assert_eq!(window.finalize(1, 3, vec![vec![4]]), vec![vec![1], vec![2], vec![1], vec![3]]);
+		assert_eq!(window.finalize(1, 4, vec![vec![5]]), vec![vec![3]]);
+	}
+
+	#[test]
+	fn should_restore_window_from_aux_storage() {
+		let mut window = PruningWindow::default();
+		window.last_finalized = Some(Hash::repeat_byte(7));
+		window.finalize(2, 1, vec![vec![1]]);
+		window.finalize(2, 2, vec![vec![2]]);
+		window.released_since_gc = 5;
+
+		let mut restored = PruningWindow::decode(&mut &window.encode()[..]).unwrap();
+		assert_eq!(restored.last_finalized, window.last_finalized);
+		assert_eq!(restored.released_since_gc, 5);
+		assert!(restored.finalize(2, 3, vec![vec![3]]).is_empty());
+		assert_eq!(restored.finalize(2, 4, vec![vec![4]]), vec![vec![1]]);
+	}
+}
@@ -0,0 +1,802 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Export and import of the ledger state reachable from `pallet_midnight::StateKey`, together
+//! with the Substrate state of the same block, to bootstrap new nodes without re-executing the
+//! chain.
+
+use clap::Parser;
+use frame_support::storage::storage_prefix;
+use midnight_node_ledger::LedgerVersion;
+use midnight_node_runtime::Block;
+use pallet_midnight::MidnightRuntimeApi;
+use parity_scale_codec::{Decode, Encode, IoReader};
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::{Backend, StorageProvider};
+use sc_consensus::{
+	BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult, ImportedState, StateAction,
+	StorageChanges,
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockOrigin;
+use sp_core::{
+	hashing::blake2_256,
+	storage::{ChildInfo, StorageKey, well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX},
+};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_state_machine::{KeyValueStates, KeyValueStorageLevel};
+use std::{
+	fs::File,
+	io::{self, BufReader, BufWriter, Read, Write},
+	path::PathBuf,
+	sync::Arc,
+};
+
+const SNAPSHOT_MAGIC: [u8; 8] = *b"MNLEDGER";
+const SNAPSHOT_VERSION: u32 = 3;
+
+/// Size of the ledger state chunks of a snapshot file, and the size its storage batches are
+/// cut at
+const CHUNK_SIZE: usize = 1 << 20;
+
+type Header = <Block as BlockT>::Header;
+
+/// Top trie key/value pairs of the snapshot block
+pub type TopStorage = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// Child tries of the snapshot block, by prefixed storage key
+pub type ChildStorage = Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum LedgerSnapshotError {
+	#[error("Not a ledger snapshot file")]
+	BadMagic,
+
+	#[error("Unsupported ledger snapshot version {0}")]
+	UnsupportedVersion(u32),
+
+	#[error("Ledger snapshot checksum mismatch")]
+	ChecksumMismatch,
+
+	#[error("Snapshot header hashes to {computed}, but the snapshot block hash is {expected}")]
+	HeaderHashMismatch { expected: String, computed: String },
+
+	#[error("Unexpected {0} frame in the ledger snapshot")]
+	UnexpectedFrame(&'static str),
+
+	#[error("Ledger state hashes to {computed}, but the snapshot state key is {expected}")]
+	StateKeyMismatch { expected: String, computed: String },
+
+	#[error("The snapshot Substrate state does not hold the snapshot ledger state key")]
+	StorageStateKeyMismatch,
+
+	#[error("Block {0} not found")]
+	BlockNotFound(String),
+
+	#[error("No ledger state key at block {0}")]
+	StateKeyNotFound(String),
+
+	#[error("Unknown ledger version 0x{0} at block {1}")]
+	UnknownLedgerVersion(String, String),
+
+	#[error("Failed to import the snapshot block: {0}")]
+	BlockImport(String),
+
+	#[error("Failed to decode ledger snapshot: {0}")]
+	Decode(#[from] parity_scale_codec::Error),
+
+	#[error("Failed to write ledger snapshot: {0}")]
+	Io(#[from] io::Error),
+
+	#[error("Ledger error: {0}")]
+	Ledger(String),
+}
+
+impl From<LedgerSnapshotError> for sc_cli::Error {
+	fn from(error: LedgerSnapshotError) -> Self {
+		sc_cli::Error::Application(Box::new(error))
+	}
+}
+
+/// First frame of a snapshot file
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct SnapshotHeader {
+	magic: [u8; 8],
+	version: u32,
+	/// Block whose `StateKey` the snapshot was taken from
+	pub block_number: u64,
+	pub block_hash: Vec<u8>,
+	/// SCALE encoded header of the snapshot block
+	pub header: Vec<u8>,
+	/// Ledger version the ledger state is serialized with
+	pub ledger_version: LedgerVersion,
+	/// Tagged serialized key of the ledger state in the storage arena
+	pub state_key: Vec<u8>,
+}
+
+impl SnapshotHeader {
+	pub fn new(
+		block_number: u64,
+		block_hash: Vec<u8>,
+		header: Vec<u8>,
+		ledger_version: LedgerVersion,
+		state_key: Vec<u8>,
+	) -> Self {
+		Self {
+			magic: SNAPSHOT_MAGIC,
+			version: SNAPSHOT_VERSION,
+			block_number,
+			block_hash,
+			header,
+			ledger_version,
+			state_key,
+		}
+	}
+}
+
+/// Frames of a snapshot file following its header, each written as its SCALE encoding.
+///
+/// The Substrate state comes first, in batches, then the ledger state in chunks of its
+/// tagged serialization, so that neither is written or read as a whole.
+#[derive(Debug, Encode, Decode)]
+enum Frame {
+	/// Batch of top trie key/value pairs
+	Storage(TopStorage),
+	/// Batch of key/value pairs of the child trie under a prefixed storage key
+	ChildStorage(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>),
+	/// Chunk of the tagged serialized ledger state
+	LedgerState(Vec<u8>),
+	/// Checksum of the header and of every frame before it, last in the file
+	End([u8; 32]),
+}
+
+impl Frame {
+	fn name(&self) -> &'static str {
+		match self {
+			Frame::Storage(_) => "storage",
+			Frame::ChildStorage(..) => "child storage",
+			Frame::LedgerState(_) => "ledger state",
+			Frame::End(_) => "end",
+		}
+	}
+}
+
+/// Chains the checksum of a snapshot file with the next frame written or read
+fn chain_checksum(checksum: [u8; 32], frame: &[u8]) -> [u8; 32] {
+	blake2_256(&[&checksum[..], frame].concat())
+}
+
+/// Writes a snapshot file frame by frame. The bytes written to it through `Write` are the
+/// tagged serialized ledger state, which must come after the Substrate state.
+pub struct SnapshotWriter<W: Write> {
+	writer: W,
+	checksum: [u8; 32],
+	chunk: Vec<u8>,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+	pub fn new(mut writer: W, header: &SnapshotHeader) -> io::Result<Self> {
+		let header = header.encode();
+		writer.write_all(&header)?;
+		Ok(Self { writer, checksum: chain_checksum([0; 32], &header), chunk: Vec::new() })
+	}
+
+	fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
+		let frame = frame.encode();
+		self.checksum = chain_checksum(self.checksum, &frame);
+		self.writer.write_all(&frame)
+	}
+
+	/// Writes a batch of top trie key/value pairs
+	pub fn write_storage(&mut self, key_values: TopStorage) -> io::Result<()> {
+		self.write_frame(&Frame::Storage(key_values))
+	}
+
+	/// Writes a batch of key/value pairs of the child trie under `prefixed_key`
+	pub fn write_child_storage(
+		&mut self,
+		prefixed_key: Vec<u8>,
+		key_values: Vec<(Vec<u8>, Vec<u8>)>,
+	) -> io::Result<()> {
+		self.write_frame(&Frame::ChildStorage(prefixed_key, key_values))
+	}
+
+	/// Writes the last ledger state chunk and the checksum
+	pub fn finish(mut self) -> io::Result<W> {
+		self.flush()?;
+		let checksum = self.checksum;
+		self.write_frame(&Frame::End(checksum))?;
+		self.writer.flush()?;
+		Ok(self.writer)
+	}
+}
+
+impl<W: Write> Write for SnapshotWriter<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let len = buf.len().min(CHUNK_SIZE - self.chunk.len());
+		self.chunk.extend_from_slice(&buf[..len]);
+		if self.chunk.len() == CHUNK_SIZE {
+			self.flush()?;
+		}
+		Ok(len)
+	}
+
+	/// Writes the pending ledger state bytes as a chunk
+	fn flush(&mut self) -> io::Result<()> {
+		if !self.chunk.is_empty() {
+			let chunk = std::mem::take(&mut self.chunk);
+			self.write_frame(&Frame::LedgerState(chunk))?;
+		}
+		Ok(())
+	}
+}
+
+/// Substrate state of the snapshot block
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SubstrateState {
+	pub storage: TopStorage,
+	pub child_storage: ChildStorage,
+}
+
+impl SubstrateState {
+	/// Key/value levels of the Substrate state, as expected by a state import: the top trie
+	/// first, then each child trie under its prefixed key and root
+	pub fn key_value_states(self) -> KeyValueStates {
+		let mut levels = Vec::with_capacity(self.child_storage.len() + 1);
+		for (prefixed_key, key_values) in self.child_storage {
+			let state_root = self
+				.storage
+				.iter()
+				.find(|(key, _)| *key == prefixed_key)
+				.map(|(_, root)| root.clone())
+				.unwrap_or_default();
+			levels.push(KeyValueStorageLevel {
+				state_root,
+				parent_storage_keys: vec![prefixed_key],
+				key_values,
+			});
+		}
+		levels.insert(
+			0,
+			KeyValueStorageLevel {
+				state_root: Vec::new(),
+				parent_storage_keys: Vec::new(),
+				key_values: self.storage,
+			},
+		);
+		KeyValueStates(levels)
+	}
+
+	/// Checks that the Substrate state points to the ledger state at `state_key`
+	pub fn check_state_key(&self, state_key: &[u8]) -> Result<(), LedgerSnapshotError> {
+		let key = storage_prefix(b"Midnight", b"StateKey").to_vec();
+		let stored = self
+			.storage
+			.iter()
+			.find(|(k, _)| *k == key)
+			.map(|(_, value)| Vec::<u8>::decode(&mut &value[..]))
+			.transpose()?;
+		if stored.as_deref() != Some(state_key) {
+			return Err(LedgerSnapshotError::StorageStateKeyMismatch);
+		}
+		Ok(())
+	}
+}
+
+/// Reads a snapshot file frame by frame, checking its header on creation and its checksum on
+/// `finish`. The bytes read from it through `Read` are the tagged serialized ledger state,
+/// once the Substrate state is read.
+pub struct SnapshotReader<R: Read> {
+	reader: IoReader<R>,
+	pub header: SnapshotHeader,
+	checksum: [u8; 32],
+	pending: Option<Frame>,
+	chunk: Vec<u8>,
+	position: usize,
+}
+
+impl<R: Read> SnapshotReader<R> {
+	pub fn new(reader: R) -> Result<Self, LedgerSnapshotError> {
+		let mut reader = IoReader(reader);
+		let header = SnapshotHeader::decode(&mut reader)?;
+		if header.magic != SNAPSHOT_MAGIC {
+			return Err(LedgerSnapshotError::BadMagic);
+		}
+		if header.version != SNAPSHOT_VERSION {
+			return Err(LedgerSnapshotError::UnsupportedVersion(header.version));
+		}
+
+		let checksum = chain_checksum([0; 32], &header.encode());
+		Ok(Self { reader, header, checksum, pending: None, chunk: Vec::new(), position: 0 })
+	}
+
+	fn read_frame(&mut self) -> Result<Frame, LedgerSnapshotError> {
+		if let Some(frame) = self.pending.take() {
+			return Ok(frame);
+		}
+		let frame = Frame::decode(&mut self.reader)?;
+		if !matches!(frame, Frame::End(_)) {
+			self.checksum = chain_checksum(self.checksum, &frame.encode());
+		}
+		Ok(frame)
+	}
+
+	/// Reads the Substrate state, up to the ledger state
+	pub fn read_substrate_state(&mut self) -> Result<SubstrateState, LedgerSnapshotError> {
+		let mut state = SubstrateState::default();
+		loop {
+			match self.read_frame()? {
+				Frame::Storage(key_values) => state.storage.extend(key_values),
+				Frame::ChildStorage(prefixed_key, key_values) => {
+					match state.child_storage.last_mut() {
+						Some((key, trie)) if *key == prefixed_key => trie.extend(key_values),
+						_ => state.child_storage.push((prefixed_key, key_values)),
+					}
+				},
+				frame => {
+					self.pending = Some(frame);
+					return Ok(state);
+				},
+			}
+		}
+	}
+
+	/// Checks that the whole ledger state was read and that the file checksum matches
+	pub fn finish(mut self) -> Result<(), LedgerSnapshotError> {
+		if self.position != self.chunk.len() {
+			return Err(LedgerSnapshotError::UnexpectedFrame("ledger state"));
+		}
+		match self.read_frame()? {
+			Frame::End(checksum) if checksum == self.checksum => Ok(()),
+			Frame::End(_) => Err(LedgerSnapshotError::ChecksumMismatch),
+			frame => Err(LedgerSnapshotError::UnexpectedFrame(frame.name())),
+		}
+	}
+}
+
+impl<R: Read> Read for SnapshotReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		while self.position == self.chunk.len() {
+			match self.read_frame().map_err(io::Error::other)? {
+				Frame::LedgerState(chunk) => {
+					self.chunk = chunk;
+					self.position = 0;
+				},
+				frame => {
+					self.pending = Some(frame);
+					return Ok(0);
+				},
+			}
+		}
+		let len = buf.len().min(self.chunk.len() - self.position);
+		buf[..len].copy_from_slice(&self.chunk[self.position..self.position + len]);
+		self.position += len;
+		Ok(len)
+	}
+}
+
+/// Restores the ledger state of the snapshot into the default ledger storage, once it is
+/// checked to hash to the snapshot state key
+pub fn restore_ledger_state<R: Read>(
+	reader: &mut SnapshotReader<R>,
+) -> Result<(), LedgerSnapshotError> {
+	let state_key = reader.header.state_key.clone();
+	let computed = match reader.header.ledger_version {
+		LedgerVersion::Latest => midnight_node_ledger::read_ledger_state(&mut *reader, &state_key)
+			.map_err(|e| LedgerSnapshotError::Ledger(e.to_string()))?,
+		LedgerVersion::HardForkTest => {
+			midnight_node_ledger::read_ledger_state_hf(&mut *reader, &state_key)
+				.map_err(|e| LedgerSnapshotError::Ledger(e.to_string()))?
+		},
+	};
+	if computed != state_key {
+		return Err(LedgerSnapshotError::StateKeyMismatch {
+			expected: hex::encode(&state_key),
+			computed: hex::encode(&computed),
+		});
+	}
+	Ok(())
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct ExportLedgerSnapshotCmd {
+	/// Block hash or number to take the ledger state from. Defaults to the best block
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// File to write the snapshot to
+	#[arg(short, long, default_value = "ledger-snapshot.bin")]
+	pub output: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ExportLedgerSnapshotCmd {
+	/// Exports the snapshot of the block at `--at`. The ledger storage at `ledger_storage_path`
+	/// is reopened with the hard-fork ledger version if the block runs it.
+	pub async fn run<BE, C>(
+		&self,
+		client: Arc<C>,
+		ledger_storage_path: PathBuf,
+		cache_size: usize,
+	) -> sc_cli::Result<()>
+	where
+		BE: Backend<Block>,
+		C: StorageProvider<Block, BE> + HeaderBackend<Block> + ProvideRuntimeApi<Block>,
+		C::Api: MidnightRuntimeApi<Block>,
+	{
+		let hash = match &self.at {
+			Some(at) => match at.parse::<Block>().map_err(sc_cli::Error::Input)? {
+				BlockId::Hash(hash) => hash,
+				BlockId::Number(number) => client
+					.hash(number)?
+					.ok_or_else(|| LedgerSnapshotError::BlockNotFound(number.to_string()))?,
+			},
+			None => client.info().best_hash,
+		};
+		let header = client
+			.header(hash)?
+			.ok_or_else(|| LedgerSnapshotError::BlockNotFound(hash.to_string()))?;
+		let number = header.number;
+
+		let key = StorageKey(storage_prefix(b"Midnight", b"StateKey").to_vec());
+		let state_key = client
+			.storage(hash, &key)?
+			.ok_or_else(|| LedgerSnapshotError::StateKeyNotFound(hash.to_string()))?;
+		let state_key =
+			Vec::<u8>::decode(&mut &state_key.0[..]).map_err(LedgerSnapshotError::Decode)?;
+
+		let version = client
+			.runtime_api()
+			.get_ledger_version(hash)
+			.map_err(|e| sc_cli::Error::Application(Box::new(e)))?;
+		let ledger_version = LedgerVersion::from_version(&version).ok_or_else(|| {
+			LedgerSnapshotError::UnknownLedgerVersion(hex::encode(&version), hash.to_string())
+		})?;
+		if ledger_version == LedgerVersion::HardForkTest {
+			midnight_node_ledger::switch_storage_paritydb_to_hard_fork(
+				&ledger_storage_path,
+				cache_size,
+			);
+		}
+
+		let header = SnapshotHeader::new(
+			number.into(),
+			hash.encode(),
+			header.encode(),
+			ledger_version,
+			state_key,
+		);
+		let file = BufWriter::new(File::create(&self.output)?);
+		let mut writer = SnapshotWriter::new(file, &header)?;
+		Self::write_substrate_state(&*client, hash, &mut writer)?;
+		match ledger_version {
+			LedgerVersion::Latest => {
+				midnight_node_ledger::write_ledger_state(&header.state_key, &mut writer)
+					.map_err(|e| LedgerSnapshotError::Ledger(e.to_string()))?
+			},
+			LedgerVersion::HardForkTest => {
+				midnight_node_ledger::write_ledger_state_hf(&header.state_key, &mut writer)
+					.map_err(|e| LedgerSnapshotError::Ledger(e.to_string()))?
+			},
+		};
+		writer.finish()?;
+
+		log::info!(
+			"Exported ledger snapshot of block #{number} ({hash:?}) to {}",
+			self.output.display()
+		);
+		Ok(())
+	}
+
+	/// Writes the top trie of the block at `hash`, then each of its child tries, in batches of
+	/// about `CHUNK_SIZE` bytes
+	fn write_substrate_state<BE, C, W>(
+		client: &C,
+		hash: <Block as BlockT>::Hash,
+		writer: &mut SnapshotWriter<W>,
+	) -> sc_cli::Result<()>
+	where
+		BE: Backend<Block>,
+		C: StorageProvider<Block, BE>,
+		W: Write,
+	{
+		let mut child_keys = Vec::new();
+		let mut batch = TopStorage::new();
+		let mut batch_size = 0;
+		for (key, value) in client.storage_pairs(hash, None, None)? {
+			if let Some(child_key) = key.0.strip_prefix(DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+				child_keys.push((key.0.clone(), child_key.to_vec()));
+			}
+			batch_size += key.0.len() + value.0.len();
+			batch.push((key.0, value.0));
+			if batch_size >= CHUNK_SIZE {
+				writer.write_storage(std::mem::take(&mut batch))?;
+				batch_size = 0;
+			}
+		}
+		if !batch.is_empty() {
+			writer.write_storage(batch)?;
+		}
+
+		for (prefixed_key, child_key) in child_keys {
+			let child_info = ChildInfo::new_default(&child_key);
+			let mut batch = Vec::new();
+			let mut batch_size = 0;
+			for key in client.child_storage_keys(hash, child_info.clone(), None, None)? {
+				if let Some(value) = client.child_storage(hash, &child_info, &key)? {
+					batch_size += key.0.len() + value.0.len();
+					batch.push((key.0, value.0));
+				}
+				if batch_size >= CHUNK_SIZE {
+					writer.write_child_storage(prefixed_key.clone(), std::mem::take(&mut batch))?;
+					batch_size = 0;
+				}
+			}
+			if !batch.is_empty() {
+				writer.write_child_storage(prefixed_key, batch)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportLedgerSnapshotCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct ImportLedgerSnapshotCmd {
+	/// Snapshot file written by `export-ledger-snapshot`
+	#[arg(short, long, default_value = "ledger-snapshot.bin")]
+	pub input: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ImportLedgerSnapshotCmd {
+	/// Restores the snapshot ledger state into the ledger storage at `ledger_storage_path`
+	/// and imports the snapshot block with its Substrate state as the finalized head through
+	/// `block_import`, which is expected to be the GRANDPA block import so that the authority
+	/// set of the block is recorded
+	pub async fn run<BI>(
+		&self,
+		block_import: BI,
+		ledger_storage_path: PathBuf,
+		cache_size: usize,
+	) -> sc_cli::Result<()>
+	where
+		BI: BlockImport<Block>,
+	{
+		let mut reader = SnapshotReader::new(BufReader::new(File::open(&self.input)?))?;
+		let header = Header::decode(&mut &reader.header.header[..])?;
+		let hash = header.hash();
+		if hash.encode() != reader.header.block_hash {
+			return Err(LedgerSnapshotError::HeaderHashMismatch {
+				expected: hex::encode(&reader.header.block_hash),
+				computed: hex::encode(hash.encode()),
+			}
+			.into());
+		}
+
+		let state = reader.read_substrate_state()?;
+		state.check_state_key(&reader.header.state_key)?;
+
+		if reader.header.ledger_version == LedgerVersion::HardForkTest {
+			midnight_node_ledger::switch_storage_paritydb_to_hard_fork(
+				&ledger_storage_path,
+				cache_size,
+			);
+		}
+		restore_ledger_state(&mut reader)?;
+		let block_number = reader.header.block_number;
+		reader.finish()?;
+
+		let mut params = BlockImportParams::new(BlockOrigin::NetworkInitialSync, header);
+		params.state_action = StateAction::ApplyChanges(StorageChanges::Import(ImportedState {
+			block: hash,
+			state: state.key_value_states(),
+		}));
+		params.finalized = true;
+		params.fork_choice = Some(ForkChoiceStrategy::Custom(true));
+		params.import_existing = true;
+
+		match block_import.import_block(params).await {
+			Ok(ImportResult::Imported(_)) | Ok(ImportResult::AlreadyInChain) => {},
+			Ok(result) => {
+				return Err(LedgerSnapshotError::BlockImport(format!("{result:?}")).into());
+			},
+			Err(e) => return Err(LedgerSnapshotError::BlockImport(e.to_string()).into()),
+		}
+
+		log::info!(
+			"Imported ledger snapshot of block #{block_number} ({hash:?}) into {}",
+			ledger_storage_path.display()
+		);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ImportLedgerSnapshotCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use midnight_node_res::networks::{MidnightNetwork, UndeployedNetwork};
+
+	fn state_key_storage(state_key: &[u8]) -> TopStorage {
+		vec![(storage_prefix(b"Midnight", b"StateKey").to_vec(), state_key.to_vec().encode())]
+	}
+
+	fn header(state_key: Vec<u8>) -> SnapshotHeader {
+		SnapshotHeader::new(42, vec![1; 32], vec![5; 80], LedgerVersion::Latest, state_key)
+	}
+
+	fn snapshot(header: &SnapshotHeader, storage: TopStorage, ledger_state: &[u8]) -> Vec<u8> {
+		let mut writer = SnapshotWriter::new(Vec::new(), header).unwrap();
+		writer.write_storage(storage).unwrap();
+		writer.write_all(ledger_state).unwrap();
+		writer.finish().unwrap()
+	}
+
+	#[test]
+	fn should_read_valid_snapshot() {
+		let header = header(vec![2; 34]);
+		let ledger_state = vec![3; 2 * CHUNK_SIZE + 100];
+		let bytes = snapshot(&header, state_key_storage(&[2; 34]), &ledger_state);
+
+		let mut reader = SnapshotReader::new(&bytes[..]).unwrap();
+		assert_eq!(reader.header, header);
+		let state = reader.read_substrate_state().unwrap();
+		assert_eq!(state.storage, state_key_storage(&[2; 34]));
+		state.check_state_key(&header.state_key).unwrap();
+		let mut read = Vec::new();
+		reader.read_to_end(&mut read).unwrap();
+		assert_eq!(read, ledger_state);
+		reader.finish().unwrap();
+	}
+
+	#[test]
+	fn should_reject_corrupted_snapshot() {
+		let bytes = snapshot(&header(vec![2; 34]), state_key_storage(&[2; 34]), &[3; 100]);
+		let mut corrupted = bytes.clone();
+		*corrupted.iter_mut().rev().nth(40).unwrap() ^= 1;
+
+		let mut reader = SnapshotReader::new(&corrupted[..]).unwrap();
+		reader.read_substrate_state().unwrap();
+		reader.read_to_end(&mut Vec::new()).unwrap();
+		assert!(matches!(reader.finish(), Err(LedgerSnapshotError::ChecksumMismatch)));
+
+		// The ledger state left unread
+		let mut reader = SnapshotReader::new(&bytes[..]).unwrap();
+		reader.read_substrate_state().unwrap();
+		reader.read_exact(&mut [0; 10]).unwrap();
+		assert!(matches!(reader.finish(), Err(LedgerSnapshotError::UnexpectedFrame(_))));
+	}
+
+	#[test]
+	fn should_reject_unknown_files() {
+		let mut header = header(vec![2; 34]);
+		header.magic = *b"NOTMINE!";
+		let bytes = snapshot(&header, Vec::new(), &[]);
+
+		assert!(matches!(SnapshotReader::new(&bytes[..]), Err(LedgerSnapshotError::BadMagic)));
+	}
+
+	#[test]
+	fn should_place_child_tries_under_their_root() {
+		let prefixed_key = [DEFAULT_CHILD_STORAGE_KEY_PREFIX, b"child"].concat();
+		let mut storage = state_key_storage(&[2; 34]);
+		storage.push((prefixed_key.clone(), vec![7; 32]));
+		let mut writer = SnapshotWriter::new(Vec::new(), &header(vec![2; 34])).unwrap();
+		writer.write_storage(storage.clone()).unwrap();
+		writer
+			.write_child_storage(prefixed_key.clone(), vec![(vec![8], vec![9])])
+			.unwrap();
+		writer
+			.write_child_storage(prefixed_key.clone(), vec![(vec![10], vec![11])])
+			.unwrap();
+		let bytes = writer.finish().unwrap();
+
+		let state = SnapshotReader::new(&bytes[..]).unwrap().read_substrate_state().unwrap();
+		let KeyValueStates(levels) = state.key_value_states();
+		assert_eq!(levels.len(), 2);
+		assert!(levels[0].parent_storage_keys.is_empty());
+		assert_eq!(levels[0].key_values, storage);
+		assert_eq!(levels[1].state_root, vec![7; 32]);
+		assert_eq!(levels[1].parent_storage_keys, vec![prefixed_key]);
+		assert_eq!(levels[1].key_values, vec![(vec![8], vec![9]), (vec![10], vec![11])]);
+	}
+
+	#[test]
+	fn should_check_the_state_key_before_writing() {
+		let dir = tempfile::tempdir().unwrap();
+		let state_key = midnight_node_ledger::init_storage_paritydb(
+			dir.path(),
+			UndeployedNetwork.genesis_state(),
+			1024,
+		);
+		let mut ledger_state = Vec::new();
+		midnight_node_ledger::write_ledger_state(&state_key, &mut ledger_state).unwrap();
+
+		let mut wrong_key = state_key.clone();
+		*wrong_key.last_mut().unwrap() ^= 1;
+		let mismatched =
+			snapshot(&header(wrong_key.clone()), state_key_storage(&wrong_key), &ledger_state);
+		let mut reader = SnapshotReader::new(&mismatched[..]).unwrap();
+		reader.read_substrate_state().unwrap();
+		assert!(matches!(
+			restore_ledger_state(&mut reader),
+			Err(LedgerSnapshotError::StateKeyMismatch { .. })
+		));
+
+		let stale_storage =
+			SubstrateState { storage: state_key_storage(&wrong_key), ..Default::default() };
+		assert!(matches!(
+			stale_storage.check_state_key(&state_key),
+			Err(LedgerSnapshotError::StorageStateKeyMismatch)
+		));
+
+		let valid =
+			snapshot(&header(state_key.clone()), state_key_storage(&state_key), &ledger_state);
+		let mut reader = SnapshotReader::new(&valid[..]).unwrap();
+		reader.read_substrate_state().unwrap().check_state_key(&state_key).unwrap();
+		restore_ledger_state(&mut reader).unwrap();
+		reader.finish().unwrap();
+		assert!(midnight_node_ledger::check_ledger_state(&state_key).unwrap().is_complete());
+	}
+}
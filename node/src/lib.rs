@@ -15,11 +15,14 @@
 pub mod benchmarking;
 pub mod cfg;
 pub mod chain_spec;
+pub mod check_ledger_storage_cmd;
 pub mod cli;
 pub mod cnight_genesis;
 pub mod command;
 pub mod extensions;
 pub mod inherent_data;
+pub mod ledger_pruning;
+pub mod ledger_snapshot_cmd;
 pub mod main_chain_follower;
 pub mod partner_chains;
 pub mod payload;
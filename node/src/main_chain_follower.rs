@@ -31,12 +31,17 @@ use super::cfg::midnight_cfg::MidnightCfg;
 use midnight_primitives::BridgeRecipient;
 use partner_chains_mock_data_sources::MockRegistrationsConfig;
 use sidechain_domain::mainchain_epoch::{Duration, MainchainEpochConfig, Timestamp};
-use std::{error::Error, str::FromStr as _, sync::Arc};
+use std::{error::Error, path::Path, str::FromStr as _, sync::Arc};
 
+use midnight_primitives_cnight_observation::CNightAddresses;
+use midnight_primitives_federated_authority_observation::FederatedAuthorityObservationConfig;
 use midnight_primitives_mainchain_follower::{
-	CNightObservationDataSourceMock, FederatedAuthorityObservationDataSource,
+	CNightObservationDataSourceMock, CNightObservationRecorder, CNightObservationReplay,
+	ChainSyncFollower, ChainSyncIndex, FederatedAuthorityObservationDataSource,
 	FederatedAuthorityObservationDataSourceImpl, FederatedAuthorityObservationDataSourceMock,
 	MidnightCNightObservationDataSource, MidnightCNightObservationDataSourceImpl,
+	chain_sync::WatchSet,
+	data_source::{cnight_observation_watch_set, federated_authority_watch_set},
 };
 
 // TODO: Decide if it should be experimental
@@ -52,6 +57,9 @@ pub struct DataSources {
 	pub federated_authority_observation:
 		Arc<dyn FederatedAuthorityObservationDataSource + Send + Sync>,
 	pub bridge: Arc<dyn TokenBridgeDataSource<BridgeRecipient> + Send + Sync>,
+	/// Follower keeping the index of the Ogmios observation data sources, to spawn with the
+	/// service
+	pub chain_sync_follower: Option<ChainSyncFollower>,
 }
 
 #[derive(Clone)]
@@ -93,16 +101,23 @@ pub async fn create_mock_data_sources(
 		)?,
 	};
 
+	let cnight_observation: Arc<dyn MidnightCNightObservationDataSource + Send + Sync> =
+		match &cfg.replay_cnight_observation_file {
+			Some(fixture) => Arc::new(CNightObservationReplay::load(Path::new(fixture))?),
+			None => Arc::new(CNightObservationDataSourceMock::new()),
+		};
+
 	Ok(DataSources {
 		sidechain_rpc: Arc::new(SidechainRpcDataSourceMock::new(block.clone())),
 		mc_hash: Arc::new(McHashDataSourceMock::new(block)),
 		authority_selection: Arc::new(authority_selection_data_source_mock),
-		cnight_observation: Arc::new(CNightObservationDataSourceMock::new()),
+		cnight_observation,
 		governed_map: Arc::new(GovernedMapDataSourceMock::default()),
 		federated_authority_observation: Arc::new(
 			FederatedAuthorityObservationDataSourceMock::new(),
 		),
 		bridge: Arc::new(TokenBridgeDataSourceMock::<BridgeRecipient>::new()),
+		chain_sync_follower: None,
 	})
 }
 
@@ -192,13 +207,6 @@ pub async fn create_cached_data_sources(
 	let mc_hash =
 		McHashDataSourceImpl::new(Arc::new(mc_hash_block_data_source), metrics_opt.clone());
 
-	let cnight_observation_pool = get_connection(postgres_uri, CNIGHT_OBSERVATION_POOL_CFG).await?;
-	let cnight_observation = MidnightCNightObservationDataSourceImpl::new(
-		cnight_observation_pool,
-		metrics_opt.clone(),
-		1000,
-	);
-
 	let governed_map_pool = get_connection(postgres_uri, GOVERNED_MAP_POOL_CFG).await?;
 	let governed_map_block_data_source = BlockDataSourceImpl::from_config(
 		governed_map_pool.clone(),
@@ -213,13 +221,66 @@ pub async fn create_cached_data_sources(
 	)
 	.await?;
 
-	let federated_authority_observation_pool =
-		get_connection(postgres_uri, FEDERATED_AUTHORITY_OBSERVATION_POOL_CFG).await?;
-	let federated_authority_observation = FederatedAuthorityObservationDataSourceImpl::new(
-		federated_authority_observation_pool,
-		metrics_opt.clone(),
-		1000,
-	);
+	let (cnight_observation, federated_authority_observation, chain_sync_follower): (
+		Arc<dyn MidnightCNightObservationDataSource + Send + Sync>,
+		Arc<dyn FederatedAuthorityObservationDataSource + Send + Sync>,
+		Option<ChainSyncFollower>,
+	) = if cfg.use_ogmios_observation {
+		let index_path = cfg.ogmios_index_path.ok_or(missing("ogmios_index_path"))?;
+		let index = ChainSyncIndex::open(Path::new(&index_path), mc.clone())?;
+		// Extending the watch set later would re-index from the start point
+		index.extend_watch_set(&observation_watch_set(
+			cfg.cnight_addresses_file.as_deref().ok_or(missing("cnight_addresses_file"))?,
+			cfg.federated_authority_config_file
+				.as_deref()
+				.ok_or(missing("federated_authority_config_file"))?,
+		)?)?;
+		let start_point = cfg.ogmios_start_point.as_deref().unwrap_or("origin").parse()?;
+		let follower = ChainSyncFollower::new(
+			cfg.ogmios_url.ok_or(missing("ogmios_url"))?,
+			start_point,
+			index.clone(),
+		);
+		(
+			record_cnight_observation(
+				MidnightCNightObservationDataSourceImpl::new(
+					index.clone(),
+					metrics_opt.clone(),
+					1000,
+				),
+				cfg.record_cnight_observation_file.as_deref(),
+				cfg.record_cnight_observation_max_responses,
+			)?,
+			Arc::new(FederatedAuthorityObservationDataSourceImpl::new(
+				index,
+				metrics_opt.clone(),
+				1000,
+			)),
+			Some(follower),
+		)
+	} else {
+		let cnight_observation_pool =
+			get_connection(postgres_uri, CNIGHT_OBSERVATION_POOL_CFG).await?;
+		let federated_authority_observation_pool =
+			get_connection(postgres_uri, FEDERATED_AUTHORITY_OBSERVATION_POOL_CFG).await?;
+		(
+			record_cnight_observation(
+				MidnightCNightObservationDataSourceImpl::new(
+					cnight_observation_pool,
+					metrics_opt.clone(),
+					1000,
+				),
+				cfg.record_cnight_observation_file.as_deref(),
+				cfg.record_cnight_observation_max_responses,
+			)?,
+			Arc::new(FederatedAuthorityObservationDataSourceImpl::new(
+				federated_authority_observation_pool,
+				metrics_opt.clone(),
+				1000,
+			)),
+			None,
+		)
+	};
 
 	let bridge_pool = get_connection(postgres_uri, BRIDGE_POOL_CFG).await?;
 
@@ -234,10 +295,53 @@ pub async fn create_cached_data_sources(
 		sidechain_rpc: Arc::new(sidechain_rpc),
 		mc_hash: Arc::new(mc_hash),
 		authority_selection: Arc::new(candidates_data_source_cached),
-		cnight_observation: Arc::new(cnight_observation),
+		cnight_observation,
 		governed_map: Arc::new(governed_map),
 		bridge: Arc::new(bridge),
-		federated_authority_observation: Arc::new(federated_authority_observation),
+		federated_authority_observation,
+		chain_sync_follower,
+	})
+}
+
+/// Outputs read by the cNight and federated authority observations, from the cNight addresses
+/// and federated authority config files of the network
+fn observation_watch_set(
+	cnight_addresses_file: &str,
+	federated_authority_config_file: &str,
+) -> Result<WatchSet, Box<dyn Error + Send + Sync + 'static>> {
+	let cnight_addresses: CNightAddresses =
+		serde_json::from_str(&std::fs::read_to_string(cnight_addresses_file)?)?;
+	let federated_authority_config: FederatedAuthorityObservationConfig =
+		serde_json::from_str(&std::fs::read_to_string(federated_authority_config_file)?)?;
+
+	let mut watch_set = cnight_observation_watch_set(&cnight_addresses)?;
+	watch_set.extend(&federated_authority_watch_set(&federated_authority_config));
+	Ok(watch_set)
+}
+
+/// Records the responses of `data_source` to the cNight observation fixture, if any, up to
+/// `max_responses`
+fn record_cnight_observation<D>(
+	data_source: D,
+	fixture: Option<&str>,
+	max_responses: u64,
+) -> Result<
+	Arc<dyn MidnightCNightObservationDataSource + Send + Sync>,
+	Box<dyn Error + Send + Sync + 'static>,
+>
+where
+	D: MidnightCNightObservationDataSource + Send + Sync + 'static,
+{
+	Ok(match fixture {
+		Some(fixture) => {
+			log::info!("Recording up to {max_responses} cNight observations to {fixture}");
+			Arc::new(CNightObservationRecorder::new(
+				data_source,
+				Path::new(fixture),
+				max_responses,
+			)?)
+		},
+		None => Arc::new(data_source),
 	})
 }
 
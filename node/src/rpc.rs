@@ -44,6 +44,8 @@ use pallet_sidechain_rpc::*;
 use sidechain_domain::mainchain_epoch::MainchainEpochConfig;
 use time_source::TimeSource;
 
+use midnight_primitives_cnight_observation::CNightObservationApi;
+use pallet_cnight_observation_rpc::{CNightObservationRpc, CNightObservationRpcApiServer};
 use pallet_midnight::MidnightRuntimeApi;
 use pallet_midnight_rpc::{Midnight, MidnightApiServer};
 use sc_consensus_beefy::communication::notification::{
@@ -111,6 +113,7 @@ where
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
 	C::Api: BlockBuilder<Block>,
 	C::Api: MidnightRuntimeApi<Block>,
+	C::Api: CNightObservationApi<Block>,
 	C::Api: sp_consensus_aura::AuraApi<Block, sp_consensus_aura::sr25519::AuthorityId>,
 	C::Api: sp_consensus_beefy::BeefyApi<Block, AuthorityId>,
 	C::Api: mmr_rpc::MmrRuntimeApi<Block, <Block as sp_runtime::traits::Block>::Hash, BlockNumber>,
@@ -204,6 +207,14 @@ where
 		)))
 		.into_rpc(),
 	)?;
+	module.merge(
+		CNightObservationRpc::new(
+			client.clone(),
+			main_chain_follower_data_sources.mc_hash.clone(),
+			time_source,
+		)
+		.into_rpc(),
+	)?;
 	module.merge(Midnight::new(client).into_rpc())?;
 
 	// Extend this RPC with a custom API by using the following syntax.
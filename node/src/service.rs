@@ -16,6 +16,7 @@
 use crate::{
 	extensions::ExtensionsFactory,
 	inherent_data::{CreateInherentDataConfig, ProposalCIDP, VerifierCIDP},
+	ledger_pruning,
 	main_chain_follower::DataSources,
 	rpc::{BeefyDeps, GrandpaDeps},
 };
@@ -62,6 +63,7 @@ use time_source::SystemTimeSource;
 pub struct StorageInit {
 	pub genesis_state: Vec<u8>,
 	pub cache_size: usize,
+	pub tx_validation_cache_size: u64,
 }
 
 pub struct GenesisBlockBuilder<Block: BlockT, B, E> {
@@ -202,6 +204,7 @@ type MidnightService = sc_service::PartialComponents<
 		Option<Telemetry>,
 		DataSources,
 		Option<McFollowerMetrics>,
+		Arc<Mutex<Option<LedgerMetrics>>>,
 	),
 >;
 
@@ -221,6 +224,7 @@ pub fn new_partial(
 		&storage_config.genesis_state,
 		storage_config.cache_size,
 	);
+	midnight_node_ledger::set_tx_validation_cache_size(storage_config.tx_validation_cache_size);
 
 	let telemetry = config
 		.telemetry_endpoints
@@ -299,12 +303,13 @@ pub fn new_partial(
 				},
 			});
 
+	let ledger_metrics = Arc::new(Mutex::new(ledger_metrics));
 	let ledger_storage = LedgerStorage::new(parity_db_path, storage_config.cache_size);
 
 	client
 		.execution_extensions()
 		.set_extensions_factory(ExtensionsFactory::<Block>::new(
-			Arc::new(Mutex::new(ledger_metrics)),
+			ledger_metrics.clone(),
 			ledger_storage,
 		));
 
@@ -392,6 +397,7 @@ pub fn new_partial(
 			telemetry,
 			data_sources,
 			mc_follower_metrics,
+			ledger_metrics,
 		),
 	};
 
@@ -427,9 +433,31 @@ pub async fn new_full<Network: sc_network::NetworkBackend<Block, <Block as Block
 				mut telemetry,
 				data_sources,
 				_mc_follower_metrics_opt,
+				ledger_metrics,
 			),
 	} = new_partial_components;
 
+	// The observation data sources read the index the follower keeps, so it is essential.
+	// Spawned here rather than in `new_partial`, so the commands built on it don't follow Cardano
+	if let Some(follower) = data_sources.chain_sync_follower.clone() {
+		task_manager.spawn_essential_handle().spawn(
+			"cardano-chain-sync",
+			Some("mainchain-follower"),
+			follower.run(),
+		);
+	}
+
+	// Registered before the network starts, so every imported block's ledger roots are recorded
+	if let Some(blocks_to_keep) = ledger_pruning::blocks_to_keep(&config.state_pruning) {
+		ledger_pruning::register(
+			&*client,
+			backend.clone(),
+			task_manager.spawn_handle(),
+			blocks_to_keep,
+			ledger_metrics,
+		)?;
+	}
+
 	let mut net_config = sc_network::config::FullNetworkConfiguration::<_, _, Network>::new(
 		&config.network,
 		config.prometheus_registry().cloned(),
@@ -100,12 +100,16 @@ impl Get<BlockReward> for LedgerBlockReward {
 
 impl pallet_midnight::Config for Test {
 	type BlockReward = LedgerBlockReward;
+	type BlockAuthorId = u64;
+	type MidnightSystemTransactionExecutor = MidnightSystem;
+	type WeightInfo = ();
 	type SlotDuration = ConstU64<SLOT_DURATION>;
 }
 
 impl pallet_midnight_system::Config for Test {
 	type LedgerStateProviderMut = Midnight;
 	type LedgerBlockContextProvider = Midnight;
+	type ParametersUpdateOrigin = frame_system::EnsureRoot<Self::AccountId>;
 }
 
 impl frame_system::Config for Test {
@@ -147,6 +151,7 @@ parameter_types! {
 
 impl pallet_cnight_observation::Config for Test {
 	type MidnightSystemTransactionExecutor = MidnightSystem;
+	type WeightInfo = ();
 }
 
 impl mock_pallet::Config for Test {}
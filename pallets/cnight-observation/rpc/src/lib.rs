@@ -0,0 +1,627 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RPC methods of the cNight observation pallet, to look up registrations, DUST mappings and
+//! the progress of the Cardano observation
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+use jsonrpsee::{
+	core::async_trait,
+	proc_macros::rpc,
+	types::error::{ErrorObject, ErrorObjectOwned, INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE},
+};
+
+use midnight_primitives_cnight_observation::{
+	CNightObservationApi, CardanoPosition, CardanoRewardAddressBytes, DustPublicKeyBytes,
+	MappingEntry,
+};
+use sidechain_domain::{MainchainBlock, McTxHash};
+use sidechain_mc_hash::McHashDataSource;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use sp_timestamp::Timestamp;
+use std::sync::Arc;
+use time_source::TimeSource;
+
+#[rpc(client, server)]
+pub trait CNightObservationRpcApi<BlockHash> {
+	#[method(name = "cnight_getRegistration")]
+	fn get_registration(
+		&self,
+		cardano_reward_address: String,
+		at: Option<BlockHash>,
+	) -> Result<RpcRegistration, CNightRpcError>;
+
+	#[method(name = "cnight_mappingsByDustKey")]
+	fn get_mappings_by_dust_key(
+		&self,
+		dust_public_key: String,
+		at: Option<BlockHash>,
+	) -> Result<Vec<RpcMappingEntry>, CNightRpcError>;
+
+	#[method(name = "cnight_utxoOwner")]
+	fn get_utxo_owner(
+		&self,
+		utxo_tx_hash: String,
+		utxo_index: u16,
+		at: Option<BlockHash>,
+	) -> Result<Option<String>, CNightRpcError>;
+
+	#[method(name = "cnight_observationProgress")]
+	async fn get_observation_progress(
+		&self,
+		at: Option<BlockHash>,
+	) -> Result<RpcObservationProgress, CNightRpcError>;
+}
+
+#[derive(Debug)]
+pub enum CNightRpcError {
+	BadCardanoRewardAddress(String),
+	BadDustPublicKey(String),
+	BadUtxoTxHash(String),
+	UnableToGetRegistration(String),
+	UnableToGetMappings(String),
+	UnableToGetUtxoOwner(String),
+	UnableToGetNextCardanoPosition(String),
+	UnableToGetFollowerTip(String),
+	UnsupportedApiVersion(u32),
+	ApiNotSupported(String),
+}
+
+impl Display for CNightRpcError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			CNightRpcError::BadCardanoRewardAddress(malformed_address) => {
+				write!(f, "Unable to decode Cardano reward address: {}", malformed_address)
+			},
+			CNightRpcError::BadDustPublicKey(malformed_key) => {
+				write!(f, "Unable to decode DUST public key: {}", malformed_key)
+			},
+			CNightRpcError::BadUtxoTxHash(malformed_hash) => {
+				write!(f, "Unable to decode UTXO transaction hash: {}", malformed_hash)
+			},
+			CNightRpcError::UnableToGetRegistration(reason) => {
+				write!(f, "Unable to get requested registration: {}", reason)
+			},
+			CNightRpcError::UnableToGetMappings(reason) => {
+				write!(f, "Unable to get requested mappings: {}", reason)
+			},
+			CNightRpcError::UnableToGetUtxoOwner(reason) => {
+				write!(f, "Unable to get requested UTXO owner: {}", reason)
+			},
+			CNightRpcError::UnableToGetNextCardanoPosition(reason) => {
+				write!(f, "Unable to get the next Cardano position: {}", reason)
+			},
+			CNightRpcError::UnableToGetFollowerTip(reason) => {
+				write!(f, "Unable to get the main chain follower tip: {}", reason)
+			},
+			CNightRpcError::UnsupportedApiVersion(api_version) => {
+				write!(f, "Not supported by the runtime API version {} of the block", api_version)
+			},
+			CNightRpcError::ApiNotSupported(block_hash) => {
+				write!(f, "CNightObservationApi not supported at block {}", block_hash)
+			},
+		}
+	}
+}
+
+impl std::error::Error for CNightRpcError {}
+
+impl From<CNightRpcError> for ErrorObjectOwned {
+	fn from(value: CNightRpcError) -> Self {
+		let code = match value {
+			CNightRpcError::BadCardanoRewardAddress(_)
+			| CNightRpcError::BadDustPublicKey(_)
+			| CNightRpcError::BadUtxoTxHash(_)
+			| CNightRpcError::UnsupportedApiVersion(_)
+			| CNightRpcError::ApiNotSupported(_) => INVALID_PARAMS_CODE,
+			CNightRpcError::UnableToGetRegistration(_)
+			| CNightRpcError::UnableToGetMappings(_)
+			| CNightRpcError::UnableToGetUtxoOwner(_)
+			| CNightRpcError::UnableToGetNextCardanoPosition(_)
+			| CNightRpcError::UnableToGetFollowerTip(_) => INTERNAL_ERROR_CODE,
+		};
+		ErrorObject::owned(code, value.to_string(), None::<()>)
+	}
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcMappingEntry {
+	pub cardano_reward_address: String,
+	pub dust_public_key: String,
+	pub utxo_tx_hash: String,
+	pub utxo_index: u16,
+}
+
+impl From<MappingEntry> for RpcMappingEntry {
+	fn from(mapping: MappingEntry) -> Self {
+		Self {
+			cardano_reward_address: hex::encode(mapping.cardano_reward_address.0),
+			dust_public_key: hex::encode(mapping.dust_public_key.0),
+			utxo_tx_hash: hex::encode(mapping.utxo_tx_hash.0),
+			utxo_index: mapping.utxo_index,
+		}
+	}
+}
+
+/// Registration of a Cardano reward address, see `cnight_getRegistration`
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcRegistration {
+	/// DUST public key generating DUST from the cNight of the address. Only set when the
+	/// registration is valid, i.e. when the address has a single mapping.
+	pub dust_public_key: Option<String>,
+	/// Every observed mapping of the address
+	pub mappings: Vec<RpcMappingEntry>,
+}
+
+/// Cardano block, as seen by the main chain follower
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcCardanoBlock {
+	pub block_number: u32,
+	pub block_hash: String,
+	pub timestamp_millis: u64,
+}
+
+impl From<MainchainBlock> for RpcCardanoBlock {
+	fn from(block: MainchainBlock) -> Self {
+		Self {
+			block_number: block.number.0,
+			block_hash: hex::encode(block.hash.0),
+			timestamp_millis: block.timestamp,
+		}
+	}
+}
+
+/// How far the cNight observation is behind the main chain follower, see
+/// `cnight_observationProgress`
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcObservationProgress {
+	/// Position the next cNight observation starts from
+	pub next_cardano_position: CardanoPosition,
+	/// Latest stable block of the main chain follower, if any
+	pub follower_tip: Option<RpcCardanoBlock>,
+	/// Number of stable Cardano blocks not observed yet
+	pub blocks_behind: Option<u32>,
+}
+
+pub struct CNightObservationRpc<C, Block, T> {
+	client: Arc<C>,
+	mc_hash_data_source: Arc<dyn McHashDataSource + Send + Sync>,
+	time_source: Arc<T>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block, T> CNightObservationRpc<C, Block, T> {
+	pub fn new(
+		client: Arc<C>,
+		mc_hash_data_source: Arc<dyn McHashDataSource + Send + Sync>,
+		time_source: Arc<T>,
+	) -> Self {
+		Self { client, mc_hash_data_source, time_source, _marker: Default::default() }
+	}
+}
+
+fn decode_cardano_reward_address(
+	cardano_reward_address: String,
+) -> Result<CardanoRewardAddressBytes, CNightRpcError> {
+	hex::decode(&cardano_reward_address)
+		.ok()
+		.and_then(|bytes| CardanoRewardAddressBytes::try_from(bytes).ok())
+		.ok_or(CNightRpcError::BadCardanoRewardAddress(cardano_reward_address))
+}
+
+fn decode_dust_public_key(dust_public_key: String) -> Result<DustPublicKeyBytes, CNightRpcError> {
+	hex::decode(&dust_public_key)
+		.ok()
+		.and_then(|bytes| DustPublicKeyBytes::try_from(bytes).ok())
+		.ok_or(CNightRpcError::BadDustPublicKey(dust_public_key))
+}
+
+/// Registrations, mappings and UTXO owners are looked up from version 2 of the runtime API
+fn ensure_lookups_supported(
+	api_version: Option<u32>,
+	block_hash: impl std::fmt::Debug,
+) -> Result<(), CNightRpcError> {
+	match api_version {
+		None => Err(CNightRpcError::ApiNotSupported(format!("{block_hash:?}"))),
+		Some(api_version) if api_version < 2 => {
+			Err(CNightRpcError::UnsupportedApiVersion(api_version))
+		},
+		Some(_) => Ok(()),
+	}
+}
+
+/// Version of the `CNightObservationApi` of the runtime at `block_hash`, if it has one
+fn get_api_version<C, Block>(
+	runtime_api: &sp_api::ApiRef<'_, <C as ProvideRuntimeApi<Block>>::Api>,
+	block_hash: Block::Hash,
+) -> Result<Option<u32>, sp_api::ApiError>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: CNightObservationApi<Block>,
+{
+	runtime_api.api_version::<dyn CNightObservationApi<Block>>(block_hash)
+}
+
+#[async_trait]
+impl<C, Block, T> CNightObservationRpcApiServer<<Block as BlockT>::Hash>
+	for CNightObservationRpc<C, Block, T>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static,
+	C: ProvideRuntimeApi<Block>,
+	C: HeaderBackend<Block>,
+	C::Api: CNightObservationApi<Block>,
+	T: TimeSource + Send + Sync + 'static,
+{
+	fn get_registration(
+		&self,
+		cardano_reward_address: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcRegistration, CNightRpcError> {
+		let cardano_reward_address = decode_cardano_reward_address(cardano_reward_address)?;
+
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let api_version = get_api_version::<C, Block>(&api, at)
+			.map_err(|e| CNightRpcError::UnableToGetRegistration(e.to_string()))?;
+		ensure_lookups_supported(api_version, at)?;
+
+		let dust_public_key = api
+			.get_registration(at, cardano_reward_address)
+			.map_err(|e| CNightRpcError::UnableToGetRegistration(e.to_string()))?;
+		let mappings = api
+			.get_mappings(at, cardano_reward_address)
+			.map_err(|e| CNightRpcError::UnableToGetRegistration(e.to_string()))?;
+
+		Ok(RpcRegistration {
+			dust_public_key: dust_public_key.map(|key| hex::encode(key.0)),
+			mappings: mappings.into_iter().map(RpcMappingEntry::from).collect(),
+		})
+	}
+
+	fn get_mappings_by_dust_key(
+		&self,
+		dust_public_key: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<RpcMappingEntry>, CNightRpcError> {
+		let dust_public_key = decode_dust_public_key(dust_public_key)?;
+
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let api_version = get_api_version::<C, Block>(&api, at)
+			.map_err(|e| CNightRpcError::UnableToGetMappings(e.to_string()))?;
+		ensure_lookups_supported(api_version, at)?;
+
+		let mappings = api
+			.get_mappings_by_dust_public_key(at, dust_public_key)
+			.map_err(|e| CNightRpcError::UnableToGetMappings(e.to_string()))?;
+
+		Ok(mappings.into_iter().map(RpcMappingEntry::from).collect())
+	}
+
+	fn get_utxo_owner(
+		&self,
+		utxo_tx_hash: String,
+		utxo_index: u16,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<String>, CNightRpcError> {
+		let utxo_tx_hash: [u8; 32] = hex::decode(&utxo_tx_hash)
+			.ok()
+			.and_then(|bytes| bytes.try_into().ok())
+			.ok_or(CNightRpcError::BadUtxoTxHash(utxo_tx_hash))?;
+
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let api_version = get_api_version::<C, Block>(&api, at)
+			.map_err(|e| CNightRpcError::UnableToGetUtxoOwner(e.to_string()))?;
+		ensure_lookups_supported(api_version, at)?;
+
+		let owner = api
+			.get_utxo_owner(at, McTxHash(utxo_tx_hash), utxo_index)
+			.map_err(|e| CNightRpcError::UnableToGetUtxoOwner(e.to_string()))?;
+
+		Ok(owner.map(|key| hex::encode(key.0)))
+	}
+
+	async fn get_observation_progress(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcObservationProgress, CNightRpcError> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let next_cardano_position = self
+			.client
+			.runtime_api()
+			.get_next_cardano_position(at)
+			.map_err(|e| CNightRpcError::UnableToGetNextCardanoPosition(e.to_string()))?;
+
+		let now = Timestamp::new(self.time_source.get_current_time_millis());
+		let follower_tip = self
+			.mc_hash_data_source
+			.get_latest_stable_block_for(now)
+			.await
+			.map_err(|e| CNightRpcError::UnableToGetFollowerTip(e.to_string()))?;
+
+		let blocks_behind = follower_tip
+			.as_ref()
+			.map(|tip| tip.number.0.saturating_sub(next_cardano_position.block_number));
+
+		Ok(RpcObservationProgress {
+			next_cardano_position,
+			follower_tip: follower_tip.map(RpcCardanoBlock::from),
+			blocks_behind,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpsee::types::error::ErrorCode;
+	use partner_chains_mock_data_sources::{BlockDataSourceMock, McHashDataSourceMock};
+	use sp_api::{ApiError, ApiRef};
+	use sp_blockchain::{BlockStatus, Info};
+	use sp_runtime::{
+		OpaqueExtrinsic, generic,
+		traits::{BlakeTwo256, NumberFor},
+	};
+	use time_source::SystemTimeSource;
+
+	type Block = generic::Block<generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+	type Hash = <Block as BlockT>::Hash;
+
+	const AT: Hash = Hash::repeat_byte(1);
+
+	fn mapping(cardano_reward_address: u8, dust_public_key: u8) -> MappingEntry {
+		MappingEntry {
+			cardano_reward_address: CardanoRewardAddressBytes([cardano_reward_address; 29]),
+			dust_public_key: DustPublicKeyBytes::try_from(vec![dust_public_key; 32])
+				.expect("valid DUST public key"),
+			utxo_tx_hash: McTxHash([cardano_reward_address; 32]),
+			utxo_index: 0,
+		}
+	}
+
+	/// Runtime API serving `mappings`, or failing every lookup when `failing`
+	#[derive(Clone, Default)]
+	struct MockRuntimeApi {
+		mappings: Vec<MappingEntry>,
+		failing: bool,
+	}
+
+	impl MockRuntimeApi {
+		fn lookup<R>(&self, f: impl FnOnce(&[MappingEntry]) -> R) -> Result<R, ApiError> {
+			if self.failing {
+				Err(ApiError::Application("storage unavailable".into()))
+			} else {
+				Ok(f(&self.mappings))
+			}
+		}
+	}
+
+	sp_api::mock_impl_runtime_apis! {
+		impl CNightObservationApi<Block> for MockRuntimeApi {
+			fn get_redemption_validator_address() -> Vec<u8> {
+				unimplemented!()
+			}
+
+			fn get_mapping_validator_address() -> Vec<u8> {
+				unimplemented!()
+			}
+
+			fn get_auth_token_asset_name() -> Vec<u8> {
+				unimplemented!()
+			}
+
+			fn get_cnight_token_identifier() -> (Vec<u8>, Vec<u8>) {
+				unimplemented!()
+			}
+
+			fn get_next_cardano_position() -> CardanoPosition {
+				unimplemented!()
+			}
+
+			fn get_cardano_block_window_size() -> u32 {
+				unimplemented!()
+			}
+
+			fn get_utxo_capacity_per_block() -> u32 {
+				unimplemented!()
+			}
+
+			#[advanced]
+			fn get_registration(
+				&self,
+				_at: <Block as BlockT>::Hash,
+				cardano_reward_address: CardanoRewardAddressBytes,
+			) -> Result<Option<DustPublicKeyBytes>, ApiError> {
+				self.lookup(|mappings| {
+					let mut owned = mappings
+						.iter()
+						.filter(|mapping| mapping.cardano_reward_address == cardano_reward_address);
+					match (owned.next(), owned.next()) {
+						(Some(mapping), None) => Some(mapping.dust_public_key.clone()),
+						_ => None,
+					}
+				})
+			}
+
+			#[advanced]
+			fn get_mappings(
+				&self,
+				_at: <Block as BlockT>::Hash,
+				cardano_reward_address: CardanoRewardAddressBytes,
+			) -> Result<Vec<MappingEntry>, ApiError> {
+				self.lookup(|mappings| {
+					mappings
+						.iter()
+						.filter(|mapping| mapping.cardano_reward_address == cardano_reward_address)
+						.cloned()
+						.collect()
+				})
+			}
+
+			#[advanced]
+			fn get_mappings_by_dust_public_key(
+				&self,
+				_at: <Block as BlockT>::Hash,
+				dust_public_key: DustPublicKeyBytes,
+			) -> Result<Vec<MappingEntry>, ApiError> {
+				self.lookup(|mappings| {
+					mappings
+						.iter()
+						.filter(|mapping| mapping.dust_public_key == dust_public_key)
+						.cloned()
+						.collect()
+				})
+			}
+
+			#[advanced]
+			fn get_utxo_owner(
+				&self,
+				_at: <Block as BlockT>::Hash,
+				utxo_tx_hash: McTxHash,
+				utxo_index: u16,
+			) -> Result<Option<DustPublicKeyBytes>, ApiError> {
+				self.lookup(|mappings| {
+					mappings
+						.iter()
+						.find(|mapping| {
+							mapping.utxo_tx_hash == utxo_tx_hash && mapping.utxo_index == utxo_index
+						})
+						.map(|mapping| mapping.dust_public_key.clone())
+				})
+			}
+		}
+	}
+
+	struct TestClient(MockRuntimeApi);
+
+	impl ProvideRuntimeApi<Block> for TestClient {
+		type Api = MockRuntimeApi;
+
+		fn runtime_api(&self) -> ApiRef<'_, Self::Api> {
+			self.0.clone().into()
+		}
+	}
+
+	impl HeaderBackend<Block> for TestClient {
+		fn header(&self, _hash: Hash) -> sp_blockchain::Result<Option<<Block as BlockT>::Header>> {
+			Ok(None)
+		}
+
+		fn info(&self) -> Info<Block> {
+			unimplemented!("the tests give the block to query")
+		}
+
+		fn status(&self, _hash: Hash) -> sp_blockchain::Result<BlockStatus> {
+			Ok(BlockStatus::Unknown)
+		}
+
+		fn number(&self, _hash: Hash) -> sp_blockchain::Result<Option<NumberFor<Block>>> {
+			Ok(None)
+		}
+
+		fn hash(&self, _number: NumberFor<Block>) -> sp_blockchain::Result<Option<Hash>> {
+			Ok(None)
+		}
+	}
+
+	fn rpc(api: MockRuntimeApi) -> CNightObservationRpc<TestClient, Block, SystemTimeSource> {
+		let block = Arc::new(BlockDataSourceMock::new(1000));
+		CNightObservationRpc::new(
+			Arc::new(TestClient(api)),
+			Arc::new(McHashDataSourceMock::new(block)),
+			Arc::new(SystemTimeSource),
+		)
+	}
+
+	#[test]
+	fn looks_up_mappings_by_dust_public_key() {
+		let rpc = rpc(MockRuntimeApi {
+			mappings: vec![mapping(1, 7), mapping(2, 7), mapping(3, 8)],
+			failing: false,
+		});
+
+		let mappings = rpc.get_mappings_by_dust_key(hex::encode([7; 32]), Some(AT)).unwrap();
+
+		assert_eq!(
+			mappings,
+			vec![RpcMappingEntry::from(mapping(1, 7)), RpcMappingEntry::from(mapping(2, 7))]
+		);
+	}
+
+	#[test]
+	fn looks_up_registrations_and_utxo_owners() {
+		let rpc = rpc(MockRuntimeApi { mappings: vec![mapping(1, 7)], failing: false });
+
+		let registration = rpc.get_registration(hex::encode([1; 29]), Some(AT)).unwrap();
+		assert_eq!(registration.dust_public_key, Some(hex::encode([7; 32])));
+		assert_eq!(registration.mappings, vec![RpcMappingEntry::from(mapping(1, 7))]);
+
+		let owner = rpc.get_utxo_owner(hex::encode([1; 32]), 0, Some(AT)).unwrap();
+		assert_eq!(owner, Some(hex::encode([7; 32])));
+		assert_eq!(rpc.get_utxo_owner(hex::encode([1; 32]), 1, Some(AT)).unwrap(), None);
+	}
+
+	#[test]
+	fn malformed_parameters_are_invalid_params() {
+		let rpc = rpc(MockRuntimeApi::default());
+
+		let error = rpc.get_mappings_by_dust_key("not hex".to_string(), Some(AT)).unwrap_err();
+		assert!(matches!(error, CNightRpcError::BadDustPublicKey(_)));
+		assert_eq!(ErrorObjectOwned::from(error).code(), ErrorCode::InvalidParams.code());
+
+		let error = rpc.get_registration(hex::encode([1; 28]), Some(AT)).unwrap_err();
+		assert!(matches!(error, CNightRpcError::BadCardanoRewardAddress(_)));
+	}
+
+	#[test]
+	fn runtime_api_failures_are_internal_errors_with_their_cause() {
+		let rpc = rpc(MockRuntimeApi { mappings: vec![], failing: true });
+
+		let error = rpc.get_mappings_by_dust_key(hex::encode([7; 32]), Some(AT)).unwrap_err();
+		assert!(matches!(error, CNightRpcError::UnableToGetMappings(_)));
+
+		let error = ErrorObjectOwned::from(error);
+		assert_eq!(error.code(), ErrorCode::InternalError.code());
+		assert!(error.message().contains("storage unavailable"), "{}", error.message());
+	}
+
+	#[test]
+	fn lookups_are_rejected_before_runtime_api_version_2() {
+		assert!(matches!(
+			ensure_lookups_supported(Some(1), AT),
+			Err(CNightRpcError::UnsupportedApiVersion(1))
+		));
+		assert!(ensure_lookups_supported(Some(2), AT).is_ok());
+	}
+
+	#[test]
+	fn lookups_are_rejected_at_blocks_without_the_runtime_api() {
+		let error = ensure_lookups_supported(None, AT).unwrap_err();
+		assert!(matches!(error, CNightRpcError::ApiNotSupported(_)));
+
+		let error = ErrorObjectOwned::from(error);
+		assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+		assert_eq!(error.message(), format!("CNightObservationApi not supported at block {AT:?}"));
+	}
+}
@@ -0,0 +1,158 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarking setup for pallet-cnight-observation
+//!
+//! Runs against the ledger state of the undeployed network genesis.
+
+use super::*;
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+use midnight_node_ledger::types::active_ledger_bridge as LedgerApi;
+use midnight_primitives_cnight_observation::{
+	CardanoRewardAddressBytes, DustPublicKeyBytes, TimestampUnixMillis,
+};
+use midnight_primitives_mainchain_follower::{
+	CreateData, ObservedUtxo, ObservedUtxoData, ObservedUtxoHeader, UtxoIndexInTx,
+};
+use sidechain_domain::McTxHash;
+use sp_std::{vec, vec::Vec};
+
+/// Block time of the observed Cardano transactions
+const CARDANO_TIMESTAMP_MS: i64 = 1_763_577_105_000;
+
+/// Untagged serialization of a small DUST public key, a little endian field element
+fn dust_public_key(seed: u8) -> DustPublicKeyBytes {
+	let mut key = vec![0; 32];
+	key[0] = seed;
+	DustPublicKeyBytes(key.try_into().expect("DUST public keys are 32 bytes long"))
+}
+
+fn position(block_number: u32, tx_index_in_block: u32) -> CardanoPosition {
+	CardanoPosition {
+		block_hash: McBlockHash([1; 32]),
+		block_number,
+		block_timestamp: TimestampUnixMillis(CARDANO_TIMESTAMP_MS),
+		tx_index_in_block,
+	}
+}
+
+/// cNight UTXOs created in `u` Cardano transactions, for a registered reward address
+fn asset_creates(owner: CardanoRewardAddressBytes, u: u32) -> Vec<ObservedUtxo> {
+	(0..u)
+		.map(|i| {
+			let mut utxo_tx_hash = [0; 32];
+			utxo_tx_hash[..4].copy_from_slice(&i.to_be_bytes());
+			ObservedUtxo {
+				header: ObservedUtxoHeader {
+					tx_position: position(1, i),
+					tx_hash: McTxHash(utxo_tx_hash),
+					utxo_tx_hash: McTxHash(utxo_tx_hash),
+					utxo_index: UtxoIndexInTx(0),
+				},
+				data: ObservedUtxoData::AssetCreate(CreateData {
+					value: 1_000_000,
+					owner,
+					utxo_tx_hash: McTxHash(utxo_tx_hash),
+					utxo_tx_index: 0,
+				}),
+			}
+		})
+		.collect()
+}
+
+/// Registers a reward address to a valid DUST public key, returning the address
+fn register_owner<T: Config>() -> CardanoRewardAddressBytes {
+	let owner = CardanoRewardAddressBytes([1; 29]);
+	// Dust events silently fail to construct for a malformed key, skipping the ledger update
+	assert!(
+		LedgerApi::construct_cnight_generates_dust_event(
+			1_000_000,
+			&dust_public_key(1).0,
+			0,
+			UtxoActionType::Create as u8,
+			[0; 32],
+		)
+		.is_ok()
+	);
+	Mappings::<T>::insert(
+		owner,
+		vec![MappingEntry {
+			cardano_reward_address: owner,
+			dust_public_key: dust_public_key(1),
+			utxo_tx_hash: McTxHash([0; 32]),
+			utxo_index: 0,
+		}],
+	);
+	MappingsByDustPublicKey::<T>::insert(dust_public_key(1), owner, ());
+	owner
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	// Every UTXO generates dust, the most expensive kind to process. Lagging behind the Cardano
+	// tip, the observation is adapted.
+	#[benchmark]
+	fn process_tokens(u: Linear<0, 1_000>) {
+		let owner = register_owner::<T>();
+		NextCardanoPosition::<T>::put(position(1, 0));
+		let utxos = asset_creates(owner, u);
+
+		#[extrinsic_call]
+		process_tokens(RawOrigin::None, utxos, position(1, u).increment(), 1);
+
+		assert_eq!(NextCardanoPosition::<T>::get(), position(1, u).increment());
+	}
+
+	// The oldest range holds every UTXO, each of them destroying the dust it generated
+	#[benchmark]
+	fn rollback_cardano_observation(r: Linear<1, 1_000>, u: Linear<0, 1_000>) {
+		let owner = register_owner::<T>();
+		NextCardanoPosition::<T>::put(position(1, 0));
+		Pallet::<T>::process_tokens(
+			RawOrigin::None.into(),
+			asset_creates(owner, u),
+			position(2, 0),
+			0,
+		)
+		.expect("the observed UTXOs are processed");
+		for block_number in 3..r + 2 {
+			Pallet::<T>::process_tokens(
+				RawOrigin::None.into(),
+				vec![],
+				position(block_number, 0),
+				0,
+			)
+			.expect("the empty range is processed");
+		}
+		assert_eq!(ConsumedCardanoRangeBounds::<T>::get(), (0, r.into()));
+
+		#[extrinsic_call]
+		rollback_cardano_observation(RawOrigin::Root, position(1, 0), r, u);
+
+		assert_eq!(NextCardanoPosition::<T>::get(), position(1, 0));
+		assert_eq!(ConsumedCardanoRangeBounds::<T>::get(), (0, 0));
+	}
+
+	#[benchmark]
+	fn set_cardano_observation_limits() {
+		let limits = ObservationLimits::default();
+
+		#[extrinsic_call]
+		set_cardano_observation_limits(RawOrigin::Root, limits);
+
+		assert_eq!(CardanoObservationLimits::<T>::get(), limits);
+	}
+}
@@ -19,7 +19,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use derive_new::new;
-use frame_support::pallet_prelude::*;
+use frame_support::{pallet_prelude::*, sp_runtime::Perbill};
 use frame_system::pallet_prelude::*;
 use midnight_primitives_cnight_observation::{CardanoPosition, INHERENT_IDENTIFIER, InherentError};
 use midnight_primitives_mainchain_follower::MidnightObservationTokenMovement;
@@ -29,6 +29,11 @@ use sidechain_domain::McBlockHash;
 
 pub mod config;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+
 /// Cardano-based Midnight System Transaction (CMST)  Header
 ///
 ///  * `block`: hash of the last processed Cardano block
@@ -55,11 +60,17 @@ pub enum UtxoActionType {
 
 pub const INITIAL_CARDANO_BLOCK_WINDOW_SIZE: u32 = 1000;
 pub const DEFAULT_CARDANO_TX_CAPACITY_PER_BLOCK: u32 = 200;
+/// Share of the block weight `process_tokens` aims at, when adapting the Cardano transaction
+/// capacity
+pub const CARDANO_OBSERVATION_WEIGHT_BUDGET: Perbill = Perbill::from_percent(10);
+/// Depth, in Cardano blocks, of the consumed ranges kept to roll back. Cardano's security parameter
+pub const CARDANO_ROLLBACK_DEPTH: u32 = 2160;
 
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::sp_runtime::traits::Hash;
 	use midnight_primitives::MidnightSystemTransactionExecutor;
+	pub use midnight_primitives_cnight_observation::MappingEntry;
 	use midnight_primitives_cnight_observation::{
 		CARDANO_BECH32_ADDRESS_MAX_LENGTH, CardanoRewardAddressBytes, DustPublicKeyBytes,
 	};
@@ -76,31 +87,17 @@ pub mod pallet {
 	};
 
 	use crate::config::CNightGenesis;
+	use crate::weights::WeightInfo;
 
 	use super::*;
 
 	struct CNightGeneratesDustEventSerialized(Vec<u8>);
 
-	pub type BoundedCardanoAddress = BoundedVec<u8, ConstU32<CARDANO_BECH32_ADDRESS_MAX_LENGTH>>;
+	/// Nonce prefixes of the cNight UTXOs, in `UtxoOwners`
+	const ASSET_CREATE: &[u8] = b"asset_create";
+	const REDEMPTION_CREATE: &[u8] = b"redemption_create";
 
-	#[derive(
-		Debug,
-		Clone,
-		PartialEq,
-		Eq,
-		Encode,
-		Decode,
-		DecodeWithMemTracking,
-		TypeInfo,
-		Serialize,
-		Deserialize,
-	)]
-	pub struct MappingEntry {
-		pub cardano_reward_address: CardanoRewardAddressBytes,
-		pub dust_public_key: DustPublicKeyBytes,
-		pub utxo_tx_hash: McTxHash,
-		pub utxo_index: u16,
-	}
+	pub type BoundedCardanoAddress = BoundedVec<u8, ConstU32<CARDANO_BECH32_ADDRESS_MAX_LENGTH>>;
 
 	#[derive(Clone, Encode, Decode, DecodeWithMemTracking, TypeInfo, Debug, PartialEq, new)]
 	pub struct Registration {
@@ -120,7 +117,63 @@ pub mod pallet {
 		pub system_transaction_hash: LedgerHash,
 	}
 
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+	/// Cardano range consumed by a `process_tokens` call, with the UTXOs observed in it
+	#[derive(Debug, Clone, PartialEq, Encode, Decode, DecodeWithMemTracking, TypeInfo)]
+	pub struct ConsumedCardanoRange {
+		pub start: CardanoPosition,
+		pub end: CardanoPosition,
+		pub utxos: Vec<ObservedUtxo>,
+	}
+
+	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, DecodeWithMemTracking, TypeInfo)]
+	pub struct CardanoObservationRolledBack {
+		/// The position observation restarts from
+		pub position: CardanoPosition,
+		/// Number of reverted UTXOs
+		pub reverted_utxos: u32,
+	}
+
+	/// Bounds within which `process_tokens` adapts the Cardano block window and transaction
+	/// capacity, set by governance
+	#[derive(
+		Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, DecodeWithMemTracking, TypeInfo,
+	)]
+	pub struct ObservationLimits {
+		pub min_block_window: u32,
+		pub max_block_window: u32,
+		pub min_tx_capacity: u32,
+		pub max_tx_capacity: u32,
+	}
+
+	impl Default for ObservationLimits {
+		fn default() -> Self {
+			Self {
+				min_block_window: 100,
+				max_block_window: 10_000,
+				min_tx_capacity: 20,
+				max_tx_capacity: 1_000,
+			}
+		}
+	}
+
+	impl ObservationLimits {
+		fn is_valid(&self) -> bool {
+			0 < self.min_block_window
+				&& self.min_block_window <= self.max_block_window
+				&& 0 < self.min_tx_capacity
+				&& self.min_tx_capacity <= self.max_tx_capacity
+		}
+	}
+
+	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, DecodeWithMemTracking, TypeInfo)]
+	pub struct CardanoObservationAdapted {
+		/// Cardano blocks observed at most by the next `process_tokens` call
+		pub block_window: u32,
+		/// Cardano transactions observed at most by the next `process_tokens` call
+		pub tx_capacity: u32,
+	}
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
@@ -130,6 +183,8 @@ pub mod pallet {
 	#[pallet::config]
 	pub trait Config: frame_system::Config<Hash = H256> {
 		type MidnightSystemTransactionExecutor: MidnightSystemTransactionExecutor;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::event]
@@ -140,6 +195,8 @@ pub mod pallet {
 		MappingAdded(MappingEntry),
 		MappingRemoved(MappingEntry),
 		SystemTransactionApplied(SystemTransactionApplied),
+		CardanoObservationRolledBack(CardanoObservationRolledBack),
+		CardanoObservationAdapted(CardanoObservationAdapted),
 	}
 
 	#[pallet::error]
@@ -148,6 +205,12 @@ pub mod pallet {
 		MaxCardanoAddrLengthExceeded,
 		MaxRegistrationsExceeded,
 		LedgerApiError(LedgerApiError),
+		/// The rollback position is not the start of a retained consumed Cardano range
+		UnknownCardanoRollbackPosition,
+		/// The rollback reverts more Cardano ranges or UTXOs than the given maximums
+		CardanoRollbackTooLarge,
+		/// The observation limits are empty or allow a zero block window or transaction capacity
+		InvalidCardanoObservationLimits,
 	}
 
 	impl<T: Config> From<LedgerApiError> for Error<T> {
@@ -175,6 +238,19 @@ pub mod pallet {
 	pub type Mappings<T: Config> =
 		StorageMap<_, Blake2_128Concat, CardanoRewardAddressBytes, Vec<MappingEntry>, ValueQuery>;
 
+	#[pallet::storage]
+	/// Cardano reward addresses with at least one mapping to a DUST public key, indexing
+	/// `Mappings` by DUST public key
+	pub type MappingsByDustPublicKey<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		DustPublicKeyBytes,
+		Blake2_128Concat,
+		CardanoRewardAddressBytes,
+		(),
+		OptionQuery,
+	>;
+
 	// TODO: Read from ledger state directly ?
 	#[pallet::storage]
 	pub type UtxoOwners<T: Config> =
@@ -184,6 +260,17 @@ pub mod pallet {
 	// The next Cardano position to look for new transactions
 	pub type NextCardanoPosition<T: Config> = StorageValue<_, CardanoPosition, ValueQuery>;
 
+	#[pallet::storage]
+	/// Cardano ranges consumed over the last `CARDANO_ROLLBACK_DEPTH` Cardano blocks, by sequence
+	/// number. Their block hashes are checked against the main chain follower, and they are
+	/// reverted by `rollback_cardano_observation`.
+	pub type ConsumedCardanoRanges<T: Config> =
+		StorageMap<_, Twox64Concat, u64, ConsumedCardanoRange, OptionQuery>;
+
+	#[pallet::storage]
+	/// Sequence numbers of the oldest retained consumed Cardano range and of the next one
+	pub type ConsumedCardanoRangeBounds<T: Config> = StorageValue<_, (u64, u64), ValueQuery>;
+
 	#[pallet::storage]
 	// A full identifier for a native asset on Cardano: (policy id, asset name)
 	pub type CNightIdentifier<T: Config> = StorageValue<
@@ -203,6 +290,7 @@ pub mod pallet {
 	}
 
 	#[pallet::storage]
+	/// Max amount of Cardano blocks that can be observed per block
 	pub type CardanoBlockWindowSize<T: Config> =
 		StorageValue<_, u32, ValueQuery, DefaultCardanoBlockWindowSize>;
 
@@ -216,6 +304,10 @@ pub mod pallet {
 	pub type CardanoTxCapacityPerBlock<T: Config> =
 		StorageValue<_, u32, ValueQuery, DefaultCardanoTxCapacityPerBlock>;
 
+	#[pallet::storage]
+	/// Bounds of `CardanoBlockWindowSize` and `CardanoTxCapacityPerBlock`
+	pub type CardanoObservationLimits<T: Config> = StorageValue<_, ObservationLimits, ValueQuery>;
+
 	#[pallet::genesis_config]
 	#[derive(frame_support::DefaultNoBound)]
 	pub struct GenesisConfig<T: Config> {
@@ -274,6 +366,9 @@ pub mod pallet {
 
 			for (k, v) in &self.config.mappings {
 				Mappings::<T>::insert(k, v.clone());
+				for mapping in v {
+					MappingsByDustPublicKey::<T>::insert(&mapping.dust_public_key, k, ());
+				}
 			}
 
 			for (k, v) in &self.config.utxo_owners {
@@ -294,16 +389,21 @@ pub mod pallet {
 			Self::get_data_from_inherent_data(data).map(|data| Call::process_tokens {
 				utxos: data.utxos,
 				next_cardano_position: data.next_cardano_position,
+				cardano_tip_lag: data.cardano_tip_lag,
 			})
 		}
 
 		fn check_inherent(call: &Self::Call, data: &InherentData) -> Result<(), Self::Error> {
-			let Call::process_tokens { utxos, next_cardano_position } = call else {
+			let Call::process_tokens { utxos, next_cardano_position, cardano_tip_lag } = call
+			else {
 				return Ok(());
 			};
 
 			let parsed = Self::get_data_from_inherent_data(data).ok_or(InherentError::Other)?;
-			if parsed.utxos != *utxos || parsed.next_cardano_position != *next_cardano_position {
+			if parsed.utxos != *utxos
+				|| parsed.next_cardano_position != *next_cardano_position
+				|| parsed.cardano_tip_lag != *cardano_tip_lag
+			{
 				return Err(InherentError::Other);
 			}
 			Ok(())
@@ -342,6 +442,39 @@ pub mod pallet {
 			mappings.len() == 1
 		}
 
+		pub fn get_mappings_by_dust_public_key(
+			dust_public_key: &DustPublicKeyBytes,
+		) -> Vec<MappingEntry> {
+			MappingsByDustPublicKey::<T>::iter_key_prefix(dust_public_key)
+				.flat_map(Mappings::<T>::get)
+				.filter(|mapping| &mapping.dust_public_key == dust_public_key)
+				.collect()
+		}
+
+		/// Keeps `MappingsByDustPublicKey` in line with the mappings of a Cardano reward address,
+		/// after one to `dust_public_key` was added or removed
+		fn index_mappings(
+			cardano_reward_address: &CardanoRewardAddressBytes,
+			dust_public_key: &DustPublicKeyBytes,
+			mappings: &[MappingEntry],
+		) {
+			if mappings.iter().any(|mapping| &mapping.dust_public_key == dust_public_key) {
+				MappingsByDustPublicKey::<T>::insert(dust_public_key, cardano_reward_address, ());
+			} else {
+				MappingsByDustPublicKey::<T>::remove(dust_public_key, cardano_reward_address);
+			}
+		}
+
+		/// Owner of an observed cNight UTXO, whether a regular or a redemption one
+		pub fn get_utxo_owner(
+			utxo_tx_hash: &McTxHash,
+			utxo_index: u16,
+		) -> Option<DustPublicKeyBytes> {
+			[ASSET_CREATE, REDEMPTION_CREATE].into_iter().find_map(|kind| {
+				UtxoOwners::<T>::get(Self::utxo_nonce(kind, utxo_tx_hash, utxo_index))
+			})
+		}
+
 		#[allow(clippy::type_complexity)]
 		fn handle_registration(
 			header: &ObservedUtxoHeader,
@@ -361,6 +494,7 @@ pub mod pallet {
 			let mut mappings = Mappings::<T>::get(cardano_reward_address);
 			mappings.push(new_reg.clone());
 			Mappings::<T>::insert(cardano_reward_address, mappings.clone());
+			Self::index_mappings(&cardano_reward_address, &dust_public_key, &mappings);
 
 			let is_registered = Self::is_registered(&cardano_reward_address);
 
@@ -413,6 +547,7 @@ pub mod pallet {
 			} else {
 				Mappings::<T>::insert(cardano_reward_address, mappings.clone());
 			}
+			Self::index_mappings(&cardano_reward_address, &dust_public_key, &mappings);
 
 			let registration = Self::get_registration(&cardano_reward_address);
 
@@ -435,27 +570,24 @@ pub mod pallet {
 			Self::deposit_event(Event::<T>::MappingRemoved(reg_entry));
 		}
 
-		fn handle_create(
+		fn utxo_nonce(kind: &[u8], utxo_tx_hash: &McTxHash, utxo_tx_index: u16) -> T::Hash {
+			T::Hashing::hash(
+				&[kind, &utxo_tx_hash.0[..], &utxo_tx_index.to_be_bytes()[..]].concat(),
+			)
+		}
+
+		fn dust_event(
+			value: u128,
+			dust_public_key: &DustPublicKeyBytes,
 			cur_time: u64,
-			data: CreateData,
+			action: UtxoActionType,
+			nonce: T::Hash,
 		) -> Option<CNightGeneratesDustEventSerialized> {
-			let Some(ref dust_public_key) = Self::get_registration(&data.owner) else {
-				log::warn!("No valid dust registration for {:?}", &data.owner);
-				return None;
-			};
-
-			let nonce = T::Hashing::hash(
-				&[b"asset_create", &data.utxo_tx_hash.0[..], &data.utxo_tx_index.to_be_bytes()[..]]
-					.concat(),
-			);
-
-			UtxoOwners::<T>::insert(nonce, dust_public_key.clone());
-
 			let event = LedgerApi::construct_cnight_generates_dust_event(
-				data.value,
+				value,
 				&dust_public_key.0,
 				cur_time,
-				UtxoActionType::Create as u8,
+				action as u8,
 				nonce.0,
 			);
 
@@ -468,16 +600,227 @@ pub mod pallet {
 			}
 		}
 
+		/// Applies the dust events of the Cardano range ending at `position` to the ledger
+		fn apply_dust_events(
+			events: Vec<CNightGeneratesDustEventSerialized>,
+			position: CardanoPosition,
+		) -> DispatchResult {
+			if events.is_empty() {
+				return Ok(());
+			}
+
+			// Construct the Ledger system transaction
+			// Note: this into-map should compile into a no-op
+			let system_tx_result = LedgerApi::construct_cnight_generates_dust_system_tx(
+				events.into_iter().map(|e| e.0).collect(),
+			);
+			if let Ok(midnight_system_tx) = system_tx_result {
+				let system_transaction_hash =
+					<T as Config>::MidnightSystemTransactionExecutor::execute_system_transaction(
+						midnight_system_tx,
+					)?;
+
+				// Emit System Transaction for the indexer
+				let system_tx = SystemTransactionApplied {
+					header: CmstHeader {
+						block_hash: position.block_hash,
+						tx_index_in_block: position.tx_index_in_block,
+					},
+					system_transaction_hash,
+				};
+				Self::deposit_event(Event::<T>::SystemTransactionApplied(system_tx));
+			} else {
+				log::error!("Fatal: failed to construct ledger system transaction");
+			}
+			Ok(())
+		}
+
+		/// Adapts the Cardano block window and transaction capacity of the next observation to the
+		/// one of `utxos`, which ended `cardano_tip_lag` blocks behind the Cardano tip
+		///
+		/// The capacity is halved when the observed UTXOs exceed the weight budget of the
+		/// observation, and doubled when it bound the observation with room to spare in the budget.
+		/// The window is doubled when it bound the observation, i.e. the observation ended before
+		/// the Cardano tip without reaching the capacity, and halved when the capacity did, as it
+		/// could not be consumed at once.
+		///
+		/// The adaptation only depends on on-chain state and on the inherent, so that the inherent
+		/// data providers of the block author and of the importers observe the same range.
+		fn adapt_cardano_observation(cardano_tip_lag: u32, utxos: &[ObservedUtxo]) {
+			let limits = CardanoObservationLimits::<T>::get();
+			let block_window = CardanoBlockWindowSize::<T>::get();
+			let tx_capacity = CardanoTxCapacityPerBlock::<T>::get();
+
+			let max_block = T::BlockWeights::get().max_block;
+			let budget = Weight::from_parts(
+				CARDANO_OBSERVATION_WEIGHT_BUDGET * max_block.ref_time(),
+				CARDANO_OBSERVATION_WEIGHT_BUDGET * max_block.proof_size(),
+			);
+			let consumed = T::WeightInfo::process_tokens(utxos.len() as u32);
+
+			let num_txs =
+				utxos.chunk_by(|a, b| a.header.tx_position == b.header.tx_position).count() as u32;
+			let capacity_bound = num_txs >= tx_capacity;
+			let window_bound = !capacity_bound && cardano_tip_lag > 0;
+
+			let next_tx_capacity = if consumed.any_gt(budget) {
+				tx_capacity / 2
+			} else if capacity_bound && !consumed.saturating_mul(2).any_gt(budget) {
+				tx_capacity.saturating_mul(2)
+			} else {
+				tx_capacity
+			};
+			let next_block_window = if capacity_bound {
+				block_window / 2
+			} else if window_bound {
+				block_window.saturating_mul(2)
+			} else {
+				block_window
+			};
+
+			let next_block_window =
+				next_block_window.clamp(limits.min_block_window, limits.max_block_window);
+			let next_tx_capacity =
+				next_tx_capacity.clamp(limits.min_tx_capacity, limits.max_tx_capacity);
+			if (next_block_window, next_tx_capacity) != (block_window, tx_capacity) {
+				if window_bound {
+					log::info!(
+						"cNight observation lags {cardano_tip_lag} Cardano blocks behind the tip, observing up to {next_block_window} Cardano blocks"
+					);
+				}
+				Self::set_cardano_observation(next_block_window, next_tx_capacity);
+			}
+		}
+
+		fn set_cardano_observation(block_window: u32, tx_capacity: u32) {
+			CardanoBlockWindowSize::<T>::put(block_window);
+			CardanoTxCapacityPerBlock::<T>::put(tx_capacity);
+			Self::deposit_event(Event::<T>::CardanoObservationAdapted(CardanoObservationAdapted {
+				block_window,
+				tx_capacity,
+			}));
+		}
+
+		/// Journals a consumed Cardano range, dropping the ranges past the rollback depth
+		fn record_consumed_range(range: ConsumedCardanoRange) {
+			let (mut first, next) = ConsumedCardanoRangeBounds::<T>::get();
+			let tip = range.end.block_number;
+			ConsumedCardanoRanges::<T>::insert(next, range);
+			while first < next {
+				match ConsumedCardanoRanges::<T>::get(first) {
+					Some(oldest)
+						if oldest.end.block_number.saturating_add(CARDANO_ROLLBACK_DEPTH) < tip =>
+					{
+						ConsumedCardanoRanges::<T>::remove(first);
+						first += 1;
+					},
+					_ => break,
+				}
+			}
+			ConsumedCardanoRangeBounds::<T>::put((first, next + 1));
+		}
+
+		/// Reverts the effects of an observed UTXO, returning the compensating dust event, if any
+		///
+		/// The compensating event is dated like the reverted one, so that no dust is generated in
+		/// between.
+		fn revert_utxo(utxo: ObservedUtxo) -> Option<CNightGeneratesDustEventSerialized> {
+			let now = utxo.header.tx_position.block_timestamp.0 as u64 / 1000;
+			match utxo.data {
+				ObservedUtxoData::Registration(RegistrationData {
+					cardano_reward_address,
+					dust_public_key,
+				}) => {
+					Self::handle_registration_removal(
+						&utxo.header,
+						DeregistrationData { cardano_reward_address, dust_public_key },
+					);
+					None
+				},
+				ObservedUtxoData::Deregistration(DeregistrationData {
+					cardano_reward_address,
+					dust_public_key,
+				}) => {
+					Self::handle_registration(
+						&utxo.header,
+						RegistrationData { cardano_reward_address, dust_public_key },
+					);
+					None
+				},
+				ObservedUtxoData::AssetCreate(data) => {
+					let nonce =
+						Self::utxo_nonce(ASSET_CREATE, &data.utxo_tx_hash, data.utxo_tx_index);
+					let dust_public_key = UtxoOwners::<T>::take(nonce)?;
+					Self::dust_event(
+						data.value,
+						&dust_public_key,
+						now,
+						UtxoActionType::Destroy,
+						nonce,
+					)
+				},
+				ObservedUtxoData::AssetSpend(data) => {
+					let nonce =
+						Self::utxo_nonce(ASSET_CREATE, &data.utxo_tx_hash, data.utxo_tx_index);
+					let dust_public_key = UtxoOwners::<T>::get(nonce)?;
+					Self::dust_event(
+						data.value,
+						&dust_public_key,
+						now,
+						UtxoActionType::Create,
+						nonce,
+					)
+				},
+				ObservedUtxoData::RedemptionCreate(data) => {
+					let nonce =
+						Self::utxo_nonce(REDEMPTION_CREATE, &data.utxo_tx_hash, data.utxo_tx_index);
+					let dust_public_key = UtxoOwners::<T>::take(nonce)?;
+					Self::dust_event(
+						data.value,
+						&dust_public_key,
+						now,
+						UtxoActionType::Destroy,
+						nonce,
+					)
+				},
+				ObservedUtxoData::RedemptionSpend(data) => {
+					let nonce =
+						Self::utxo_nonce(REDEMPTION_CREATE, &data.utxo_tx_hash, data.utxo_tx_index);
+					let dust_public_key = UtxoOwners::<T>::get(nonce)?;
+					Self::dust_event(
+						data.value,
+						&dust_public_key,
+						now,
+						UtxoActionType::Create,
+						nonce,
+					)
+				},
+			}
+		}
+
+		fn handle_create(
+			cur_time: u64,
+			data: CreateData,
+		) -> Option<CNightGeneratesDustEventSerialized> {
+			let Some(ref dust_public_key) = Self::get_registration(&data.owner) else {
+				log::warn!("No valid dust registration for {:?}", &data.owner);
+				return None;
+			};
+
+			let nonce = Self::utxo_nonce(ASSET_CREATE, &data.utxo_tx_hash, data.utxo_tx_index);
+
+			UtxoOwners::<T>::insert(nonce, dust_public_key.clone());
+
+			Self::dust_event(data.value, dust_public_key, cur_time, UtxoActionType::Create, nonce)
+		}
+
 		fn handle_spend(
 			cur_time: u64,
 			data: SpendData,
 		) -> Option<CNightGeneratesDustEventSerialized> {
-			let nonce = T::Hashing::hash(
-				&[b"asset_create", &data.utxo_tx_hash.0[..], &data.utxo_tx_index.to_be_bytes()[..]]
-					.concat(),
-			);
+			let nonce = Self::utxo_nonce(ASSET_CREATE, &data.utxo_tx_hash, data.utxo_tx_index);
 
-			let Some(dust_public_key) = UtxoOwners::<T>::get(nonce) else {
+			let Some(ref dust_public_key) = UtxoOwners::<T>::get(nonce) else {
 				log::warn!(
 					"No create event for UTXO: {}#{}",
 					hex::encode(data.utxo_tx_hash.0),
@@ -486,21 +829,7 @@ pub mod pallet {
 				return None;
 			};
 
-			let event = LedgerApi::construct_cnight_generates_dust_event(
-				data.value,
-				&dust_public_key.0,
-				cur_time,
-				UtxoActionType::Destroy as u8,
-				nonce.0,
-			);
-
-			match event {
-				Ok(event_bytes) => Some(CNightGeneratesDustEventSerialized(event_bytes)),
-				Err(e) => {
-					log::error!("Fatal: Unable to construct CNightGeneratesDustEvent: {e:?}");
-					None
-				},
-			}
+			Self::dust_event(data.value, dust_public_key, cur_time, UtxoActionType::Destroy, nonce)
 		}
 
 		fn handle_redemption_create(
@@ -512,46 +841,18 @@ pub mod pallet {
 				return None;
 			};
 
-			let nonce = T::Hashing::hash(
-				&[
-					b"redemption_create",
-					&data.utxo_tx_hash.0[..],
-					&data.utxo_tx_index.to_be_bytes()[..],
-				]
-				.concat(),
-			);
+			let nonce = Self::utxo_nonce(REDEMPTION_CREATE, &data.utxo_tx_hash, data.utxo_tx_index);
 
 			UtxoOwners::<T>::insert(nonce, dust_public_key.clone());
 
-			let event = LedgerApi::construct_cnight_generates_dust_event(
-				data.value,
-				&dust_public_key.0,
-				cur_time,
-				UtxoActionType::Create as u8,
-				nonce.0,
-			);
-
-			match event {
-				Ok(event_bytes) => Some(CNightGeneratesDustEventSerialized(event_bytes)),
-				Err(e) => {
-					log::error!("Fatal: Unable to construct CNightGeneratesDustEvent: {e:?}");
-					None
-				},
-			}
+			Self::dust_event(data.value, dust_public_key, cur_time, UtxoActionType::Create, nonce)
 		}
 
 		fn handle_redemption_spend(
 			cur_time: u64,
 			data: RedemptionSpendData,
 		) -> Option<CNightGeneratesDustEventSerialized> {
-			let nonce = T::Hashing::hash(
-				&[
-					b"redemption_create",
-					&data.utxo_tx_hash.0[..],
-					&data.utxo_tx_index.to_be_bytes()[..],
-				]
-				.concat(),
-			);
+			let nonce = Self::utxo_nonce(REDEMPTION_CREATE, &data.utxo_tx_hash, data.utxo_tx_index);
 
 			let Some(ref dust_public_key) = UtxoOwners::<T>::get(nonce) else {
 				log::warn!(
@@ -562,35 +863,32 @@ pub mod pallet {
 				return None;
 			};
 
-			let event = LedgerApi::construct_cnight_generates_dust_event(
-				data.value,
-				&dust_public_key.0,
-				cur_time,
-				UtxoActionType::Destroy as u8,
-				nonce.0,
-			);
-
-			match event {
-				Ok(event_bytes) => Some(CNightGeneratesDustEventSerialized(event_bytes)),
-				Err(e) => {
-					log::error!("Fatal: Unable to construct CNightGeneratesDustEvent: {e:?}");
-					None
-				},
-			}
+			Self::dust_event(data.value, dust_public_key, cur_time, UtxoActionType::Destroy, nonce)
 		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
-		#[pallet::weight((0, DispatchClass::Mandatory))]
+		#[pallet::weight((T::WeightInfo::process_tokens(utxos.len() as u32), DispatchClass::Mandatory))]
 		pub fn process_tokens(
 			origin: OriginFor<T>,
 			utxos: Vec<ObservedUtxo>,
 			next_cardano_position: CardanoPosition,
+			cardano_tip_lag: u32,
 		) -> DispatchResult {
 			ensure_none(origin)?;
 
+			let start = NextCardanoPosition::<T>::get();
+			Self::adapt_cardano_observation(cardano_tip_lag, &utxos);
+			if start != next_cardano_position || !utxos.is_empty() {
+				Self::record_consumed_range(ConsumedCardanoRange {
+					start,
+					end: next_cardano_position.clone(),
+					utxos: utxos.clone(),
+				});
+			}
+
 			let mut events: Vec<CNightGeneratesDustEventSerialized> = Vec::new();
 
 			for utxo in utxos {
@@ -641,30 +939,7 @@ pub mod pallet {
 
 			NextCardanoPosition::<T>::set(next_cardano_position.clone());
 
-			if !events.is_empty() {
-				// Construct the Ledger system transaction
-				// Note: this into-map should compile into a no-op
-				let system_tx_result = LedgerApi::construct_cnight_generates_dust_system_tx(
-					events.into_iter().map(|e| e.0).collect(),
-				);
-				if let Ok(midnight_system_tx) = system_tx_result {
-					let system_transaction_hash =
-						<T as Config>::MidnightSystemTransactionExecutor::execute_system_transaction(midnight_system_tx)?;
-
-					// Emit System Transaction for the indexer
-					let system_tx = SystemTransactionApplied {
-						header: CmstHeader {
-							block_hash: next_cardano_position.block_hash,
-							tx_index_in_block: next_cardano_position.tx_index_in_block,
-						},
-						system_transaction_hash,
-					};
-					Self::deposit_event(Event::<T>::SystemTransactionApplied(system_tx));
-				} else {
-					log::error!("Fatal: failed to construct ledger system transaction");
-				}
-			}
-			Ok(())
+			Self::apply_dust_events(events, next_cardano_position)
 		}
 
 		/// Changes the mainchain address for the mapping validator contract
@@ -686,5 +961,99 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Reverts the cNight observation to `position`, after a Cardano rollback
+		///
+		/// `position` must be the start of one of the last `max_ranges` consumed Cardano ranges.
+		/// Every range consumed from it is reverted, most recent first: mappings are restored,
+		/// and compensating dust events are issued for the cNight UTXOs whose creation or spend
+		/// is reverted. The call is weighed by `max_ranges` and `max_utxos`, and fails if the
+		/// reverted ranges hold more than `max_utxos` UTXOs. The actual weight is refunded.
+		///
+		/// This extrinsic must be run either using `sudo` or some other chain governance mechanism.
+		#[pallet::call_index(3)]
+		#[pallet::weight((
+			T::WeightInfo::rollback_cardano_observation(*max_ranges, *max_utxos),
+			DispatchClass::Normal
+		))]
+		pub fn rollback_cardano_observation(
+			origin: OriginFor<T>,
+			position: CardanoPosition,
+			max_ranges: u32,
+			max_utxos: u32,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			// Only the last `max_ranges` ranges are read
+			let (first, next) = ConsumedCardanoRangeBounds::<T>::get();
+			let oldest = next.saturating_sub(max_ranges.into()).max(first);
+			let mut ranges = Vec::new();
+			let mut rollback_from = None;
+			for seq in (oldest..next).rev() {
+				let Some(range) = ConsumedCardanoRanges::<T>::get(seq) else { continue };
+				let found = range.start == position;
+				ranges.push(range);
+				if found {
+					rollback_from = Some(seq);
+					break;
+				}
+			}
+			let rollback_from = rollback_from.ok_or(Error::<T>::UnknownCardanoRollbackPosition)?;
+
+			let reverted_ranges = (next - rollback_from) as u32;
+			let reverted_utxos = ranges
+				.iter()
+				.map(|range| range.utxos.len() as u32)
+				.fold(0u32, u32::saturating_add);
+			ensure!(reverted_utxos <= max_utxos, Error::<T>::CardanoRollbackTooLarge);
+
+			let mut events = Vec::new();
+			for range in ranges {
+				for utxo in range.utxos.into_iter().rev() {
+					if let Some(event) = Self::revert_utxo(utxo) {
+						events.push(event);
+					}
+				}
+			}
+			for seq in rollback_from..next {
+				ConsumedCardanoRanges::<T>::remove(seq);
+			}
+			ConsumedCardanoRangeBounds::<T>::put((first, rollback_from));
+			NextCardanoPosition::<T>::set(position.clone());
+
+			log::warn!(
+				"Rolled back cNight observation to {position}, reverting {reverted_utxos} UTXOs"
+			);
+			Self::deposit_event(Event::<T>::CardanoObservationRolledBack(
+				CardanoObservationRolledBack { position: position.clone(), reverted_utxos },
+			));
+			Self::apply_dust_events(events, position)?;
+
+			Ok(Some(T::WeightInfo::rollback_cardano_observation(reverted_ranges, reverted_utxos))
+				.into())
+		}
+
+		/// Changes the bounds within which the Cardano block window and transaction capacity
+		/// are adapted, clamping their current values
+		///
+		/// This extrinsic must be run either using `sudo` or some other chain governance mechanism.
+		#[pallet::call_index(4)]
+		#[pallet::weight((T::WeightInfo::set_cardano_observation_limits(), DispatchClass::Normal))]
+		pub fn set_cardano_observation_limits(
+			origin: OriginFor<T>,
+			limits: ObservationLimits,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(limits.is_valid(), Error::<T>::InvalidCardanoObservationLimits);
+
+			CardanoObservationLimits::<T>::put(limits);
+			Self::set_cardano_observation(
+				CardanoBlockWindowSize::<T>::get()
+					.clamp(limits.min_block_window, limits.max_block_window),
+				CardanoTxCapacityPerBlock::<T>::get()
+					.clamp(limits.min_tx_capacity, limits.max_tx_capacity),
+			);
+			Ok(())
+		}
 	}
 }
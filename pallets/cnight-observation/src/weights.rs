@@ -0,0 +1,218 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for `pallet_cnight_observation`
+//!
+//! TODO: These are estimates, laid out as the benchmark CLI generates them. Replace this file
+//! with the output of the `pallet_cnight_observation` benchmarks on reference hardware:
+//!
+//! ./target/release/midnight-node benchmark pallet \
+//!     --runtime ./target/release/wbuild/midnight-node-runtime/midnight_node_runtime.wasm \
+//!     --genesis-builder=spec --wasm-execution=compiled --pallet=pallet_cnight_observation \
+//!     --extrinsic=* --steps 50 --repeat 20 --output pallets/cnight-observation/src/weights.rs \
+//!     --template=./res/weights-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+#![allow(clippy::too_many_arguments)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::ParityDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for `pallet_cnight_observation`.
+pub trait WeightInfo {
+	fn process_tokens(u: u32, ) -> Weight;
+	fn rollback_cardano_observation(r: u32, u: u32, ) -> Weight;
+	fn set_cardano_observation_limits() -> Weight;
+}
+
+/// Weights for `pallet_cnight_observation` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `CNightObservation::CardanoObservationLimits` (r:1 w:0)
+	/// Proof: `CNightObservation::CardanoObservationLimits` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::CardanoBlockWindowSize` (r:1 w:1)
+	/// Proof: `CNightObservation::CardanoBlockWindowSize` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::CardanoTxCapacityPerBlock` (r:1 w:1)
+	/// Proof: `CNightObservation::CardanoTxCapacityPerBlock` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::NextCardanoPosition` (r:1 w:1)
+	/// Proof: `CNightObservation::NextCardanoPosition` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::ConsumedCardanoRangeBounds` (r:1 w:1)
+	/// Proof: `CNightObservation::ConsumedCardanoRangeBounds` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::ConsumedCardanoRanges` (r:1 w:2)
+	/// Proof: `CNightObservation::ConsumedCardanoRanges` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Midnight::StateKey` (r:1 w:1)
+	/// Proof: `Midnight::StateKey` (`max_values`: Some(1), `max_size`: Some(130), added: 625, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `System::ParentHash` (r:1 w:0)
+	/// Proof: `System::ParentHash` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `CNightObservation::Mappings` (r:1000 w:1000)
+	/// Proof: `CNightObservation::Mappings` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::MappingsByDustPublicKey` (r:0 w:1000)
+	/// Proof: `CNightObservation::MappingsByDustPublicKey` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::UtxoOwners` (r:0 w:1000)
+	/// Proof: `CNightObservation::UtxoOwners` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// The range of component `u` is `[0, 1000]`.
+	fn process_tokens(u: u32, ) -> Weight {
+		// Estimate: 2 ms to apply the dust system transaction to the ledger state, and 100 µs
+		// per UTXO to build its dust event. Each UTXO reads the mappings of its owner, and
+		// writes at most twice: a registration writes `Mappings` and `MappingsByDustPublicKey`,
+		// a cNight UTXO creation writes `UtxoOwners`.
+		Weight::from_parts(2_000_000_000, 4507)
+			.saturating_add(Weight::from_parts(100_000_000, 0).saturating_mul(u.into()))
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(u.into())))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(u.into())))
+	}
+	/// Storage: `CNightObservation::ConsumedCardanoRangeBounds` (r:1 w:1)
+	/// Proof: `CNightObservation::ConsumedCardanoRangeBounds` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::ConsumedCardanoRanges` (r:1000 w:1000)
+	/// Proof: `CNightObservation::ConsumedCardanoRanges` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Midnight::StateKey` (r:1 w:1)
+	/// Proof: `Midnight::StateKey` (`max_values`: Some(1), `max_size`: Some(130), added: 625, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `System::ParentHash` (r:1 w:0)
+	/// Proof: `System::ParentHash` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `CNightObservation::Mappings` (r:1000 w:1000)
+	/// Proof: `CNightObservation::Mappings` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::MappingsByDustPublicKey` (r:0 w:1000)
+	/// Proof: `CNightObservation::MappingsByDustPublicKey` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::UtxoOwners` (r:1000 w:1000)
+	/// Proof: `CNightObservation::UtxoOwners` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::NextCardanoPosition` (r:0 w:1)
+	/// Proof: `CNightObservation::NextCardanoPosition` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// The range of component `r` is `[1, 1000]`.
+	/// The range of component `u` is `[0, 1000]`.
+	fn rollback_cardano_observation(r: u32, u: u32, ) -> Weight {
+		// Estimate: 2 ms to apply the compensating dust system transaction to the ledger state,
+		// 20 µs to read and remove each consumed range, and 100 µs per reverted UTXO. Each
+		// reverted UTXO reads once and writes at most twice, like in `process_tokens`.
+		Weight::from_parts(2_000_000_000, 4601)
+			.saturating_add(Weight::from_parts(20_000_000, 0).saturating_mul(r.into()))
+			.saturating_add(Weight::from_parts(100_000_000, 0).saturating_mul(u.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(r.into())))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(u.into())))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(r.into())))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(u.into())))
+			.saturating_add(Weight::from_parts(0, 2596).saturating_mul(r.into()))
+			.saturating_add(Weight::from_parts(0, 143).saturating_mul(u.into()))
+	}
+	/// Storage: `CNightObservation::CardanoBlockWindowSize` (r:1 w:1)
+	/// Proof: `CNightObservation::CardanoBlockWindowSize` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::CardanoTxCapacityPerBlock` (r:1 w:1)
+	/// Proof: `CNightObservation::CardanoTxCapacityPerBlock` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::CardanoObservationLimits` (r:0 w:1)
+	/// Proof: `CNightObservation::CardanoObservationLimits` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	fn set_cardano_observation_limits() -> Weight {
+		// Estimate: a few storage values, without ledger access
+		Weight::from_parts(10_000_000, 1527)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `CNightObservation::CardanoObservationLimits` (r:1 w:0)
+	/// Proof: `CNightObservation::CardanoObservationLimits` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::CardanoBlockWindowSize` (r:1 w:1)
+	/// Proof: `CNightObservation::CardanoBlockWindowSize` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::CardanoTxCapacityPerBlock` (r:1 w:1)
+	/// Proof: `CNightObservation::CardanoTxCapacityPerBlock` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::NextCardanoPosition` (r:1 w:1)
+	/// Proof: `CNightObservation::NextCardanoPosition` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::ConsumedCardanoRangeBounds` (r:1 w:1)
+	/// Proof: `CNightObservation::ConsumedCardanoRangeBounds` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::ConsumedCardanoRanges` (r:1 w:2)
+	/// Proof: `CNightObservation::ConsumedCardanoRanges` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Midnight::StateKey` (r:1 w:1)
+	/// Proof: `Midnight::StateKey` (`max_values`: Some(1), `max_size`: Some(130), added: 625, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `System::ParentHash` (r:1 w:0)
+	/// Proof: `System::ParentHash` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `CNightObservation::Mappings` (r:1000 w:1000)
+	/// Proof: `CNightObservation::Mappings` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::MappingsByDustPublicKey` (r:0 w:1000)
+	/// Proof: `CNightObservation::MappingsByDustPublicKey` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::UtxoOwners` (r:0 w:1000)
+	/// Proof: `CNightObservation::UtxoOwners` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// The range of component `u` is `[0, 1000]`.
+	fn process_tokens(u: u32, ) -> Weight {
+		// Estimate: 2 ms to apply the dust system transaction to the ledger state, and 100 µs
+		// per UTXO to build its dust event. Each UTXO reads the mappings of its owner, and
+		// writes at most twice: a registration writes `Mappings` and `MappingsByDustPublicKey`,
+		// a cNight UTXO creation writes `UtxoOwners`.
+		Weight::from_parts(2_000_000_000, 4507)
+			.saturating_add(Weight::from_parts(100_000_000, 0).saturating_mul(u.into()))
+			.saturating_add(ParityDbWeight::get().reads(9_u64))
+			.saturating_add(ParityDbWeight::get().reads((1_u64).saturating_mul(u.into())))
+			.saturating_add(ParityDbWeight::get().writes(7_u64))
+			.saturating_add(ParityDbWeight::get().writes((2_u64).saturating_mul(u.into())))
+	}
+	/// Storage: `CNightObservation::ConsumedCardanoRangeBounds` (r:1 w:1)
+	/// Proof: `CNightObservation::ConsumedCardanoRangeBounds` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::ConsumedCardanoRanges` (r:1000 w:1000)
+	/// Proof: `CNightObservation::ConsumedCardanoRanges` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Midnight::StateKey` (r:1 w:1)
+	/// Proof: `Midnight::StateKey` (`max_values`: Some(1), `max_size`: Some(130), added: 625, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `System::ParentHash` (r:1 w:0)
+	/// Proof: `System::ParentHash` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// Storage: `CNightObservation::Mappings` (r:1000 w:1000)
+	/// Proof: `CNightObservation::Mappings` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::MappingsByDustPublicKey` (r:0 w:1000)
+	/// Proof: `CNightObservation::MappingsByDustPublicKey` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::UtxoOwners` (r:1000 w:1000)
+	/// Proof: `CNightObservation::UtxoOwners` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::NextCardanoPosition` (r:0 w:1)
+	/// Proof: `CNightObservation::NextCardanoPosition` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// The range of component `r` is `[1, 1000]`.
+	/// The range of component `u` is `[0, 1000]`.
+	fn rollback_cardano_observation(r: u32, u: u32, ) -> Weight {
+		// Estimate: 2 ms to apply the compensating dust system transaction to the ledger state,
+		// 20 µs to read and remove each consumed range, and 100 µs per reverted UTXO. Each
+		// reverted UTXO reads once and writes at most twice, like in `process_tokens`.
+		Weight::from_parts(2_000_000_000, 4601)
+			.saturating_add(Weight::from_parts(20_000_000, 0).saturating_mul(r.into()))
+			.saturating_add(Weight::from_parts(100_000_000, 0).saturating_mul(u.into()))
+			.saturating_add(ParityDbWeight::get().reads(4_u64))
+			.saturating_add(ParityDbWeight::get().reads((1_u64).saturating_mul(r.into())))
+			.saturating_add(ParityDbWeight::get().reads((1_u64).saturating_mul(u.into())))
+			.saturating_add(ParityDbWeight::get().writes(3_u64))
+			.saturating_add(ParityDbWeight::get().writes((1_u64).saturating_mul(r.into())))
+			.saturating_add(ParityDbWeight::get().writes((2_u64).saturating_mul(u.into())))
+			.saturating_add(Weight::from_parts(0, 2596).saturating_mul(r.into()))
+			.saturating_add(Weight::from_parts(0, 143).saturating_mul(u.into()))
+	}
+	/// Storage: `CNightObservation::CardanoBlockWindowSize` (r:1 w:1)
+	/// Proof: `CNightObservation::CardanoBlockWindowSize` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::CardanoTxCapacityPerBlock` (r:1 w:1)
+	/// Proof: `CNightObservation::CardanoTxCapacityPerBlock` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `CNightObservation::CardanoObservationLimits` (r:0 w:1)
+	/// Proof: `CNightObservation::CardanoObservationLimits` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	fn set_cardano_observation_limits() -> Weight {
+		// Estimate: a few storage values, without ledger access
+		Weight::from_parts(10_000_000, 1527)
+			.saturating_add(ParityDbWeight::get().reads(2_u64))
+			.saturating_add(ParityDbWeight::get().writes(3_u64))
+	}
+}
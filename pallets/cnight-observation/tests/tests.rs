@@ -11,7 +11,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 use frame_support::{
-	assert_ok, inherent::InherentData, pallet_prelude::*, sp_runtime::traits::Dispatchable,
+	assert_noop, assert_ok, inherent::InherentData, pallet_prelude::*,
+	sp_runtime::traits::Dispatchable,
 };
 use midnight_node_ledger::types::BlockContext;
 use midnight_node_ledger_helpers::{
@@ -39,17 +40,41 @@ use test_log::test;
 fn create_inherent(
 	utxos: Vec<ObservedUtxo>,
 	next_cardano_position: CardanoPosition,
+) -> InherentData {
+	create_inherent_behind_tip(utxos, next_cardano_position, 0)
+}
+
+fn create_inherent_behind_tip(
+	utxos: Vec<ObservedUtxo>,
+	next_cardano_position: CardanoPosition,
+	cardano_tip_lag: u32,
 ) -> InherentData {
 	let mut inherent_data = InherentData::new();
 	inherent_data
 		.put_data(
 			INHERENT_IDENTIFIER,
-			&MidnightObservationTokenMovement { utxos, next_cardano_position },
+			&MidnightObservationTokenMovement { utxos, next_cardano_position, cardano_tip_lag },
 		)
 		.expect("inherent data insertion should not fail");
 	inherent_data
 }
 
+fn process_tokens(utxos: Vec<ObservedUtxo>, next_cardano_position: CardanoPosition) {
+	process_tokens_behind_tip(utxos, next_cardano_position, 0)
+}
+
+fn process_tokens_behind_tip(
+	utxos: Vec<ObservedUtxo>,
+	next_cardano_position: CardanoPosition,
+	cardano_tip_lag: u32,
+) {
+	let inherent_data = create_inherent_behind_tip(utxos, next_cardano_position, cardano_tip_lag);
+	let call = CNightObservation::create_inherent(&inherent_data)
+		.expect("Expected to create inherent call");
+	let call = RuntimeCall::CNightObservation(call);
+	assert_ok!(call.dispatch(frame_system::RawOrigin::None.into()));
+}
+
 fn tx_hash(block_number: u32, tx_index_in_block: u32) -> McTxHash {
 	let mut seed = [0u8; 32];
 	seed[0..4].copy_from_slice(&block_number.to_be_bytes());
@@ -785,6 +810,450 @@ fn emits_deregistration_and_mapping_removed_on_last_mapping_removed() {
 	});
 }
 
+#[test]
+fn rollback_reverts_mappings_and_utxo_owners() {
+	new_test_ext().execute_with(|| {
+		init_ledger_state();
+		let (cardano_reward_address, dust_public_key) = test_wallet_pairing();
+
+		let reg_header = test_header(1, 0, 0, None);
+		process_tokens(
+			vec![ObservedUtxo {
+				header: reg_header.clone(),
+				data: ObservedUtxoData::Registration(RegistrationData {
+					cardano_reward_address,
+					dust_public_key: dust_public_key.clone(),
+				}),
+			}],
+			test_position(2, 0),
+		);
+		process_tokens(
+			vec![
+				ObservedUtxo {
+					header: test_header(2, 0, 0, None),
+					data: ObservedUtxoData::AssetCreate(CreateData {
+						value: 100,
+						owner: cardano_reward_address,
+						utxo_tx_hash: tx_hash(2, 0),
+						utxo_tx_index: 0,
+					}),
+				},
+				ObservedUtxo {
+					header: test_header(2, 1, 0, Some(reg_header.utxo_tx_hash)),
+					data: ObservedUtxoData::Deregistration(DeregistrationData {
+						cardano_reward_address,
+						dust_public_key: dust_public_key.clone(),
+					}),
+				},
+			],
+			test_position(3, 0),
+		);
+		assert!(Mappings::<Test>::get(cardano_reward_address).is_empty());
+		assert_eq!(UtxoOwners::<Test>::iter().count(), 1);
+		assert_eq!(ConsumedCardanoRangeBounds::<Test>::get(), (0, 2));
+
+		System::set_block_number(System::block_number() + 1);
+		frame_system::Pallet::<Test>::reset_events();
+
+		// Only the actual work is charged
+		let post_info = CNightObservation::rollback_cardano_observation(
+			frame_system::RawOrigin::Root.into(),
+			test_position(2, 0),
+			10,
+			10,
+		)
+		.unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(<() as weights::WeightInfo>::rollback_cardano_observation(1, 2))
+		);
+
+		// The deregistration and the asset creation are reverted, not the registration
+		let mappings = Mappings::<Test>::get(cardano_reward_address);
+		assert_eq!(mappings.len(), 1);
+		assert_eq!(mappings[0].dust_public_key, dust_public_key);
+		assert_eq!(UtxoOwners::<Test>::iter().count(), 0);
+		assert_eq!(NextCardanoPosition::<Test>::get(), test_position(2, 0));
+		assert_eq!(ConsumedCardanoRangeBounds::<Test>::get(), (0, 1));
+
+		assert!(any_event(|e| matches!(
+			e,
+			RuntimeEvent::CNightObservation(Event::CardanoObservationRolledBack(
+				CardanoObservationRolledBack { reverted_utxos: 2, .. }
+			))
+		)));
+		let dust_public_key_deser: DustPublicKey =
+			deserialize_untagged(&mut &dust_public_key.0[..]).unwrap();
+		let destroyed = System::events().iter().any(|record| {
+			let mock::RuntimeEvent::MidnightSystem(
+				pallet_midnight_system::Event::SystemTransactionApplied(e),
+			) = &record.event
+			else {
+				return false;
+			};
+			extract_events(&e.serialized_system_transaction).iter().any(|event| {
+				event.action == CNightGeneratesDustActionType::Destroy
+					&& event.owner == dust_public_key_deser
+			})
+		});
+		assert!(destroyed, "expected a compensating Destroy event for the reverted asset creation");
+	});
+}
+
+#[test]
+fn rollback_to_an_unknown_position_fails() {
+	new_test_ext().execute_with(|| {
+		process_tokens(vec![], test_position(2, 0));
+
+		assert_noop!(
+			CNightObservation::rollback_cardano_observation(
+				frame_system::RawOrigin::Root.into(),
+				test_position(1, 5),
+				10,
+				10,
+			),
+			Error::<Test>::UnknownCardanoRollbackPosition
+		);
+		assert_noop!(
+			CNightObservation::rollback_cardano_observation(
+				frame_system::RawOrigin::None.into(),
+				CardanoPosition::default(),
+				10,
+				10,
+			),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn rollback_is_bounded_by_the_given_maximums() {
+	new_test_ext().execute_with(|| {
+		init_ledger_state();
+		let (cardano_reward_address, dust_public_key) = test_wallet_pairing();
+		process_tokens(vec![], test_position(2, 0));
+		process_tokens(
+			vec![ObservedUtxo {
+				header: test_header(2, 0, 0, None),
+				data: ObservedUtxoData::Registration(RegistrationData {
+					cardano_reward_address,
+					dust_public_key,
+				}),
+			}],
+			test_position(3, 0),
+		);
+		process_tokens(vec![], test_position(4, 0));
+
+		// The range starting at the position is older than the last one
+		assert_noop!(
+			CNightObservation::rollback_cardano_observation(
+				frame_system::RawOrigin::Root.into(),
+				test_position(2, 0),
+				1,
+				10,
+			),
+			Error::<Test>::UnknownCardanoRollbackPosition
+		);
+		assert_noop!(
+			CNightObservation::rollback_cardano_observation(
+				frame_system::RawOrigin::Root.into(),
+				test_position(2, 0),
+				2,
+				0,
+			),
+			Error::<Test>::CardanoRollbackTooLarge
+		);
+		assert_ok!(CNightObservation::rollback_cardano_observation(
+			frame_system::RawOrigin::Root.into(),
+			test_position(2, 0),
+			2,
+			1,
+		));
+		assert_eq!(ConsumedCardanoRangeBounds::<Test>::get(), (0, 1));
+		assert!(Mappings::<Test>::get(cardano_reward_address).is_empty());
+	});
+}
+
+#[test]
+fn consumed_ranges_are_pruned_past_the_rollback_depth() {
+	new_test_ext().execute_with(|| {
+		process_tokens(vec![], test_position(10, 0));
+		process_tokens(vec![], test_position(20, 0));
+		// Nothing consumed, nothing journaled
+		process_tokens(vec![], test_position(20, 0));
+		assert_eq!(ConsumedCardanoRangeBounds::<Test>::get(), (0, 2));
+
+		process_tokens(vec![], test_position(20 + CARDANO_ROLLBACK_DEPTH + 1, 0));
+		assert_eq!(ConsumedCardanoRangeBounds::<Test>::get(), (2, 3));
+		assert!(ConsumedCardanoRanges::<Test>::get(0).is_none());
+		assert!(ConsumedCardanoRanges::<Test>::get(1).is_none());
+	});
+}
+
+#[test]
+fn looks_up_mappings_by_dust_public_key_and_utxo_owners() {
+	new_test_ext().execute_with(|| {
+		init_ledger_state();
+		let other_reward_address = cardano_reward_address(b"cardano2");
+		let unknown_dust_public_key = dust_public_key();
+		let (reward_address, dust_public_key) = test_wallet_pairing();
+
+		process_tokens(
+			vec![
+				ObservedUtxo {
+					header: test_header(1, 0, 0, None),
+					data: ObservedUtxoData::Registration(RegistrationData {
+						cardano_reward_address: reward_address,
+						dust_public_key: dust_public_key.clone(),
+					}),
+				},
+				ObservedUtxo {
+					header: test_header(1, 1, 0, None),
+					data: ObservedUtxoData::Registration(RegistrationData {
+						cardano_reward_address: other_reward_address,
+						dust_public_key: dust_public_key.clone(),
+					}),
+				},
+				ObservedUtxo {
+					header: test_header(2, 0, 0, None),
+					data: ObservedUtxoData::AssetCreate(CreateData {
+						value: 100,
+						owner: reward_address,
+						utxo_tx_hash: tx_hash(2, 0),
+						utxo_tx_index: 3,
+					}),
+				},
+			],
+			test_position(3, 0),
+		);
+
+		let mut owners: Vec<_> =
+			CNightObservation::get_mappings_by_dust_public_key(&dust_public_key)
+				.into_iter()
+				.map(|mapping| mapping.cardano_reward_address)
+				.collect();
+		owners.sort();
+		let mut expected = vec![reward_address, other_reward_address];
+		expected.sort();
+		assert_eq!(owners, expected);
+		assert!(
+			CNightObservation::get_mappings_by_dust_public_key(&unknown_dust_public_key).is_empty()
+		);
+
+		assert_eq!(CNightObservation::get_utxo_owner(&tx_hash(2, 0), 3), Some(dust_public_key));
+		assert_eq!(CNightObservation::get_utxo_owner(&tx_hash(2, 0), 0), None);
+	});
+}
+
+#[test]
+fn dust_public_key_index_follows_the_mappings() {
+	new_test_ext().execute_with(|| {
+		let (reward_address, dust_public_key) = test_wallet_pairing();
+		let registration = |header: ObservedUtxoHeader| ObservedUtxo {
+			header,
+			data: ObservedUtxoData::Registration(RegistrationData {
+				cardano_reward_address: reward_address,
+				dust_public_key: dust_public_key.clone(),
+			}),
+		};
+		let first_header = test_header(1, 0, 0, None);
+		let second_header = test_header(1, 1, 0, None);
+
+		process_tokens(
+			vec![registration(first_header.clone()), registration(second_header.clone())],
+			test_position(2, 0),
+		);
+		assert!(MappingsByDustPublicKey::<Test>::contains_key(&dust_public_key, reward_address));
+		assert_eq!(CNightObservation::get_mappings_by_dust_public_key(&dust_public_key).len(), 2);
+
+		// The address stays indexed while one of its mappings is left
+		let deregistration =
+			|block_number, registration_header: &ObservedUtxoHeader| ObservedUtxo {
+				header: test_header(block_number, 0, 0, Some(registration_header.utxo_tx_hash)),
+				data: ObservedUtxoData::Deregistration(DeregistrationData {
+					cardano_reward_address: reward_address,
+					dust_public_key: dust_public_key.clone(),
+				}),
+			};
+		process_tokens(vec![deregistration(2, &first_header)], test_position(3, 0));
+		assert!(MappingsByDustPublicKey::<Test>::contains_key(&dust_public_key, reward_address));
+		assert_eq!(CNightObservation::get_mappings_by_dust_public_key(&dust_public_key).len(), 1);
+
+		process_tokens(vec![deregistration(3, &second_header)], test_position(4, 0));
+		assert!(!MappingsByDustPublicKey::<Test>::contains_key(&dust_public_key, reward_address));
+		assert!(CNightObservation::get_mappings_by_dust_public_key(&dust_public_key).is_empty());
+	});
+}
+
+/// cNight UTXOs created in `num_txs` Cardano transactions of block 1, for an unregistered owner
+fn unregistered_asset_creates(num_txs: u32) -> Vec<ObservedUtxo> {
+	(0..num_txs)
+		.map(|i| ObservedUtxo {
+			header: test_header(1, i, 0, None),
+			data: ObservedUtxoData::AssetCreate(CreateData {
+				value: 100,
+				owner: cardano_reward_address(b"unregistered"),
+				utxo_tx_hash: tx_hash(1, i),
+				utxo_tx_index: 0,
+			}),
+		})
+		.collect()
+}
+
+#[test]
+fn block_window_grows_while_lagging_behind_the_cardano_tip() {
+	new_test_ext().execute_with(|| {
+		let block_window = CardanoBlockWindowSize::<Test>::get();
+		let tx_capacity = CardanoTxCapacityPerBlock::<Test>::get();
+
+		// The observation ends in the last block of the window, before the Cardano tip
+		let start = NextCardanoPosition::<Test>::get();
+		process_tokens_behind_tip(
+			vec![],
+			test_position(start.block_number + block_window - 1, 4),
+			100,
+		);
+
+		assert_eq!(CardanoBlockWindowSize::<Test>::get(), block_window * 2);
+		assert_eq!(CardanoTxCapacityPerBlock::<Test>::get(), tx_capacity);
+		assert!(System::events().iter().any(|record| {
+			record.event
+				== RuntimeEvent::CNightObservation(Event::CardanoObservationAdapted(
+					CardanoObservationAdapted { block_window: block_window * 2, tx_capacity },
+				))
+		}));
+
+		// Caught up with the Cardano tip, within the window
+		let start = NextCardanoPosition::<Test>::get();
+		process_tokens(vec![], test_position(start.block_number + 10, 0));
+		assert_eq!(CardanoBlockWindowSize::<Test>::get(), block_window * 2);
+
+		// Caught up with the Cardano tip, at the end of the window
+		let start = NextCardanoPosition::<Test>::get();
+		process_tokens(vec![], test_position(start.block_number + block_window * 2 - 1, 0));
+		assert_eq!(CardanoBlockWindowSize::<Test>::get(), block_window * 2);
+	});
+}
+
+#[test]
+fn inherent_with_another_cardano_tip_lag_is_rejected() {
+	new_test_ext().execute_with(|| {
+		let call = CNightObservation::create_inherent(&create_inherent_behind_tip(
+			vec![],
+			test_position(2, 0),
+			100,
+		))
+		.expect("Expected to create inherent call");
+
+		assert_ok!(CNightObservation::check_inherent(
+			&call,
+			&create_inherent_behind_tip(vec![], test_position(2, 0), 100)
+		));
+		assert!(
+			CNightObservation::check_inherent(
+				&call,
+				&create_inherent_behind_tip(vec![], test_position(2, 0), 0)
+			)
+			.is_err()
+		);
+	});
+}
+
+#[test]
+fn tx_capacity_grows_when_it_bounds_the_observation() {
+	new_test_ext().execute_with(|| {
+		let block_window = CardanoBlockWindowSize::<Test>::get();
+		let tx_capacity = CardanoTxCapacityPerBlock::<Test>::get();
+
+		// Well within the weight budget of the mock block weights
+		process_tokens(unregistered_asset_creates(tx_capacity), test_position(1, tx_capacity));
+
+		assert_eq!(CardanoTxCapacityPerBlock::<Test>::get(), tx_capacity * 2);
+		assert_eq!(CardanoBlockWindowSize::<Test>::get(), block_window / 2);
+	});
+}
+
+#[test]
+fn tx_capacity_shrinks_when_the_observation_exceeds_its_weight_budget() {
+	new_test_ext().execute_with(|| {
+		let tx_capacity = CardanoTxCapacityPerBlock::<Test>::get();
+
+		let max_block = <Test as frame_system::Config>::BlockWeights::get().max_block;
+		let budget = CARDANO_OBSERVATION_WEIGHT_BUDGET * max_block.ref_time();
+		let num_utxos = (tx_capacity..)
+			.find(|&u| <() as weights::WeightInfo>::process_tokens(u).ref_time() > budget)
+			.unwrap();
+		process_tokens(unregistered_asset_creates(num_utxos), test_position(1, num_utxos));
+
+		assert_eq!(CardanoTxCapacityPerBlock::<Test>::get(), tx_capacity / 2);
+	});
+}
+
+#[test]
+fn observation_is_adapted_within_the_governance_limits() {
+	new_test_ext().execute_with(|| {
+		let block_window = CardanoBlockWindowSize::<Test>::get();
+		let limits = ObservationLimits {
+			min_block_window: 10,
+			max_block_window: block_window,
+			min_tx_capacity: 5,
+			max_tx_capacity: 50,
+		};
+		assert_ok!(CNightObservation::set_cardano_observation_limits(
+			frame_system::RawOrigin::Root.into(),
+			limits,
+		));
+		assert_eq!(CardanoObservationLimits::<Test>::get(), limits);
+		// The current capacity is clamped to the new limits
+		assert_eq!(CardanoTxCapacityPerBlock::<Test>::get(), 50);
+
+		process_tokens(unregistered_asset_creates(50), test_position(1, 50));
+		assert_eq!(CardanoTxCapacityPerBlock::<Test>::get(), 50);
+
+		let start = NextCardanoPosition::<Test>::get();
+		let block_window = CardanoBlockWindowSize::<Test>::get();
+		process_tokens_behind_tip(
+			vec![],
+			test_position(start.block_number + block_window - 1, 0),
+			100,
+		);
+		process_tokens_behind_tip(
+			vec![],
+			test_position(start.block_number + block_window * 3 - 1, 0),
+			100,
+		);
+		assert_eq!(CardanoBlockWindowSize::<Test>::get(), limits.max_block_window);
+	});
+}
+
+#[test]
+fn invalid_observation_limits_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let limits = ObservationLimits::default();
+		assert_noop!(
+			CNightObservation::set_cardano_observation_limits(
+				frame_system::RawOrigin::Root.into(),
+				ObservationLimits { min_tx_capacity: 0, ..limits },
+			),
+			Error::<Test>::InvalidCardanoObservationLimits
+		);
+		assert_noop!(
+			CNightObservation::set_cardano_observation_limits(
+				frame_system::RawOrigin::Root.into(),
+				ObservationLimits { min_block_window: 20_000, ..limits },
+			),
+			Error::<Test>::InvalidCardanoObservationLimits
+		);
+		assert_noop!(
+			CNightObservation::set_cardano_observation_limits(
+				frame_system::RawOrigin::None.into(),
+				limits,
+			),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
 // #[test]
 // fn no_registered_event_when_still_invalid_after_removal() {
 // 	new_test_ext().execute_with(|| {
@@ -28,12 +28,13 @@ pub use types::*;
 use frame_support::{
 	BoundedBTreeSet,
 	dispatch::{Pays, PostDispatchInfo},
+	traits::EnsureOrigin,
 };
 use sp_runtime::{
 	DispatchError, DispatchErrorWithPostInfo, Saturating,
 	traits::{Dispatchable, Hash},
 };
-use sp_std::prelude::*;
+use sp_std::{marker::PhantomData, prelude::*};
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -85,6 +86,10 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type Motions<T: Config> = StorageMap<_, Identity, T::Hash, MotionInfo<T>, OptionQuery>;
 
+	/// Set only while the call of an approved motion is being dispatched
+	#[pallet::storage]
+	pub type DispatchingMotion<T: Config> = StorageValue<_, bool, ValueQuery>;
+
 	#[pallet::error]
 	pub enum Error<T> {
 		/// The motion has already been approved by this authority.
@@ -321,7 +326,9 @@ pub mod pallet {
 	impl<T: Config> Pallet<T> {
 		fn motion_dispatch(motion_hash: T::Hash) -> DispatchResult {
 			let motion = Motions::<T>::get(motion_hash).ok_or(Error::<T>::MotionNotFound)?;
+			DispatchingMotion::<T>::put(true);
 			let res = motion.call.dispatch(frame_system::RawOrigin::Root.into());
+			DispatchingMotion::<T>::kill();
 			let motion_result = res.map(|_| ()).map_err(|e| e.error);
 			Self::deposit_event(Event::MotionDispatched { motion_hash, motion_result });
 			motion_result
@@ -421,3 +428,26 @@ pub mod pallet {
 		}
 	}
 }
+
+/// Ensures the origin is the `Root` origin an approved motion dispatches its call with.
+/// `Root` from any other source, such as `Sudo`, is rejected.
+pub struct EnsureFederatedAuthorityMotion<T>(PhantomData<T>);
+
+impl<T: Config> EnsureOrigin<T::RuntimeOrigin> for EnsureFederatedAuthorityMotion<T> {
+	type Success = ();
+
+	fn try_origin(o: T::RuntimeOrigin) -> Result<Self::Success, T::RuntimeOrigin> {
+		if !DispatchingMotion::<T>::get() {
+			return Err(o);
+		}
+		frame_system::EnsureRoot::<T::AccountId>::try_origin(o)
+	}
+
+	/// The origin only exists while an approved motion is dispatched, and setting
+	/// `DispatchingMotion` here would leave it granted to every later `Root` call.
+	/// Calls behind this origin are benchmarked by dispatching them through `motion_close`.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<T::RuntimeOrigin, ()> {
+		Err(())
+	}
+}
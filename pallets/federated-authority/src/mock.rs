@@ -12,7 +12,7 @@
 // limitations under the License.
 
 use crate::{
-	self as pallet_federated_authority, AuthId,
+	self as pallet_federated_authority, AuthId, EnsureFederatedAuthorityMotion,
 	types::{
 		AuthorityBody, FederatedAuthorityEnsureProportionAtLeast, FederatedAuthorityOriginManager,
 	},
@@ -42,9 +42,35 @@ frame_support::construct_runtime!(
 		TechnicalCommittee: pallet_collective::<Instance2> = 42,
 		TechnicalCommitteeMembership: pallet_membership::<Instance2> = 43,
 		FederatedAuthority: pallet_federated_authority = 44,
+		MotionOnly: pallet_motion_only = 45,
 	}
 );
 
+/// Pallet with a call only dispatchable by a federated authority motion, to test
+/// `EnsureFederatedAuthorityMotion`
+#[frame_support::pallet(dev_mode)]
+pub mod pallet_motion_only {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type MotionOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::call_index(0)]
+		pub fn motion_only(origin: OriginFor<T>) -> DispatchResult {
+			T::MotionOrigin::ensure_origin(origin)?;
+			Ok(())
+		}
+	}
+}
+
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
 	pub const SS58Prefix: u8 = 42;
@@ -183,6 +209,10 @@ impl crate::Config for Test {
 	type WeightInfo = ();
 }
 
+impl pallet_motion_only::Config for Test {
+	type MotionOrigin = EnsureFederatedAuthorityMotion<Test>;
+}
+
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
 
@@ -11,7 +11,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Config, Error, Event, MotionInfo, Motions, mock::*, weights::WeightInfo};
+use crate::{
+	Config, DispatchingMotion, Error, Event, MotionInfo, Motions, mock::*, weights::WeightInfo,
+};
 use frame_support::{
 	BoundedBTreeSet, assert_noop, assert_ok,
 	dispatch::{DispatchErrorWithPostInfo, Pays, PostDispatchInfo},
@@ -637,6 +639,34 @@ fn motion_dispatchs_with_root_origin() {
 	});
 }
 
+#[test]
+fn federated_authority_motion_origin_rejects_root_outside_motions() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let call = Box::new(RuntimeCall::MotionOnly(pallet_motion_only::Call::motion_only {}));
+
+		// `Root` from any source other than a motion (e.g. `Sudo`) is rejected
+		assert_noop!(
+			call.clone().dispatch(RuntimeOrigin::root()).map_err(|e| e.error),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		let motion_hash = get_motion_hash(&call);
+		assert_ok!(FederatedAuthority::motion_approve(council_origin(), call.clone()));
+		assert_ok!(FederatedAuthority::motion_approve(tech_origin(), call.clone()));
+		assert_ok!(FederatedAuthority::motion_close(RuntimeOrigin::signed(10), motion_hash));
+
+		let events = federated_authority_events();
+		assert_eq!(events[2], Event::MotionDispatched { motion_hash, motion_result: Ok(()) });
+		// The origin is only granted for the duration of the motion dispatch
+		assert!(!DispatchingMotion::<Test>::get());
+		assert_noop!(
+			call.dispatch(RuntimeOrigin::root()).map_err(|e| e.error),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
+
 #[test]
 fn complete_collective_to_federated_flow() {
 	new_test_ext().execute_with(|| {
@@ -5,6 +5,12 @@ use frame_system::pallet_prelude::*;
 
 pub use pallet::*;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use midnight_primitives::{
@@ -12,7 +18,8 @@ pub mod pallet {
 	};
 
 	use midnight_node_ledger::types::{
-		Hash, active_ledger_bridge as LedgerApi, active_version::LedgerApiError,
+		Hash, LedgerParametersUpdate, LedgerParametersUpdateError,
+		active_ledger_bridge as LedgerApi, active_version::LedgerApiError,
 	};
 	use sp_std::vec::Vec;
 
@@ -36,6 +43,18 @@ pub mod pallet {
 	pub enum Error<T> {
 		#[codec(index = 0)]
 		LedgerApiError(LedgerApiError),
+		/// The ledger parameters update doesn't change any parameter
+		#[codec(index = 1)]
+		EmptyParametersUpdate,
+		/// A fee price has a zero denominator or is too high
+		#[codec(index = 2)]
+		InvalidFeePrice,
+		/// A DUST parameter is zero or out of range
+		#[codec(index = 3)]
+		InvalidDustParameter,
+		/// A limit is zero, or the transaction byte limit exceeds the block limits
+		#[codec(index = 4)]
+		InvalidLimit,
 	}
 
 	impl<T: Config> From<LedgerApiError> for Error<T> {
@@ -44,10 +63,25 @@ pub mod pallet {
 		}
 	}
 
+	impl<T: Config> From<LedgerParametersUpdateError> for Error<T> {
+		fn from(value: LedgerParametersUpdateError) -> Self {
+			match value {
+				LedgerParametersUpdateError::Empty => Error::<T>::EmptyParametersUpdate,
+				LedgerParametersUpdateError::InvalidFeePrice => Error::<T>::InvalidFeePrice,
+				LedgerParametersUpdateError::InvalidDustParameter => {
+					Error::<T>::InvalidDustParameter
+				},
+				LedgerParametersUpdateError::InvalidLimit => Error::<T>::InvalidLimit,
+			}
+		}
+	}
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type LedgerStateProviderMut: LedgerStateProviderMut;
 		type LedgerBlockContextProvider: LedgerBlockContextProvider;
+		/// Origin allowed to update the ledger parameters
+		type ParametersUpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 	}
 
 	#[pallet::pallet]
@@ -88,6 +122,26 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Applies `update` to the current ledger parameters through an `OverwriteParameters`
+		/// system transaction
+		#[pallet::call_index(1)]
+		#[pallet::weight((ConfigurableSystemTxWeight::<T>::get(), DispatchClass::Operational))]
+		pub fn update_ledger_parameters(
+			origin: OriginFor<T>,
+			update: LedgerParametersUpdate,
+		) -> DispatchResult {
+			T::ParametersUpdateOrigin::ensure_origin(origin)?;
+			update.validate().map_err(Error::<T>::from)?;
+
+			let state_key = <T as Config>::LedgerStateProviderMut::get_ledger_state_key();
+			let midnight_system_tx =
+				LedgerApi::construct_update_parameters_system_tx(&state_key, update)
+					.map_err(Error::<T>::from)?;
+			Self::execute_system_transaction(midnight_system_tx)?;
+
+			Ok(())
+		}
 	}
 
 	impl<T: Config> MidnightSystemTransactionExecutor for Pallet<T> {
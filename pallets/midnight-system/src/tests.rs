@@ -0,0 +1,101 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// grcov-excl-start
+use super::*;
+use crate::mock::{self, RuntimeOrigin};
+use frame_support::{assert_noop, assert_ok};
+use midnight_node_ledger::types::{BlockContext, FixedPointRatio, LedgerParametersUpdate};
+use midnight_node_res::{
+	networks::{MidnightNetwork, UndeployedNetwork},
+	undeployed::transactions::DEPLOY_TX,
+};
+use sp_runtime::DispatchError;
+
+fn init_ledger_state() {
+	let (_, block_context) =
+		midnight_node_ledger_helpers::extract_info_from_tx_with_context(DEPLOY_TX);
+	let block_context: BlockContext = block_context.into();
+	let path_buf = tempfile::tempdir().unwrap().keep();
+	let state_key = midnight_node_ledger::init_storage_paritydb(
+		&path_buf,
+		UndeployedNetwork.genesis_state(),
+		1024 * 1024,
+	);
+
+	mock::Midnight::initialize_state(UndeployedNetwork.id(), &state_key);
+	mock::System::set_block_number(1);
+	mock::Timestamp::set_timestamp(block_context.tblock * 1000);
+}
+
+#[test]
+fn update_ledger_parameters_rejects_unauthorized_origin() {
+	mock::new_test_ext().execute_with(|| {
+		init_ledger_state();
+
+		let update = LedgerParametersUpdate {
+			read_price: Some(FixedPointRatio { numerator: 1, denominator: 10 }),
+			..Default::default()
+		};
+		assert_noop!(
+			mock::MidnightSystem::update_ledger_parameters(RuntimeOrigin::signed(1), update),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn update_ledger_parameters_rejects_invalid_update() {
+	mock::new_test_ext().execute_with(|| {
+		init_ledger_state();
+
+		assert_noop!(
+			mock::MidnightSystem::update_ledger_parameters(
+				RuntimeOrigin::root(),
+				LedgerParametersUpdate::default()
+			),
+			Error::<mock::Test>::EmptyParametersUpdate
+		);
+		assert_noop!(
+			mock::MidnightSystem::update_ledger_parameters(
+				RuntimeOrigin::root(),
+				LedgerParametersUpdate {
+					dust_grace_period_secs: Some(u64::MAX),
+					..Default::default()
+				}
+			),
+			Error::<mock::Test>::InvalidDustParameter
+		);
+	});
+}
+
+#[test]
+fn update_ledger_parameters_applies_system_transaction() {
+	mock::new_test_ext().execute_with(|| {
+		init_ledger_state();
+		let parameters_before = mock::Midnight::get_ledger_parameters().unwrap();
+
+		let update = LedgerParametersUpdate {
+			read_price: Some(FixedPointRatio { numerator: 1, denominator: 10 }),
+			dust_grace_period_secs: Some(60 * 60),
+			..Default::default()
+		};
+		assert_ok!(mock::MidnightSystem::update_ledger_parameters(RuntimeOrigin::root(), update));
+
+		let parameters_after = mock::Midnight::get_ledger_parameters().unwrap();
+		assert_ne!(parameters_before, parameters_after);
+
+		let events = mock::midnight_system_events();
+		assert!(matches!(events.as_slice(), [Event::SystemTransactionApplied(_)]));
+	});
+}
@@ -20,6 +20,14 @@ use jsonrpsee::{
 	types::error::{ErrorObject, ErrorObjectOwned, INVALID_PARAMS_CODE},
 };
 
+use midnight_node_ledger::{
+	rejections::{TransactionRejection, last_rejection},
+	types::{
+		ArenaPathStep, ContractStateProof, DustGenerationDetails, GasCost, Hash, StorageCost,
+		TransactionSimulationResult, UtxoInfo, ZswapCommitmentProof,
+		active_version::transaction_error_codes,
+	},
+};
 use pallet_midnight::MidnightRuntimeApi;
 use sc_client_api::{BlockBackend, BlockchainEvents};
 use sp_api::{ApiExt, ProvideRuntimeApi};
@@ -41,6 +49,67 @@ pub trait MidnightApi<BlockHash> {
 	#[method(name = "midnight_zswapStateRoot")]
 	fn get_zswap_state_root(&self, at: Option<BlockHash>) -> Result<Vec<u8>, StateRpcError>;
 
+	#[method(name = "midnight_unclaimedAmount")]
+	fn get_unclaimed_amount(
+		&self,
+		beneficiary: String,
+		at: Option<BlockHash>,
+	) -> Result<u128, StateRpcError>;
+
+	#[method(name = "midnight_ledgerParameters")]
+	fn get_ledger_parameters(&self, at: Option<BlockHash>) -> Result<String, StateRpcError>;
+
+	#[method(name = "midnight_unshieldedUtxos")]
+	fn get_unshielded_utxos(
+		&self,
+		address: String,
+		at: Option<BlockHash>,
+	) -> Result<Vec<RpcUtxo>, StateRpcError>;
+
+	#[method(name = "midnight_dustGenerationInfo")]
+	fn get_dust_generation_info(
+		&self,
+		nonce: String,
+		at: Option<BlockHash>,
+	) -> Result<Option<RpcDustGenerationInfo>, StateRpcError>;
+
+	#[method(name = "midnight_transactionCost")]
+	fn get_transaction_cost(
+		&self,
+		midnight_tx: String,
+		at: Option<BlockHash>,
+	) -> Result<RpcTransactionCost, StateRpcError>;
+
+	#[method(name = "midnight_zswapCommitmentProof")]
+	fn get_zswap_commitment_proof(
+		&self,
+		commitment: String,
+		at: Option<BlockHash>,
+	) -> Result<Option<RpcZswapCommitmentProof>, StateRpcError>;
+
+	#[method(name = "midnight_contractStateProof")]
+	fn get_contract_state_proof(
+		&self,
+		contract_address: String,
+		at: Option<BlockHash>,
+	) -> Result<Option<RpcContractStateProof>, StateRpcError>;
+
+	#[method(name = "midnight_simulateTransaction")]
+	fn simulate_transaction(
+		&self,
+		midnight_tx: String,
+		at: Option<BlockHash>,
+	) -> Result<SimulatedTransaction, StateRpcError>;
+
+	#[method(name = "midnight_lastRejection")]
+	fn get_last_rejection(
+		&self,
+		tx_hash: String,
+	) -> Result<Option<RpcTransactionRejection>, StateRpcError>;
+
+	#[method(name = "midnight_transactionErrorCodes")]
+	fn get_transaction_error_codes(&self) -> RpcResult<Vec<RpcErrorCode>>;
+
 	#[method(name = "midnight_apiVersions")]
 	fn get_supported_api_versions(&self) -> RpcResult<Vec<u32>>;
 
@@ -55,6 +124,19 @@ pub enum StateRpcError {
 	UnableToGetContractState,
 	UnableToGetZSwapChainState,
 	UnableToGetZSwapStateRoot,
+	BadTransaction(String),
+	UnableToSimulateTransaction(String),
+	BadTransactionHash(String),
+	UnableToGetUnclaimedAmount,
+	UnableToGetLedgerParameters,
+	UnableToGetUnshieldedUtxos,
+	UnableToGetDustGenerationInfo,
+	UnableToGetTransactionCost,
+	BadCommitment(String),
+	UnableToGetZSwapCommitmentProof,
+	UnableToGetContractStateProof,
+	BadNonce(String),
+	UnsupportedApiVersion(u32),
 }
 
 #[derive(Debug)]
@@ -117,6 +199,45 @@ impl Display for StateRpcError {
 			StateRpcError::UnableToGetZSwapStateRoot => {
 				write!(f, "Unable to get requested zswap state root")
 			},
+			StateRpcError::BadTransaction(malformed_tx) => {
+				write!(f, "Unable to decode transaction: {}", malformed_tx)
+			},
+			StateRpcError::UnableToSimulateTransaction(reason) => {
+				write!(f, "Unable to simulate transaction: {}", reason)
+			},
+			StateRpcError::BadTransactionHash(malformed_hash) => {
+				write!(f, "Unable to decode transaction hash: {}", malformed_hash)
+			},
+			StateRpcError::UnableToGetUnclaimedAmount => {
+				write!(f, "Unable to get requested unclaimed amount")
+			},
+			StateRpcError::UnableToGetLedgerParameters => {
+				write!(f, "Unable to get requested ledger parameters")
+			},
+			StateRpcError::UnableToGetUnshieldedUtxos => {
+				write!(f, "Unable to get requested unshielded utxos")
+			},
+			StateRpcError::UnableToGetDustGenerationInfo => {
+				write!(f, "Unable to get requested dust generation info")
+			},
+			StateRpcError::UnableToGetTransactionCost => {
+				write!(f, "Unable to get requested transaction cost")
+			},
+			StateRpcError::BadCommitment(malformed_commitment) => {
+				write!(f, "Unable to decode coin commitment: {}", malformed_commitment)
+			},
+			StateRpcError::UnableToGetZSwapCommitmentProof => {
+				write!(f, "Unable to get requested zswap commitment proof")
+			},
+			StateRpcError::UnableToGetContractStateProof => {
+				write!(f, "Unable to get requested contract state proof")
+			},
+			StateRpcError::BadNonce(malformed_nonce) => {
+				write!(f, "Unable to decode nonce: {}", malformed_nonce)
+			},
+			StateRpcError::UnsupportedApiVersion(api_version) => {
+				write!(f, "Not supported by the runtime API version {} of the block", api_version)
+			},
 		}
 	}
 }
@@ -201,6 +322,220 @@ pub struct RpcBlock<Header> {
 	pub transactions_index: Vec<(String, String)>,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcUtxo {
+	pub address: String,
+	pub token_type: String,
+	pub intent_hash: String,
+	pub value: u128,
+	pub output_no: u32,
+}
+
+impl From<UtxoInfo> for RpcUtxo {
+	fn from(utxo: UtxoInfo) -> Self {
+		Self {
+			address: hex::encode(utxo.address),
+			token_type: hex::encode(utxo.token_type),
+			intent_hash: hex::encode(utxo.intent_hash),
+			value: utxo.value,
+			output_no: utxo.output_no,
+		}
+	}
+}
+
+/// DUST generation of a cNight UTXO, see `midnight_dustGenerationInfo`
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcDustGenerationInfo {
+	pub value: u128,
+	/// Serialized DUST public key the DUST is generated for
+	pub owner: String,
+	pub nonce: String,
+	/// Time the generation stops, in seconds since the epoch
+	pub dtime: u64,
+}
+
+impl From<DustGenerationDetails> for RpcDustGenerationInfo {
+	fn from(info: DustGenerationDetails) -> Self {
+		Self {
+			value: info.value,
+			owner: hex::encode(info.owner),
+			nonce: hex::encode(info.nonce),
+			dtime: info.dtime,
+		}
+	}
+}
+
+/// Inclusion proof of a coin commitment in the zswap commitment tree, see
+/// `midnight_zswapCommitmentProof`. Can be checked with
+/// `midnight_node_ledger_helpers::verify_zswap_commitment_proof`.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcZswapCommitmentProof {
+	/// Key of the ledger state the proof was generated against
+	pub state_key: String,
+	/// Nodes from the ledger state root down to the parent of `tree_node`
+	pub tree_path: Vec<RpcArenaPathStep>,
+	/// Top node of the commitment tree, which records `root`
+	pub tree_node: RpcArenaNode,
+	/// Serialized root of the commitment tree
+	pub root: String,
+	/// Serialized merkle path from `root` to the commitment
+	pub proof: String,
+}
+
+impl From<ZswapCommitmentProof> for RpcZswapCommitmentProof {
+	fn from(proof: ZswapCommitmentProof) -> Self {
+		Self {
+			state_key: hex::encode(proof.state_key),
+			tree_path: proof.tree_path.into_iter().map(RpcArenaPathStep::from).collect(),
+			tree_node: RpcArenaNode {
+				data: hex::encode(proof.tree_node.data),
+				children: proof.tree_node.children.into_iter().map(hex::encode).collect(),
+			},
+			root: hex::encode(proof.root),
+			proof: hex::encode(proof.path),
+		}
+	}
+}
+
+/// A storage arena node of an inclusion proof
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcArenaNode {
+	/// Binary representation of the node
+	pub data: String,
+	/// Serialized arena keys of the node's children
+	pub children: Vec<String>,
+}
+
+/// A storage arena node on the path of an inclusion proof
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcArenaPathStep {
+	/// Binary representation of the node
+	pub data: String,
+	/// Serialized arena keys of the node's children
+	pub children: Vec<String>,
+	/// Index in `children` of the next node on the path
+	pub child: u32,
+}
+
+impl From<ArenaPathStep> for RpcArenaPathStep {
+	fn from(step: ArenaPathStep) -> Self {
+		Self {
+			data: hex::encode(step.data),
+			children: step.children.into_iter().map(hex::encode).collect(),
+			child: step.child,
+		}
+	}
+}
+
+/// Inclusion proof of a contract in the ledger state, see `midnight_contractStateProof`. Can be
+/// checked against the contract state returned by `midnight_contractState` with
+/// `midnight_node_ledger_helpers::verify_contract_state_proof`.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcContractStateProof {
+	/// Key of the ledger state the proof was generated against
+	pub state_key: String,
+	/// Nodes from the ledger state root down to the map entry holding the contract
+	pub path: Vec<RpcArenaPathStep>,
+}
+
+impl From<ContractStateProof> for RpcContractStateProof {
+	fn from(proof: ContractStateProof) -> Self {
+		Self {
+			state_key: hex::encode(proof.state_key),
+			path: proof.path.into_iter().map(RpcArenaPathStep::from).collect(),
+		}
+	}
+}
+
+/// Cost of a transaction, see `midnight_transactionCost`
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcTransactionCost {
+	pub storage_cost: StorageCost,
+	pub gas_cost: GasCost,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcFailedSegment {
+	pub segment_id: u16,
+	pub error_code: u8,
+}
+
+/// Outcome of applying a transaction without persisting it, see `midnight_simulateTransaction`
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SimulatedTransaction {
+	pub tx_hash: String,
+	pub all_applied: bool,
+	pub failed_segments: Vec<RpcFailedSegment>,
+	pub call_addresses: Vec<String>,
+	pub deploy_addresses: Vec<String>,
+	pub maintain_addresses: Vec<String>,
+	pub claim_rewards: Vec<u128>,
+	pub unshielded_utxos_created: Vec<RpcUtxo>,
+	pub unshielded_utxos_spent: Vec<RpcUtxo>,
+	pub fees: u128,
+}
+
+impl From<TransactionSimulationResult> for SimulatedTransaction {
+	fn from(result: TransactionSimulationResult) -> Self {
+		Self {
+			tx_hash: hex::encode(result.tx_hash),
+			all_applied: result.all_applied,
+			failed_segments: result
+				.failed_segments
+				.into_iter()
+				.map(|s| RpcFailedSegment { segment_id: s.segment_id, error_code: s.error_code })
+				.collect(),
+			call_addresses: result.call_addresses.iter().map(hex::encode).collect(),
+			deploy_addresses: result.deploy_addresses.iter().map(hex::encode).collect(),
+			maintain_addresses: result.maintain_addresses.iter().map(hex::encode).collect(),
+			claim_rewards: result.claim_rewards,
+			unshielded_utxos_created: result
+				.unshielded_utxos_created
+				.into_iter()
+				.map(RpcUtxo::from)
+				.collect(),
+			unshielded_utxos_spent: result
+				.unshielded_utxos_spent
+				.into_iter()
+				.map(RpcUtxo::from)
+				.collect(),
+			fees: result.fees,
+		}
+	}
+}
+
+/// Why the ledger rejected a transaction, see `midnight_lastRejection`
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcTransactionRejection {
+	pub error_code: u8,
+	pub error: String,
+	pub segment_id: Option<u16>,
+	pub contract_address: Option<String>,
+	pub nullifier: Option<String>,
+	pub commitment: Option<String>,
+	pub details: String,
+}
+
+impl From<TransactionRejection> for RpcTransactionRejection {
+	fn from(rejection: TransactionRejection) -> Self {
+		Self {
+			error_code: rejection.error_code,
+			error: rejection.error,
+			segment_id: rejection.segment_id,
+			contract_address: rejection.contract_address.map(hex::encode),
+			nullifier: rejection.nullifier.map(hex::encode),
+			commitment: rejection.commitment.map(hex::encode),
+			details: rejection.details,
+		}
+	}
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RpcErrorCode {
+	pub code: u8,
+	pub error: String,
+}
+
 pub struct Midnight<C, Block> {
 	/// Shared reference to the client.
 	client: Arc<C>,
@@ -292,6 +627,221 @@ where
 		Ok(root)
 	}
 
+	fn get_unclaimed_amount(
+		&self,
+		beneficiary: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<u128, StateRpcError> {
+		let dehexed = hex::decode(&beneficiary)
+			.map_err(|_e| StateRpcError::BadAccountAddress(beneficiary))?;
+
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let api_version = get_api_version::<C, Block>(&api, at)
+			.map_err(|_| StateRpcError::UnableToGetUnclaimedAmount)?;
+
+		if api_version < 2 {
+			#[allow(deprecated)]
+			api.get_unclaimed_amount_before_version_2(at, dehexed)
+				.map_err(|_e| StateRpcError::UnableToGetUnclaimedAmount)
+		} else {
+			api.get_unclaimed_amount(at, dehexed)
+				.map_err(|_e| StateRpcError::UnableToGetUnclaimedAmount)
+				.and_then(|inner_res| {
+					inner_res.map_err(|_| StateRpcError::UnableToGetUnclaimedAmount)
+				})
+		}
+	}
+
+	fn get_ledger_parameters(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<String, StateRpcError> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let parameters = self
+			.client
+			.runtime_api()
+			.get_ledger_parameters(at)
+			.map_err(|_e| StateRpcError::UnableToGetLedgerParameters)
+			.and_then(|inner_res| {
+				inner_res.map_err(|_| StateRpcError::UnableToGetLedgerParameters)
+			})?;
+
+		Ok(hex::encode(parameters))
+	}
+
+	fn get_unshielded_utxos(
+		&self,
+		address: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<RpcUtxo>, StateRpcError> {
+		let dehexed =
+			hex::decode(&address).map_err(|_e| StateRpcError::BadAccountAddress(address))?;
+
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let api_version = get_api_version::<C, Block>(&api, at)
+			.map_err(|_| StateRpcError::UnableToGetUnshieldedUtxos)?;
+		if api_version < 7 {
+			return Err(StateRpcError::UnsupportedApiVersion(api_version));
+		}
+
+		let utxos = api
+			.get_unshielded_utxos(at, dehexed)
+			.map_err(|_e| StateRpcError::UnableToGetUnshieldedUtxos)
+			.and_then(|inner_res| {
+				inner_res.map_err(|_| StateRpcError::UnableToGetUnshieldedUtxos)
+			})?;
+
+		Ok(utxos.into_iter().map(RpcUtxo::from).collect())
+	}
+
+	fn get_dust_generation_info(
+		&self,
+		nonce: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RpcDustGenerationInfo>, StateRpcError> {
+		let dehexed = hex::decode(&nonce).map_err(|_e| StateRpcError::BadNonce(nonce))?;
+
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let api_version = get_api_version::<C, Block>(&api, at)
+			.map_err(|_| StateRpcError::UnableToGetDustGenerationInfo)?;
+		if api_version < 7 {
+			return Err(StateRpcError::UnsupportedApiVersion(api_version));
+		}
+
+		let info = api
+			.get_dust_generation_info(at, dehexed)
+			.map_err(|_e| StateRpcError::UnableToGetDustGenerationInfo)
+			.and_then(|inner_res| {
+				inner_res.map_err(|_| StateRpcError::UnableToGetDustGenerationInfo)
+			})?;
+
+		Ok(info.map(RpcDustGenerationInfo::from))
+	}
+
+	fn get_transaction_cost(
+		&self,
+		midnight_tx: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcTransactionCost, StateRpcError> {
+		let dehexed =
+			hex::decode(&midnight_tx).map_err(|_e| StateRpcError::BadTransaction(midnight_tx))?;
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let (storage_cost, gas_cost) = self
+			.client
+			.runtime_api()
+			.get_transaction_cost(at, dehexed)
+			.map_err(|_e| StateRpcError::UnableToGetTransactionCost)
+			.and_then(|inner_res| {
+				inner_res.map_err(|_| StateRpcError::UnableToGetTransactionCost)
+			})?;
+
+		Ok(RpcTransactionCost { storage_cost, gas_cost })
+	}
+
+	fn get_zswap_commitment_proof(
+		&self,
+		commitment: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RpcZswapCommitmentProof>, StateRpcError> {
+		let dehexed =
+			hex::decode(&commitment).map_err(|_e| StateRpcError::BadCommitment(commitment))?;
+
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let api_version = get_api_version::<C, Block>(&api, at)
+			.map_err(|_| StateRpcError::UnableToGetZSwapCommitmentProof)?;
+		if api_version < 8 {
+			return Err(StateRpcError::UnsupportedApiVersion(api_version));
+		}
+
+		let proof = api
+			.get_zswap_commitment_proof(at, dehexed)
+			.map_err(|_e| StateRpcError::UnableToGetZSwapCommitmentProof)
+			.and_then(|inner_res| {
+				inner_res.map_err(|_| StateRpcError::UnableToGetZSwapCommitmentProof)
+			})?;
+
+		Ok(proof.map(RpcZswapCommitmentProof::from))
+	}
+
+	fn get_contract_state_proof(
+		&self,
+		contract_address: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RpcContractStateProof>, StateRpcError> {
+		let dehexed = hex::decode(&contract_address)
+			.map_err(|_e| StateRpcError::BadContractAddress(contract_address))?;
+
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let api_version = get_api_version::<C, Block>(&api, at)
+			.map_err(|_| StateRpcError::UnableToGetContractStateProof)?;
+		if api_version < 8 {
+			return Err(StateRpcError::UnsupportedApiVersion(api_version));
+		}
+
+		let proof = api
+			.get_contract_state_proof(at, dehexed)
+			.map_err(|_e| StateRpcError::UnableToGetContractStateProof)
+			.and_then(|inner_res| {
+				inner_res.map_err(|_| StateRpcError::UnableToGetContractStateProof)
+			})?;
+
+		Ok(proof.map(RpcContractStateProof::from))
+	}
+
+	fn simulate_transaction(
+		&self,
+		midnight_tx: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<SimulatedTransaction, StateRpcError> {
+		let dehexed =
+			hex::decode(&midnight_tx).map_err(|_e| StateRpcError::BadTransaction(midnight_tx))?;
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let result = self
+			.client
+			.runtime_api()
+			.simulate_transaction(at, dehexed)
+			.map_err(|e| StateRpcError::UnableToSimulateTransaction(e.to_string()))
+			.and_then(|inner_res| {
+				inner_res.map_err(|e| StateRpcError::UnableToSimulateTransaction(e.to_string()))
+			})?;
+
+		Ok(result.into())
+	}
+
+	fn get_last_rejection(
+		&self,
+		tx_hash: String,
+	) -> Result<Option<RpcTransactionRejection>, StateRpcError> {
+		let hash: Hash = hex::decode(&tx_hash)
+			.ok()
+			.and_then(|bytes| bytes.try_into().ok())
+			.ok_or(StateRpcError::BadTransactionHash(tx_hash))?;
+
+		Ok(last_rejection(&hash).map(RpcTransactionRejection::from))
+	}
+
+	fn get_transaction_error_codes(&self) -> RpcResult<Vec<RpcErrorCode>> {
+		Ok(transaction_error_codes()
+			.into_iter()
+			.map(|(code, error)| RpcErrorCode { code, error })
+			.collect())
+	}
+
 	fn get_supported_api_versions(&self) -> RpcResult<Vec<u32>> {
 		Ok(API_VERSIONS.to_vec())
 	}
@@ -11,20 +11,217 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-// //! Benchmarking setup for pallet-midnight
+//! Benchmarking setup for pallet-midnight
+//!
+//! Runs against the ledger state of the undeployed network genesis.
 
 use super::*;
 use frame_benchmarking::v2::*;
+use frame_support::traits::Get;
 use frame_system::RawOrigin;
-use sp_std::vec::Vec;
+use midnight_node_ledger::types::{FixedPointRatio, LedgerParametersUpdate, active_ledger_bridge};
+use midnight_primitives::LedgerBlockContextProvider;
+use sp_runtime::traits::{UniqueSaturatedFrom, UniqueSaturatedInto};
+
+/// A transaction applying on the undeployed network genesis once `setup` is applied
+struct RepresentativeTx {
+	tx: &'static [u8],
+	/// Block time (in milliseconds) the transaction was generated for, see the matching
+	/// `*_with_context.mn` or `contract_tx_*_undeployed.mn` file
+	timestamp_ms: u64,
+	setup: &'static [RepresentativeTx],
+}
+
+/// Unshielded transfer
+const TRANSFER: RepresentativeTx = RepresentativeTx {
+	tx: include_bytes!("../../../res/test-tx-deserialize/serialized_tx_no_context.mn"),
+	timestamp_ms: 1_763_577_105_000,
+	setup: &[],
+};
+/// Contract deployment, more than twice as long as `TRANSFER` but without contract calls
+const DEPLOY: RepresentativeTx = RepresentativeTx {
+	tx: include_bytes!("../../../res/test-contract/contract_tx_1_deploy_undeployed_no_context.mn"),
+	timestamp_ms: 1_763_577_030_000,
+	setup: &[],
+};
+/// Call of the contract of `DEPLOY`, about as long as `DEPLOY` but declaring gas
+const STORE: RepresentativeTx = RepresentativeTx {
+	tx: include_bytes!("../../../res/test-contract/contract_tx_2_store_undeployed_no_context.mn"),
+	timestamp_ms: 1_763_577_055_000,
+	setup: &[DEPLOY],
+};
+
+/// Representative transactions of each `send_mn_transaction` component, from the smallest to
+/// the largest. Along a component, the transactions only differ in that component as much as
+/// the available fixtures allow.
+// TODO: Generate transactions with several intents and with shielded inputs and outputs. The
+// undeployed network genesis has no shielded tokens to spend, see `res/test-zswap`. Until then,
+// the benchmark measures no cost for the `i`, `n` and `o` components.
+const LENGTH_TXS: &[RepresentativeTx] = &[TRANSFER, DEPLOY];
+const INTENT_TXS: &[RepresentativeTx] = &[TRANSFER];
+const INPUT_TXS: &[RepresentativeTx] = &[TRANSFER];
+const OUTPUT_TXS: &[RepresentativeTx] = &[TRANSFER];
+const GAS_TXS: &[RepresentativeTx] = &[DEPLOY, STORE];
+
+// Upper bounds of the `send_mn_transaction` components
+const MAX_TX_LENGTH: u32 = 65_536;
+const MAX_INTENTS: u32 = 16;
+const MAX_OFFER_INPUTS: u32 = 64;
+const MAX_OFFER_OUTPUTS: u32 = 64;
+/// In nanoseconds
+const MAX_GAS: u32 = 1_000_000_000;
+
+/// The representative transaction of the component being varied, which is the one furthest
+/// below its upper bound as the benchmark keeps the others at theirs. That is the smallest
+/// transaction reaching the requested value of the component, or the largest one if none does.
+fn representative_tx(l: u32, i: u32, n: u32, o: u32, g: u32) -> &'static RepresentativeTx {
+	let component = |tx: &RepresentativeTx, index: usize| {
+		let components =
+			active_ledger_bridge::get_transaction_weight_components(tx.tx).unwrap_or_default();
+		match index {
+			0 => tx.tx.len() as u64,
+			1 => components.intents.into(),
+			2 => components.inputs.into(),
+			3 => components.outputs.into(),
+			_ => (components.gas / 1_000).unique_saturated_into(),
+		}
+	};
+	let requested = [
+		(l, MAX_TX_LENGTH, LENGTH_TXS),
+		(i, MAX_INTENTS, INTENT_TXS),
+		(n, MAX_OFFER_INPUTS, INPUT_TXS),
+		(o, MAX_OFFER_OUTPUTS, OUTPUT_TXS),
+		(g, MAX_GAS, GAS_TXS),
+	];
+	let (index, (value, _, txs)) = requested
+		.iter()
+		.enumerate()
+		.min_by_key(|(_, (value, max, _))| u64::from(*value) * 1_000_000 / u64::from(*max))
+		.expect("There are components");
+
+	txs.iter()
+		.find(|tx| component(tx, index) >= u64::from(*value))
+		.or(txs.last())
+		.expect("There is at least one representative transaction per component")
+}
+
+/// Applies `tx` at the block time it was generated for
+fn apply_tx<T: Config>(tx: &RepresentativeTx) {
+	set_timestamp::<T>(tx.timestamp_ms);
+	Pallet::<T>::send_mn_transaction(RawOrigin::None.into(), tx.tx.to_vec())
+		.expect("Setup transaction applies");
+}
+
+fn set_timestamp<T: Config>(timestamp_ms: u64) {
+	pallet_timestamp::Now::<T>::put(T::Moment::unique_saturated_from(timestamp_ms));
+}
+
+/// Ledger parameters update changing most parameters, the worst case of the scheduled changes
+fn ledger_parameters_update() -> LedgerParametersUpdate {
+	LedgerParametersUpdate {
+		read_price: Some(FixedPointRatio { numerator: 1, denominator: 10 }),
+		compute_price: Some(FixedPointRatio { numerator: 1, denominator: 10 }),
+		block_usage_price: Some(FixedPointRatio { numerator: 1, denominator: 10 }),
+		write_price: Some(FixedPointRatio { numerator: 1, denominator: 10 }),
+		night_dust_ratio: Some(5_000_000_000),
+		generation_decay_rate: Some(8_267),
+		dust_grace_period_secs: Some(3 * 60 * 60),
+		..Default::default()
+	}
+}
+
+/// Schedules `count` ledger parameters updates which never become due
+fn schedule_parameters_changes<T: Config>(count: u32) {
+	for _ in 0..count {
+		Pallet::<T>::schedule_parameters_change(
+			RawOrigin::Root.into(),
+			ScheduledChange::LedgerParameters(ledger_parameters_update()),
+			Activation::AtTimestamp(u64::MAX),
+		)
+		.expect("The pending changes queue isn't full");
+	}
+}
 
-// TODO
 #[benchmarks]
 mod benchmarks {
 	use super::*;
+
+	#[benchmark]
+	fn send_mn_transaction(
+		l: Linear<0, MAX_TX_LENGTH>,
+		i: Linear<0, MAX_INTENTS>,
+		n: Linear<0, MAX_OFFER_INPUTS>,
+		o: Linear<0, MAX_OFFER_OUTPUTS>,
+		g: Linear<0, MAX_GAS>,
+	) {
+		let tx = representative_tx(l, i, n, o, g);
+		tx.setup.iter().for_each(apply_tx::<T>);
+		set_timestamp::<T>(tx.timestamp_ms);
+		let midnight_tx = tx.tx.to_vec();
+
+		#[extrinsic_call]
+		send_mn_transaction(RawOrigin::None, midnight_tx);
+	}
+
+	// Minting to a new beneficiary, which adds a UTXO to the ledger state
 	#[benchmark]
-	fn todo() {
+	fn mint_block_reward() {
+		set_timestamp::<T>(TRANSFER.timestamp_ms);
+		let block_context = Pallet::<T>::get_block_context();
+		let state_key = StateKey::<T>::get();
+
+		#[block]
+		{
+			Pallet::<T>::mint_block_reward(1_000_000, [1; 32], block_context);
+		}
+
+		// `mint_block_reward` only logs ledger errors, make sure the minting was measured
+		assert_eq!(TotalRewardsMinted::<T>::get(), 1_000_000);
+		assert_ne!(StateKey::<T>::get(), state_key);
+	}
+
+	// Worst case of the scheduled changes, a ledger parameters update
+	#[benchmark]
+	fn apply_parameters_change() {
+		set_timestamp::<T>(TRANSFER.timestamp_ms);
+		let update = ledger_parameters_update();
+		let state_key = StateKey::<T>::get();
+
+		#[block]
+		{
+			Pallet::<T>::apply_change(ScheduledChange::LedgerParameters(update))
+				.expect("Ledger parameters update is applied");
+		}
+
+		// The update is applied by a system transaction on the ledger state
+		assert_ne!(StateKey::<T>::get(), state_key);
+	}
+
+	// Worst case, validating a ledger parameters update into an almost full queue
+	#[benchmark]
+	fn schedule_parameters_change() {
+		let max_pending = MaxPendingParametersChanges::get();
+		schedule_parameters_changes::<T>(max_pending - 1);
+
+		#[extrinsic_call]
+		schedule_parameters_change(
+			RawOrigin::Root,
+			ScheduledChange::LedgerParameters(ledger_parameters_update()),
+			Activation::AtTimestamp(u64::MAX),
+		);
+
+		assert_eq!(PendingParametersChanges::<T>::get().len() as u32, max_pending);
+	}
+
+	// Worst case, cancelling the last change of a full queue
+	#[benchmark]
+	fn cancel_parameters_change() {
+		let max_pending = MaxPendingParametersChanges::get();
+		schedule_parameters_changes::<T>(max_pending);
+
 		#[extrinsic_call]
-		send_mn_transaction(RawOrigin::None, Vec::default());
+		cancel_parameters_change(RawOrigin::Root, max_pending - 1);
+
+		assert_eq!(PendingParametersChanges::<T>::get().len() as u32, max_pending - 1);
 	}
 }
@@ -36,17 +36,39 @@ mod tests;
 mod benchmarking;
 
 pub mod migrations;
+pub mod weights;
+
+environmental::environmental!(
+	/// Hashes of the Midnight transactions of the block being executed whose proofs and
+	/// signatures were verified beforehand, see `execute_with_pre_verified_transactions`
+	pre_verified_txs: sp_std::collections::btree_set::BTreeSet<[u8; 32]>
+);
 
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::{pallet_prelude::*, sp_runtime::traits::UniqueSaturatedInto};
+	use frame_support::{
+		Blake2_256, StorageHasher,
+		pallet_prelude::*,
+		sp_runtime::{
+			Perbill,
+			traits::{Saturating, UniqueSaturatedInto},
+		},
+		weights::constants::WEIGHT_REF_TIME_PER_SECOND,
+	};
 	use frame_system::pallet_prelude::*;
-	use midnight_primitives::LedgerBlockContextProvider;
+	use midnight_primitives::{LedgerBlockContextProvider, MidnightSystemTransactionExecutor};
 	use scale_info::prelude::{string::String, vec::Vec};
+	#[cfg(feature = "try-runtime")]
+	use sp_runtime::TryRuntimeError;
+	use sp_std::collections::btree_set::BTreeSet;
+
+	use crate::weights::WeightInfo;
 
 	use midnight_node_ledger::types::{
-		self as LedgerTypes, GasCost, StorageCost, Tx as LedgerTx, UtxoInfo,
-		active_ledger_bridge as LedgerApi,
+		self as LedgerTypes, ContractStateProof, DustGenerationDetails, GasCost,
+		LedgerParametersUpdate, StorageCost, TransactionOutcome, TransactionPoolInfo,
+		TransactionSimulationResult, TransactionWeightComponents, Tx as LedgerTx, UtxoInfo,
+		ZswapCommitmentProof, active_ledger_bridge as LedgerApi,
 		active_version::{
 			DeserializationError, LedgerApiError, SerializationError, TransactionError,
 		},
@@ -124,6 +146,15 @@ pub mod pallet {
 		/// Block reward getter.
 		type BlockReward: Get<(u128, Option<LedgerTypes::Hash>)>;
 
+		/// Identifies block authors in the reward beneficiary registry.
+		type BlockAuthorId: Parameter + MaxEncodedLen;
+
+		/// Applies the ledger parameters updates of scheduled changes.
+		type MidnightSystemTransactionExecutor: MidnightSystemTransactionExecutor;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+
 		#[pallet::constant]
 		type SlotDuration: Get<<Self as pallet_timestamp::Config>::Moment>;
 	}
@@ -154,11 +185,6 @@ pub mod pallet {
 		1
 	}
 
-	#[pallet::storage]
-	#[pallet::getter(fn configurable_transaction_size_weight)]
-	pub type ConfigurableTransactionSizeWeight<T> =
-		StorageValue<_, Weight, ValueQuery, DefaultWeight>;
-
 	#[pallet::storage]
 	pub type ConfigurableOnInitializeWeight<T> = StorageValue<_, Weight, ValueQuery, DefaultWeight>;
 
@@ -169,11 +195,88 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type MaxSkippedSlots<T> = StorageValue<_, u8, ValueQuery, DefaultMaxSkippedSlots>;
 
+	pub type MaxPendingParametersChanges = ConstU32<16>;
+
+	/// Parameter and weight changes waiting for their activation point, in scheduling order.
+	#[pallet::storage]
+	pub type PendingParametersChanges<T: Config> = StorageValue<
+		_,
+		BoundedVec<PendingChange<BlockNumberFor<T>>, MaxPendingParametersChanges>,
+		ValueQuery,
+	>;
+
+	/// Scheduled changes which became due in `on_initialize`, applied in `on_finalize`.
+	#[pallet::storage]
+	pub type DueParametersChanges<T: Config> = StorageValue<
+		_,
+		BoundedVec<PendingChange<BlockNumberFor<T>>, MaxPendingParametersChanges>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	pub type NextPendingChangeId<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// Emission schedule of the block rewards, with the block it was set at.
+	/// No rewards are minted while unset.
+	#[pallet::storage]
+	pub type BlockRewardSchedule<T: Config> =
+		StorageValue<_, (BlockNumberFor<T>, RewardSchedule), OptionQuery>;
+
+	/// Sum of all block rewards minted, bounded by the schedule's `cap`.
+	#[pallet::storage]
+	pub type TotalRewardsMinted<T> = StorageValue<_, u128, ValueQuery>;
+
+	/// Block reward of the current block and its beneficiary. Set in `on_initialize`, where the
+	/// weight of minting it is accounted, and minted in `on_finalize`.
+	#[pallet::storage]
+	pub type PendingBlockReward<T> = StorageValue<_, (u128, LedgerTypes::Hash), OptionQuery>;
+
+	/// Ledger `UserAddress` receiving the block rewards of each block author.
+	#[pallet::storage]
+	pub type RewardBeneficiaries<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::BlockAuthorId, LedgerTypes::Hash, OptionQuery>;
+
+	/// Block reward emission schedule.
+	///
+	/// Every `decay_interval` blocks since the schedule was set, the reward is reduced by
+	/// `decay` (e.g. 50% for halvings), and no more rewards are minted once `cap` is reached.
+	#[derive(
+		Debug, Clone, PartialEq, Eq, Encode, Decode, DecodeWithMemTracking, TypeInfo, MaxEncodedLen,
+	)]
+	pub struct RewardSchedule {
+		/// Reward of each block until the first decay.
+		pub initial_reward: u128,
+		/// Number of blocks between two reward decays. Zero disables the decay.
+		pub decay_interval: u32,
+		/// Share of the reward removed at each decay.
+		pub decay: Perbill,
+		/// Maximum sum of all minted block rewards.
+		pub cap: Option<u128>,
+	}
+
+	impl RewardSchedule {
+		/// Reward of a block `elapsed` blocks after the schedule was set, ignoring the cap.
+		pub fn reward_at(&self, elapsed: u32) -> u128 {
+			if self.decay_interval == 0 {
+				return self.initial_reward;
+			}
+			let decays = elapsed / self.decay_interval;
+			let retained = Perbill::one().saturating_sub(self.decay);
+			retained.saturating_pow(decays as usize).mul_floor(self.initial_reward)
+		}
+	}
+
 	#[derive(Debug, Clone, PartialEq, Encode, Decode, DecodeWithMemTracking, TypeInfo)]
 	pub struct TxAppliedDetails {
 		pub tx_hash: LedgerTypes::Hash,
 	}
 
+	#[derive(Debug, Clone, PartialEq, Encode, Decode, DecodeWithMemTracking, TypeInfo)]
+	pub struct TxOutcomeDetails {
+		pub tx_hash: LedgerTypes::Hash,
+		pub outcome: TransactionOutcome,
+	}
+
 	#[derive(Debug, Clone, PartialEq, Encode, Decode, DecodeWithMemTracking, TypeInfo)]
 	pub struct MaintainDetails {
 		pub tx_hash: LedgerTypes::Hash,
@@ -192,12 +295,69 @@ pub mod pallet {
 		pub contract_address: Vec<u8>,
 	}
 
+	/// State hashes of a contract around the calls a transaction made to it, in serialized
+	/// arena hash form. The old hash is empty for a contract deployed by the same transaction.
+	#[derive(Debug, Clone, PartialEq, Encode, Decode, DecodeWithMemTracking, TypeInfo)]
+	pub struct ContractStateChangedDetails {
+		pub tx_hash: LedgerTypes::Hash,
+		pub contract_address: Vec<u8>,
+		pub entry_points: Vec<Vec<u8>>,
+		pub old_state_hash: Vec<u8>,
+		pub new_state_hash: Vec<u8>,
+	}
+
 	#[derive(Debug, Clone, PartialEq, Encode, Decode, DecodeWithMemTracking, TypeInfo)]
 	pub struct ClaimRewardsDetails {
 		pub tx_hash: LedgerTypes::Hash,
 		pub value: u128,
 	}
 
+	/// A change which can be scheduled with `schedule_parameters_change`.
+	#[derive(
+		Debug, Clone, PartialEq, Encode, Decode, DecodeWithMemTracking, TypeInfo, MaxEncodedLen,
+	)]
+	pub enum ScheduledChange {
+		/// Same as `override_d_parameter`
+		DParameterOverride(Option<(u16, u16)>),
+		/// Applied through an `OverwriteParameters` system transaction
+		LedgerParameters(LedgerParametersUpdate),
+	}
+
+	/// First block, or first block timestamp (in milliseconds), a scheduled change applies to.
+	#[derive(
+		Debug,
+		Clone,
+		Copy,
+		PartialEq,
+		Encode,
+		Decode,
+		DecodeWithMemTracking,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	pub enum Activation<BlockNumber> {
+		AtBlock(BlockNumber),
+		AtTimestamp(u64),
+	}
+
+	impl<BlockNumber: PartialOrd + Copy> Activation<BlockNumber> {
+		fn is_due(&self, block_number: BlockNumber, timestamp: u64) -> bool {
+			match *self {
+				Activation::AtBlock(block) => block <= block_number,
+				Activation::AtTimestamp(time) => time <= timestamp,
+			}
+		}
+	}
+
+	#[derive(
+		Debug, Clone, PartialEq, Encode, Decode, DecodeWithMemTracking, TypeInfo, MaxEncodedLen,
+	)]
+	pub struct PendingChange<BlockNumber> {
+		pub id: u32,
+		pub change: ScheduledChange,
+		pub activation: Activation<BlockNumber>,
+	}
+
 	#[derive(Debug, Clone, PartialEq, Encode, Decode, DecodeWithMemTracking, TypeInfo)]
 	pub struct PayoutDetails {
 		pub amount: u128,
@@ -215,7 +375,7 @@ pub mod pallet {
 	// https://docs.substrate.io/main-docs/build/events-errors/
 	#[pallet::event]
 	#[pallet::generate_deposit(pub (super) fn deposit_event)]
-	pub enum Event {
+	pub enum Event<T: Config> {
 		/// A contract was called.
 		ContractCall(CallDetails),
 		/// A contract has been deployed.
@@ -232,6 +392,19 @@ pub mod pallet {
 		UnshieldedTokens(UnshieldedTokensDetails),
 		/// Partial Success.
 		TxPartialSuccess(TxAppliedDetails),
+		/// Fees paid, per-segment results and DUST spent by an applied transaction.
+		TxOutcome(TxOutcomeDetails),
+		/// A parameter or weight change was scheduled.
+		PendingParametersChanged(PendingChange<BlockNumberFor<T>>),
+		/// A scheduled parameter or weight change was cancelled.
+		PendingParametersCancelled(u32),
+		/// A scheduled parameter or weight change reached its activation point and was applied.
+		PendingParametersApplied(u32),
+		/// A scheduled parameter or weight change reached its activation point but failed to
+		/// apply, and was dropped.
+		PendingParametersFailed(u32, DispatchError),
+		/// The state of a called contract changed (or not), one per call of a transaction.
+		ContractStateChanged(ContractStateChangedDetails),
 	}
 
 	// Errors inform users that something went wrong.
@@ -261,6 +434,14 @@ pub mod pallet {
 		HostApiError,
 		#[codec(index = 11)]
 		NetworkIdNotString,
+		#[codec(index = 12)]
+		ActivationInPast,
+		#[codec(index = 13)]
+		TooManyPendingChanges,
+		#[codec(index = 14)]
+		PendingChangeNotFound,
+		#[codec(index = 15)]
+		InvalidParametersUpdate,
 	}
 	// grcov-excl-stop
 
@@ -278,6 +459,7 @@ pub mod pallet {
 				LedgerApiError::ContractCallCostError => Error::<T>::ContractCallCostError,
 				LedgerApiError::BlockLimitExceededError => Error::<T>::BlockLimitExceededError,
 				LedgerApiError::FeeCalculationError => Error::<T>::FeeCalculationError,
+				LedgerApiError::InvalidParametersUpdate => Error::<T>::InvalidParametersUpdate,
 				LedgerApiError::HostApiError => Error::<T>::HostApiError,
 			}
 		}
@@ -290,10 +472,33 @@ pub mod pallet {
 
 			LedgerApi::pre_fetch_storage(&state_key).expect("Failed to pre-fetch storage");
 
-			ConfigurableOnInitializeWeight::<T>::get()
+			// `PendingParametersChanges` here and `DueParametersChanges` in `on_finalize`
+			let mut weight = ConfigurableOnInitializeWeight::<T>::get()
+				.saturating_add(T::DbWeight::get().reads(2));
+
+			// The due changes are fixed here, so that applying them in `on_finalize` is accounted
+			// for, whether they succeed or not
+			let due_changes = Self::take_due_parameters_changes();
+			if due_changes > 0 {
+				weight = weight.saturating_add(T::DbWeight::get().writes(3)).saturating_add(
+					T::WeightInfo::apply_parameters_change().saturating_mul(due_changes.into()),
+				);
+			}
+
+			// The reward is fixed here, so that the minting in `on_finalize` is accounted for
+			let (reward, beneficiary) = T::BlockReward::get();
+			if let Some(beneficiary) = beneficiary.filter(|_| reward > 0) {
+				PendingBlockReward::<T>::put((reward, beneficiary));
+				weight = weight.saturating_add(T::WeightInfo::mint_block_reward());
+			}
+			weight
 		}
 
 		fn on_finalize(_block: BlockNumberFor<T>) {
+			// Apply due scheduled changes first, so ledger parameters updates are part of the
+			// block's ledger state
+			Self::apply_due_parameters_changes();
+
 			// Post Block Ledger Update
 			let state_key = StateKey::<T>::get().expect("Failed to get state key");
 			let block_context = Self::get_block_context();
@@ -308,40 +513,73 @@ pub mod pallet {
 			// Flush ledger storage changes to disk
 			LedgerApi::flush_storage();
 
-			let (reward, beneficiary) = T::BlockReward::get();
-			if reward == 0 {
-				return;
-			}
-			if let Some(beneficiary) = beneficiary {
-				let state_key = StateKey::<T>::get().expect("Failed to get state key");
-
-				match LedgerApi::mint_coins(&state_key, reward, &beneficiary[..], block_context) {
-					Ok(new_state_key) => {
-						log::info!("Minting {reward:?} coins for {beneficiary:?}");
-						Self::deposit_event(Event::PayoutMinted(PayoutDetails {
-							amount: reward,
-							receiver: beneficiary.to_vec(),
-						}));
-						let state_key: BoundedVec<_, _> =
-							new_state_key.try_into().expect("New state key size out of boundaries");
-						StateKey::<T>::put(state_key);
-
-						LedgerApi::flush_storage();
-					},
-					Err(e) => log::error!("Unable to mint coins: {e:#?}"),
-				};
+			if let Some((reward, beneficiary)) = PendingBlockReward::<T>::take() {
+				Self::mint_block_reward(reward, beneficiary, block_context);
 			}
 		}
 
 		#[cfg(hardfork_test)]
 		fn on_runtime_upgrade() -> Weight {
 			if Self::in_code_storage_version() != Self::on_chain_storage_version() {
-				LedgerApi::drop_default_storage();
-				LedgerApi::set_default_storage();
+				// Every later block needs the translated state, so the upgrade fails without it.
+				// The default storage is then left to the previous ledger version.
+				Self::translate_ledger_state()
+					.unwrap_or_else(|e| panic!("Unable to translate the ledger state: {e:?}"));
+				Self::in_code_storage_version().put::<Self>();
 			}
 			// TODO: Benchmark Weight in case of a real hard-fork
 			ConfigurableOnRuntimeUpgradeWeight::<T>::get()
 		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			let state_key = StateKey::<T>::get().ok_or("Missing ledger state key")?;
+
+			// Before a ledger switch, the state can only be read with the previous ledger version
+			let ledger_switch = Self::ledger_switch_pending();
+			let summary = if ledger_switch {
+				LedgerApi::get_previous_ledger_state_summary(&state_key)
+			} else {
+				LedgerApi::get_ledger_state_summary(&state_key)
+			}
+			.map_err(|e| {
+				log::error!("Unable to read the ledger state: {e:?}");
+				"Unable to read the ledger state"
+			})?;
+			log::info!("Pre-upgrade: ledger state {summary:?}, ledger switch: {ledger_switch}");
+
+			Ok((state_key, summary, ledger_switch).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let (pre_state_key, pre_summary, ledger_switch): (
+				BoundedVec<u8, StateKeyLength>,
+				LedgerTypes::LedgerStateSummary,
+				bool,
+			) = Decode::decode(&mut &state[..])
+				.map_err(|_| "Failed to decode the pre-upgrade ledger state")?;
+
+			let state_key = StateKey::<T>::get().ok_or("Missing ledger state key")?;
+			// A ledger switch translates the state, which may change its key
+			ensure!(ledger_switch || state_key == pre_state_key, "Ledger state key changed");
+
+			// Fails if the ledger state can't be read with the ledger version after the upgrade
+			let summary = LedgerApi::get_ledger_state_summary(&state_key).map_err(|e| {
+				log::error!("Unable to read the ledger state: {e:?}");
+				"Unable to read the ledger state"
+			})?;
+			log::info!("Post-upgrade: ledger state {summary:?}");
+
+			ensure!(
+				summary.zswap_state_root == pre_summary.zswap_state_root,
+				"Zswap state root changed"
+			);
+			ensure!(summary.utxo_count == pre_summary.utxo_count, "UTXO count changed");
+			ensure!(summary.contract_count == pre_summary.contract_count, "Contract count changed");
+
+			Ok(())
+		}
 	}
 
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -351,17 +589,21 @@ pub mod pallet {
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
-		#[pallet::weight(ConfigurableTransactionSizeWeight::<T>::get())]
-		pub fn send_mn_transaction(_origin: OriginFor<T>, midnight_tx: Vec<u8>) -> DispatchResult {
+		#[pallet::weight(Pallet::<T>::send_mn_transaction_weight(midnight_tx))]
+		pub fn send_mn_transaction(
+			_origin: OriginFor<T>,
+			midnight_tx: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
 			let state_key = StateKey::<T>::get().expect("Failed to get state key");
 			let block_context = Self::get_block_context();
 			let runtime_version = <frame_system::Pallet<T>>::runtime_version().spec_version;
 
-			let result = LedgerApi::apply_transaction(
+			let (result, outcome, contract_state_changes) = LedgerApi::apply_transaction(
 				&state_key,
 				&midnight_tx,
 				block_context,
 				runtime_version,
+				Self::is_pre_verified(&midnight_tx),
 			)
 			.map_err(Error::<T>::from)?;
 
@@ -369,6 +611,11 @@ pub mod pallet {
 				result.state_root.to_vec().try_into().expect("State key size out of boundaries");
 			StateKey::<T>::put(state_key);
 
+			// The gas declared by the fallible transcripts of failed segments was charged but
+			// not spent
+			let actual_weight =
+				Self::transaction_weight(midnight_tx.len(), &outcome.applied_weight_components);
+
 			let tx_hash = result.tx_hash;
 			for address in result.call_addresses {
 				let call_event =
@@ -376,6 +623,16 @@ pub mod pallet {
 				Self::deposit_event(call_event);
 			}
 
+			for change in contract_state_changes {
+				Self::deposit_event(Event::ContractStateChanged(ContractStateChangedDetails {
+					tx_hash,
+					contract_address: change.contract_address,
+					entry_points: change.entry_points,
+					old_state_hash: change.old_state_hash,
+					new_state_hash: change.new_state_hash,
+				}));
+			}
+
 			for address in result.deploy_addresses {
 				let deploy_event =
 					Event::ContractDeploy(DeploymentDetails { tx_hash, contract_address: address });
@@ -408,7 +665,9 @@ pub mod pallet {
 				Self::deposit_event(Event::TxPartialSuccess(TxAppliedDetails { tx_hash }));
 			}
 
-			Ok(())
+			Self::deposit_event(Event::TxOutcome(TxOutcomeDetails { tx_hash, outcome }));
+
+			Ok(Some(actual_weight).into())
 		}
 
 		#[pallet::call_index(1)]
@@ -422,12 +681,75 @@ pub mod pallet {
 			Ok(())
 		}
 
-		#[pallet::call_index(2)]
+		#[pallet::call_index(3)]
+		#[pallet::weight((T::DbWeight::get().writes(1), DispatchClass::Operational))]
+		/// Sets the block reward emission schedule, starting at the current block.
+		/// `None` stops the block rewards.
+		pub fn set_block_reward_schedule(
+			origin: OriginFor<T>,
+			schedule: Option<RewardSchedule>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			BlockRewardSchedule::<T>::set(schedule.map(|schedule| (now, schedule)));
+			Ok(())
+		}
+
+		#[pallet::call_index(4)]
 		#[pallet::weight((T::DbWeight::get().writes(1), DispatchClass::Operational))]
-		// A system transaction for configuring contract call weights
-		pub fn set_tx_size_weight(origin: OriginFor<T>, new_weight: Weight) -> DispatchResult {
+		/// Registers the ledger `UserAddress` receiving the block rewards of `author`.
+		/// `None` removes the registration.
+		pub fn set_reward_beneficiary(
+			origin: OriginFor<T>,
+			author: T::BlockAuthorId,
+			beneficiary: Option<LedgerTypes::Hash>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			RewardBeneficiaries::<T>::set(author, beneficiary);
+			Ok(())
+		}
+
+		#[pallet::call_index(5)]
+		#[pallet::weight((T::WeightInfo::schedule_parameters_change(), DispatchClass::Operational))]
+		/// Schedules `change` to be applied at the end of the first block reaching `activation`,
+		/// so wallets and block producers can prepare for it.
+		pub fn schedule_parameters_change(
+			origin: OriginFor<T>,
+			change: ScheduledChange,
+			activation: Activation<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let (block_number, timestamp) = Self::activation_now();
+			ensure!(!activation.is_due(block_number, timestamp), Error::<T>::ActivationInPast);
+			if let ScheduledChange::LedgerParameters(update) = &change {
+				ensure!(update.validate().is_ok(), Error::<T>::InvalidParametersUpdate);
+			}
+
+			let id = NextPendingChangeId::<T>::get();
+			let pending_change = PendingChange { id, change, activation };
+			PendingParametersChanges::<T>::try_append(pending_change.clone())
+				.map_err(|_| Error::<T>::TooManyPendingChanges)?;
+			NextPendingChangeId::<T>::put(id.wrapping_add(1));
+
+			Self::deposit_event(Event::PendingParametersChanged(pending_change));
+			Ok(())
+		}
+
+		#[pallet::call_index(6)]
+		#[pallet::weight((T::WeightInfo::cancel_parameters_change(), DispatchClass::Operational))]
+		/// Cancels the scheduled change `id` before its activation.
+		pub fn cancel_parameters_change(origin: OriginFor<T>, id: u32) -> DispatchResult {
 			ensure_root(origin)?;
-			ConfigurableTransactionSizeWeight::<T>::set(new_weight);
+			PendingParametersChanges::<T>::try_mutate(|pending| {
+				let index = pending
+					.iter()
+					.position(|change| change.id == id)
+					.ok_or(Error::<T>::PendingChangeNotFound)?;
+				pending.remove(index);
+				Ok::<(), Error<T>>(())
+			})?;
+
+			Self::deposit_event(Event::PendingParametersCancelled(id));
 			Ok(())
 		}
 	}
@@ -436,29 +758,13 @@ pub mod pallet {
 	impl<T: Config> ValidateUnsigned for Pallet<T> {
 		type Call = Call<T>;
 		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-			let mut block_context = Self::get_block_context();
-			let slot_duration: u64 = T::SlotDuration::get().unique_saturated_into();
-			let slot_duration_secs = slot_duration.saturating_div(1000);
-
-			// Simulate the expected next block time during validation.
-			// This is needed to avoid potential `OutOfDustValidityWindow` tx validation errors where `ctime > tblock`.
-			// During transaction pool validation, the stored Timestamp still corresponds to the last produced block.
-			// Validity is increased by `slot_duration_secs * MaxSkippedSlots` to prevent the node
-			// from rejecting potentially valid transactions if an AURA block production slots are skipped.
-			let skipped_slots_margin =
-				slot_duration_secs.saturating_mul(MaxSkippedSlots::<T>::get() as u64);
-			block_context.tblock = block_context
-				.tblock
-				.saturating_add(slot_duration_secs)
-				.saturating_add(skipped_slots_margin);
-
-			Self::validate_unsigned(call, block_context)
+			Self::validate_unsigned(call, Self::get_next_block_context())
 		}
 
 		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
 			let block_context = Self::get_block_context();
 
-			Self::validate_unsigned(call, block_context).map(|_| ())
+			Self::validate_midnight_transaction(call, block_context).map(|_| ())
 		}
 	}
 
@@ -478,6 +784,222 @@ pub mod pallet {
 			NetworkId::<T>::put(network_id);
 		}
 
+		/// Current block number and timestamp (in milliseconds), as compared to `Activation`s.
+		fn activation_now() -> (BlockNumberFor<T>, u64) {
+			let block_number = <frame_system::Pallet<T>>::block_number();
+			let timestamp = pallet_timestamp::Pallet::<T>::get().unique_saturated_into();
+			(block_number, timestamp)
+		}
+
+		/// Moves the scheduled changes which reached their activation point to
+		/// `DueParametersChanges`, and returns how many there are.
+		/// Block timestamp activations are compared to the parent block's timestamp, as the
+		/// current one isn't set yet in `on_initialize`.
+		pub(crate) fn take_due_parameters_changes() -> u32 {
+			let pending = PendingParametersChanges::<T>::get();
+			if pending.is_empty() {
+				return 0;
+			}
+
+			let (block_number, timestamp) = Self::activation_now();
+			let (due, remaining): (Vec<_>, Vec<_>) = pending
+				.into_iter()
+				.partition(|change| change.activation.is_due(block_number, timestamp));
+			if due.is_empty() {
+				return 0;
+			}
+			PendingParametersChanges::<T>::put(BoundedVec::truncate_from(remaining));
+
+			let due_changes = due.len() as u32;
+			DueParametersChanges::<T>::put(BoundedVec::truncate_from(due));
+			due_changes
+		}
+
+		/// Applies the changes of `DueParametersChanges`. Changes failing to apply are dropped.
+		pub(crate) fn apply_due_parameters_changes() {
+			for PendingChange { id, change, .. } in DueParametersChanges::<T>::take() {
+				match Self::apply_change(change) {
+					Ok(()) => Self::deposit_event(Event::PendingParametersApplied(id)),
+					Err(e) => {
+						log::error!("Unable to apply scheduled change {id}: {e:?}");
+						Self::deposit_event(Event::PendingParametersFailed(id, e));
+					},
+				}
+			}
+		}
+
+		pub(crate) fn apply_change(change: ScheduledChange) -> DispatchResult {
+			match change {
+				ScheduledChange::DParameterOverride(d_parameter_override) => {
+					DParameterOverride::<T>::set(d_parameter_override)
+				},
+				ScheduledChange::LedgerParameters(update) => {
+					let state_key = StateKey::<T>::get().ok_or(Error::<T>::NoLedgerState)?;
+					let system_tx =
+						LedgerApi::construct_update_parameters_system_tx(&state_key, update)
+							.map_err(Error::<T>::from)?;
+					T::MidnightSystemTransactionExecutor::execute_system_transaction(system_tx)?;
+				},
+			}
+			Ok(())
+		}
+
+		pub(crate) fn mint_block_reward(
+			reward: u128,
+			beneficiary: LedgerTypes::Hash,
+			block_context: LedgerTypes::BlockContext,
+		) {
+			let state_key = StateKey::<T>::get().expect("Failed to get state key");
+
+			match LedgerApi::mint_coins(&state_key, reward, &beneficiary[..], block_context) {
+				Ok(new_state_key) => {
+					log::info!("Minting {reward:?} coins for {beneficiary:?}");
+					TotalRewardsMinted::<T>::mutate(|total| *total = total.saturating_add(reward));
+					Self::deposit_event(Event::PayoutMinted(PayoutDetails {
+						amount: reward,
+						receiver: beneficiary.to_vec(),
+					}));
+					let state_key: BoundedVec<_, _> =
+						new_state_key.try_into().expect("New state key size out of boundaries");
+					StateKey::<T>::put(state_key);
+
+					LedgerApi::flush_storage();
+				},
+				Err(e) => log::error!("Unable to mint coins: {e:#?}"),
+			};
+		}
+
+		/// Weight of `send_mn_transaction`, from the components of `midnight_tx`.
+		/// Transactions which can't be deserialized only weigh their length.
+		pub fn send_mn_transaction_weight(midnight_tx: &[u8]) -> Weight {
+			let components =
+				LedgerApi::get_transaction_weight_components(midnight_tx).unwrap_or_default();
+			Self::transaction_weight(midnight_tx.len(), &components)
+		}
+
+		fn transaction_weight(length: usize, components: &TransactionWeightComponents) -> Weight {
+			T::WeightInfo::send_mn_transaction(
+				length as u32,
+				components.intents,
+				components.inputs,
+				components.outputs,
+				// Benchmarked in nanoseconds, to fit a `u32` component
+				(components.gas / 1_000).unique_saturated_into(),
+			)
+		}
+
+		/// Block reward of the current block according to the emission schedule,
+		/// limited to what is left below the schedule's cap.
+		pub fn scheduled_block_reward() -> u128 {
+			let Some((start, schedule)) = BlockRewardSchedule::<T>::get() else {
+				return 0;
+			};
+			let now = <frame_system::Pallet<T>>::block_number();
+			let elapsed: u32 = now.saturating_sub(start).unique_saturated_into();
+
+			let reward = schedule.reward_at(elapsed);
+			match schedule.cap {
+				Some(cap) => reward.min(cap.saturating_sub(TotalRewardsMinted::<T>::get())),
+				None => reward,
+			}
+		}
+
+		/// Ledger `UserAddress` registered to receive the block rewards of `author`.
+		pub fn reward_beneficiary(author: &T::BlockAuthorId) -> Option<LedgerTypes::Hash> {
+			RewardBeneficiaries::<T>::get(author)
+		}
+
+		/// Block context for a transaction that would be included in the next block.
+		///
+		/// This is needed to avoid potential `OutOfDustValidityWindow` tx validation errors where `ctime > tblock`.
+		/// Outside of block production, the stored Timestamp still corresponds to the last produced block.
+		/// Validity is increased by `slot_duration_secs * MaxSkippedSlots` to prevent the node
+		/// from rejecting potentially valid transactions if an AURA block production slots are skipped.
+		pub fn get_next_block_context() -> LedgerTypes::BlockContext {
+			let mut block_context = Self::get_block_context();
+			let slot_duration: u64 = T::SlotDuration::get().unique_saturated_into();
+			let slot_duration_secs = slot_duration.saturating_div(1000);
+
+			let skipped_slots_margin =
+				slot_duration_secs.saturating_mul(MaxSkippedSlots::<T>::get() as u64);
+			block_context.tblock = block_context
+				.tblock
+				.saturating_add(slot_duration_secs)
+				.saturating_add(skipped_slots_margin);
+
+			block_context
+		}
+
+		/// Executes the block with `execute_block`, after verifying in parallel on the host the
+		/// proofs and signatures of its Midnight transactions, so that applying them doesn't
+		/// verify them again. Has to be called on the parent state of the block.
+		///
+		/// The transactions skipping verification only depend on the parent state and the block,
+		/// and are forgotten once it is executed, so every node applies them the same way.
+		pub fn execute_with_pre_verified_transactions<R>(
+			parent_hash: T::Hash,
+			midnight_txs: Vec<Vec<u8>>,
+			execute_block: impl FnOnce() -> R,
+		) -> R {
+			let mut pre_verified = Self::pre_verify_transactions(parent_hash, midnight_txs);
+			crate::pre_verified_txs::using(&mut pre_verified, execute_block)
+		}
+
+		fn pre_verify_transactions(
+			parent_hash: T::Hash,
+			midnight_txs: Vec<Vec<u8>>,
+		) -> BTreeSet<LedgerTypes::Hash> {
+			if midnight_txs.is_empty() {
+				return BTreeSet::new();
+			}
+			let Some(state_key) = StateKey::<T>::get() else {
+				return BTreeSet::new();
+			};
+
+			let mut block_context = Self::get_next_block_context();
+			block_context.parent_block_hash = parent_hash.as_ref().to_vec();
+
+			let tx_hashes: Vec<_> = midnight_txs.iter().map(|tx| Blake2_256::hash(tx)).collect();
+			match LedgerApi::pre_verify_transactions(&state_key, midnight_txs, block_context) {
+				Ok(verified) => tx_hashes
+					.into_iter()
+					.zip(verified)
+					.filter_map(|(tx_hash, verified)| verified.then_some(tx_hash))
+					.collect(),
+				Err(e) => {
+					log::warn!("Unable to pre-verify Midnight transactions: {e:?}");
+					BTreeSet::new()
+				},
+			}
+		}
+
+		/// Whether the proofs and signatures of `midnight_tx` were verified before executing the
+		/// current block
+		pub(crate) fn is_pre_verified(midnight_tx: &[u8]) -> bool {
+			crate::pre_verified_txs::with(|txs| txs.contains(&Blake2_256::hash(midnight_tx)))
+				.unwrap_or(false)
+		}
+
+		/// Translates the ledger state into the ledger version of the runtime, see
+		/// `on_runtime_upgrade`
+		#[cfg(hardfork_test)]
+		fn translate_ledger_state() -> Result<(), LedgerApiError> {
+			let state_key = StateKey::<T>::get().ok_or(LedgerApiError::NoLedgerState)?;
+			let new_state_key: BoundedVec<_, _> = LedgerApi::translate_ledger_state(&state_key)?
+				.try_into()
+				.map_err(|_| LedgerApiError::Serialization(SerializationError::LedgerState))?;
+			StateKey::<T>::put(new_state_key);
+			Ok(())
+		}
+
+		/// Whether the runtime upgrade switches to a new ledger version, as `on_runtime_upgrade`
+		/// does with `hardfork_test`
+		#[cfg(feature = "try-runtime")]
+		fn ledger_switch_pending() -> bool {
+			cfg!(hardfork_test)
+				&& Self::in_code_storage_version() != Self::on_chain_storage_version()
+		}
+
 		pub fn get_contract_state(contract_address: &[u8]) -> Result<Vec<u8>, LedgerApiError> {
 			let state_key = StateKey::<T>::get().expect("Failed to get state key");
 			LedgerApi::get_contract_state(&state_key, contract_address)
@@ -512,10 +1034,62 @@ pub mod pallet {
 			TransactionValidityError::Invalid(InvalidTransaction::Custom(error_code))
 		}
 
+		/// Validates a Midnight transaction for the transaction pool.
+		///
+		/// Transactions are prioritised by the DUST fee they pay per second of block weight, and
+		/// provide a tag for every coin and UTXO they spend. Conflicting transactions are then
+		/// deduplicated by the pool, which keeps the one with the highest priority, so a spend can
+		/// be replaced by paying a higher fee.
+		///
+		/// No tags are required, as the spent coins and UTXOs must already be on chain for the
+		/// transaction to be valid.
 		fn validate_unsigned(
 			call: &Call<T>,
 			block_context: LedgerTypes::BlockContext,
 		) -> TransactionValidity {
+			let Call::send_mn_transaction { midnight_tx } = call else {
+				// grcov-excl-start
+				return Err(Self::invalid_transaction(Default::default()));
+				// grcov-excl-stop
+			};
+			let state_key = StateKey::<T>::get().expect("Failed to get state key");
+			let runtime_version = <frame_system::Pallet<T>>::runtime_version().spec_version;
+
+			let pool_info = LedgerApi::get_transaction_pool_info(
+				&state_key,
+				midnight_tx,
+				block_context,
+				runtime_version,
+			)
+			.map_err(|e| Self::invalid_transaction(e.into()))?;
+			let weight = Self::transaction_weight(midnight_tx.len(), &pool_info.weight_components);
+
+			Self::pool_validity(pool_info, weight)
+		}
+
+		/// Valid transaction of the given pool info, see `validate_unsigned`
+		pub fn pool_validity(
+			pool_info: TransactionPoolInfo,
+			weight: Weight,
+		) -> TransactionValidity {
+			pool_info
+				.spends
+				.into_iter()
+				.fold(
+					ValidTransaction::with_tag_prefix("Midnight")
+						.priority(Self::fee_priority(pool_info.fee, weight))
+						// Transactions can live in the pool for max 600 blocks before they must be revalidated
+						.longevity(600)
+						.and_provides(pool_info.tx_hash),
+					|builder, spend| builder.and_provides(spend),
+				)
+				.build()
+		}
+
+		fn validate_midnight_transaction(
+			call: &Call<T>,
+			block_context: LedgerTypes::BlockContext,
+		) -> Result<(LedgerTypes::Hash, &Vec<u8>), TransactionValidityError> {
 			if let Call::send_mn_transaction { midnight_tx } = call {
 				let state_key = StateKey::<T>::get().expect("Failed to get state key");
 				let runtime_version = <frame_system::Pallet<T>>::runtime_version().spec_version;
@@ -527,11 +1101,7 @@ pub mod pallet {
 					runtime_version,
 				)
 				.map_err(|e| Self::invalid_transaction(e.into()))?;
-				ValidTransaction::with_tag_prefix("Midnight")
-					// Transactions can live in the pool for max 600 blocks before they must be revalidated
-					.longevity(600)
-					.and_provides(tx_hash)
-					.build()
+				Ok((tx_hash, midnight_tx))
 			} else {
 				// grcov-excl-start
 				Err(Self::invalid_transaction(Default::default()))
@@ -539,6 +1109,12 @@ pub mod pallet {
 			}
 		}
 
+		/// DUST `fee` paid per second of `weight`
+		pub fn fee_priority(fee: u128, weight: Weight) -> TransactionPriority {
+			let per_second = fee.saturating_mul(WEIGHT_REF_TIME_PER_SECOND.into());
+			(per_second / u128::from(weight.ref_time().max(1))).unique_saturated_into()
+		}
+
 		pub fn get_unclaimed_amount(beneficiary: &[u8]) -> Result<u128, LedgerApiError> {
 			let state_key = StateKey::<T>::get().expect("Failed to get state key");
 			LedgerApi::get_unclaimed_amount(&state_key, beneficiary)
@@ -549,6 +1125,32 @@ pub mod pallet {
 			LedgerApi::get_ledger_parameters(&state_key)
 		}
 
+		pub fn get_unshielded_utxos(address: &[u8]) -> Result<Vec<UtxoInfo>, LedgerApiError> {
+			let state_key = StateKey::<T>::get().expect("Failed to get state key");
+			LedgerApi::get_unshielded_utxos(&state_key, address)
+		}
+
+		pub fn get_zswap_commitment_proof(
+			commitment: &[u8],
+		) -> Result<Option<ZswapCommitmentProof>, LedgerApiError> {
+			let state_key = StateKey::<T>::get().expect("Failed to get state key");
+			LedgerApi::get_zswap_commitment_proof(&state_key, commitment)
+		}
+
+		pub fn get_contract_state_proof(
+			contract_address: &[u8],
+		) -> Result<Option<ContractStateProof>, LedgerApiError> {
+			let state_key = StateKey::<T>::get().expect("Failed to get state key");
+			LedgerApi::get_contract_state_proof(&state_key, contract_address)
+		}
+
+		pub fn get_dust_generation_info(
+			nonce: &[u8],
+		) -> Result<Option<DustGenerationDetails>, LedgerApiError> {
+			let state_key = StateKey::<T>::get().expect("Failed to get state key");
+			LedgerApi::get_dust_generation_info(&state_key, nonce)
+		}
+
 		pub fn get_transaction_cost(tx: &[u8]) -> Result<(StorageCost, GasCost), LedgerApiError> {
 			let state_key = StateKey::<T>::get().expect("Failed to get state key");
 			let block_context = Self::get_block_context();
@@ -559,5 +1161,14 @@ pub mod pallet {
 			let state_key = StateKey::<T>::get().expect("Failed to get state key");
 			LedgerApi::get_zswap_state_root(&state_key)
 		}
+
+		/// Dry-runs `midnight_tx` as if it were included in the next block, without
+		/// updating `StateKey` or emitting any events.
+		pub fn simulate_transaction(
+			midnight_tx: &[u8],
+		) -> Result<TransactionSimulationResult, LedgerApiError> {
+			let state_key = StateKey::<T>::get().expect("Failed to get state key");
+			LedgerApi::simulate_transaction(&state_key, midnight_tx, Self::get_next_block_context())
+		}
 	}
 }
@@ -13,12 +13,14 @@
 
 // grcov-excl-start
 use crate as pallet_midnight;
+use core::cell::RefCell;
 use frame_support::traits::{ConstU16, ConstU64};
 //#[cfg(feature = "experimental")]
 //use sp_block_rewards::GetBlockRewardPoints;
+use midnight_primitives::MidnightSystemTransactionExecutor;
 use sp_core::H256;
 use sp_runtime::{
-	BuildStorage,
+	BuildStorage, DispatchError,
 	traits::{BlakeTwo256, Get, IdentityLookup},
 };
 
@@ -83,25 +85,45 @@ pub type BeneficiaryId = midnight_node_ledger::types::Hash;
 pub type BlockRewardPoints = u128;
 pub type BlockReward = (BlockRewardPoints, Option<BeneficiaryId>);
 pub struct LedgerBlockReward;
+impl LedgerBlockReward {
+	pub fn set(reward: BlockReward) {
+		BLOCK_REWARD.with(|r| *r.borrow_mut() = reward);
+	}
+}
 impl Get<BlockReward> for LedgerBlockReward {
-	#[cfg(feature = "experimental")]
 	fn get() -> BlockReward {
-		/*
-		(
-			<Test as pallet_block_rewards::Config>::GetBlockRewardPoints::get_block_reward(),
-			pallet_block_rewards::CurrentBlockBeneficiary::<Test>::get(),
-		)
-		*/
-		(0, None)
+		BLOCK_REWARD.with(|r| *r.borrow())
 	}
-	#[cfg(not(feature = "experimental"))]
-	fn get() -> BlockReward {
-		(0, None)
+}
+
+thread_local! {
+	static BLOCK_REWARD: RefCell<BlockReward> = const { RefCell::new((0, None)) };
+	static EXECUTED_SYSTEM_TXS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Records the system transactions instead of applying them
+pub struct MockSystemTransactionExecutor;
+
+impl MockSystemTransactionExecutor {
+	pub fn executed_system_txs() -> Vec<Vec<u8>> {
+		EXECUTED_SYSTEM_TXS.with(|txs| txs.borrow().clone())
+	}
+}
+
+impl MidnightSystemTransactionExecutor for MockSystemTransactionExecutor {
+	fn execute_system_transaction(
+		serialized_system_transaction: Vec<u8>,
+	) -> Result<midnight_node_ledger::types::Hash, DispatchError> {
+		EXECUTED_SYSTEM_TXS.with(|txs| txs.borrow_mut().push(serialized_system_transaction));
+		Ok(Default::default())
 	}
 }
 
 impl pallet_midnight::Config for Test {
 	type BlockReward = LedgerBlockReward;
+	type BlockAuthorId = u64;
+	type MidnightSystemTransactionExecutor = MockSystemTransactionExecutor;
+	type WeightInfo = ();
 	type SlotDuration = ConstU64<SLOT_DURATION>;
 }
 
@@ -132,7 +154,7 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	frame_system::GenesisConfig::<Test>::default().build_storage().unwrap().into()
 }
 
-pub fn midnight_events() -> Vec<super::Event> {
+pub fn midnight_events() -> Vec<super::Event<Test>> {
 	System::events()
 		.into_iter()
 		.map(|r| r.event)
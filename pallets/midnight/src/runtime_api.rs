@@ -13,12 +13,15 @@
 
 //! Runtime API definition for Midnight pallet
 
-use midnight_node_ledger::types::{GasCost, StorageCost, Tx, active_version::LedgerApiError};
+use midnight_node_ledger::types::{
+	ContractStateProof, DustGenerationDetails, GasCost, StorageCost, TransactionSimulationResult,
+	Tx, UtxoInfo, ZswapCommitmentProof, active_version::LedgerApiError,
+};
 use scale_info::prelude::string::String;
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
-	#[api_version(5)]
+	#[api_version(8)]
 	pub trait MidnightRuntimeApi {
 		#[changed_in(2)]
 		fn get_contract_state(contract_address: Vec<u8>) -> Vec<u8>;
@@ -39,5 +42,10 @@ sp_api::decl_runtime_apis! {
 		fn get_ledger_parameters() -> Result<Vec<u8>, LedgerApiError>;
 		fn get_transaction_cost(transaction_bytes: Vec<u8>) -> Result<(StorageCost, GasCost), LedgerApiError>;
 		fn get_zswap_state_root() -> Result<Vec<u8>, LedgerApiError>;
+		fn simulate_transaction(transaction_bytes: Vec<u8>) -> Result<TransactionSimulationResult, LedgerApiError>;
+		fn get_unshielded_utxos(address: Vec<u8>) -> Result<Vec<UtxoInfo>, LedgerApiError>;
+		fn get_dust_generation_info(nonce: Vec<u8>) -> Result<Option<DustGenerationDetails>, LedgerApiError>;
+		fn get_zswap_commitment_proof(commitment: Vec<u8>) -> Result<Option<ZswapCommitmentProof>, LedgerApiError>;
+		fn get_contract_state_proof(contract_address: Vec<u8>) -> Result<Option<ContractStateProof>, LedgerApiError>;
 	}
 }
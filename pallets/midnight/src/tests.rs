@@ -16,12 +16,18 @@ use super::*;
 use crate::{
 	Call as MidnightCall, mock,
 	mock::{RuntimeOrigin, Test},
+	weights::WeightInfo,
 };
 use assert_matches::assert_matches;
-use frame_support::{assert_err, assert_ok, pallet_prelude::Weight, traits::OnFinalize};
+use frame_support::{
+	assert_err, assert_err_ignore_postinfo, assert_ok,
+	dispatch::GetDispatchInfo,
+	traits::{OnFinalize, OnInitialize},
+	weights::{Weight, constants::WEIGHT_REF_TIME_PER_SECOND},
+};
 use frame_system::RawOrigin;
 use midnight_node_ledger::types::{
-	BlockContext,
+	BlockContext, LedgerParametersUpdate, TransactionPoolInfo, active_ledger_bridge,
 	active_version::{DeserializationError, LedgerApiError, MalformedError, TransactionError},
 };
 use midnight_node_res::{
@@ -63,12 +69,129 @@ fn test_send_mn_transaction() {
 			midnight_node_ledger_helpers::extract_info_from_tx_with_context(DEPLOY_TX);
 		init_ledger_state(block_context.into());
 
-		assert_ok!(mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), tx));
+		let weight = mock::Midnight::send_mn_transaction_weight(&tx);
+		let post_info = mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), tx).unwrap();
+		// Every segment was applied, so the whole weight was used
+		assert_eq!(post_info.actual_weight, Some(weight));
 
 		// Check emitted events
 		let events = mock::midnight_events();
 		assert_matches!(events[0], Event::ContractDeploy(_));
 		assert_matches!(events[1], Event::TxApplied(_));
+		assert_matches!(&events[2], Event::TxOutcome(TxOutcomeDetails { outcome, .. }) => {
+			assert!(outcome.fees_paid > 0);
+			assert!(outcome.dust_spends > 0);
+			assert!(outcome.segment_results.is_empty());
+			assert_eq!(
+				outcome.zswap_state_root,
+				mock::Midnight::get_zswap_state_root().unwrap()
+			);
+		});
+	})
+}
+
+#[test]
+fn pre_verified_transactions_apply_the_same() {
+	mock::new_test_ext().execute_with(|| {
+		let (tx, block_context) =
+			midnight_node_ledger_helpers::extract_info_from_tx_with_context(DEPLOY_TX);
+		init_ledger_state(block_context.into());
+
+		let parent_hash = mock::System::parent_hash();
+		let midnight_txs = vec![tx.clone(), vec![1, 2, 3]];
+		mock::Midnight::execute_with_pre_verified_transactions(parent_hash, midnight_txs, || {
+			// Only the transaction passing verification skips it when applied
+			assert!(mock::Midnight::is_pre_verified(&tx));
+			assert!(!mock::Midnight::is_pre_verified(&[1, 2, 3]));
+
+			assert_ok!(mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), tx.clone()));
+			assert_err_ignore_postinfo!(
+				mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), vec![1, 2, 3]),
+				Error::<Test>::Deserialization(DeserializationError::Transaction)
+			);
+		});
+
+		// Nothing is remembered once the block is executed
+		assert!(!mock::Midnight::is_pre_verified(&tx));
+	})
+}
+
+#[test]
+fn contract_calls_emit_contract_state_changes() {
+	mock::new_test_ext().execute_with(|| {
+		let (tx_deploy, block_context_deploy) =
+			midnight_node_ledger_helpers::extract_info_from_tx_with_context(DEPLOY_TX);
+		let (tx_store, block_context_store) =
+			midnight_node_ledger_helpers::extract_info_from_tx_with_context(STORE_TX);
+
+		init_ledger_state(block_context_deploy.into());
+		assert_ok!(mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), tx_deploy));
+
+		// A deployment is not a call
+		assert!(
+			!mock::midnight_events()
+				.iter()
+				.any(|e| matches!(e, Event::ContractStateChanged(_)))
+		);
+
+		process_block(2, block_context_store.into());
+		assert_ok!(mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), tx_store));
+
+		let changes: Vec<_> = mock::midnight_events()
+			.into_iter()
+			.filter_map(|e| match e {
+				Event::ContractStateChanged(details) => Some(details),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(changes.len(), 1);
+		let change = &changes[0];
+		assert_eq!(change.entry_points, vec![b"store".to_vec()]);
+		assert!(!change.old_state_hash.is_empty());
+		assert_ne!(change.new_state_hash, change.old_state_hash);
+		assert_matches!(
+			mock::midnight_events().iter().find(|e| matches!(e, Event::ContractCall(_))),
+			Some(Event::ContractCall(CallDetails { tx_hash, contract_address })) => {
+				assert_eq!(*tx_hash, change.tx_hash);
+				assert_eq!(*contract_address, change.contract_address);
+			}
+		);
+	})
+}
+
+#[test]
+fn test_simulate_transaction() {
+	mock::new_test_ext().execute_with(|| {
+		let (tx, block_context) =
+			midnight_node_ledger_helpers::extract_info_from_tx_with_context(DEPLOY_TX);
+		init_ledger_state(block_context.into());
+
+		let state_key = StateKey::<Test>::get();
+
+		let result = mock::Midnight::simulate_transaction(&tx).unwrap();
+		assert!(result.all_applied);
+		assert!(result.failed_segments.is_empty());
+		assert_eq!(result.deploy_addresses.len(), 1);
+		assert!(result.fees > 0);
+
+		// Nothing should have been persisted nor emitted
+		assert_eq!(StateKey::<Test>::get(), state_key);
+		assert!(mock::midnight_events().is_empty());
+
+		// The simulated transaction can still be applied
+		assert_ok!(mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), tx));
+	})
+}
+
+#[test]
+fn test_simulate_transaction_malformed_tx() {
+	mock::new_test_ext().execute_with(|| {
+		init_ledger_state(BlockContext::default());
+
+		assert_matches!(
+			mock::Midnight::simulate_transaction(&[1, 2, 3]),
+			Err(LedgerApiError::Deserialization(DeserializationError::Transaction))
+		);
 	})
 }
 
@@ -80,7 +203,7 @@ fn test_send_mn_transaction_malformed_tx() {
 		let bytes = vec![1, 2, 3];
 		let error: sp_runtime::DispatchError =
 			Error::<Test>::Deserialization(DeserializationError::Transaction).into();
-		assert_err!(
+		assert_err_ignore_postinfo!(
 			mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), bytes.clone()),
 			error
 		);
@@ -101,7 +224,10 @@ fn test_send_mn_transaction_invalid_tx() {
 			TransactionError::Malformed(MalformedError::ContractNotPresent),
 		)
 		.into();
-		assert_err!(mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), tx), error);
+		assert_err_ignore_postinfo!(
+			mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), tx),
+			error
+		);
 
 		// Check emitted events
 		assert!(mock::midnight_events().is_empty());
@@ -156,6 +282,75 @@ fn test_validation_works() {
 	})
 }
 
+#[test]
+fn validation_prioritises_by_fee_and_provides_spends() {
+	let (tx, block_context) =
+		midnight_node_ledger_helpers::extract_info_from_tx_with_context(DEPLOY_TX);
+
+	let call = MidnightCall::send_mn_transaction { midnight_tx: tx };
+	mock::new_test_ext().execute_with(|| {
+		init_ledger_state(block_context.into());
+
+		let valid = <mock::Midnight as ValidateUnsigned>::validate_unsigned(
+			TransactionSource::External,
+			&call,
+		)
+		.unwrap();
+		assert!(valid.priority > 0);
+		// The transaction hash, and the DUST spent to pay the fees
+		assert!(valid.provides.len() > 1);
+		assert!(valid.requires.is_empty());
+	})
+}
+
+#[test]
+fn higher_paying_conflicting_transaction_replaces_the_lower_one() {
+	let (tx, block_context) =
+		midnight_node_ledger_helpers::extract_info_from_tx_with_context(DEPLOY_TX);
+
+	mock::new_test_ext().execute_with(|| {
+		init_ledger_state(block_context.into());
+
+		let state_key = StateKey::<Test>::get().unwrap();
+		let runtime_version = frame_system::Pallet::<Test>::runtime_version().spec_version;
+		let pool_info = active_ledger_bridge::get_transaction_pool_info(
+			&state_key,
+			&tx,
+			mock::Midnight::get_next_block_context(),
+			runtime_version,
+		)
+		.unwrap();
+		assert!(pool_info.fee > 0);
+
+		// Another transaction spending the same coins, paying twice the fee
+		let conflicting = TransactionPoolInfo {
+			tx_hash: [0xff; 32],
+			fee: pool_info.fee * 2,
+			..pool_info.clone()
+		};
+		let weight = mock::Midnight::send_mn_transaction_weight(&tx);
+
+		let lower = mock::Midnight::pool_validity(pool_info, weight).unwrap();
+		let higher = mock::Midnight::pool_validity(conflicting, weight).unwrap();
+
+		// The pool replaces the transactions providing the same tags with a lower priority
+		assert_ne!(lower.provides[0], higher.provides[0]);
+		assert!(lower.provides[1..].iter().all(|tag| higher.provides.contains(tag)));
+		assert!(higher.priority > lower.priority);
+	})
+}
+
+#[test]
+fn fee_priority_is_fee_per_second_of_weight() {
+	let half_second = Weight::from_parts(WEIGHT_REF_TIME_PER_SECOND / 2, 0);
+	assert_eq!(mock::Midnight::fee_priority(1_000, half_second), 2_000);
+	assert_eq!(
+		mock::Midnight::fee_priority(1_000, Weight::zero()),
+		1_000 * WEIGHT_REF_TIME_PER_SECOND
+	);
+	assert_eq!(mock::Midnight::fee_priority(u128::MAX, half_second), u64::MAX);
+}
+
 #[test]
 fn test_validation_fails() {
 	let call = MidnightCall::send_mn_transaction { midnight_tx: vec![1, 2, 3] };
@@ -177,19 +372,236 @@ fn test_validation_fails() {
 }
 
 #[test]
-fn sets_extra_transaction_size_weight() {
+fn weighs_transaction_by_its_components() {
+	mock::new_test_ext().execute_with(|| {
+		let (tx, block_context) =
+			midnight_node_ledger_helpers::extract_info_from_tx_with_context(DEPLOY_TX);
+		init_ledger_state(block_context.into());
+
+		let length_weight = <() as WeightInfo>::send_mn_transaction(tx.len() as u32, 0, 0, 0, 0);
+		let call = MidnightCall::<Test>::send_mn_transaction { midnight_tx: tx };
+		assert!(call.get_dispatch_info().call_weight.all_gt(length_weight));
+
+		// Transactions which can't be deserialized only weigh their length
+		let call = MidnightCall::<Test>::send_mn_transaction { midnight_tx: vec![1, 2, 3] };
+		assert_eq!(
+			call.get_dispatch_info().call_weight,
+			<() as WeightInfo>::send_mn_transaction(3, 0, 0, 0, 0)
+		);
+		assert!(mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), vec![1, 2, 3]).is_err());
+	})
+}
+
+#[test]
+fn applies_scheduled_change_at_activation() {
+	mock::new_test_ext().execute_with(|| {
+		mock::System::set_block_number(1);
+		let d_parameter = Some((1, 2));
+
+		assert_err!(
+			mock::Midnight::schedule_parameters_change(
+				RawOrigin::Root.into(),
+				ScheduledChange::DParameterOverride(d_parameter),
+				Activation::AtBlock(1),
+			),
+			Error::<Test>::ActivationInPast
+		);
+		assert_ok!(mock::Midnight::schedule_parameters_change(
+			RawOrigin::Root.into(),
+			ScheduledChange::DParameterOverride(d_parameter),
+			Activation::AtBlock(3),
+		));
+		assert_matches!(
+			mock::midnight_events().last(),
+			Some(Event::PendingParametersChanged(PendingChange { id: 0, .. }))
+		);
+
+		mock::System::set_block_number(2);
+		assert_eq!(mock::Midnight::take_due_parameters_changes(), 0);
+		mock::Midnight::apply_due_parameters_changes();
+		assert_eq!(DParameterOverride::<Test>::get(), None);
+
+		mock::System::set_block_number(3);
+		assert_eq!(mock::Midnight::take_due_parameters_changes(), 1);
+		mock::Midnight::apply_due_parameters_changes();
+		assert_eq!(DParameterOverride::<Test>::get(), d_parameter);
+		assert!(DueParametersChanges::<Test>::get().is_empty());
+		assert!(PendingParametersChanges::<Test>::get().is_empty());
+		assert_matches!(mock::midnight_events().last(), Some(Event::PendingParametersApplied(0)));
+	});
+}
+
+#[test]
+fn cancels_scheduled_change() {
+	mock::new_test_ext().execute_with(|| {
+		mock::System::set_block_number(1);
+		mock::Timestamp::set_timestamp(6000);
+
+		assert_ok!(mock::Midnight::schedule_parameters_change(
+			RawOrigin::Root.into(),
+			ScheduledChange::DParameterOverride(Some((1, 2))),
+			Activation::AtTimestamp(12000),
+		));
+		assert_err!(
+			mock::Midnight::cancel_parameters_change(RawOrigin::Root.into(), 1),
+			Error::<Test>::PendingChangeNotFound
+		);
+		assert_ok!(mock::Midnight::cancel_parameters_change(RawOrigin::Root.into(), 0));
+
+		mock::Timestamp::set_timestamp(12000);
+		assert_eq!(mock::Midnight::take_due_parameters_changes(), 0);
+		mock::Midnight::apply_due_parameters_changes();
+		assert_eq!(DParameterOverride::<Test>::get(), None);
+		assert_matches!(mock::midnight_events().last(), Some(Event::PendingParametersCancelled(0)));
+	});
+}
+
+#[test]
+fn applies_scheduled_ledger_parameters_update() {
+	mock::new_test_ext().execute_with(|| {
+		init_ledger_state(BlockContext::default());
+
+		let update = LedgerParametersUpdate { night_dust_ratio: Some(1_000), ..Default::default() };
+		assert_err!(
+			mock::Midnight::schedule_parameters_change(
+				RawOrigin::Root.into(),
+				ScheduledChange::LedgerParameters(LedgerParametersUpdate::default()),
+				Activation::AtBlock(2),
+			),
+			Error::<Test>::InvalidParametersUpdate
+		);
+		assert_ok!(mock::Midnight::schedule_parameters_change(
+			RawOrigin::Root.into(),
+			ScheduledChange::LedgerParameters(update),
+			Activation::AtBlock(2),
+		));
+
+		// Applying the due change is accounted for in `on_initialize`
+		mock::System::set_block_number(2);
+		let due_weight = mock::Midnight::on_initialize(2);
+		assert!(PendingParametersChanges::<Test>::get().is_empty());
+
+		mock::Midnight::on_finalize(2);
+		assert_eq!(mock::MockSystemTransactionExecutor::executed_system_txs().len(), 1);
+		assert!(
+			mock::midnight_events().contains(&Event::PendingParametersApplied(0)),
+			"PendingParametersApplied event should be emitted"
+		);
+
+		// No change is due anymore
+		mock::System::set_block_number(3);
+		let idle_weight = mock::Midnight::on_initialize(3);
+		assert_eq!(
+			due_weight,
+			idle_weight.saturating_add(<() as WeightInfo>::apply_parameters_change())
+		);
+	});
+}
+
+#[test]
+fn reports_failed_scheduled_change() {
+	mock::new_test_ext().execute_with(|| {
+		mock::System::set_block_number(1);
+
+		// Without a ledger state, the update can't be applied
+		let update = LedgerParametersUpdate { night_dust_ratio: Some(1_000), ..Default::default() };
+		assert_ok!(mock::Midnight::schedule_parameters_change(
+			RawOrigin::Root.into(),
+			ScheduledChange::LedgerParameters(update),
+			Activation::AtBlock(2),
+		));
+
+		mock::System::set_block_number(2);
+		assert_eq!(mock::Midnight::take_due_parameters_changes(), 1);
+		mock::Midnight::apply_due_parameters_changes();
+		assert!(DueParametersChanges::<Test>::get().is_empty());
+		assert_eq!(
+			mock::midnight_events().last(),
+			Some(&Event::PendingParametersFailed(0, Error::<Test>::NoLedgerState.into()))
+		);
+	});
+}
+
+#[test]
+fn block_reward_schedule_decays_and_caps() {
 	mock::new_test_ext().execute_with(|| {
-		let before_weight = mock::Midnight::configurable_transaction_size_weight();
+		assert_eq!(mock::Midnight::scheduled_block_reward(), 0);
+
+		mock::System::set_block_number(10);
+		let schedule = RewardSchedule {
+			initial_reward: 1000,
+			decay_interval: 5,
+			decay: sp_runtime::Perbill::from_percent(50),
+			cap: Some(2000),
+		};
+		assert_ok!(mock::Midnight::set_block_reward_schedule(
+			RawOrigin::Root.into(),
+			Some(schedule)
+		));
+		assert_eq!(mock::Midnight::scheduled_block_reward(), 1000);
 
-		assert_eq!(before_weight, crate::EXTRA_WEIGHT_TX_SIZE);
+		mock::System::set_block_number(15);
+		assert_eq!(mock::Midnight::scheduled_block_reward(), 500);
+		mock::System::set_block_number(21);
+		assert_eq!(mock::Midnight::scheduled_block_reward(), 250);
 
-		let new_weight = Weight::from_parts(42, 0);
+		TotalRewardsMinted::<Test>::put(1900);
+		assert_eq!(mock::Midnight::scheduled_block_reward(), 100);
+		TotalRewardsMinted::<Test>::put(2000);
+		assert_eq!(mock::Midnight::scheduled_block_reward(), 0);
+	});
+}
 
-		mock::Midnight::set_tx_size_weight(RawOrigin::Root.into(), new_weight).unwrap();
+#[test]
+fn sets_reward_beneficiary() {
+	mock::new_test_ext().execute_with(|| {
+		let beneficiary = [7u8; 32];
 
-		let after_weight = mock::Midnight::configurable_transaction_size_weight();
+		assert_err!(
+			mock::Midnight::set_reward_beneficiary(RuntimeOrigin::none(), 1, Some(beneficiary)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(mock::Midnight::set_reward_beneficiary(
+			RawOrigin::Root.into(),
+			1,
+			Some(beneficiary)
+		));
+		assert_eq!(mock::Midnight::reward_beneficiary(&1), Some(beneficiary));
+		assert_eq!(mock::Midnight::reward_beneficiary(&2), None);
+	});
+}
 
-		assert_eq!(after_weight, new_weight);
+#[test]
+fn mints_block_reward_in_on_finalize() {
+	mock::new_test_ext().execute_with(|| {
+		init_ledger_state(BlockContext::default());
+		let beneficiary = [7u8; 32];
+
+		// Without a reward or a beneficiary, nothing is minted
+		let base_weight = mock::Midnight::on_initialize(1);
+		mock::LedgerBlockReward::set((1000, None));
+		assert_eq!(mock::Midnight::on_initialize(1), base_weight);
+		process_block(1, BlockContext::default());
+		assert!(PendingBlockReward::<Test>::get().is_none());
+		assert_eq!(TotalRewardsMinted::<Test>::get(), 0);
+		assert!(!mock::midnight_events().iter().any(|e| matches!(e, Event::PayoutMinted(_))));
+
+		mock::LedgerBlockReward::set((1000, Some(beneficiary)));
+		let weight = mock::Midnight::on_initialize(2);
+		assert_eq!(weight, base_weight.saturating_add(<() as WeightInfo>::mint_block_reward()));
+		assert_eq!(PendingBlockReward::<Test>::get(), Some((1000, beneficiary)));
+
+		// A reward change after `on_initialize` doesn't change the minted reward
+		mock::LedgerBlockReward::set((0, None));
+		process_block(2, BlockContext::default());
+
+		assert!(PendingBlockReward::<Test>::get().is_none());
+		assert_eq!(TotalRewardsMinted::<Test>::get(), 1000);
+		assert_matches!(
+			mock::midnight_events().last(),
+			Some(Event::PayoutMinted(PayoutDetails { amount: 1000, receiver }))
+				if receiver == &beneficiary.to_vec()
+		);
 	});
 }
 
@@ -220,6 +632,90 @@ fn test_get_ledger_parameters() {
 	});
 }
 
+#[test]
+fn test_get_unshielded_utxos() {
+	mock::new_test_ext().execute_with(|| {
+		init_ledger_state(BlockContext::default());
+
+		let utxos = mock::Midnight::get_unshielded_utxos(&[0u8; 32]);
+		assert_matches!(utxos, Ok(utxos) if utxos.is_empty());
+
+		assert_matches!(
+			mock::Midnight::get_unshielded_utxos(&[1, 2, 3]),
+			Err(LedgerApiError::Deserialization(DeserializationError::UserAddress))
+		);
+	});
+}
+
+#[test]
+fn test_get_zswap_commitment_proof() {
+	mock::new_test_ext().execute_with(|| {
+		init_ledger_state(BlockContext::default());
+
+		assert_matches!(mock::Midnight::get_zswap_commitment_proof(&[0u8; 32]), Ok(None));
+		assert_matches!(
+			mock::Midnight::get_zswap_commitment_proof(&[1, 2, 3]),
+			Err(LedgerApiError::Deserialization(DeserializationError::Commitment))
+		);
+	});
+}
+
+#[test]
+fn contract_state_proofs_verify_against_the_state_key() {
+	mock::new_test_ext().execute_with(|| {
+		let (tx, block_context) =
+			midnight_node_ledger_helpers::extract_info_from_tx_with_context(DEPLOY_TX);
+		init_ledger_state(block_context.into());
+		let addr = hex::decode(CONTRACT_ADDR).expect("Address should be a valid hex code");
+		assert_matches!(mock::Midnight::get_contract_state_proof(&addr), Ok(None));
+
+		assert_ok!(mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), tx));
+
+		let proof = mock::Midnight::get_contract_state_proof(&addr)
+			.unwrap()
+			.expect("The deployed contract should have a proof");
+		assert_eq!(Some(proof.state_key.clone()), StateKey::<Test>::get().map(|key| key.to_vec()));
+		let path: Vec<_> = proof
+			.path
+			.into_iter()
+			.map(|step| midnight_node_ledger_helpers::ArenaPathStep {
+				data: step.data,
+				children: step.children,
+				child: step.child as usize,
+			})
+			.collect();
+		let contract_state = mock::Midnight::get_contract_state(&addr).unwrap();
+		assert!(midnight_node_ledger_helpers::verify_contract_state_proof(
+			&proof.state_key,
+			&addr,
+			&contract_state,
+			&path
+		));
+
+		let mut other_state_key = proof.state_key.clone();
+		*other_state_key.last_mut().unwrap() ^= 1;
+		assert!(!midnight_node_ledger_helpers::verify_contract_state_proof(
+			&other_state_key,
+			&addr,
+			&contract_state,
+			&path
+		));
+	});
+}
+
+#[test]
+fn test_get_dust_generation_info() {
+	mock::new_test_ext().execute_with(|| {
+		init_ledger_state(BlockContext::default());
+
+		assert_matches!(mock::Midnight::get_dust_generation_info(&[0u8; 32]), Ok(None));
+		assert_matches!(
+			mock::Midnight::get_dust_generation_info(&[1, 2, 3]),
+			Err(LedgerApiError::Deserialization(DeserializationError::InitialNonce))
+		);
+	});
+}
+
 #[test]
 #[ignore = "Cannot update ZSWAP_TX because we have no test tokens in genesis"]
 fn test_send_zswap_tx() {
@@ -254,6 +750,88 @@ fn test_get_zswap_state_root() {
 	});
 }
 
+#[cfg(feature = "try-runtime")]
+mod try_runtime {
+	use super::*;
+	use frame_support::traits::Hooks;
+	use midnight_node_ledger::types::LedgerStateSummary;
+	use parity_scale_codec::{Decode, Encode};
+
+	type UpgradeState = (BoundedVec<u8, StateKeyLength>, LedgerStateSummary, bool);
+
+	fn pre_upgrade() -> UpgradeState {
+		let state = <mock::Midnight as Hooks<u64>>::pre_upgrade().unwrap();
+		UpgradeState::decode(&mut &state[..]).unwrap()
+	}
+
+	fn post_upgrade(state: UpgradeState) -> Result<(), sp_runtime::TryRuntimeError> {
+		<mock::Midnight as Hooks<u64>>::post_upgrade(state.encode())
+	}
+
+	#[test]
+	fn upgrade_keeping_the_ledger_state_passes() {
+		mock::new_test_ext().execute_with(|| {
+			init_ledger_state(BlockContext::default());
+
+			let state = pre_upgrade();
+			assert_eq!(Some(state.0.clone()), StateKey::<Test>::get());
+			assert!(state.1.utxo_count > 0);
+			assert!(!state.2);
+
+			assert_ok!(post_upgrade(state));
+		})
+	}
+
+	#[test]
+	fn upgrade_changing_the_ledger_state_fails() {
+		let (tx, block_context) =
+			midnight_node_ledger_helpers::extract_info_from_tx_with_context(DEPLOY_TX);
+
+		mock::new_test_ext().execute_with(|| {
+			init_ledger_state(block_context.into());
+
+			let state = pre_upgrade();
+			assert_ok!(mock::Midnight::send_mn_transaction(RuntimeOrigin::none(), tx));
+
+			assert_err!(post_upgrade(state), "Ledger state key changed");
+		})
+	}
+
+	#[test]
+	fn ledger_switch_compares_the_ledger_state_summaries() {
+		mock::new_test_ext().execute_with(|| {
+			init_ledger_state(BlockContext::default());
+
+			let (_, summary, _) = pre_upgrade();
+			// The translated state gets a new key, but has to hold the same ledger state
+			let other_state_key: BoundedVec<_, _> = vec![0; 8].try_into().unwrap();
+			assert_ok!(post_upgrade((other_state_key.clone(), summary.clone(), true)));
+
+			let utxo_lost =
+				LedgerStateSummary { utxo_count: summary.utxo_count + 1, ..summary.clone() };
+			assert_err!(
+				post_upgrade((other_state_key.clone(), utxo_lost, true)),
+				"UTXO count changed"
+			);
+
+			let contract_lost = LedgerStateSummary {
+				contract_count: summary.contract_count + 1,
+				..summary.clone()
+			};
+			assert_err!(
+				post_upgrade((other_state_key.clone(), contract_lost, true)),
+				"Contract count changed"
+			);
+
+			let zswap_changed = LedgerStateSummary { zswap_state_root: vec![1, 2, 3], ..summary };
+			assert_err!(
+				post_upgrade((other_state_key, zswap_changed, true)),
+				"Zswap state root changed"
+			);
+		})
+	}
+}
+
 #[cfg(feature = "experimental")]
 #[ignore = "TODO UNSHIELDED - fix when Claim Mint is properly handled for Unshielded"]
 #[test]
@@ -0,0 +1,182 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for `pallet_midnight`
+//!
+//! TODO: These are estimates, laid out as the benchmark CLI generates them. Replace this file
+//! with the output of the `pallet_midnight` benchmarks on reference hardware:
+//!
+//! ./target/release/midnight-node benchmark pallet \
+//!     --runtime ./target/release/wbuild/midnight-node-runtime/midnight_node_runtime.wasm \
+//!     --genesis-builder=spec --wasm-execution=compiled --pallet=pallet_midnight \
+//!     --extrinsic=* --steps 50 --repeat 20 --output pallets/midnight/src/weights.rs \
+//!     --template=./res/weights-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+#![allow(clippy::too_many_arguments)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::ParityDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for `pallet_midnight`.
+pub trait WeightInfo {
+	fn send_mn_transaction(l: u32, i: u32, n: u32, o: u32, g: u32, ) -> Weight;
+	fn mint_block_reward() -> Weight;
+	fn apply_parameters_change() -> Weight;
+	fn schedule_parameters_change() -> Weight;
+	fn cancel_parameters_change() -> Weight;
+}
+
+/// Weights for `pallet_midnight` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `Midnight::StateKey` (r:1 w:1)
+	/// Proof: `Midnight::StateKey` (`max_values`: Some(1), `max_size`: Some(130), added: 625, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `System::ParentHash` (r:1 w:0)
+	/// Proof: `System::ParentHash` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// The range of component `l` is `[0, 65536]`.
+	/// The range of component `i` is `[0, 16]`.
+	/// The range of component `n` is `[0, 64]`.
+	/// The range of component `o` is `[0, 64]`.
+	/// The range of component `g` is `[0, 1000000000]`.
+	fn send_mn_transaction(l: u32, i: u32, n: u32, o: u32, g: u32, ) -> Weight {
+		// Estimate: 4 ms of ledger state access and fee checks, 2 ns per byte to deserialize,
+		// 3 ms per intent, 6 ms per shielded input and 4 ms per shielded output to verify their
+		// proofs, and the declared gas as is
+		Weight::from_parts(4_000_000_000, 1615)
+			.saturating_add(Weight::from_parts(2_000, 0).saturating_mul(l.into()))
+			.saturating_add(Weight::from_parts(3_000_000_000, 0).saturating_mul(i.into()))
+			.saturating_add(Weight::from_parts(6_000_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(4_000_000_000, 0).saturating_mul(o.into()))
+			.saturating_add(Weight::from_parts(1_000, 0).saturating_mul(g.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Midnight::StateKey` (r:1 w:1)
+	/// Proof: `Midnight::StateKey` (`max_values`: Some(1), `max_size`: Some(130), added: 625, mode: `MaxEncodedLen`)
+	/// Storage: `Midnight::TotalRewardsMinted` (r:1 w:1)
+	/// Proof: `Midnight::TotalRewardsMinted` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	fn mint_block_reward() -> Weight {
+		// Estimate: a ledger system transaction without proofs, persisting the ledger state
+		Weight::from_parts(2_000_000_000, 1615)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Midnight::StateKey` (r:1 w:1)
+	/// Proof: `Midnight::StateKey` (`max_values`: Some(1), `max_size`: Some(130), added: 625, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `System::ParentHash` (r:1 w:0)
+	/// Proof: `System::ParentHash` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	fn apply_parameters_change() -> Weight {
+		// Estimate: a ledger system transaction without proofs
+		Weight::from_parts(2_000_000_000, 1615)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Midnight::PendingParametersChanges` (r:1 w:1)
+	/// Proof: `Midnight::PendingParametersChanges` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Midnight::NextPendingChangeId` (r:1 w:1)
+	/// Proof: `Midnight::NextPendingChangeId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	fn schedule_parameters_change() -> Weight {
+		// Estimate: validating a ledger parameters update and appending it
+		Weight::from_parts(25_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Midnight::PendingParametersChanges` (r:1 w:1)
+	/// Proof: `Midnight::PendingParametersChanges` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	fn cancel_parameters_change() -> Weight {
+		// Estimate: removing the last of `MaxPendingParametersChanges` changes
+		Weight::from_parts(25_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `Midnight::StateKey` (r:1 w:1)
+	/// Proof: `Midnight::StateKey` (`max_values`: Some(1), `max_size`: Some(130), added: 625, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `System::ParentHash` (r:1 w:0)
+	/// Proof: `System::ParentHash` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	/// The range of component `l` is `[0, 65536]`.
+	/// The range of component `i` is `[0, 16]`.
+	/// The range of component `n` is `[0, 64]`.
+	/// The range of component `o` is `[0, 64]`.
+	/// The range of component `g` is `[0, 1000000000]`.
+	fn send_mn_transaction(l: u32, i: u32, n: u32, o: u32, g: u32, ) -> Weight {
+		// Estimate: 4 ms of ledger state access and fee checks, 2 ns per byte to deserialize,
+		// 3 ms per intent, 6 ms per shielded input and 4 ms per shielded output to verify their
+		// proofs, and the declared gas as is
+		Weight::from_parts(4_000_000_000, 1615)
+			.saturating_add(Weight::from_parts(2_000, 0).saturating_mul(l.into()))
+			.saturating_add(Weight::from_parts(3_000_000_000, 0).saturating_mul(i.into()))
+			.saturating_add(Weight::from_parts(6_000_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(Weight::from_parts(4_000_000_000, 0).saturating_mul(o.into()))
+			.saturating_add(Weight::from_parts(1_000, 0).saturating_mul(g.into()))
+			.saturating_add(ParityDbWeight::get().reads(3_u64))
+			.saturating_add(ParityDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Midnight::StateKey` (r:1 w:1)
+	/// Proof: `Midnight::StateKey` (`max_values`: Some(1), `max_size`: Some(130), added: 625, mode: `MaxEncodedLen`)
+	/// Storage: `Midnight::TotalRewardsMinted` (r:1 w:1)
+	/// Proof: `Midnight::TotalRewardsMinted` (`max_values`: Some(1), `max_size`: Some(16), added: 511, mode: `MaxEncodedLen`)
+	fn mint_block_reward() -> Weight {
+		// Estimate: a ledger system transaction without proofs, persisting the ledger state
+		Weight::from_parts(2_000_000_000, 1615)
+			.saturating_add(ParityDbWeight::get().reads(2_u64))
+			.saturating_add(ParityDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Midnight::StateKey` (r:1 w:1)
+	/// Proof: `Midnight::StateKey` (`max_values`: Some(1), `max_size`: Some(130), added: 625, mode: `MaxEncodedLen`)
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `System::ParentHash` (r:1 w:0)
+	/// Proof: `System::ParentHash` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	fn apply_parameters_change() -> Weight {
+		// Estimate: a ledger system transaction without proofs
+		Weight::from_parts(2_000_000_000, 1615)
+			.saturating_add(ParityDbWeight::get().reads(3_u64))
+			.saturating_add(ParityDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Timestamp::Now` (r:1 w:0)
+	/// Proof: `Timestamp::Now` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `MaxEncodedLen`)
+	/// Storage: `Midnight::PendingParametersChanges` (r:1 w:1)
+	/// Proof: `Midnight::PendingParametersChanges` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Midnight::NextPendingChangeId` (r:1 w:1)
+	/// Proof: `Midnight::NextPendingChangeId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	fn schedule_parameters_change() -> Weight {
+		// Estimate: validating a ledger parameters update and appending it
+		Weight::from_parts(25_000_000, 0)
+			.saturating_add(ParityDbWeight::get().reads(3_u64))
+			.saturating_add(ParityDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Midnight::PendingParametersChanges` (r:1 w:1)
+	/// Proof: `Midnight::PendingParametersChanges` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	fn cancel_parameters_change() -> Weight {
+		// Estimate: removing the last of `MaxPendingParametersChanges` changes
+		Weight::from_parts(25_000_000, 0)
+			.saturating_add(ParityDbWeight::get().reads(1_u64))
+			.saturating_add(ParityDbWeight::get().writes(1_u64))
+	}
+}
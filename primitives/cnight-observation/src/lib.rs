@@ -233,6 +233,8 @@ impl sp_inherents::IsFatalError for InherentError {
 pub struct MidnightObservationTokenMovement {
 	pub utxos: Vec<ObservedUtxo>,
 	pub next_cardano_position: CardanoPosition,
+	/// Stable Cardano blocks past `next_cardano_position`, not observed yet
+	pub cardano_tip_lag: u32,
 }
 
 #[derive(
@@ -265,6 +267,16 @@ pub struct ObservedUtxos {
 	// End position (inclusive)
 	pub end: CardanoPosition,
 	pub utxos: Vec<ObservedUtxo>,
+	// Block number of the Cardano tip the UTXOs were observed up to
+	#[serde(default)]
+	pub tip_block_number: u32,
+}
+
+impl ObservedUtxos {
+	/// Cardano blocks between the end of the observation and the tip, not observed yet
+	pub fn tip_lag(&self) -> u32 {
+		self.tip_block_number.saturating_sub(self.end.block_number)
+	}
 }
 
 #[derive(
@@ -300,6 +312,26 @@ pub struct RedemptionSpendData {
 	pub spending_tx_hash: McTxHash,
 }
 
+/// Registration of a Cardano reward address to a DUST public key, by the UTXO which carries it
+#[derive(
+	Debug,
+	Clone,
+	PartialEq,
+	Eq,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	Serialize,
+	Deserialize,
+)]
+pub struct MappingEntry {
+	pub cardano_reward_address: CardanoRewardAddressBytes,
+	pub dust_public_key: DustPublicKeyBytes,
+	pub utxo_tx_hash: McTxHash,
+	pub utxo_index: u16,
+}
+
 #[derive(
 	Debug, Clone, PartialEq, Encode, Decode, DecodeWithMemTracking, TypeInfo, Serialize, Deserialize,
 )]
@@ -411,6 +443,7 @@ impl PartialOrd for ObservedUtxoHeader {
 }
 
 decl_runtime_apis! {
+	#[api_version(2)]
 	pub trait CNightObservationApi {
 		/// Get the contract address on Cardano which executes Glacier Drop redemptions
 		fn get_redemption_validator_address() -> Vec<u8>;
@@ -427,5 +460,18 @@ decl_runtime_apis! {
 		fn get_cardano_block_window_size() -> u32;
 
 		fn get_utxo_capacity_per_block() -> u32;
+
+		/// Get the DUST public key registered to a Cardano reward address, if the registration
+		/// is valid, i.e. it is the only mapping of the address
+		fn get_registration(cardano_reward_address: CardanoRewardAddressBytes) -> Option<DustPublicKeyBytes>;
+
+		/// Get the mappings of a Cardano reward address, valid or not
+		fn get_mappings(cardano_reward_address: CardanoRewardAddressBytes) -> Vec<MappingEntry>;
+
+		/// Get the mappings to a DUST public key, from any Cardano reward address
+		fn get_mappings_by_dust_public_key(dust_public_key: DustPublicKeyBytes) -> Vec<MappingEntry>;
+
+		/// Get the DUST public key generating DUST from a cNight UTXO
+		fn get_utxo_owner(utxo_tx_hash: McTxHash, utxo_index: u16) -> Option<DustPublicKeyBytes>;
 	}
 }
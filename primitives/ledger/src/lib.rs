@@ -12,7 +12,7 @@
 // limitations under the License.
 
 use prometheus_endpoint::{
-	self as prometheus, HistogramOpts, HistogramVec, PrometheusError, Registry,
+	self as prometheus, Counter, HistogramOpts, HistogramVec, PrometheusError, Registry, U64,
 };
 use std::{
 	path::PathBuf,
@@ -35,6 +35,16 @@ pub struct LedgerMetrics {
 	pub storage_fetch_time: HistogramVec,
 	/// Storage flush time
 	pub storage_flush_time: HistogramVec,
+	/// Storage garbage collection time
+	pub storage_gc_time: HistogramVec,
+	/// Number of ledger states pruned from storage
+	pub pruned_states: Counter<U64>,
+	/// Transaction validations served from the validation cache
+	pub tx_validation_cache_hits: Counter<U64>,
+	/// Transaction validations missing from the validation cache
+	pub tx_validation_cache_misses: Counter<U64>,
+	/// Transaction validations evicted from the validation cache
+	pub tx_validation_cache_evictions: Counter<U64>,
 }
 
 /// Time constants to build a Prometheus Histogram bucket
@@ -149,6 +159,45 @@ impl LedgerMetrics {
 				)?,
 				registry,
 			)?,
+			storage_gc_time: prometheus::register(
+				HistogramVec::new(
+					HistogramOpts::new(
+						"storage_gc_time",
+						"Time spent garbage collecting pruned ledger states",
+					)
+					.buckets(time_buckets.clone()),
+					&["storage"],
+				)?,
+				registry,
+			)?,
+			pruned_states: prometheus::register(
+				Counter::new(
+					"ledger_pruned_states",
+					"Number of ledger states pruned from storage",
+				)?,
+				registry,
+			)?,
+			tx_validation_cache_hits: prometheus::register(
+				Counter::new(
+					"ledger_tx_validation_cache_hits",
+					"Number of transaction validations served from the validation cache",
+				)?,
+				registry,
+			)?,
+			tx_validation_cache_misses: prometheus::register(
+				Counter::new(
+					"ledger_tx_validation_cache_misses",
+					"Number of transaction validations missing from the validation cache",
+				)?,
+				registry,
+			)?,
+			tx_validation_cache_evictions: prometheus::register(
+				Counter::new(
+					"ledger_tx_validation_cache_evictions",
+					"Number of transaction validations evicted from the validation cache",
+				)?,
+				registry,
+			)?,
 		})
 	}
 }
@@ -215,6 +264,36 @@ impl LedgerMetricsExt {
 			m.storage_flush_time.with_label_values(&[label]).observe(time);
 		});
 	}
+
+	pub fn observe_storage_gc_time(&mut self, time: f64, label: &'static str) {
+		self.observe(|m| {
+			m.storage_gc_time.with_label_values(&[label]).observe(time);
+		});
+	}
+
+	pub fn inc_pruned_states(&mut self, count: u64) {
+		self.observe(|m| {
+			m.pruned_states.inc_by(count);
+		});
+	}
+
+	pub fn inc_tx_validation_cache_hits(&mut self) {
+		self.observe(|m| {
+			m.tx_validation_cache_hits.inc();
+		});
+	}
+
+	pub fn inc_tx_validation_cache_misses(&mut self) {
+		self.observe(|m| {
+			m.tx_validation_cache_misses.inc();
+		});
+	}
+
+	pub fn inc_tx_validation_cache_evictions(&mut self, count: u64) {
+		self.observe(|m| {
+			m.tx_validation_cache_evictions.inc_by(count);
+		});
+	}
 }
 
 /// Ledger Storage info to be sent to host functions
@@ -0,0 +1,691 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compact on-disk index of the watched Cardano outputs
+//!
+//! Only the outputs matching the watch set are kept, with their datum and the transaction
+//! spending them. Every block is recorded by hash and number, to resolve main chain references
+//! and to roll back.
+//!
+//! Columns:
+//! - `COL_META`: index tip and watch set
+//! - `COL_BLOCKS`: block hash -> `IndexedBlock`
+//! - `COL_BLOCK_NUMBERS` (ordered): block number -> block hash
+//! - `COL_OUTPUTS`: output reference -> `IndexedOutput`
+//! - `COL_EVENTS` (ordered): (block number, tx index, event kind, output reference) -> ()
+//! - `COL_ADDRESS_OUTPUTS` (ordered): (address hash, block number, tx index, output reference) -> ()
+
+use super::ogmios::{Block, Point};
+use midnight_primitives_cnight_observation::CardanoPosition;
+use parity_scale_codec::{Decode, Encode};
+use sidechain_domain::mainchain_epoch::MainchainEpochConfig;
+use sp_core::hashing::blake2_256;
+use std::{
+	collections::{BTreeSet, HashMap, HashSet},
+	path::Path,
+	sync::{
+		Arc, Mutex, RwLock,
+		atomic::{AtomicU64, Ordering},
+	},
+};
+
+const COL_META: u8 = 0;
+const COL_BLOCKS: u8 = 1;
+const COL_BLOCK_NUMBERS: u8 = 2;
+const COL_OUTPUTS: u8 = 3;
+const COL_EVENTS: u8 = 4;
+const COL_ADDRESS_OUTPUTS: u8 = 5;
+const NUM_COLUMNS: u8 = 6;
+
+const TIP_KEY: &[u8] = b"tip";
+const WATCH_SET_KEY: &[u8] = b"watch_set";
+
+const CREATED: u8 = 0;
+const SPENT: u8 = 1;
+
+/// Ogmios looks for the most recent of these points when reconnecting, the older ones covering
+/// rollbacks that happened while disconnected
+const RECENT_POINTS_DEPTHS: [u32; 8] = [0, 1, 2, 5, 10, 100, 1000, 2160];
+
+type Changes = Vec<(u8, Vec<u8>, Option<Vec<u8>>)>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ChainSyncIndexError {
+	#[error("chain-sync index database error: {0}")]
+	Db(#[from] parity_db::Error),
+	#[error("corrupted chain-sync index entry: {0}")]
+	Decode(#[from] parity_scale_codec::Error),
+	#[error("invalid hex `{0}` in Ogmios block")]
+	InvalidHex(String),
+	#[error("block height {0} out of range")]
+	InvalidHeight(u64),
+	#[error("invalid datum in the chain-sync index: {0}")]
+	InvalidDatum(String),
+}
+
+/// Outputs at one of `addresses`, or holding an asset of one of `policy_ids`
+#[derive(Debug, Clone, Default, PartialEq, Encode, Decode)]
+pub struct WatchSet {
+	pub addresses: BTreeSet<String>,
+	pub policy_ids: BTreeSet<[u8; 28]>,
+}
+
+impl WatchSet {
+	pub fn extend(&mut self, other: &WatchSet) {
+		self.addresses.extend(other.addresses.iter().cloned());
+		self.policy_ids.extend(other.policy_ids.iter().copied());
+	}
+
+	fn matches(&self, address: &str, assets: &[([u8; 28], Vec<u8>, u64)]) -> bool {
+		self.addresses.contains(address)
+			|| assets.iter().any(|(policy_id, ..)| self.policy_ids.contains(policy_id))
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct IndexedBlock {
+	pub hash: [u8; 32],
+	pub number: u32,
+	pub slot: u64,
+	pub timestamp_millis: i64,
+	pub tx_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct IndexedTxPosition {
+	pub block_hash: [u8; 32],
+	pub block_number: u32,
+	pub timestamp_millis: i64,
+	pub tx_index_in_block: u32,
+	pub tx_hash: [u8; 32],
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct IndexedOutput {
+	pub created: IndexedTxPosition,
+	pub index: u16,
+	pub address: String,
+	/// `(policy id, asset name, quantity)`
+	pub assets: Vec<([u8; 28], Vec<u8>, u64)>,
+	/// CBOR of the inline or witnessed datum
+	pub datum: Option<Vec<u8>>,
+	pub spent: Option<IndexedTxPosition>,
+}
+
+impl IndexedOutput {
+	pub fn quantity(&self, policy_id: &[u8; 28], asset_name: &[u8]) -> Option<u64> {
+		self.assets
+			.iter()
+			.find(|(policy, name, _)| policy == policy_id && name == asset_name)
+			.map(|(.., quantity)| *quantity)
+	}
+
+	pub fn holds_policy(&self, policy_id: &[u8; 28]) -> bool {
+		self.assets.iter().any(|(policy, ..)| policy == policy_id)
+	}
+}
+
+struct Inner {
+	db: parity_db::Db,
+	epoch_config: MainchainEpochConfig,
+	watch_set: RwLock<WatchSet>,
+	/// Bumped whenever the index is reset, so the follower can restart from the start point
+	generation: AtomicU64,
+	/// Serializes the writes, which read-modify-write the outputs
+	write_lock: Mutex<()>,
+}
+
+#[derive(Clone)]
+pub struct ChainSyncIndex {
+	inner: Arc<Inner>,
+}
+
+impl ChainSyncIndex {
+	pub fn open(
+		path: &Path,
+		epoch_config: MainchainEpochConfig,
+	) -> Result<Self, ChainSyncIndexError> {
+		let mut options = parity_db::Options::with_columns(path, NUM_COLUMNS);
+		for column in [COL_BLOCK_NUMBERS, COL_EVENTS, COL_ADDRESS_OUTPUTS] {
+			options.columns[column as usize].btree_index = true;
+		}
+		let db = parity_db::Db::open_or_create(&options)?;
+
+		let watch_set = match db.get(COL_META, WATCH_SET_KEY)? {
+			Some(encoded) => WatchSet::decode(&mut &encoded[..])?,
+			None => WatchSet::default(),
+		};
+
+		Ok(Self {
+			inner: Arc::new(Inner {
+				db,
+				epoch_config,
+				watch_set: RwLock::new(watch_set),
+				generation: AtomicU64::new(0),
+				write_lock: Mutex::new(()),
+			}),
+		})
+	}
+
+	pub fn generation(&self) -> u64 {
+		self.inner.generation.load(Ordering::SeqCst)
+	}
+
+	pub fn watch_set(&self) -> WatchSet {
+		self.inner.watch_set.read().expect("watch set lock poisoned").clone()
+	}
+
+	/// Adds the addresses and policy ids of `watch_set` to the watch set. Outputs of the blocks
+	/// already indexed weren't checked against them, so a new watch set resets the index, which
+	/// is rebuilt from the start point. The full watch set should therefore be known before the
+	/// follower starts.
+	pub fn extend_watch_set(&self, new_watch_set: &WatchSet) -> Result<(), ChainSyncIndexError> {
+		let _write = self.inner.write_lock.lock().expect("write lock poisoned");
+		let mut watch_set = self.inner.watch_set.write().expect("watch set lock poisoned");
+
+		let mut extended = watch_set.clone();
+		extended.extend(new_watch_set);
+		if extended == *watch_set {
+			return Ok(());
+		}
+
+		let mut changes = self.truncate_changes(0)?;
+		changes.push((COL_META, WATCH_SET_KEY.to_vec(), Some(extended.encode())));
+		self.inner.db.commit(changes)?;
+
+		*watch_set = extended;
+		self.inner.generation.fetch_add(1, Ordering::SeqCst);
+		log::info!("Chain-sync index watch set extended, re-indexing from the start point");
+		Ok(())
+	}
+
+	/// Points of the index tip and some of its ancestors, most recent first
+	pub fn recent_points(&self) -> Result<Vec<Point>, ChainSyncIndexError> {
+		let Some(tip) = self.tip_number()? else {
+			return Ok(vec![]);
+		};
+
+		let mut points = vec![];
+		for depth in RECENT_POINTS_DEPTHS.into_iter().take_while(|depth| *depth <= tip) {
+			if let Some(block) = self.get_block_by_number(tip - depth)? {
+				points.push(Point::Block { slot: block.slot, id: hex::encode(block.hash) });
+			}
+		}
+		Ok(points)
+	}
+
+	/// Indexes the next block, unless the index was reset since `generation`
+	pub fn roll_forward(&self, generation: u64, block: &Block) -> Result<(), ChainSyncIndexError> {
+		let watch_set = self.watch_set();
+		let _write = self.inner.write_lock.lock().expect("write lock poisoned");
+		if generation != self.generation() {
+			return Ok(());
+		}
+
+		let hash = decode_hash(&block.id)?;
+		let number: u32 = block
+			.height
+			.try_into()
+			.map_err(|_| ChainSyncIndexError::InvalidHeight(block.height))?;
+		let timestamp_millis = self.slot_timestamp_millis(block.slot);
+
+		let mut changes = Changes::new();
+		// Outputs created or spent by this block
+		let mut outputs: HashMap<Vec<u8>, IndexedOutput> = HashMap::new();
+
+		for (tx_index, tx) in block.transactions.iter().enumerate() {
+			let position = IndexedTxPosition {
+				block_hash: hash,
+				block_number: number,
+				timestamp_millis,
+				tx_index_in_block: tx_index as u32,
+				tx_hash: decode_hash(&tx.id)?,
+			};
+			let (inputs, created) = tx.effects();
+
+			for input in inputs {
+				let key = output_key(&decode_hash(&input.transaction.id)?, input.index);
+				if !outputs.contains_key(&key) {
+					let Some(output) = self.get_output(&key)? else {
+						continue;
+					};
+					outputs.insert(key.clone(), output);
+				}
+				if let Some(output) = outputs.get_mut(&key) {
+					output.spent = Some(position.clone());
+					changes.push((COL_EVENTS, event_key(&position, SPENT, &key), Some(vec![])));
+				}
+			}
+
+			for (index, output) in created {
+				let assets = output
+					.assets()
+					.map(|(policy_id, name, quantity)| {
+						Ok((decode_hash::<28>(policy_id)?, decode_hex(name)?, quantity))
+					})
+					.collect::<Result<Vec<_>, ChainSyncIndexError>>()?;
+				if !watch_set.matches(&output.address, &assets) {
+					continue;
+				}
+
+				let key = output_key(&position.tx_hash, index);
+				changes.push((COL_EVENTS, event_key(&position, CREATED, &key), Some(vec![])));
+				changes.push((
+					COL_ADDRESS_OUTPUTS,
+					address_output_key(&output.address, &position, &key),
+					Some(vec![]),
+				));
+				outputs.insert(
+					key,
+					IndexedOutput {
+						created: position.clone(),
+						index,
+						address: output.address.clone(),
+						assets,
+						datum: tx.output_datum(output).map(decode_hex).transpose()?,
+						spent: None,
+					},
+				);
+			}
+		}
+
+		changes.extend(
+			outputs
+				.into_iter()
+				.map(|(key, output)| (COL_OUTPUTS, key, Some(output.encode()))),
+		);
+		let indexed_block = IndexedBlock {
+			hash,
+			number,
+			slot: block.slot,
+			timestamp_millis,
+			tx_count: block.transactions.len() as u32,
+		};
+		changes.push((COL_BLOCKS, hash.to_vec(), Some(indexed_block.encode())));
+		changes.push((COL_BLOCK_NUMBERS, number.to_be_bytes().to_vec(), Some(hash.to_vec())));
+		changes.push((COL_META, TIP_KEY.to_vec(), Some(number.encode())));
+		self.inner.db.commit(changes)?;
+		Ok(())
+	}
+
+	/// Drops the blocks after `point`, unless the index was reset since `generation`. Every block
+	/// is dropped if `point` isn't indexed, which is the case of the start point.
+	pub fn roll_backward(&self, generation: u64, point: &Point) -> Result<(), ChainSyncIndexError> {
+		let _write = self.inner.write_lock.lock().expect("write lock poisoned");
+		if generation != self.generation() {
+			return Ok(());
+		}
+
+		let keep_up_to = match point {
+			Point::Origin(_) => None,
+			Point::Block { id, .. } => self.get_block(&decode_hash(id)?)?.map(|block| block.number),
+		};
+		let changes = self.truncate_changes(keep_up_to.map_or(0, |number| number + 1))?;
+		self.inner.db.commit(changes)?;
+		Ok(())
+	}
+
+	pub fn get_block(&self, hash: &[u8; 32]) -> Result<Option<IndexedBlock>, ChainSyncIndexError> {
+		self.inner
+			.db
+			.get(COL_BLOCKS, hash)?
+			.map(|encoded| IndexedBlock::decode(&mut &encoded[..]))
+			.transpose()
+			.map_err(Into::into)
+	}
+
+	/// Watched outputs created in `[start, end)`, in creation order
+	pub fn created_outputs(
+		&self,
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+	) -> Result<Vec<IndexedOutput>, ChainSyncIndexError> {
+		self.outputs_by_event(CREATED, start, end)
+	}
+
+	/// Watched outputs spent in `[start, end)`, in spending order
+	pub fn spent_outputs(
+		&self,
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+	) -> Result<Vec<IndexedOutput>, ChainSyncIndexError> {
+		self.outputs_by_event(SPENT, start, end)
+	}
+
+	/// Most recent output at `address` up to `block_number` matching `predicate`
+	pub fn latest_output_at(
+		&self,
+		address: &str,
+		block_number: u32,
+		predicate: impl Fn(&IndexedOutput) -> bool,
+	) -> Result<Option<IndexedOutput>, ChainSyncIndexError> {
+		let address_hash = blake2_256(address.as_bytes());
+		let mut upper_bound = address_hash.to_vec();
+		upper_bound.extend(block_number.saturating_add(1).to_be_bytes());
+
+		let mut iter = self.inner.db.iter(COL_ADDRESS_OUTPUTS)?;
+		iter.seek(&upper_bound)?;
+		while let Some((key, _)) = iter.prev()? {
+			if !key.starts_with(&address_hash) {
+				break;
+			}
+			let output_key = &key[40..];
+			let Some(output) = self.get_output(output_key)? else {
+				continue;
+			};
+			if output.created.block_number <= block_number && predicate(&output) {
+				return Ok(Some(output));
+			}
+		}
+		Ok(None)
+	}
+
+	fn outputs_by_event(
+		&self,
+		kind: u8,
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+	) -> Result<Vec<IndexedOutput>, ChainSyncIndexError> {
+		let end_key = position_key(end.block_number, end.tx_index_in_block);
+		let mut iter = self.inner.db.iter(COL_EVENTS)?;
+		iter.seek(&position_key(start.block_number, start.tx_index_in_block))?;
+
+		let mut outputs = vec![];
+		while let Some((key, _)) = iter.next()? {
+			if key[..8] >= end_key[..] {
+				break;
+			}
+			if key[8] != kind {
+				continue;
+			}
+			if let Some(output) = self.get_output(&key[9..])? {
+				outputs.push(output);
+			}
+		}
+		Ok(outputs)
+	}
+
+	/// Changes dropping every block from `from_number` on, with their outputs and spends
+	fn truncate_changes(&self, from_number: u32) -> Result<Changes, ChainSyncIndexError> {
+		let mut changes = Changes::new();
+		if self.tip_number()?.is_none_or(|tip| tip < from_number) {
+			return Ok(changes);
+		}
+
+		let mut removed = HashSet::new();
+		let mut unspent = vec![];
+		let mut iter = self.inner.db.iter(COL_EVENTS)?;
+		iter.seek(&from_number.to_be_bytes())?;
+		while let Some((key, _)) = iter.next()? {
+			let output_key = key[9..].to_vec();
+			if key[8] == CREATED {
+				if let Some(output) = self.get_output(&output_key)? {
+					changes.push((
+						COL_ADDRESS_OUTPUTS,
+						address_output_key(&output.address, &output.created, &output_key),
+						None,
+					));
+				}
+				changes.push((COL_OUTPUTS, output_key.clone(), None));
+				removed.insert(output_key);
+			} else {
+				unspent.push(output_key);
+			}
+			changes.push((COL_EVENTS, key, None));
+		}
+
+		for output_key in unspent.into_iter().filter(|key| !removed.contains(key)) {
+			if let Some(mut output) = self.get_output(&output_key)? {
+				output.spent = None;
+				changes.push((COL_OUTPUTS, output_key, Some(output.encode())));
+			}
+		}
+
+		let mut iter = self.inner.db.iter(COL_BLOCK_NUMBERS)?;
+		iter.seek(&from_number.to_be_bytes())?;
+		while let Some((key, hash)) = iter.next()? {
+			changes.push((COL_BLOCKS, hash, None));
+			changes.push((COL_BLOCK_NUMBERS, key, None));
+		}
+
+		let tip = from_number.checked_sub(1).map(|number| number.encode());
+		changes.push((COL_META, TIP_KEY.to_vec(), tip));
+		Ok(changes)
+	}
+
+	fn tip_number(&self) -> Result<Option<u32>, ChainSyncIndexError> {
+		self.inner
+			.db
+			.get(COL_META, TIP_KEY)?
+			.map(|encoded| u32::decode(&mut &encoded[..]))
+			.transpose()
+			.map_err(Into::into)
+	}
+
+	pub fn get_block_by_number(
+		&self,
+		number: u32,
+	) -> Result<Option<IndexedBlock>, ChainSyncIndexError> {
+		match self.inner.db.get(COL_BLOCK_NUMBERS, &number.to_be_bytes())? {
+			Some(hash) => {
+				let hash: [u8; 32] = hash
+					.try_into()
+					.map_err(|_| parity_scale_codec::Error::from("invalid block hash length"))?;
+				self.get_block(&hash)
+			},
+			None => Ok(None),
+		}
+	}
+
+	fn get_output(&self, key: &[u8]) -> Result<Option<IndexedOutput>, ChainSyncIndexError> {
+		self.inner
+			.db
+			.get(COL_OUTPUTS, key)?
+			.map(|encoded| IndexedOutput::decode(&mut &encoded[..]))
+			.transpose()
+			.map_err(Into::into)
+	}
+
+	fn slot_timestamp_millis(&self, slot: u64) -> i64 {
+		let config = &self.inner.epoch_config;
+		let elapsed_slots = slot.saturating_sub(config.first_slot_number);
+		(config.first_epoch_timestamp_millis.unix_millis()
+			+ elapsed_slots * config.slot_duration_millis.millis()) as i64
+	}
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, ChainSyncIndexError> {
+	hex::decode(value).map_err(|_| ChainSyncIndexError::InvalidHex(value.to_string()))
+}
+
+fn decode_hash<const N: usize>(value: &str) -> Result<[u8; N], ChainSyncIndexError> {
+	decode_hex(value)?
+		.try_into()
+		.map_err(|_| ChainSyncIndexError::InvalidHex(value.to_string()))
+}
+
+fn output_key(tx_hash: &[u8; 32], index: u16) -> Vec<u8> {
+	[&tx_hash[..], &index.to_be_bytes()].concat()
+}
+
+fn position_key(block_number: u32, tx_index_in_block: u32) -> Vec<u8> {
+	[block_number.to_be_bytes(), tx_index_in_block.to_be_bytes()].concat()
+}
+
+fn event_key(position: &IndexedTxPosition, kind: u8, output_key: &[u8]) -> Vec<u8> {
+	let mut key = position_key(position.block_number, position.tx_index_in_block);
+	key.push(kind);
+	key.extend_from_slice(output_key);
+	key
+}
+
+fn address_output_key(address: &str, position: &IndexedTxPosition, output_key: &[u8]) -> Vec<u8> {
+	let mut key = blake2_256(address.as_bytes()).to_vec();
+	key.extend(position_key(position.block_number, position.tx_index_in_block));
+	key.extend_from_slice(output_key);
+	key
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chain_sync::ogmios::NextBlock;
+	use sidechain_domain::McBlockHash;
+	use sp_core::offchain::{Duration, Timestamp};
+
+	const ADDRESS: &str = "addr_test1watched";
+	const POLICY_ID: [u8; 28] = [7; 28];
+
+	fn open_index() -> ChainSyncIndex {
+		let path = tempfile::tempdir().unwrap().keep();
+		let epoch_config = MainchainEpochConfig {
+			first_epoch_timestamp_millis: Timestamp::from_unix_millis(1_000_000),
+			epoch_duration_millis: Duration::from_millis(432_000_000),
+			first_epoch_number: 0,
+			first_slot_number: 0,
+			slot_duration_millis: Duration::from_millis(1000),
+		};
+		ChainSyncIndex::open(&path, epoch_config).unwrap()
+	}
+
+	fn watching(addresses: &[&str], policy_ids: &[[u8; 28]]) -> WatchSet {
+		WatchSet {
+			addresses: addresses.iter().map(|address| address.to_string()).collect(),
+			policy_ids: policy_ids.iter().copied().collect(),
+		}
+	}
+
+	fn hash(byte: u8) -> String {
+		hex::encode([byte; 32])
+	}
+
+	/// Block `height` with one transaction spending `inputs` and creating `outputs`
+	fn block(height: u64, inputs: &[(u8, u16)], outputs: &[(&str, bool)]) -> Block {
+		let inputs: Vec<_> = inputs
+			.iter()
+			.map(|(tx, index)| {
+				format!(r#"{{"transaction":{{"id":"{}"}},"index":{index}}}"#, hash(*tx))
+			})
+			.collect();
+		let outputs: Vec<_> = outputs
+			.iter()
+			.map(|(address, holds_asset)| {
+				let assets = if *holds_asset {
+					format!(r#","{}":{{"6e69676874":5}}"#, hex::encode(POLICY_ID))
+				} else {
+					String::new()
+				};
+				format!(
+					r#"{{"address":"{address}","value":{{"ada":{{"lovelace":1}}{assets}}},"datum":"d87980"}}"#
+				)
+			})
+			.collect();
+		let json = format!(
+			r#"{{"direction":"forward","block":{{"id":"{}","height":{height},"slot":{},"transactions":[{{"id":"{}","inputs":[{}],"outputs":[{}]}}]}}}}"#,
+			hash(height as u8),
+			height * 20,
+			hash(100 + height as u8),
+			inputs.join(","),
+			outputs.join(","),
+		);
+		match serde_json::from_str(&json).unwrap() {
+			NextBlock::Forward { block } => block,
+			NextBlock::Backward { .. } => unreachable!(),
+		}
+	}
+
+	fn position(block_number: u32) -> CardanoPosition {
+		CardanoPosition {
+			block_hash: McBlockHash([0; 32]),
+			block_number,
+			block_timestamp: Default::default(),
+			tx_index_in_block: 0,
+		}
+	}
+
+	#[test]
+	fn only_watched_outputs_and_their_spends_are_indexed() {
+		let index = open_index();
+		index.extend_watch_set(&watching(&[], &[POLICY_ID])).unwrap();
+		let generation = index.generation();
+
+		index
+			.roll_forward(
+				generation,
+				&block(1, &[], &[("addr_test1other", true), ("addr_test1other", false)]),
+			)
+			.unwrap();
+		// Spends the watched output, and an unknown one
+		index.roll_forward(generation, &block(2, &[(101, 0), (50, 0)], &[])).unwrap();
+
+		let created = index.created_outputs(&position(0), &position(3)).unwrap();
+		assert_eq!(created.len(), 1);
+		assert_eq!(created[0].quantity(&POLICY_ID, b"night"), Some(5));
+		assert_eq!(created[0].datum, Some(vec![0xd8, 0x79, 0x80]));
+
+		let spent = index.spent_outputs(&position(0), &position(3)).unwrap();
+		assert_eq!(spent.len(), 1);
+		assert_eq!(spent[0].spent.as_ref().unwrap().block_number, 2);
+		assert!(index.spent_outputs(&position(0), &position(2)).unwrap().is_empty());
+
+		let block = index.get_block(&[1; 32]).unwrap().unwrap();
+		assert_eq!((block.number, block.timestamp_millis, block.tx_count), (1, 1_020_000, 1));
+	}
+
+	#[test]
+	fn rollbacks_drop_blocks_outputs_and_spends() {
+		let index = open_index();
+		index.extend_watch_set(&watching(&[ADDRESS], &[])).unwrap();
+		let generation = index.generation();
+
+		index.roll_forward(generation, &block(1, &[], &[(ADDRESS, false)])).unwrap();
+		index
+			.roll_forward(generation, &block(2, &[(101, 0)], &[(ADDRESS, false)]))
+			.unwrap();
+		assert!(index.latest_output_at(ADDRESS, 2, |o| o.spent.is_none()).unwrap().is_some());
+
+		index
+			.roll_backward(generation, &Point::Block { slot: 20, id: hash(1) })
+			.unwrap();
+		assert!(index.get_block(&[2; 32]).unwrap().is_none());
+		assert!(index.spent_outputs(&position(0), &position(10)).unwrap().is_empty());
+		let latest = index.latest_output_at(ADDRESS, 10, |o| o.spent.is_none()).unwrap().unwrap();
+		assert_eq!(latest.created.block_number, 1);
+		assert_eq!(index.recent_points().unwrap(), vec![Point::Block { slot: 20, id: hash(1) }]);
+
+		// The start point isn't indexed, so rolling back to it empties the index
+		index.roll_backward(generation, &Point::Block { slot: 0, id: hash(0) }).unwrap();
+		assert!(index.recent_points().unwrap().is_empty());
+		assert!(index.created_outputs(&position(0), &position(10)).unwrap().is_empty());
+	}
+
+	#[test]
+	fn extending_the_watch_set_resets_the_index() {
+		let index = open_index();
+		index.extend_watch_set(&watching(&[ADDRESS], &[])).unwrap();
+		let generation = index.generation();
+		index.roll_forward(generation, &block(1, &[], &[(ADDRESS, false)])).unwrap();
+
+		// Already watched
+		index.extend_watch_set(&watching(&[ADDRESS], &[])).unwrap();
+		assert_eq!(index.generation(), generation);
+
+		index.extend_watch_set(&watching(&[], &[POLICY_ID])).unwrap();
+		assert_ne!(index.generation(), generation);
+		assert!(index.recent_points().unwrap().is_empty());
+
+		// Blocks of the previous generation are ignored
+		index.roll_forward(generation, &block(2, &[], &[(ADDRESS, false)])).unwrap();
+		assert!(index.get_block(&[2; 32]).unwrap().is_none());
+	}
+}
@@ -0,0 +1,92 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Main chain follower backed by Ogmios chain-sync instead of db-sync.
+//!
+//! `ChainSyncFollower` follows the Cardano chain through Ogmios and keeps a `ChainSyncIndex` of
+//! the outputs watched by the observation data sources. The index answers the same
+//! `ObservationQueries` as db-sync, so the data sources of the `data_source` module work on top
+//! of either.
+
+mod index;
+pub mod ogmios;
+mod queries;
+
+pub use index::{
+	ChainSyncIndex, ChainSyncIndexError, IndexedBlock, IndexedOutput, IndexedTxPosition, WatchSet,
+};
+pub use ogmios::Point;
+
+use jsonrpsee::core::ClientError;
+use ogmios::{NextBlock, OgmiosChainSync};
+use std::time::Duration;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+#[derive(thiserror::Error, Debug)]
+pub enum ChainSyncError {
+	#[error("Ogmios request failed: {0}")]
+	Ogmios(#[from] ClientError),
+	#[error(transparent)]
+	Index(#[from] ChainSyncIndexError),
+}
+
+#[derive(Clone)]
+pub struct ChainSyncFollower {
+	ogmios_url: String,
+	/// Point the index is built from. Blocks before it aren't indexed.
+	start_point: Point,
+	index: ChainSyncIndex,
+}
+
+impl ChainSyncFollower {
+	pub fn new(ogmios_url: String, start_point: Point, index: ChainSyncIndex) -> Self {
+		Self { ogmios_url, start_point, index }
+	}
+
+	/// Follows the chain forever, reconnecting to Ogmios on errors
+	pub async fn run(self) {
+		loop {
+			if let Err(e) = self.follow().await {
+				log::warn!(
+					"Cardano chain-sync interrupted: {e}. Reconnecting in {}s",
+					RECONNECT_DELAY.as_secs()
+				);
+				tokio::time::sleep(RECONNECT_DELAY).await;
+			}
+		}
+	}
+
+	/// Follows the chain from the index tip, until the index is reset
+	async fn follow(&self) -> Result<(), ChainSyncError> {
+		let generation = self.index.generation();
+		let client = OgmiosChainSync::connect(&self.ogmios_url).await?;
+
+		let mut points = self.index.recent_points()?;
+		points.push(self.start_point.clone());
+		let intersection = client.find_intersection(points).await?;
+		log::info!("Following Cardano with Ogmios from {intersection}");
+		self.index.roll_backward(generation, &intersection)?;
+
+		while generation == self.index.generation() {
+			match client.next_block().await? {
+				NextBlock::Forward { block } => self.index.roll_forward(generation, &block)?,
+				NextBlock::Backward { point } => {
+					log::debug!("Cardano rollback to {point}");
+					self.index.roll_backward(generation, &point)?
+				},
+			}
+		}
+		Ok(())
+	}
+}
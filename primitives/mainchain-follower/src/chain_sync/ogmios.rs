@@ -0,0 +1,271 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ogmios chain-sync client
+//!
+//! Only the parts of the Ogmios JSON-RPC chain-sync protocol used by the follower are covered:
+//! https://ogmios.dev/mini-protocols/local-chain-sync/
+
+use jsonrpsee::{
+	core::{ClientError, client::ClientT, params::ObjectParams},
+	rpc_params,
+	ws_client::{WsClient, WsClientBuilder},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, str::FromStr, time::Duration};
+
+/// `nextBlock` only answers once a new block is available, which can take minutes at the tip
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Origin {
+	Origin,
+}
+
+/// Point of the Cardano chain, `origin` or the slot and hash of a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Point {
+	Origin(Origin),
+	Block { slot: u64, id: String },
+}
+
+impl Point {
+	pub fn origin() -> Self {
+		Point::Origin(Origin::Origin)
+	}
+}
+
+impl fmt::Display for Point {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Point::Origin(_) => write!(f, "origin"),
+			Point::Block { slot, id } => write!(f, "{slot}.{id}"),
+		}
+	}
+}
+
+/// Parses `origin` or `<slot>.<block hash>`, as printed by `Display`
+impl FromStr for Point {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s == "origin" {
+			return Ok(Point::origin());
+		}
+		let (slot, id) = s
+			.split_once('.')
+			.ok_or_else(|| format!("expected `origin` or `<slot>.<block hash>`, got `{s}`"))?;
+		let slot = slot.parse().map_err(|e| format!("invalid slot `{slot}`: {e}"))?;
+		let hash = hex::decode(id).map_err(|e| format!("invalid block hash `{id}`: {e}"))?;
+		if hash.len() != 32 {
+			return Err(format!("invalid block hash length: {}", hash.len()));
+		}
+		Ok(Point::Block { slot, id: id.to_lowercase() })
+	}
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Intersection {
+	pub intersection: Point,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "direction", rename_all = "lowercase")]
+pub enum NextBlock {
+	Forward { block: Block },
+	Backward { point: Point },
+}
+
+/// Byron epoch boundary blocks have no slot nor transactions
+#[derive(Debug, Clone, Deserialize)]
+pub struct Block {
+	pub id: String,
+	pub height: u64,
+	#[serde(default)]
+	pub slot: u64,
+	#[serde(default)]
+	pub transactions: Vec<Transaction>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Spends {
+	#[default]
+	Inputs,
+	/// The scripts of the transaction failed, only its collaterals were consumed
+	Collaterals,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+	pub id: String,
+	#[serde(default)]
+	pub spends: Spends,
+	#[serde(default)]
+	pub inputs: Vec<OutputReference>,
+	#[serde(default)]
+	pub collaterals: Vec<OutputReference>,
+	#[serde(default)]
+	pub outputs: Vec<Output>,
+	pub collateral_return: Option<Output>,
+	/// Witnessed datums, CBOR by datum hash
+	#[serde(default)]
+	pub datums: HashMap<String, String>,
+}
+
+impl Transaction {
+	/// Consumed inputs and produced outputs, with their index
+	pub fn effects(&self) -> (&[OutputReference], Vec<(u16, &Output)>) {
+		match self.spends {
+			Spends::Inputs => (
+				&self.inputs,
+				self.outputs.iter().enumerate().map(|(i, o)| (i as u16, o)).collect(),
+			),
+			// The collateral return output comes after the regular outputs
+			Spends::Collaterals => (
+				&self.collaterals,
+				self.collateral_return.iter().map(|o| (self.outputs.len() as u16, o)).collect(),
+			),
+		}
+	}
+
+	/// CBOR of the output datum, whether inline or witnessed by this transaction
+	pub fn output_datum<'a>(&'a self, output: &'a Output) -> Option<&'a str> {
+		output.datum.as_deref().or_else(|| {
+			output
+				.datum_hash
+				.as_ref()
+				.and_then(|hash| self.datums.get(hash))
+				.map(String::as_str)
+		})
+	}
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransactionId {
+	pub id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OutputReference {
+	pub transaction: TransactionId,
+	pub index: u16,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Output {
+	pub address: String,
+	/// Quantities by asset name, by policy id, all hex encoded. Ada is under `ada.lovelace`.
+	pub value: HashMap<String, HashMap<String, u64>>,
+	pub datum_hash: Option<String>,
+	pub datum: Option<String>,
+}
+
+impl Output {
+	/// Native assets held by the output, as `(policy id, asset name, quantity)`
+	pub fn assets(&self) -> impl Iterator<Item = (&str, &str, u64)> {
+		self.value.iter().filter(|(policy_id, _)| policy_id.as_str() != "ada").flat_map(
+			|(policy_id, assets)| {
+				assets
+					.iter()
+					.map(move |(name, quantity)| (policy_id.as_str(), name.as_str(), *quantity))
+			},
+		)
+	}
+}
+
+pub struct OgmiosChainSync {
+	client: WsClient,
+}
+
+impl OgmiosChainSync {
+	pub async fn connect(url: &str) -> Result<Self, ClientError> {
+		let client = WsClientBuilder::default().request_timeout(REQUEST_TIMEOUT).build(url).await?;
+		Ok(Self { client })
+	}
+
+	/// Finds the most recent of `points` on the chain, to follow the chain from
+	pub async fn find_intersection(&self, points: Vec<Point>) -> Result<Point, ClientError> {
+		let mut params = ObjectParams::new();
+		params.insert("points", points)?;
+		let response: Intersection = self.client.request("findIntersection", params).await?;
+		Ok(response.intersection)
+	}
+
+	pub async fn next_block(&self) -> Result<NextBlock, ClientError> {
+		self.client.request("nextBlock", rpc_params![]).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn points_are_parsed_and_printed_alike() {
+		let hash = "ab".repeat(32);
+		let point: Point = format!("42.{hash}").parse().unwrap();
+		assert_eq!(point, Point::Block { slot: 42, id: hash.clone() });
+		assert_eq!(point.to_string(), format!("42.{hash}"));
+		assert_eq!("origin".parse::<Point>().unwrap(), Point::origin());
+		assert!("42.abcd".parse::<Point>().is_err());
+		assert!(hash.parse::<Point>().is_err());
+	}
+
+	#[test]
+	fn next_block_responses_are_decoded() {
+		let forward = r#"{
+			"direction": "forward",
+			"block": {
+				"type": "praos",
+				"era": "conway",
+				"id": "aa",
+				"ancestor": "bb",
+				"height": 7,
+				"slot": 1234,
+				"transactions": [{
+					"id": "cc",
+					"spends": "inputs",
+					"inputs": [{ "transaction": { "id": "dd" }, "index": 1 }],
+					"outputs": [{
+						"address": "addr_test1",
+						"value": { "ada": { "lovelace": 2000000 }, "ee": { "ff": 10 } },
+						"datumHash": "01"
+					}],
+					"datums": { "01": "d87980" }
+				}]
+			},
+			"tip": { "slot": 1234, "id": "aa", "height": 7 }
+		}"#;
+		let NextBlock::Forward { block } = serde_json::from_str(forward).unwrap() else {
+			panic!("expected a forward block");
+		};
+		assert_eq!((block.height, block.slot), (7, 1234));
+		let tx = &block.transactions[0];
+		let (inputs, outputs) = tx.effects();
+		assert_eq!(inputs[0].transaction.id, "dd");
+		assert_eq!(outputs.len(), 1);
+		assert_eq!(outputs[0].1.assets().collect::<Vec<_>>(), vec![("ee", "ff", 10)]);
+		assert_eq!(tx.output_datum(outputs[0].1), Some("d87980"));
+
+		let backward = r#"{ "direction": "backward", "point": "origin", "tip": "origin" }"#;
+		assert!(matches!(
+			serde_json::from_str(backward).unwrap(),
+			NextBlock::Backward { point: Point::Origin(_) }
+		));
+	}
+}
@@ -0,0 +1,289 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `ObservationQueries` answered from the chain-sync index, returning the same rows as db-sync
+
+use super::index::{
+	ChainSyncIndex, ChainSyncIndexError, IndexedBlock, IndexedOutput, IndexedTxPosition, WatchSet,
+};
+use crate::db::{
+	AssetCreateRow, AssetSpendRow, DbBlockHash, DbDatum, DeregistrationRow, GovernanceBodyUtxoRow,
+	ObservationQueries, ObservationQueryError, RedemptionCreateRow, RedemptionSpendRow,
+	RegistrationRow,
+};
+use cardano_serialization_lib::{PlutusData, ScriptHash};
+use db_sync_sqlx::{BlockNumber, TxHash, TxIndex, TxIndexInBlock};
+use midnight_primitives_cnight_observation::{CardanoPosition, TimestampUnixMillis};
+use sidechain_domain::{McBlockHash, PolicyId};
+use sqlx::types::chrono::{DateTime, NaiveDateTime};
+
+fn datum(output: &IndexedOutput) -> Result<Option<DbDatum>, ChainSyncIndexError> {
+	output
+		.datum
+		.as_ref()
+		.map(|cbor| {
+			PlutusData::from_bytes(cbor.clone())
+				.map(DbDatum)
+				.map_err(|e| ChainSyncIndexError::InvalidDatum(e.to_string()))
+		})
+		.transpose()
+}
+
+fn timestamp(position: &IndexedTxPosition) -> NaiveDateTime {
+	DateTime::from_timestamp_millis(position.timestamp_millis)
+		.unwrap_or_default()
+		.naive_utc()
+}
+
+fn quantity(output: &IndexedOutput, policy_id: &[u8; 28], asset_name: &[u8]) -> Option<i64> {
+	output.quantity(policy_id, asset_name).map(|quantity| quantity as i64)
+}
+
+fn block_position(block: IndexedBlock) -> CardanoPosition {
+	CardanoPosition {
+		block_hash: McBlockHash(block.hash),
+		block_number: block.number,
+		block_timestamp: TimestampUnixMillis(block.timestamp_millis),
+		tx_index_in_block: block.tx_count,
+	}
+}
+
+fn page<T>(rows: impl Iterator<Item = T>, limit: usize, offset: usize) -> Vec<T> {
+	rows.skip(offset).take(limit).collect()
+}
+
+#[async_trait::async_trait]
+impl ObservationQueries for ChainSyncIndex {
+	async fn watch(&self, watch_set: &WatchSet) -> Result<(), ObservationQueryError> {
+		Ok(self.extend_watch_set(watch_set)?)
+	}
+
+	async fn get_block_position(
+		&self,
+		hash: McBlockHash,
+	) -> Result<Option<CardanoPosition>, ObservationQueryError> {
+		Ok(self.get_block(&hash.0)?.map(block_position))
+	}
+
+	async fn get_block_position_by_number(
+		&self,
+		number: u32,
+	) -> Result<Option<CardanoPosition>, ObservationQueryError> {
+		Ok(self.get_block_by_number(number)?.map(block_position))
+	}
+
+	async fn get_registrations(
+		&self,
+		smart_contract_address: &str,
+		mapping_validator_policy_id: &ScriptHash,
+		auth_token_asset_name: &str,
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<RegistrationRow>, ObservationQueryError> {
+		let policy_id: [u8; 28] = mapping_validator_policy_id
+			.to_bytes()
+			.try_into()
+			.expect("script hashes are 28 bytes long");
+		let mut rows = vec![];
+		for output in self.created_outputs(start, end)? {
+			if output.address != smart_contract_address
+				|| quantity(&output, &policy_id, auth_token_asset_name.as_bytes()) != Some(1)
+			{
+				continue;
+			}
+			let Some(full_datum) = datum(&output)? else { continue };
+			let created = &output.created;
+			rows.push(RegistrationRow {
+				full_datum,
+				block_number: BlockNumber(created.block_number),
+				block_hash: DbBlockHash(created.block_hash),
+				block_timestamp: timestamp(created),
+				tx_index_in_block: TxIndexInBlock(created.tx_index_in_block),
+				tx_hash: TxHash(created.tx_hash),
+				utxo_index: TxIndex(output.index),
+			});
+		}
+		Ok(page(rows.into_iter(), limit, offset))
+	}
+
+	async fn get_deregistrations(
+		&self,
+		smart_contract_address: &str,
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<DeregistrationRow>, ObservationQueryError> {
+		let mut rows = vec![];
+		for output in self.spent_outputs(start, end)? {
+			let Some(spent) = &output.spent else { continue };
+			if output.address != smart_contract_address {
+				continue;
+			}
+			let Some(full_datum) = datum(&output)? else { continue };
+			rows.push(DeregistrationRow {
+				full_datum,
+				block_number: BlockNumber(spent.block_number),
+				block_hash: DbBlockHash(spent.block_hash),
+				block_timestamp: timestamp(spent),
+				tx_index_in_block: TxIndexInBlock(spent.tx_index_in_block),
+				tx_hash: TxHash(spent.tx_hash),
+				utxo_tx_hash: TxHash(output.created.tx_hash),
+				utxo_index: TxIndex(output.index),
+			});
+		}
+		Ok(page(rows.into_iter(), limit, offset))
+	}
+
+	async fn get_asset_creates(
+		&self,
+		policy_id: [u8; 28],
+		asset_name: &[u8],
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<AssetCreateRow>, ObservationQueryError> {
+		let rows = self.created_outputs(start, end)?.into_iter().filter_map(|output| {
+			let quantity = quantity(&output, &policy_id, asset_name)?;
+			let created = &output.created;
+			Some(AssetCreateRow {
+				block_number: BlockNumber(created.block_number),
+				block_hash: DbBlockHash(created.block_hash),
+				block_timestamp: timestamp(created),
+				tx_index_in_block: TxIndexInBlock(created.tx_index_in_block),
+				quantity,
+				holder_address: output.address.clone(),
+				tx_hash: TxHash(created.tx_hash),
+				utxo_index: TxIndex(output.index),
+			})
+		});
+		Ok(page(rows, limit, offset))
+	}
+
+	async fn get_asset_spends(
+		&self,
+		policy_id: [u8; 28],
+		asset_name: &[u8],
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<AssetSpendRow>, ObservationQueryError> {
+		let rows = self.spent_outputs(start, end)?.into_iter().filter_map(|output| {
+			let quantity = quantity(&output, &policy_id, asset_name)?;
+			let spent = output.spent.as_ref()?;
+			Some(AssetSpendRow {
+				block_number: BlockNumber(spent.block_number),
+				block_hash: DbBlockHash(spent.block_hash),
+				block_timestamp: timestamp(spent),
+				tx_index_in_block: TxIndexInBlock(spent.tx_index_in_block),
+				quantity,
+				holder_address: output.address.clone(),
+				utxo_tx_hash: TxHash(output.created.tx_hash),
+				utxo_index: TxIndex(output.index),
+				spending_tx_hash: TxHash(spent.tx_hash),
+			})
+		});
+		Ok(page(rows, limit, offset))
+	}
+
+	async fn get_redemption_creates(
+		&self,
+		smart_contract_address: &str,
+		policy_id: [u8; 28],
+		asset_name: &[u8],
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<RedemptionCreateRow>, ObservationQueryError> {
+		let mut rows = vec![];
+		for output in self.created_outputs(start, end)? {
+			if output.address != smart_contract_address {
+				continue;
+			}
+			let Some(quantity) = quantity(&output, &policy_id, asset_name) else { continue };
+			let Some(full_datum) = datum(&output)? else { continue };
+			let created = &output.created;
+			rows.push(RedemptionCreateRow {
+				full_datum,
+				block_number: BlockNumber(created.block_number),
+				block_hash: DbBlockHash(created.block_hash),
+				block_timestamp: timestamp(created),
+				tx_index_in_block: TxIndexInBlock(created.tx_index_in_block),
+				tx_hash: TxHash(created.tx_hash),
+				utxo_index: TxIndex(output.index),
+				quantity,
+			});
+		}
+		Ok(page(rows.into_iter(), limit, offset))
+	}
+
+	async fn get_redemption_spends(
+		&self,
+		smart_contract_address: &str,
+		policy_id: [u8; 28],
+		asset_name: &[u8],
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<RedemptionSpendRow>, ObservationQueryError> {
+		let mut rows = vec![];
+		for output in self.spent_outputs(start, end)? {
+			let Some(spent) = &output.spent else { continue };
+			if output.address != smart_contract_address {
+				continue;
+			}
+			let Some(quantity) = quantity(&output, &policy_id, asset_name) else { continue };
+			let Some(full_datum) = datum(&output)? else { continue };
+			rows.push(RedemptionSpendRow {
+				full_datum,
+				block_number: BlockNumber(spent.block_number),
+				block_hash: DbBlockHash(spent.block_hash),
+				block_timestamp: timestamp(spent),
+				tx_index_in_block: TxIndexInBlock(spent.tx_index_in_block),
+				tx_hash: TxHash(spent.tx_hash),
+				utxo_tx_hash: TxHash(output.created.tx_hash),
+				utxo_index: TxIndex(output.index),
+				quantity,
+			});
+		}
+		Ok(page(rows.into_iter(), limit, offset))
+	}
+
+	async fn get_governance_body_utxo(
+		&self,
+		script_address: &str,
+		policy_id: &PolicyId,
+		block_number: u32,
+	) -> Result<Option<GovernanceBodyUtxoRow>, ObservationQueryError> {
+		let output = self.latest_output_at(script_address, block_number, |output| {
+			output.spent.is_none() && output.datum.is_some() && output.holds_policy(&policy_id.0)
+		})?;
+		let Some(output) = output else { return Ok(None) };
+		let Some(full_datum) = datum(&output)? else { return Ok(None) };
+		let created = &output.created;
+		Ok(Some(GovernanceBodyUtxoRow {
+			full_datum,
+			block_number: BlockNumber(created.block_number),
+			block_hash: DbBlockHash(created.block_hash),
+			tx_index_in_block: TxIndexInBlock(created.tx_index_in_block),
+			tx_hash: TxHash(created.tx_hash),
+			utxo_index: TxIndex(output.index),
+		}))
+	}
+}
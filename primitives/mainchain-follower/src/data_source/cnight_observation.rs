@@ -11,9 +11,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::db::{
-	get_deregistrations, get_redemption_creates, get_redemption_spends, get_registrations,
-};
+use crate::chain_sync::WatchSet;
+use crate::db::{ObservationQueries, ObservationQueryError};
 use crate::{
 	CreateData, DeregistrationData, MidnightCNightObservationDataSource, ObservedUtxo,
 	ObservedUtxoData, ObservedUtxoHeader, RedemptionCreateData, RedemptionSpendData,
@@ -60,10 +59,12 @@ pub struct TxPosition {
 
 #[derive(thiserror::Error, Debug)]
 pub enum MidnightCNightObservationDataSourceError {
-	#[error("missing reference for block hash `{0}` in db-sync")]
+	#[error("missing reference for block hash `{0}` in the main chain follower")]
 	MissingBlockReference(McBlockHash),
-	#[error("Error querying database")]
-	DBQueryError(#[from] sqlx::error::Error),
+	#[error("missing block number `{0}` in the main chain follower")]
+	MissingBlockNumber(u32),
+	#[error(transparent)]
+	QueryError(#[from] ObservationQueryError),
 	#[error("Error extracting network id from Cardano address")]
 	CardanoNetworkError(String),
 	#[error("Invalid value for mapping validator address")]
@@ -86,9 +87,89 @@ pub enum RegistrationDatumDecodeError {
 	DustAddressInvalidLength(usize),
 }
 
+/// Number of rows fetched per query, when paging through the observed range
+const PAGE_SIZE: usize = 1000;
+
+/// UTXOs decoded from a page of query rows. Rows failing to decode are skipped, so the number
+/// of rows is kept to page through the query.
+struct UtxoPage {
+	utxos: Vec<ObservedUtxo>,
+	row_count: usize,
+}
+
+/// Pages through the UTXOs of a single query, until the query is exhausted or UTXOs of more than
+/// `tx_capacity` transactions are fetched. UTXOs of later transactions would be truncated anyway,
+/// as the queries are ordered by transaction position.
+async fn fetch_up_to_capacity<F, Fut, E>(
+	tx_capacity: usize,
+	mut fetch_page: F,
+) -> Result<Vec<ObservedUtxo>, E>
+where
+	F: FnMut(usize) -> Fut,
+	Fut: Future<Output = Result<UtxoPage, E>>,
+{
+	let mut utxos: Vec<ObservedUtxo> = vec![];
+	let mut offset = 0;
+	loop {
+		let page = fetch_page(offset).await?;
+		offset += page.row_count;
+		utxos.extend(page.utxos);
+		let num_txs = utxos.chunk_by(|a, b| a.header.tx_position == b.header.tx_position).count();
+		if page.row_count < PAGE_SIZE || num_txs > tx_capacity {
+			return Ok(utxos);
+		}
+	}
+}
+
+fn parse_mapping_validator_address(
+	config: &CNightAddresses,
+) -> Result<Address, MidnightCNightObservationDataSourceError> {
+	Address::from_bech32(&config.mapping_validator_address).map_err(|e| {
+		MidnightCNightObservationDataSourceError::MappingValidatorInvalidAddress(e.to_string())
+	})
+}
+
+fn mapping_validator_policy_id(
+	mapping_validator_address: &Address,
+) -> Result<ScriptHash, MidnightCNightObservationDataSourceError> {
+	EnterpriseAddress::from_address(mapping_validator_address)
+		.ok_or(MidnightCNightObservationDataSourceError::MappingValidatorInvalidAddress(
+			"Not EnterpriseAddress".to_string(),
+		))?
+		.payment_cred()
+		.to_scripthash()
+		.ok_or(MidnightCNightObservationDataSourceError::MappingValidatorInvalidAddress(
+			"MappingValidator address does not contain a script hash".to_string(),
+		))
+}
+
+/// Cardano addresses and policy ids of the outputs read by the cNight observation of `config`
+pub fn cnight_observation_watch_set(
+	config: &CNightAddresses,
+) -> Result<WatchSet, MidnightCNightObservationDataSourceError> {
+	let mapping_validator_policy_id =
+		mapping_validator_policy_id(&parse_mapping_validator_address(config)?)?;
+	Ok(WatchSet {
+		addresses: [
+			config.mapping_validator_address.clone(),
+			config.redemption_validator_address.clone(),
+		]
+		.into(),
+		policy_ids: [
+			mapping_validator_policy_id
+				.to_bytes()
+				.try_into()
+				.expect("script hashes are 28 bytes long"),
+			config.cnight_policy_id,
+		]
+		.into(),
+	})
+}
+
+/// Observes cNight from db-sync by default, or from any other `ObservationQueries`
 #[derive(new)]
-pub struct MidnightCNightObservationDataSourceImpl {
-	pub pool: PgPool,
+pub struct MidnightCNightObservationDataSourceImpl<Q = PgPool> {
+	pub queries: Q,
 	pub metrics_opt: Option<McFollowerMetrics>,
 	#[allow(dead_code)]
 	cache_size: u16,
@@ -97,22 +178,20 @@ pub struct MidnightCNightObservationDataSourceImpl {
 // If we need better logging here, we could use use db_sync_follower::observed_async_trait
 // But perhaps there are better options for tracing
 #[async_trait::async_trait]
-impl MidnightCNightObservationDataSource for MidnightCNightObservationDataSourceImpl {
+impl<Q: ObservationQueries> MidnightCNightObservationDataSource
+	for MidnightCNightObservationDataSourceImpl<Q>
+{
 	async fn get_utxos_up_to_capacity(
 		&self,
 		config: &CNightAddresses,
 		start_position: &CardanoPosition,
 		current_tip: McBlockHash,
 		tx_capacity: usize,
+		block_window: u32,
 	) -> Result<ObservedUtxos, Box<dyn std::error::Error + Send + Sync>> {
 		let cnight_asset_name = config.cnight_asset_name.as_bytes();
 
-		let mapping_validator_address = Address::from_bech32(&config.mapping_validator_address)
-			.map_err(|e| {
-				MidnightCNightObservationDataSourceError::MappingValidatorInvalidAddress(
-					e.to_string(),
-				)
-			})?;
+		let mapping_validator_address = parse_mapping_validator_address(config)?;
 
 		let cardano_network = mapping_validator_address.network_id().map_err(|_| {
 			MidnightCNightObservationDataSourceError::CardanoNetworkError(
@@ -120,95 +199,112 @@ impl MidnightCNightObservationDataSource for MidnightCNightObservationDataSource
 			)
 		})?;
 
-		let mapping_validator_policy_id =
-			EnterpriseAddress::from_address(&mapping_validator_address)
-				.ok_or(MidnightCNightObservationDataSourceError::MappingValidatorInvalidAddress(
-					"Not EnterpriseAddress".to_string(),
-				))?
-				.payment_cred()
-				.to_scripthash()
-				.ok_or(MidnightCNightObservationDataSourceError::MappingValidatorInvalidAddress(
-					"MappingValidator address does not contain a script hash".to_string(),
-				))?;
+		let mapping_validator_policy_id = mapping_validator_policy_id(&mapping_validator_address)?;
+
+		self.queries.watch(&cnight_observation_watch_set(config)?).await?;
 
 		// Get end position from cardano block hash
-		let end: CardanoPosition = crate::db::get_block_by_hash(&self.pool, current_tip.clone())
-			.await?
-			.ok_or(MidnightCNightObservationDataSourceError::MissingBlockReference(current_tip))?
-			.into();
-		// Increment the end position to tx_index + 1 of the current mainchain position
+		let tip: CardanoPosition =
+			self.queries.get_block_position(current_tip.clone()).await?.ok_or(
+				MidnightCNightObservationDataSourceError::MissingBlockReference(current_tip),
+			)?;
+
+		let tip_block_number = tip.block_number;
+
+		// Observe at most `block_window` blocks, starting from the block of the start position
+		let last_block_number =
+			start_position.block_number.saturating_add(block_window.saturating_sub(1));
+		let end = if tip.block_number > last_block_number {
+			log::info!(
+				"cNight observation is {} Cardano blocks behind the tip, observing {block_window} of them",
+				tip.block_number - start_position.block_number,
+			);
+			self.queries.get_block_position_by_number(last_block_number).await?.ok_or(
+				MidnightCNightObservationDataSourceError::MissingBlockNumber(last_block_number),
+			)?
+		} else {
+			tip
+		};
+		// Increment the end position to tx_index + 1 of the last observed block
 		let end = end.increment();
 
-		// The "capacity" argument is capacity in terms of TRANSACTIONS,
-		// but the various sql queries below want a capacity in terms of UTXOs.
-		// Use a generous overestimate of how many UTXOs each TX _may_ have.
-		let utxo_capacity = tx_capacity * 64;
-
-		// Call db methods to get UTXOs (offset + limit) until we reach our capacity
 		// TODO: (possibly) Replace this with grabbing from a queue that's filled async by an offchain thread
 		// ^ We may not have to do the above if the queries are fast enough
+		let (start, end) = (start_position, &end);
 		let mut utxos = [
-			self.get_registration_utxos(
-				cardano_network,
-				&mapping_validator_policy_id,
-				&config.mapping_validator_address,
-				&config.auth_token_asset_name,
-				start_position,
-				&end,
-				utxo_capacity,
-				0,
-			)
+			fetch_up_to_capacity(tx_capacity, |offset| {
+				self.get_registration_utxos(
+					cardano_network,
+					&mapping_validator_policy_id,
+					&config.mapping_validator_address,
+					&config.auth_token_asset_name,
+					start,
+					end,
+					PAGE_SIZE,
+					offset,
+				)
+			})
 			.await?,
-			self.get_deregistration_utxos(
-				cardano_network,
-				&config.mapping_validator_address,
-				start_position,
-				&end,
-				utxo_capacity,
-				0,
-			)
+			fetch_up_to_capacity(tx_capacity, |offset| {
+				self.get_deregistration_utxos(
+					cardano_network,
+					&config.mapping_validator_address,
+					start,
+					end,
+					PAGE_SIZE,
+					offset,
+				)
+			})
 			.await?,
-			self.get_asset_create_utxos(
-				cardano_network,
-				config.cnight_policy_id,
-				cnight_asset_name,
-				start_position,
-				&end,
-				utxo_capacity,
-				0,
-			)
+			fetch_up_to_capacity(tx_capacity, |offset| {
+				self.get_asset_create_utxos(
+					cardano_network,
+					config.cnight_policy_id,
+					cnight_asset_name,
+					start,
+					end,
+					PAGE_SIZE,
+					offset,
+				)
+			})
 			.await?,
-			self.get_asset_spend_utxos(
-				cardano_network,
-				config.cnight_policy_id,
-				cnight_asset_name,
-				start_position,
-				&end,
-				utxo_capacity,
-				0,
-			)
+			fetch_up_to_capacity(tx_capacity, |offset| {
+				self.get_asset_spend_utxos(
+					cardano_network,
+					config.cnight_policy_id,
+					cnight_asset_name,
+					start,
+					end,
+					PAGE_SIZE,
+					offset,
+				)
+			})
 			.await?,
-			self.get_redemption_create_utxos(
-				cardano_network,
-				&config.redemption_validator_address,
-				config.cnight_policy_id,
-				cnight_asset_name,
-				start_position,
-				&end,
-				utxo_capacity,
-				0,
-			)
+			fetch_up_to_capacity(tx_capacity, |offset| {
+				self.get_redemption_create_utxos(
+					cardano_network,
+					&config.redemption_validator_address,
+					config.cnight_policy_id,
+					cnight_asset_name,
+					start,
+					end,
+					PAGE_SIZE,
+					offset,
+				)
+			})
 			.await?,
-			self.get_redemption_spend_utxos(
-				cardano_network,
-				&config.redemption_validator_address,
-				config.cnight_policy_id,
-				cnight_asset_name,
-				start_position,
-				&end,
-				utxo_capacity,
-				0,
-			)
+			fetch_up_to_capacity(tx_capacity, |offset| {
+				self.get_redemption_spend_utxos(
+					cardano_network,
+					&config.redemption_validator_address,
+					config.cnight_policy_id,
+					cnight_asset_name,
+					start,
+					end,
+					PAGE_SIZE,
+					offset,
+				)
+			})
 			.await?,
 		]
 		.concat();
@@ -216,38 +312,51 @@ impl MidnightCNightObservationDataSource for MidnightCNightObservationDataSource
 		utxos.sort();
 
 		// Truncate UTXOs but include full transactions
-		let mut truncated_utxos = Vec::with_capacity(utxo_capacity);
+		let mut truncated_utxos = Vec::new();
 		let mut num_txs = 0;
 		let mut cur_tx: Option<CardanoPosition> = None;
+		let mut capacity_reached = false;
 		for utxo in utxos {
 			if cur_tx.as_ref().is_none_or(|tx| tx < &utxo.header.tx_position) {
+				if num_txs == tx_capacity {
+					capacity_reached = true;
+					break;
+				}
 				num_txs += 1;
 				cur_tx = Some(utxo.header.tx_position.clone());
 			}
-			if num_txs == tx_capacity {
-				break;
-			}
 			truncated_utxos.push(utxo);
 		}
 
-		if num_txs < tx_capacity {
-			// We couldn't find enough UTXOs in the range, which means we're up-to-date with the
-			// current_tip
-			Ok(ObservedUtxos { start: start_position.clone(), end, utxos: truncated_utxos })
-		} else {
+		if capacity_reached {
 			Ok(ObservedUtxos {
 				start: start_position.clone(),
 				end: truncated_utxos
 					.last()
-					.map_or(start_position.clone(), |u| u.header.tx_position.clone())
-					.increment(),
+					.map_or(start_position.clone(), |u| u.header.tx_position.increment()),
+				utxos: truncated_utxos,
+				tip_block_number,
+			})
+		} else {
+			// Every transaction of the window fits, so the whole window is observed
+			Ok(ObservedUtxos {
+				start: start_position.clone(),
+				end: end.clone(),
 				utxos: truncated_utxos,
+				tip_block_number,
 			})
 		}
 	}
+
+	async fn is_block_on_chain(
+		&self,
+		block_hash: McBlockHash,
+	) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+		Ok(self.queries.get_block_position(block_hash).await?.is_some())
+	}
 }
 
-impl MidnightCNightObservationDataSourceImpl {
+impl<Q: ObservationQueries> MidnightCNightObservationDataSourceImpl<Q> {
 	fn decode_registration_datum(
 		datum: ConstrPlutusData,
 	) -> Result<(Credential, DustPublicKeyBytes), RegistrationDatumDecodeError> {
@@ -303,13 +412,14 @@ impl MidnightCNightObservationDataSourceImpl {
 		end: &CardanoPosition,
 		limit: usize,
 		offset: usize,
-	) -> Result<Vec<ObservedUtxo>, Box<dyn std::error::Error + Send + Sync>> {
-		let rows = get_redemption_creates(
-			&self.pool, address, policy_id, asset_name, start, end, limit, offset,
-		)
-		.await
-		.map_err(|e| format!("Failed to fetch data: {e}"))?;
-
+	) -> Result<UtxoPage, Box<dyn std::error::Error + Send + Sync>> {
+		let rows = self
+			.queries
+			.get_redemption_creates(address, policy_id, asset_name, start, end, limit, offset)
+			.await
+			.map_err(|e| format!("Failed to fetch data: {e}"))?;
+
+		let row_count = rows.len();
 		let mut utxos = Vec::new();
 
 		for row in rows {
@@ -368,7 +478,7 @@ impl MidnightCNightObservationDataSourceImpl {
 			utxos.push(utxo);
 		}
 
-		Ok(utxos)
+		Ok(UtxoPage { utxos, row_count })
 	}
 
 	#[allow(clippy::too_many_arguments)]
@@ -382,13 +492,14 @@ impl MidnightCNightObservationDataSourceImpl {
 		end: &CardanoPosition,
 		limit: usize,
 		offset: usize,
-	) -> Result<Vec<ObservedUtxo>, Box<dyn std::error::Error + Send + Sync>> {
-		let rows = get_redemption_spends(
-			&self.pool, address, policy_id, asset_name, start, end, limit, offset,
-		)
-		.await
-		.map_err(|e| format!("Failed to fetch data: {e}"))?;
-
+	) -> Result<UtxoPage, Box<dyn std::error::Error + Send + Sync>> {
+		let rows = self
+			.queries
+			.get_redemption_spends(address, policy_id, asset_name, start, end, limit, offset)
+			.await
+			.map_err(|e| format!("Failed to fetch data: {e}"))?;
+
+		let row_count = rows.len();
 		let mut utxos = Vec::new();
 
 		for row in rows {
@@ -450,7 +561,7 @@ impl MidnightCNightObservationDataSourceImpl {
 			utxos.push(utxo);
 		}
 
-		Ok(utxos)
+		Ok(UtxoPage { utxos, row_count })
 	}
 
 	#[allow(clippy::too_many_arguments)]
@@ -464,19 +575,21 @@ impl MidnightCNightObservationDataSourceImpl {
 		end: &CardanoPosition,
 		limit: usize,
 		offset: usize,
-	) -> Result<Vec<ObservedUtxo>, MidnightCNightObservationDataSourceError> {
-		let rows = get_registrations(
-			&self.pool,
-			address,
-			mapping_validator_policy_id,
-			auth_asset_name,
-			start,
-			end,
-			limit,
-			offset,
-		)
-		.await?;
+	) -> Result<UtxoPage, MidnightCNightObservationDataSourceError> {
+		let rows = self
+			.queries
+			.get_registrations(
+				address,
+				mapping_validator_policy_id,
+				auth_asset_name,
+				start,
+				end,
+				limit,
+				offset,
+			)
+			.await?;
 
+		let row_count = rows.len();
 		let mut utxos = Vec::new();
 
 		for row in rows {
@@ -519,7 +632,7 @@ impl MidnightCNightObservationDataSourceImpl {
 			utxos.push(utxo);
 		}
 
-		Ok(utxos)
+		Ok(UtxoPage { utxos, row_count })
 	}
 
 	async fn get_deregistration_utxos(
@@ -530,9 +643,10 @@ impl MidnightCNightObservationDataSourceImpl {
 		end: &CardanoPosition,
 		limit: usize,
 		offset: usize,
-	) -> Result<Vec<ObservedUtxo>, MidnightCNightObservationDataSourceError> {
-		let rows = get_deregistrations(&self.pool, address, start, end, limit, offset).await?;
+	) -> Result<UtxoPage, MidnightCNightObservationDataSourceError> {
+		let rows = self.queries.get_deregistrations(address, start, end, limit, offset).await?;
 
+		let row_count = rows.len();
 		let mut utxos = Vec::new();
 
 		for row in rows {
@@ -575,7 +689,7 @@ impl MidnightCNightObservationDataSourceImpl {
 			utxos.push(utxo);
 		}
 
-		Ok(utxos)
+		Ok(UtxoPage { utxos, row_count })
 	}
 
 	#[allow(clippy::too_many_arguments)]
@@ -588,12 +702,13 @@ impl MidnightCNightObservationDataSourceImpl {
 		end: &CardanoPosition,
 		limit: usize,
 		offset: usize,
-	) -> Result<Vec<ObservedUtxo>, MidnightCNightObservationDataSourceError> {
-		let rows = crate::db::get_asset_creates(
-			&self.pool, policy_id, asset_name, start, end, limit, offset,
-		)
-		.await?;
+	) -> Result<UtxoPage, MidnightCNightObservationDataSourceError> {
+		let rows = self
+			.queries
+			.get_asset_creates(policy_id, asset_name, start, end, limit, offset)
+			.await?;
 
+		let row_count = rows.len();
 		let mut utxos = Vec::new();
 
 		for row in rows {
@@ -639,7 +754,7 @@ impl MidnightCNightObservationDataSourceImpl {
 			utxos.push(utxo);
 		}
 
-		Ok(utxos)
+		Ok(UtxoPage { utxos, row_count })
 	}
 
 	#[allow(clippy::too_many_arguments)]
@@ -652,12 +767,13 @@ impl MidnightCNightObservationDataSourceImpl {
 		end: &CardanoPosition,
 		limit: usize,
 		offset: usize,
-	) -> Result<Vec<ObservedUtxo>, MidnightCNightObservationDataSourceError> {
-		let rows = crate::db::get_asset_spends(
-			&self.pool, policy_id, asset_name, start, end, limit, offset,
-		)
-		.await?;
+	) -> Result<UtxoPage, MidnightCNightObservationDataSourceError> {
+		let rows = self
+			.queries
+			.get_asset_spends(policy_id, asset_name, start, end, limit, offset)
+			.await?;
 
+		let row_count = rows.len();
 		let mut utxos = Vec::new();
 
 		for row in rows {
@@ -704,6 +820,6 @@ impl MidnightCNightObservationDataSourceImpl {
 			utxos.push(utxo);
 		}
 
-		Ok(utxos)
+		Ok(UtxoPage { utxos, row_count })
 	}
 }
@@ -0,0 +1,300 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Record and replay of the cNight observation data source
+//!
+//! A fixture is a JSON lines file, with one `ObservedUtxos` response per line. The recorder
+//! appends the responses of the wrapped data source, up to a maximum number of lines, and the
+//! replay serves them back by start position, so a mock-mode run observes the same UTXOs as the
+//! recorded one.
+
+use crate::MidnightCNightObservationDataSource;
+use midnight_primitives_cnight_observation::{CNightAddresses, CardanoPosition, ObservedUtxos};
+use sidechain_domain::McBlockHash;
+use std::{
+	collections::HashMap,
+	fs::{File, OpenOptions},
+	io::{BufRead, BufReader, Write},
+	path::Path,
+	sync::Mutex,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum CNightObservationFixtureError {
+	#[error("cNight observation fixture I/O error: {0}")]
+	Io(#[from] std::io::Error),
+	#[error("invalid cNight observation fixture at line {0}: {1}")]
+	InvalidLine(usize, serde_json::Error),
+	#[error("no recorded cNight observation starting at {0}")]
+	MissingPosition(CardanoPosition),
+}
+
+/// Identifies a start position, `CardanoPosition` not being hashable
+type PositionKey = (u32, u32, [u8; 32]);
+
+fn position_key(position: &CardanoPosition) -> PositionKey {
+	(position.block_number, position.tx_index_in_block, position.block_hash.0)
+}
+
+struct RecordedFixture {
+	file: File,
+	/// Number of responses in the file, including the ones of previous runs
+	responses: u64,
+}
+
+/// Wraps a cNight observation data source, appending its responses to a fixture file
+///
+/// Responses observing an empty range aren't recorded, the replay serves them from the next
+/// recorded response. Recording stops once the fixture holds `max_responses` responses.
+pub struct CNightObservationRecorder<D> {
+	inner: D,
+	fixture: Mutex<RecordedFixture>,
+	max_responses: u64,
+}
+
+impl<D> CNightObservationRecorder<D> {
+	pub fn new(
+		inner: D,
+		path: &Path,
+		max_responses: u64,
+	) -> Result<Self, CNightObservationFixtureError> {
+		let file = OpenOptions::new().create(true).append(true).read(true).open(path)?;
+		let mut responses = 0;
+		for line in BufReader::new(&file).lines() {
+			if !line?.trim().is_empty() {
+				responses += 1;
+			}
+		}
+		Ok(Self { inner, fixture: Mutex::new(RecordedFixture { file, responses }), max_responses })
+	}
+
+	fn record(&self, utxos: &ObservedUtxos) -> Result<(), CNightObservationFixtureError> {
+		if utxos.start == utxos.end && utxos.utxos.is_empty() {
+			return Ok(());
+		}
+
+		let mut fixture = self.fixture.lock().expect("fixture lock is not poisoned");
+		if fixture.responses >= self.max_responses {
+			return Ok(());
+		}
+		let mut line = serde_json::to_vec(utxos).expect("ObservedUtxos serialize to JSON");
+		line.push(b'\n');
+		// A single write per line, so an interrupted run leaves complete lines only
+		fixture.file.write_all(&line)?;
+		fixture.responses += 1;
+		if fixture.responses == self.max_responses {
+			log::warn!(
+				"cNight observation fixture is full with {} responses, no longer recording",
+				self.max_responses
+			);
+		}
+		Ok(())
+	}
+}
+
+#[async_trait::async_trait]
+impl<D: MidnightCNightObservationDataSource + Send + Sync> MidnightCNightObservationDataSource
+	for CNightObservationRecorder<D>
+{
+	async fn get_utxos_up_to_capacity(
+		&self,
+		config: &CNightAddresses,
+		start_position: &CardanoPosition,
+		current_tip: McBlockHash,
+		tx_capacity: usize,
+		block_window: u32,
+	) -> Result<ObservedUtxos, Box<dyn std::error::Error + Send + Sync>> {
+		let utxos = self
+			.inner
+			.get_utxos_up_to_capacity(
+				config,
+				start_position,
+				current_tip,
+				tx_capacity,
+				block_window,
+			)
+			.await?;
+		self.record(&utxos)?;
+		Ok(utxos)
+	}
+
+	async fn is_block_on_chain(
+		&self,
+		block_hash: McBlockHash,
+	) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+		self.inner.is_block_on_chain(block_hash).await
+	}
+}
+
+/// Serves the responses of a fixture file by start position
+///
+/// When a position was recorded several times, the last response wins. Once the fixture is
+/// exhausted, i.e. from the end of the last recorded response, nothing more is observed.
+pub struct CNightObservationReplay {
+	responses: HashMap<PositionKey, ObservedUtxos>,
+	last_end: Option<CardanoPosition>,
+}
+
+impl CNightObservationReplay {
+	pub fn load(path: &Path) -> Result<Self, CNightObservationFixtureError> {
+		let mut responses = HashMap::new();
+		let mut last_end = None;
+		for (number, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+			let line = line?;
+			if line.trim().is_empty() {
+				continue;
+			}
+			let utxos: ObservedUtxos = serde_json::from_str(&line)
+				.map_err(|e| CNightObservationFixtureError::InvalidLine(number + 1, e))?;
+			last_end = Some(utxos.end.clone());
+			responses.insert(position_key(&utxos.start), utxos);
+		}
+		Ok(Self { responses, last_end })
+	}
+}
+
+#[async_trait::async_trait]
+impl MidnightCNightObservationDataSource for CNightObservationReplay {
+	async fn get_utxos_up_to_capacity(
+		&self,
+		_config: &CNightAddresses,
+		start_position: &CardanoPosition,
+		_current_tip: McBlockHash,
+		_tx_capacity: usize,
+		_block_window: u32,
+	) -> Result<ObservedUtxos, Box<dyn std::error::Error + Send + Sync>> {
+		if let Some(utxos) = self.responses.get(&position_key(start_position)) {
+			return Ok(utxos.clone());
+		}
+		if self.last_end.as_ref() == Some(start_position) {
+			return Ok(ObservedUtxos {
+				start: start_position.clone(),
+				end: start_position.clone(),
+				utxos: vec![],
+				tip_block_number: start_position.block_number,
+			});
+		}
+		Err(CNightObservationFixtureError::MissingPosition(start_position.clone()).into())
+	}
+
+	/// Recorded chains are never rolled back
+	async fn is_block_on_chain(
+		&self,
+		_block_hash: McBlockHash,
+	) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+		Ok(true)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::data_source::CNightObservationDataSourceMock;
+
+	fn position(block_number: u32) -> CardanoPosition {
+		CardanoPosition { block_number, ..Default::default() }
+	}
+
+	#[tokio::test]
+	async fn replay_serves_the_recorded_responses() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("cnight.jsonl");
+		let config = CNightAddresses::default();
+
+		let recorder =
+			CNightObservationRecorder::new(CNightObservationDataSourceMock::new(), &path, 100)
+				.unwrap();
+		let mut recorded = vec![];
+		let mut start = position(0);
+		for _ in 0..6 {
+			let utxos = recorder
+				.get_utxos_up_to_capacity(&config, &start, McBlockHash([0; 32]), 10, 100)
+				.await
+				.unwrap();
+			start = utxos.end.clone();
+			recorded.push(utxos);
+		}
+		drop(recorder);
+
+		let replay = CNightObservationReplay::load(&path).unwrap();
+		for utxos in &recorded {
+			let replayed = replay
+				.get_utxos_up_to_capacity(&config, &utxos.start, McBlockHash([0; 32]), 10, 100)
+				.await
+				.unwrap();
+			assert_eq!(replayed.end, utxos.end);
+			assert_eq!(replayed.utxos, utxos.utxos);
+		}
+
+		let exhausted = replay
+			.get_utxos_up_to_capacity(&config, &start, McBlockHash([0; 32]), 10, 100)
+			.await
+			.unwrap();
+		assert_eq!((exhausted.start, exhausted.end), (start.clone(), start));
+		assert!(exhausted.utxos.is_empty());
+
+		assert!(
+			replay
+				.get_utxos_up_to_capacity(&config, &position(42), McBlockHash([0; 32]), 10, 100)
+				.await
+				.is_err()
+		);
+	}
+
+	#[tokio::test]
+	async fn recording_stops_once_the_fixture_is_full() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("cnight.jsonl");
+		let config = CNightAddresses::default();
+		let recorded_lines = || std::fs::read_to_string(&path).unwrap().lines().count();
+
+		let recorder =
+			CNightObservationRecorder::new(CNightObservationDataSourceMock::new(), &path, 3)
+				.unwrap();
+		let mut start = position(0);
+		for _ in 0..2 {
+			start = recorder
+				.get_utxos_up_to_capacity(&config, &start, McBlockHash([0; 32]), 10, 100)
+				.await
+				.unwrap()
+				.end;
+		}
+		drop(recorder);
+		assert_eq!(recorded_lines(), 2);
+
+		// The responses of the previous run count towards the maximum
+		let recorder =
+			CNightObservationRecorder::new(CNightObservationDataSourceMock::new(), &path, 3)
+				.unwrap();
+		for _ in 0..3 {
+			start = recorder
+				.get_utxos_up_to_capacity(&config, &start, McBlockHash([0; 32]), 10, 100)
+				.await
+				.unwrap()
+				.end;
+		}
+		assert_eq!(recorded_lines(), 3);
+
+		// Empty ranges are never recorded
+		let empty = ObservedUtxos {
+			start: start.clone(),
+			tip_block_number: start.block_number,
+			end: start,
+			utxos: vec![],
+		};
+		let recorder =
+			CNightObservationRecorder::new((), &dir.path().join("empty.jsonl"), 3).unwrap();
+		recorder.record(&empty).unwrap();
+		assert_eq!(recorder.fixture.lock().unwrap().responses, 0);
+	}
+}
@@ -68,6 +68,7 @@ impl MidnightCNightObservationDataSource for CNightObservationDataSourceMock {
 		start: &CardanoPosition,
 		_current_tip: McBlockHash,
 		_capacity: usize,
+		_block_window: u32,
 	) -> Result<ObservedUtxos, Box<dyn std::error::Error + Send + Sync>> {
 		let mut end = start.clone();
 		end.block_number += 1;
@@ -76,6 +77,15 @@ impl MidnightCNightObservationDataSource for CNightObservationDataSourceMock {
 		let utxos =
 			if start.block_number.is_multiple_of(5) { mock_utxos(start) } else { Vec::new() };
 
-		Ok(ObservedUtxos { start: start.clone(), end, utxos })
+		// The mock chain ends where the observation does
+		let tip_block_number = end.block_number;
+		Ok(ObservedUtxos { start: start.clone(), end, utxos, tip_block_number })
+	}
+
+	async fn is_block_on_chain(
+		&self,
+		_block_hash: McBlockHash,
+	) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+		Ok(true)
 	}
 }
@@ -11,7 +11,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{FederatedAuthorityObservationDataSource, db::get_governance_body_utxo};
+use crate::{
+	FederatedAuthorityObservationDataSource, chain_sync::WatchSet, db::ObservationQueries,
+};
 use cardano_serialization_lib::PlutusData;
 use derive_new::new;
 use midnight_primitives_federated_authority_observation::{
@@ -22,39 +24,55 @@ use partner_chains_db_sync_data_sources::McFollowerMetrics;
 use sidechain_domain::{McBlockHash, PolicyId};
 pub use sqlx::PgPool;
 
+/// Cardano addresses and policy ids of the outputs read by the observation of the governance
+/// bodies of `config`
+pub fn federated_authority_watch_set(config: &FederatedAuthorityObservationConfig) -> WatchSet {
+	WatchSet {
+		addresses: [config.council.address.clone(), config.technical_committee.address.clone()]
+			.into(),
+		policy_ids: [config.council.policy_id.0, config.technical_committee.policy_id.0].into(),
+	}
+}
+
+/// Observes the governance bodies from db-sync by default, or from any other `ObservationQueries`
 #[derive(new)]
-pub struct FederatedAuthorityObservationDataSourceImpl {
-	pub pool: PgPool,
+pub struct FederatedAuthorityObservationDataSourceImpl<Q = PgPool> {
+	pub queries: Q,
 	pub metrics_opt: Option<McFollowerMetrics>,
 	#[allow(dead_code)]
 	cache_size: u16,
 }
 
 #[async_trait::async_trait]
-impl FederatedAuthorityObservationDataSource for FederatedAuthorityObservationDataSourceImpl {
+impl<Q: ObservationQueries> FederatedAuthorityObservationDataSource
+	for FederatedAuthorityObservationDataSourceImpl<Q>
+{
 	async fn get_federated_authority_data(
 		&self,
 		config: &FederatedAuthorityObservationConfig,
 		mc_block_hash: &McBlockHash,
 	) -> Result<FederatedAuthorityData, Box<dyn std::error::Error + Send + Sync>> {
+		self.queries.watch(&federated_authority_watch_set(config)).await?;
+
 		// Get block number from hash
-		let block = crate::db::get_block_by_hash(&self.pool, mc_block_hash.clone()).await?;
+		let block = self.queries.get_block_position(mc_block_hash.clone()).await?;
 
 		let block_number = match block {
-			Some(b) => b.block_number.0,
+			Some(b) => b.block_number,
 			None => {
 				return Err(format!("Block not found for hash: {:?}", mc_block_hash).into());
 			},
 		};
 
 		// Query council UTXO
-		let council_utxo = get_governance_body_utxo(
-			&self.pool,
-			&config.council.address,
-			&config.council.policy_id,
-			block_number,
-		)
-		.await?;
+		let council_utxo = self
+			.queries
+			.get_governance_body_utxo(
+				&config.council.address,
+				&config.council.policy_id,
+				block_number,
+			)
+			.await?;
 
 		let council_authorities = match council_utxo {
 			Some(utxo) => match Self::decode_governance_datum(&utxo.full_datum.0) {
@@ -83,13 +101,14 @@ impl FederatedAuthorityObservationDataSource for FederatedAuthorityObservationDa
 		};
 
 		// Query technical committee UTXO
-		let technical_committee_utxo = get_governance_body_utxo(
-			&self.pool,
-			&config.technical_committee.address,
-			&config.technical_committee.policy_id,
-			block_number,
-		)
-		.await?;
+		let technical_committee_utxo = self
+			.queries
+			.get_governance_body_utxo(
+				&config.technical_committee.address,
+				&config.technical_committee.policy_id,
+				block_number,
+			)
+			.await?;
 
 		let technical_committee_authorities = match technical_committee_utxo {
 			Some(utxo) => match Self::decode_governance_datum(&utxo.full_datum.0) {
@@ -128,7 +147,7 @@ impl FederatedAuthorityObservationDataSource for FederatedAuthorityObservationDa
 	}
 }
 
-impl FederatedAuthorityObservationDataSourceImpl {
+impl<Q> FederatedAuthorityObservationDataSourceImpl<Q> {
 	/// Decode PlutusData containing governance body members
 	///
 	/// Expected format: `[total_signers: Int, [...(CborBytes, Sr25519Keys)]]`
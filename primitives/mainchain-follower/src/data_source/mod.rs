@@ -13,19 +13,27 @@
 
 //! Data sources implementations that read from db-sync postgres.
 //!
-//! This module uses the types and functions provided by the `db` module
+//! This module uses the types and functions provided by the `db` module. The observation data
+//! sources only depend on `ObservationQueries`, so they can also read from the chain-sync index of
+//! the `chain_sync` module.
 
 pub mod cnight_observation;
+pub mod cnight_observation_fixture;
 pub mod cnight_observation_mock;
 pub mod federated_authority_observation;
 pub mod federated_authority_observation_mock;
 
 pub use cnight_observation::{
 	MidnightCNightObservationDataSourceError, MidnightCNightObservationDataSourceImpl, TxHash,
-	TxPosition,
+	TxPosition, cnight_observation_watch_set,
+};
+pub use cnight_observation_fixture::{
+	CNightObservationFixtureError, CNightObservationRecorder, CNightObservationReplay,
 };
 pub use cnight_observation_mock::CNightObservationDataSourceMock;
-pub use federated_authority_observation::FederatedAuthorityObservationDataSourceImpl;
+pub use federated_authority_observation::{
+	FederatedAuthorityObservationDataSourceImpl, federated_authority_watch_set,
+};
 pub use federated_authority_observation_mock::FederatedAuthorityObservationDataSourceMock;
 
 pub use sqlx::PgPool;
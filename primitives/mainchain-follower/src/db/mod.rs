@@ -16,4 +16,5 @@ mod types;
 
 pub use queries::cnight_observation::*;
 pub use queries::federated_authority_observation::*;
+pub use queries::observation_queries::*;
 pub use types::*;
@@ -368,3 +368,27 @@ WHERE hash = $1
 	.fetch_optional(pool)
 	.await
 }
+
+/// Query to get the block by its number
+pub(crate) async fn get_block_by_number(
+	pool: &Pool<Postgres>,
+	number: u32,
+) -> Result<Option<Block>, SqlxError> {
+	sqlx::query_as!(
+		Block,
+		r#"
+SELECT 
+    block_no as "block_number!: _", 
+    hash as "hash: _",
+    epoch_no as "epoch_number!: _",
+    slot_no as "slot_number!: _", 
+    time,
+    tx_count
+FROM block
+WHERE block_no = $1
+"#,
+		number as i32
+	)
+	.fetch_optional(pool)
+	.await
+}
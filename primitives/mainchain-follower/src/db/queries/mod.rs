@@ -13,5 +13,6 @@
 
 pub mod cnight_observation;
 pub mod federated_authority_observation;
+pub mod observation_queries;
 
 pub use cnight_observation::*;
@@ -0,0 +1,269 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Queries of the observation data sources
+//!
+//! The cNight and federated authority observation data sources only depend on these queries,
+//! answered either by db-sync or by the chain-sync index of the `chain_sync` module.
+
+use crate::{
+	chain_sync::{ChainSyncIndexError, WatchSet},
+	db::{
+		AssetCreateRow, AssetSpendRow, DeregistrationRow, GovernanceBodyUtxoRow,
+		RedemptionCreateRow, RedemptionSpendRow, RegistrationRow,
+	},
+};
+use cardano_serialization_lib::ScriptHash;
+use midnight_primitives_cnight_observation::CardanoPosition;
+use sidechain_domain::{McBlockHash, PolicyId};
+use sqlx::PgPool;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ObservationQueryError {
+	#[error("Error querying database")]
+	DBQueryError(#[from] sqlx::error::Error),
+	#[error("Error reading the chain-sync index")]
+	ChainSyncIndexError(#[from] ChainSyncIndexError),
+}
+
+/// Ranges are `[start, end)`, ordered by block number then transaction index, as in the db-sync
+/// queries.
+#[async_trait::async_trait]
+pub trait ObservationQueries: Send + Sync {
+	/// Makes the outputs matching `watch_set` available to the other queries. db-sync indexes
+	/// every output, so only the chain-sync index needs it.
+	async fn watch(&self, _watch_set: &WatchSet) -> Result<(), ObservationQueryError> {
+		Ok(())
+	}
+
+	/// Position right after the last transaction of the block
+	async fn get_block_position(
+		&self,
+		hash: McBlockHash,
+	) -> Result<Option<CardanoPosition>, ObservationQueryError>;
+
+	/// Position right after the last transaction of the block at `number`
+	async fn get_block_position_by_number(
+		&self,
+		number: u32,
+	) -> Result<Option<CardanoPosition>, ObservationQueryError>;
+
+	#[allow(clippy::too_many_arguments)]
+	async fn get_registrations(
+		&self,
+		smart_contract_address: &str,
+		mapping_validator_policy_id: &ScriptHash,
+		auth_token_asset_name: &str,
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<RegistrationRow>, ObservationQueryError>;
+
+	async fn get_deregistrations(
+		&self,
+		smart_contract_address: &str,
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<DeregistrationRow>, ObservationQueryError>;
+
+	#[allow(clippy::too_many_arguments)]
+	async fn get_asset_creates(
+		&self,
+		policy_id: [u8; 28],
+		asset_name: &[u8],
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<AssetCreateRow>, ObservationQueryError>;
+
+	#[allow(clippy::too_many_arguments)]
+	async fn get_asset_spends(
+		&self,
+		policy_id: [u8; 28],
+		asset_name: &[u8],
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<AssetSpendRow>, ObservationQueryError>;
+
+	#[allow(clippy::too_many_arguments)]
+	async fn get_redemption_creates(
+		&self,
+		smart_contract_address: &str,
+		policy_id: [u8; 28],
+		asset_name: &[u8],
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<RedemptionCreateRow>, ObservationQueryError>;
+
+	#[allow(clippy::too_many_arguments)]
+	async fn get_redemption_spends(
+		&self,
+		smart_contract_address: &str,
+		policy_id: [u8; 28],
+		asset_name: &[u8],
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<RedemptionSpendRow>, ObservationQueryError>;
+
+	/// Most recent output up to `block_number`, among those still unspent
+	async fn get_governance_body_utxo(
+		&self,
+		script_address: &str,
+		policy_id: &PolicyId,
+		block_number: u32,
+	) -> Result<Option<GovernanceBodyUtxoRow>, ObservationQueryError>;
+}
+
+#[async_trait::async_trait]
+impl ObservationQueries for PgPool {
+	async fn get_block_position(
+		&self,
+		hash: McBlockHash,
+	) -> Result<Option<CardanoPosition>, ObservationQueryError> {
+		Ok(crate::db::get_block_by_hash(self, hash).await?.map(Into::into))
+	}
+
+	async fn get_block_position_by_number(
+		&self,
+		number: u32,
+	) -> Result<Option<CardanoPosition>, ObservationQueryError> {
+		Ok(crate::db::get_block_by_number(self, number).await?.map(Into::into))
+	}
+
+	async fn get_registrations(
+		&self,
+		smart_contract_address: &str,
+		mapping_validator_policy_id: &ScriptHash,
+		auth_token_asset_name: &str,
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<RegistrationRow>, ObservationQueryError> {
+		Ok(crate::db::get_registrations(
+			self,
+			smart_contract_address,
+			mapping_validator_policy_id,
+			auth_token_asset_name,
+			start,
+			end,
+			limit,
+			offset,
+		)
+		.await?)
+	}
+
+	async fn get_deregistrations(
+		&self,
+		smart_contract_address: &str,
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<DeregistrationRow>, ObservationQueryError> {
+		Ok(crate::db::get_deregistrations(self, smart_contract_address, start, end, limit, offset)
+			.await?)
+	}
+
+	async fn get_asset_creates(
+		&self,
+		policy_id: [u8; 28],
+		asset_name: &[u8],
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<AssetCreateRow>, ObservationQueryError> {
+		Ok(crate::db::get_asset_creates(self, policy_id, asset_name, start, end, limit, offset)
+			.await?)
+	}
+
+	async fn get_asset_spends(
+		&self,
+		policy_id: [u8; 28],
+		asset_name: &[u8],
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<AssetSpendRow>, ObservationQueryError> {
+		Ok(crate::db::get_asset_spends(self, policy_id, asset_name, start, end, limit, offset)
+			.await?)
+	}
+
+	async fn get_redemption_creates(
+		&self,
+		smart_contract_address: &str,
+		policy_id: [u8; 28],
+		asset_name: &[u8],
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<RedemptionCreateRow>, ObservationQueryError> {
+		Ok(crate::db::get_redemption_creates(
+			self,
+			smart_contract_address,
+			policy_id,
+			asset_name,
+			start,
+			end,
+			limit,
+			offset,
+		)
+		.await?)
+	}
+
+	async fn get_redemption_spends(
+		&self,
+		smart_contract_address: &str,
+		policy_id: [u8; 28],
+		asset_name: &[u8],
+		start: &CardanoPosition,
+		end: &CardanoPosition,
+		limit: usize,
+		offset: usize,
+	) -> Result<Vec<RedemptionSpendRow>, ObservationQueryError> {
+		Ok(crate::db::get_redemption_spends(
+			self,
+			smart_contract_address,
+			policy_id,
+			asset_name,
+			start,
+			end,
+			limit,
+			offset,
+		)
+		.await?)
+	}
+
+	async fn get_governance_body_utxo(
+		&self,
+		script_address: &str,
+		policy_id: &PolicyId,
+		block_number: u32,
+	) -> Result<Option<GovernanceBodyUtxoRow>, ObservationQueryError> {
+		Ok(crate::db::get_governance_body_utxo(self, script_address, policy_id, block_number)
+			.await?)
+	}
+}
@@ -30,6 +30,13 @@ pub const DEFAULT_CARDANO_BLOCK_WINDOW_SIZE: u32 = 10000;
 pub struct MidnightCNightObservationInherentDataProvider {
 	pub utxos: Vec<ObservedUtxo>,
 	pub next_cardano_position: CardanoPosition,
+	/// Stable Cardano blocks past `next_cardano_position`, up to the main chain reference block of
+	/// the block. Measured here, so that the observation adapts to how far it lags behind.
+	pub cardano_tip_lag: u32,
+	/// Set when the last consumed Cardano block was rolled back. No inherent data is provided then,
+	/// so that blocks observing the rolled back chain are rejected until the observation is rolled
+	/// back with `rollback_cardano_observation`.
+	pub rolled_back_position: Option<CardanoPosition>,
 }
 
 #[derive(thiserror::Error, sp_runtime::RuntimeDebug)]
@@ -78,6 +85,8 @@ impl MidnightCNightObservationInherentDataProvider {
 					block_timestamp: TimestampUnixMillis(0),
 					tx_index_in_block: 0,
 				},
+				cardano_tip_lag: 0,
+				rolled_back_position: None,
 			})
 		}
 	}
@@ -99,7 +108,9 @@ impl MidnightCNightObservationInherentDataProvider {
 			String::from_utf8(api.get_redemption_validator_address(parent_hash)?)?;
 		let mapping_validator_address =
 			String::from_utf8(api.get_mapping_validator_address(parent_hash)?)?;
+		// Both are adapted on-chain by `process_tokens`, so every importer observes the same range
 		let utxo_capacity = api.get_utxo_capacity_per_block(parent_hash)?;
+		let block_window = api.get_cardano_block_window_size(parent_hash)?;
 
 		let (cnight_policy_id, cnight_asset_name) = api.get_cnight_token_identifier(parent_hash)?;
 		let auth_token_asset_name: String = api
@@ -108,6 +119,25 @@ impl MidnightCNightObservationInherentDataProvider {
 			.map_err(|_| IDPCreationError::AuthTokenAssetNameNotString)?;
 		let cardano_position_start = api.get_next_cardano_position(parent_hash)?;
 
+		// The genesis position may not refer to any block
+		if cardano_position_start.block_hash != McBlockHash([0; 32])
+			&& !data_source
+				.is_block_on_chain(cardano_position_start.block_hash.clone())
+				.await
+				.map_err(IDPCreationError::DataSourceError)?
+		{
+			log::error!(
+				"Cardano block {} consumed by the cNight observation was rolled back. cNight observation is suspended until it is rolled back with `rollback_cardano_observation`",
+				cardano_position_start
+			);
+			return Ok(Self {
+				utxos: vec![],
+				next_cardano_position: cardano_position_start.clone(),
+				cardano_tip_lag: 0,
+				rolled_back_position: Some(cardano_position_start),
+			});
+		}
+
 		let config = CNightAddresses {
 			mapping_validator_address,
 			redemption_validator_address,
@@ -126,11 +156,18 @@ impl MidnightCNightObservationInherentDataProvider {
 				&cardano_position_start,
 				mc_hash,
 				utxo_capacity as usize,
+				block_window,
 			)
 			.await
 			.map_err(IDPCreationError::DataSourceError)?;
 
-		Ok(Self { utxos: observed_utxos.utxos, next_cardano_position: observed_utxos.end })
+		let cardano_tip_lag = observed_utxos.tip_lag();
+		Ok(Self {
+			utxos: observed_utxos.utxos,
+			next_cardano_position: observed_utxos.end,
+			cardano_tip_lag,
+			rolled_back_position: None,
+		})
 	}
 }
 
@@ -140,11 +177,15 @@ impl sp_inherents::InherentDataProvider for MidnightCNightObservationInherentDat
 		&self,
 		inherent_data: &mut sp_inherents::InherentData,
 	) -> Result<(), sp_inherents::Error> {
+		if self.rolled_back_position.is_some() {
+			return Ok(());
+		}
 		inherent_data.put_data(
 			INHERENT_IDENTIFIER,
 			&MidnightObservationTokenMovement {
 				utxos: self.utxos.clone(),
 				next_cardano_position: self.next_cardano_position.clone(),
+				cardano_tip_lag: self.cardano_tip_lag,
 			},
 		)
 	}
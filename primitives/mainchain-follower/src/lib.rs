@@ -27,11 +27,16 @@ pub mod db;
 #[cfg(feature = "std")]
 pub mod data_source;
 
+#[cfg(feature = "std")]
+pub mod chain_sync;
+
 #[cfg(feature = "std")]
 pub use {
+	chain_sync::{ChainSyncFollower, ChainSyncIndex},
 	data_source::{
-		CNightObservationDataSourceMock, FederatedAuthorityObservationDataSourceImpl,
-		FederatedAuthorityObservationDataSourceMock, MidnightCNightObservationDataSourceImpl,
+		CNightObservationDataSourceMock, CNightObservationRecorder, CNightObservationReplay,
+		FederatedAuthorityObservationDataSourceImpl, FederatedAuthorityObservationDataSourceMock,
+		MidnightCNightObservationDataSourceImpl,
 	},
 	inherent_provider::*,
 	partner_chains_db_sync_data_sources,
@@ -51,13 +56,23 @@ pub mod inherent_provider {
 	// Simple wrapper trait for native token observation
 	pub trait MidnightCNightObservationDataSource {
 		// TODO: Change the error type to something explicit
+		/// UTXOs of at most `capacity` transactions, from `start_position` up to `current_tip`,
+		/// within `block_window` Cardano blocks of `start_position`
 		async fn get_utxos_up_to_capacity(
 			&self,
 			config: &CNightAddresses,
 			start_position: &CardanoPosition,
 			current_tip: McBlockHash,
 			capacity: usize,
+			block_window: u32,
 		) -> Result<ObservedUtxos, Box<dyn std::error::Error + Send + Sync>>;
+
+		/// Whether the Cardano block is still on the chain followed by the data source, i.e. it
+		/// hasn't been rolled back
+		async fn is_block_on_chain(
+			&self,
+			block_hash: McBlockHash,
+		) -> Result<bool, Box<dyn std::error::Error + Send + Sync>>;
 	}
 
 	#[async_trait::async_trait]
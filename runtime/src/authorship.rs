@@ -20,6 +20,16 @@ pub fn current_block_author_aura_index<T: pallet_aura::Config>() -> Option<usize
 	pallet_aura::Pallet::<T>::find_author(pre_runtime_digests).map(|i| i as usize)
 }
 
+/// Author of the current block, if its digest carries an Aura pre-runtime digest of a
+/// current committee member.
+pub fn try_current_block_author<
+	T: pallet_aura::Config + pallet_session_validator_management::Config,
+>() -> Option<<T as pallet_session_validator_management::Config>::AuthorityId> {
+	let author = current_block_author_aura_index::<T>()?;
+	pallet_session_validator_management::Pallet::<T>::get_current_authority_round_robin(author)
+		.map(|member| member.authority_id())
+}
+
 pub fn current_block_author<
 	T: pallet_aura::Config + pallet_session_validator_management::Config,
 >() -> <T as pallet_session_validator_management::Config>::AuthorityId {
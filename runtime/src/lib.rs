@@ -48,10 +48,15 @@ pub use frame_support::{
 };
 pub use frame_system::Call as SystemCall;
 use frame_system::{EnsureNone, EnsureRoot};
-use midnight_node_ledger::types::{GasCost, StorageCost, Tx, active_version::LedgerApiError};
+use midnight_node_ledger::types::{
+	ContractStateProof, DustGenerationDetails, GasCost, StorageCost, TransactionSimulationResult,
+	Tx, UtxoInfo, ZswapCommitmentProof, active_version::LedgerApiError,
+};
 use midnight_primitives::BridgeRecipient;
 use midnight_primitives_beefy::BeefyStakes;
-use midnight_primitives_cnight_observation::CardanoPosition;
+use midnight_primitives_cnight_observation::{
+	CardanoPosition, CardanoRewardAddressBytes, DustPublicKeyBytes, MappingEntry,
+};
 use opaque::{CrossChainKey, SessionKeys};
 pub use pallet_cnight_observation::Call as CNightObservationCall;
 use pallet_grandpa::AuthorityId as GrandpaId;
@@ -63,8 +68,9 @@ pub use pallet_version::VERSION_ID;
 use parity_scale_codec::Encode;
 use session_manager::ValidatorManagementSessionManager;
 use sidechain_domain::{
-	MainchainAddress, PermissionedCandidateData, PolicyId, RegistrationData, ScEpochNumber,
-	ScSlotNumber, StakeDelegation, StakePoolPublicKey, UtxoId, byte_string::ByteString,
+	MainchainAddress, McTxHash, PermissionedCandidateData, PolicyId, RegistrationData,
+	ScEpochNumber, ScSlotNumber, StakeDelegation, StakePoolPublicKey, UtxoId,
+	byte_string::ByteString,
 };
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -79,8 +85,6 @@ use sp_partner_chains_bridge::{
 	BridgeDataCheckpoint, BridgeTransferV1, MainChainScripts as BridgeMainChainScripts,
 };
 
-//#[cfg(feature = "experimental")]
-//use sp_block_rewards::GetBlockRewardPoints;
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
 use sp_runtime::traits::{Convert, Keccak256};
@@ -126,7 +130,8 @@ mod session_manager;
 use check_call_filter::CheckCallFilter;
 use constants::time_units::DAYS;
 use pallet_federated_authority::{
-	AuthorityBody, FederatedAuthorityEnsureProportionAtLeast, FederatedAuthorityOriginManager,
+	AuthorityBody, EnsureFederatedAuthorityMotion, FederatedAuthorityEnsureProportionAtLeast,
+	FederatedAuthorityOriginManager,
 };
 use runtime_common::governance::{AlwaysNo, MembershipHandler, MembershipObservationHandler};
 
@@ -527,8 +532,9 @@ parameter_types! {
 
 impl pallet_migrations::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	// unreleased: `cnight_mappingsByDustKey` looks mappings up by DUST public key
 	#[cfg(not(feature = "runtime-benchmarks"))]
-	type Migrations = ();
+	type Migrations = (migrations::IndexCNightMappingsByDustPublicKey<Runtime>,);
 	// Benchmarks need mocked migrations to guarantee that they succeed.
 	#[cfg(feature = "runtime-benchmarks")]
 	type Migrations = pallet_migrations::mock_helpers::MockedMigrations;
@@ -640,53 +646,31 @@ impl pallet_sidechain::Config for Runtime {
 	type OnNewEpoch = LogBeneficiaries;
 }
 
-pub const BLOCK_REWARD_POINTS: u128 = 500_000;
-
 pub type BeneficiaryId = midnight_node_ledger::types::Hash;
 pub type BlockRewardPoints = u128;
 pub type BlockReward = (BlockRewardPoints, Option<BeneficiaryId>);
 
-/*
-#[cfg(feature = "experimental")]
-pub struct LedgerBlockRewardPoints;
-#[cfg(feature = "experimental")]
-impl GetBlockRewardPoints<BlockRewardPoints> for LedgerBlockRewardPoints {
-	fn get_block_reward() -> BlockRewardPoints {
-		BLOCK_REWARD_POINTS
-	}
-}
-*/
-
+/// Block reward of the emission schedule, paid to the ledger address registered by the
+/// Aura author of the block
 pub struct LedgerBlockReward;
 impl Get<BlockReward> for LedgerBlockReward {
-	#[cfg(feature = "experimental")]
 	fn get() -> BlockReward {
-		/*
-		(
-			<Runtime as pallet_block_rewards::Config>::GetBlockRewardPoints::get_block_reward(),
-			pallet_block_rewards::CurrentBlockBeneficiary::<Runtime>::get(),
-		)
-		*/
-		(0, None)
-	}
-	#[cfg(not(feature = "experimental"))]
-	fn get() -> BlockReward {
-		(0, None)
+		let reward = Midnight::scheduled_block_reward();
+		if reward == 0 {
+			return (0, None);
+		}
+		let beneficiary = authorship::try_current_block_author::<Runtime>()
+			.and_then(|author| Midnight::reward_beneficiary(&author));
+		(reward, beneficiary)
 	}
 }
 
-/*
-#[cfg(feature = "experimental")]
-impl pallet_block_rewards::Config for Runtime {
-	type BeneficiaryId = BeneficiaryId;
-	type BlockRewardPoints = BlockRewardPoints;
-	type GetBlockRewardPoints = LedgerBlockRewardPoints;
-}
-*/
-
 /// Configure the pallet-midnight in pallets/midnight.
 impl pallet_midnight::Config for Runtime {
 	type BlockReward = LedgerBlockReward;
+	type BlockAuthorId = CrossChainPublic;
+	type MidnightSystemTransactionExecutor = MidnightSystem;
+	type WeightInfo = pallet_midnight::weights::SubstrateWeight<Runtime>;
 	type SlotDuration = ConstU64<SLOT_DURATION>;
 }
 
@@ -694,6 +678,7 @@ impl pallet_midnight::Config for Runtime {
 impl pallet_midnight_system::Config for Runtime {
 	type LedgerStateProviderMut = Midnight;
 	type LedgerBlockContextProvider = Midnight;
+	type ParametersUpdateOrigin = EnsureFederatedAuthorityMotion<Runtime>;
 }
 
 pub struct ValidatorSet;
@@ -869,6 +854,7 @@ impl pallet_partner_chains_bridge::TransferHandler<BridgeRecipient>
 
 impl pallet_cnight_observation::Config for Runtime {
 	type MidnightSystemTransactionExecutor = MidnightSystem;
+	type WeightInfo = pallet_cnight_observation::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -1035,10 +1021,22 @@ pub type Executive = frame_executive::Executive<
 	Migrations,
 >;
 
+parameter_types! {
+	pub const MidnightPalletName: &'static str = "Midnight";
+	pub const ConfigurableTransactionSizeWeightName: &'static str =
+		"ConfigurableTransactionSizeWeight";
+}
+
 /// Migrations to apply on runtime upgrade.
 pub type Migrations = (
 	// unreleased
 	migrations::IncrementSudoSufficients<Runtime>,
+	// unreleased: `send_mn_transaction` is weighed by `pallet_midnight::weights`
+	frame_support::migrations::RemoveStorage<
+		MidnightPalletName,
+		ConfigurableTransactionSizeWeightName,
+		ParityDbWeight,
+	>,
 );
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -1052,6 +1050,7 @@ mod benches {
 		[pallet_migrations, MultiBlockMigrations]
 		[pallet_session_validator_management, SessionCommitteeManagement]
 		[pallet_midnight, Midnight]
+		[pallet_cnight_observation, CNightObservation]
 		[pallet_federated_authority, FederatedAuthority]
 		[pallet_federated_authority_observation, FederatedAuthorityObservation]
 	);
@@ -1079,7 +1078,20 @@ impl_runtime_apis! {
 		}
 
 		fn execute_block(block: Block) {
-			Executive::execute_block(block);
+			let midnight_txs = block
+				.extrinsics
+				.iter()
+				.filter_map(|xt| match &xt.function {
+					RuntimeCall::Midnight(MidnightCall::send_mn_transaction { midnight_tx }) =>
+						Some(midnight_tx.clone()),
+					_ => None,
+				})
+				.collect();
+			Midnight::execute_with_pre_verified_transactions(
+				block.header.parent_hash,
+				midnight_txs,
+				|| Executive::execute_block(block),
+			);
 		}
 
 		fn initialize_block(header: &<Block as BlockT>::Header) -> sp_runtime::ExtrinsicInclusionMode {
@@ -1115,6 +1127,21 @@ impl_runtime_apis! {
 		fn get_zswap_state_root() -> Result<Vec<u8>, LedgerApiError> {
 			Midnight::get_zswap_state_root()
 		}
+		fn simulate_transaction(midnight_transaction: Vec<u8>) -> Result<TransactionSimulationResult, LedgerApiError> {
+			Midnight::simulate_transaction(&midnight_transaction)
+		}
+		fn get_unshielded_utxos(address: Vec<u8>) -> Result<Vec<UtxoInfo>, LedgerApiError> {
+			Midnight::get_unshielded_utxos(&address)
+		}
+		fn get_dust_generation_info(nonce: Vec<u8>) -> Result<Option<DustGenerationDetails>, LedgerApiError> {
+			Midnight::get_dust_generation_info(&nonce)
+		}
+		fn get_zswap_commitment_proof(commitment: Vec<u8>) -> Result<Option<ZswapCommitmentProof>, LedgerApiError> {
+			Midnight::get_zswap_commitment_proof(&commitment)
+		}
+		fn get_contract_state_proof(contract_address: Vec<u8>) -> Result<Option<ContractStateProof>, LedgerApiError> {
+			Midnight::get_contract_state_proof(&contract_address)
+		}
 	}
 
 	impl sp_partner_chains_bridge::TokenBridgeIDPRuntimeApi<Block> for Runtime {
@@ -1531,6 +1558,22 @@ impl_runtime_apis! {
 		fn get_auth_token_asset_name() -> Vec<u8> {
 			pallet_cnight_observation::MainChainAuthTokenAssetName::<Runtime>::get().into_inner()
 		}
+
+		fn get_registration(cardano_reward_address: CardanoRewardAddressBytes) -> Option<DustPublicKeyBytes> {
+			CNightObservation::get_registration(&cardano_reward_address)
+		}
+
+		fn get_mappings(cardano_reward_address: CardanoRewardAddressBytes) -> Vec<MappingEntry> {
+			pallet_cnight_observation::Mappings::<Runtime>::get(cardano_reward_address)
+		}
+
+		fn get_mappings_by_dust_public_key(dust_public_key: DustPublicKeyBytes) -> Vec<MappingEntry> {
+			CNightObservation::get_mappings_by_dust_public_key(&dust_public_key)
+		}
+
+		fn get_utxo_owner(utxo_tx_hash: McTxHash, utxo_index: u16) -> Option<DustPublicKeyBytes> {
+			CNightObservation::get_utxo_owner(&utxo_tx_hash, utxo_index)
+		}
 	}
 
 	impl sp_governed_map::GovernedMapIDPApi<Block> for Runtime {
@@ -1614,6 +1657,21 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn midnight_transactions_fit_in_a_block() {
+		use pallet_midnight::weights::WeightInfo;
+
+		// One intent with a shielded input and output, and no contract call
+		let weight = <Runtime as pallet_midnight::Config>::WeightInfo::send_mn_transaction(
+			10_000, 1, 1, 1, 0,
+		);
+		let max_extrinsic = BlockWeights::get()
+			.get(frame_support::dispatch::DispatchClass::Normal)
+			.max_extrinsic
+			.expect("Normal extrinsics have a maximum weight");
+		assert!(weight.saturating_mul(10).all_lt(max_extrinsic));
+	}
+
 	// The set committee takes effect next session. Committee can be set for 1 session in advance.
 	#[test]
 	fn check_grandpa_authorities_rotation() {
@@ -1759,6 +1817,41 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn resolves_reward_beneficiary_of_block_author() {
+		new_test_ext().execute_with(|| {
+			advance_block();
+			let author = current_block_author_keys().expect("Aura block should have an author");
+			let author_id = author.cross_chain.public();
+			assert_eq!(
+				crate::authorship::try_current_block_author::<Test>(),
+				Some(author_id.clone())
+			);
+			assert_eq!(Midnight::reward_beneficiary(&author_id), None);
+
+			let beneficiary = [42u8; 32];
+			assert_ok!(Midnight::set_reward_beneficiary(
+				RawOrigin::Root.into(),
+				author_id.clone(),
+				Some(beneficiary)
+			));
+			assert_eq!(Midnight::reward_beneficiary(&author_id), Some(beneficiary));
+
+			// Another author doesn't get the rewards
+			advance_block();
+			let next_author = current_block_author_keys().unwrap().cross_chain.public();
+			assert_ne!(next_author, author_id);
+			assert_eq!(Midnight::reward_beneficiary(&next_author), None);
+		})
+	}
+
+	#[test]
+	fn block_without_aura_digest_has_no_author() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(crate::authorship::try_current_block_author::<Test>(), None);
+		})
+	}
+
 	pub fn set_committee_through_inherent_data(
 		expected_authorities: &[TestKeys],
 	) -> PostDispatchInfo {
@@ -14,9 +14,11 @@
 //! Runtime migrations
 
 use frame_support::{
-	traits::{Get, OnRuntimeUpgrade},
-	weights::Weight,
+	migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	weights::{Weight, WeightMeter},
 };
+use midnight_primitives_cnight_observation::CardanoRewardAddressBytes;
 use sp_std::marker::PhantomData;
 
 #[cfg(feature = "try-runtime")]
@@ -99,3 +101,90 @@ where
 		Ok(())
 	}
 }
+
+/// Multi-block migration indexing the existing cNight observation mappings by DUST public key
+///
+/// Each step indexes the mappings of as many Cardano reward addresses as its weight allows,
+/// starting after the address in the cursor. The storage version of
+/// `pallet_cnight_observation` is bumped to 1 once every address is indexed.
+pub struct IndexCNightMappingsByDustPublicKey<T>(PhantomData<T>);
+
+impl<T> SteppedMigration for IndexCNightMappingsByDustPublicKey<T>
+where
+	T: pallet_cnight_observation::Config,
+{
+	type Cursor = CardanoRewardAddressBytes;
+	type Identifier = MigrationId<17>;
+
+	fn id() -> Self::Identifier {
+		MigrationId { pallet_id: *b"CNightObservation", version_from: 0, version_to: 1 }
+	}
+
+	fn step(
+		mut cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+		type Mappings<T> = pallet_cnight_observation::Mappings<T>;
+		type Pallet<T> = pallet_cnight_observation::Pallet<T>;
+
+		if cursor.is_none() {
+			let required = T::DbWeight::get().reads(1);
+			if meter.try_consume(required).is_err() {
+				return Err(SteppedMigrationError::InsufficientWeight { required });
+			}
+			if Pallet::<T>::on_chain_storage_version() >= 1 {
+				return Ok(None);
+			}
+			log::info!("🔄 Running migration: IndexCNightMappingsByDustPublicKey");
+		}
+
+		let mut indexed = 0;
+		loop {
+			let mut iter = match cursor {
+				Some(last) => Mappings::<T>::iter_from(Mappings::<T>::hashed_key_for(last)),
+				None => Mappings::<T>::iter(),
+			};
+			let Some((cardano_reward_address, mappings)) = iter.next() else {
+				StorageVersion::new(1).put::<Pallet<T>>();
+				log::info!("✅ Indexed the cNight mappings by DUST public key");
+				return Ok(None);
+			};
+
+			// The mappings of an address are indexed at once, in the next step if they don't fit
+			let required = T::DbWeight::get().reads_writes(1, mappings.len() as u64);
+			if meter.try_consume(required).is_err() {
+				if indexed == 0 {
+					return Err(SteppedMigrationError::InsufficientWeight { required });
+				}
+				return Ok(cursor);
+			}
+			for mapping in mappings {
+				pallet_cnight_observation::MappingsByDustPublicKey::<T>::insert(
+					&mapping.dust_public_key,
+					cardano_reward_address,
+					(),
+				);
+			}
+			indexed += 1;
+			cursor = Some(cardano_reward_address);
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(_state: sp_std::vec::Vec<u8>) -> Result<(), TryRuntimeError> {
+		for (cardano_reward_address, mappings) in pallet_cnight_observation::Mappings::<T>::iter() {
+			for mapping in mappings {
+				if !pallet_cnight_observation::MappingsByDustPublicKey::<T>::contains_key(
+					&mapping.dust_public_key,
+					cardano_reward_address,
+				) {
+					return Err(TryRuntimeError::Other(
+						"cNight mapping not indexed by DUST public key",
+					));
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
@@ -421,6 +421,12 @@ impl TestKeys {
 	}
 }
 
+/// Keys of the committee member authoring the current block
+pub fn current_block_author_keys() -> Option<TestKeys> {
+	let author = crate::authorship::try_current_block_author::<Test>()?;
+	[alice(), bob()].into_iter().find(|keys| keys.cross_chain.public() == author)
+}
+
 pub fn pair_from_seed<P: Pair>(seed: &str) -> P {
 	<P as Pair>::from_string(seed, None).expect("static values are valid; qed")
 }
@@ -166,3 +166,15 @@ pub fn utxo_id_decode(input: &str) -> Result<UtxoId, clap::Error> {
 		err
 	})
 }
+
+pub fn viewing_key(input: &str) -> Result<ViewingKeyWallet, clap::Error> {
+	ViewingKeyWallet::from_viewing_key(input).map_err(|error| {
+		let mut err = clap::Error::new(clap::error::ErrorKind::ValueValidation);
+		err.insert(
+			clap::error::ContextKind::Custom,
+			clap::error::ContextValue::String(format!("invalid viewing key: {:?}", error)),
+		);
+
+		err
+	})
+}
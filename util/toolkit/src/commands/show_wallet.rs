@@ -1,8 +1,8 @@
 use std::collections::HashMap;
 
 use crate::{
-	DB, DefaultDB, HRP_CREDENTIAL_SHIELDED, LedgerContext, ProofType, SignatureType, Source,
-	TxGenerator, Utxo, Wallet, WalletAddress, WalletSeed,
+	DB, DefaultDB, HRP_CREDENTIAL_SHIELDED, LedgerContext, LedgerState, ProofType, SignatureType,
+	Source, TxGenerator, Utxo, Wallet, WalletAddress, WalletSeed,
 };
 use clap::Args;
 use hex::ToHex;
@@ -51,6 +51,27 @@ pub struct ShowWalletArgs {
 	dry_run: bool,
 }
 
+/// Coins, unshielded UTXOs and DUST UTXOs of a wallet, including private state
+pub fn wallet_info_json(
+	wallet: &Wallet<DefaultDB>,
+	ledger_state: &LedgerState<DefaultDB>,
+) -> WalletInfoJson {
+	let utxos = wallet.unshielded_utxos(ledger_state).into_iter().map(|u| u.into()).collect();
+	let coins = wallet
+		.shielded
+		.state
+		.coins
+		.iter()
+		.map(|(k, v)| (serialize_untagged(&k).unwrap().encode_hex(), (*v).into()))
+		.collect();
+	let dust_utxos = wallet
+		.dust
+		.dust_local_state
+		.as_ref()
+		.map_or(vec![], |s| s.utxos().map(|s| s.into()).collect());
+	WalletInfoJson { coins, dust_utxos, utxos }
+}
+
 pub async fn execute(
 	args: ShowWalletArgs,
 ) -> Result<ShowWalletResult<DefaultDB>, Box<dyn std::error::Error + Send + Sync>> {
@@ -81,24 +102,7 @@ pub async fn execute(
 					let utxos = wallet.unshielded_utxos(ledger_state);
 					ShowWalletResult::Debug(WalletInfo { wallet: wallet.clone(), utxos })
 				} else {
-					let utxos = wallet
-						.unshielded_utxos(ledger_state)
-						.into_iter()
-						.map(|u| u.into())
-						.collect();
-					let coins = wallet
-						.shielded
-						.state
-						.coins
-						.iter()
-						.map(|(k, v)| (serialize_untagged(&k).unwrap().encode_hex(), (*v).into()))
-						.collect();
-					let dust_utxos = wallet
-						.dust
-						.dust_local_state
-						.as_ref()
-						.map_or(vec![], |s| s.utxos().map(|s| s.into()).collect());
-					ShowWalletResult::Json(WalletInfoJson { coins, dust_utxos, utxos })
+					ShowWalletResult::Json(wallet_info_json(wallet, ledger_state))
 				}
 			})
 		}))
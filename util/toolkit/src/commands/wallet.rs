@@ -0,0 +1,549 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+	collections::BTreeMap,
+	io::Write,
+	net::SocketAddr,
+	path::{Path, PathBuf},
+	sync::Arc,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+	DefaultDB, HRP_CREDENTIAL_SHIELDED, IntoWalletAddress, LedgerContext, LedgerContextSnapshot,
+	ProofType, SignatureType, Source, Timestamp, TxGenerator, ViewingKeyWallet, WalletAddress,
+	WalletSeed, key_fingerprint,
+};
+use clap::{Args, Subcommand};
+use midnight_node_toolkit::{
+	cli_parsers::{self as cli},
+	serde_def::{CoinInfoSer, SourceBlockTransactions},
+};
+use tokio::{
+	io::{AsyncReadExt, AsyncWriteExt},
+	net::{TcpListener, TcpStream},
+	sync::RwLock,
+};
+
+use super::show_wallet::{WalletInfoJson, wallet_info_json};
+
+/// Poll interval used when serving wallet states without an explicit `--interval`
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 6;
+
+/// Time a client of the wallet states endpoint has to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Subcommand)]
+pub enum WalletCommand {
+	/// Keep a persistent wallet database in sync with the chain and output the state of the
+	/// watched wallets as JSON, once or via a local HTTP endpoint
+	Watch(WatchArgs),
+}
+
+#[derive(Args)]
+pub struct WalletArgs {
+	#[clap(subcommand)]
+	command: WalletCommand,
+}
+
+#[derive(Args)]
+pub struct WatchArgs {
+	#[command(flatten)]
+	source: Source,
+	/// Seed of a wallet to watch, including private state. Can be repeated
+	#[arg(long = "seed", value_parser = cli::wallet_seed_decode)]
+	seeds: Vec<WalletSeed>,
+	/// Viewing key of a shielded wallet to watch, as printed by `show-viewing-key`. Only the
+	/// received coins are known, not whether they were spent. Can be repeated
+	#[arg(long = "viewing-key", value_parser = cli::viewing_key)]
+	viewing_keys: Vec<ViewingKeyWallet>,
+	/// Unshielded address to watch. Can be repeated
+	#[arg(long = "address", value_parser = cli::wallet_address)]
+	addresses: Vec<WalletAddress>,
+	/// Wallet database file. Contains the private state of the watched wallets but not their
+	/// keys, and is only readable by its owner
+	#[arg(long, default_value = ".wallet_db")]
+	db: PathBuf,
+	/// Poll the source for new blocks every `interval` seconds. Syncs once if not set
+	#[arg(long)]
+	interval: Option<u64>,
+	/// Serve the wallet states as JSON on this local address, e.g. `127.0.0.1:8099`
+	#[arg(long)]
+	listen: Option<SocketAddr>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WalletWatchError {
+	#[error("no wallet to watch, pass at least one `--seed`, `--viewing-key` or `--address`")]
+	NoWallets,
+	#[error("`--dust-warp` is not supported when watching wallets")]
+	DustWarp,
+	#[error("unavailable information for {0} - secret key needed")]
+	ShieldedAddress(String),
+	#[error("failed to read or write the wallet database")]
+	Io(#[from] std::io::Error),
+	#[error("failed to decode the wallet database")]
+	Decode(#[from] bincode::Error),
+}
+
+/// Content of the wallet database file
+#[derive(serde::Serialize, serde::Deserialize)]
+struct WalletDb {
+	network_id: String,
+	/// Sorted `key_fingerprint`s of the watched seeds and viewing keys
+	fingerprints: Vec<String>,
+	/// Number of source blocks applied to `context`
+	applied_blocks: usize,
+	/// Last applied block, to check that the source still follows the same chain
+	last_block: Option<BlockAnchor>,
+	context: LedgerContextSnapshot,
+}
+
+/// Identifies a source block, which doesn't carry its own hash
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct BlockAnchor {
+	parent_block_hash: [u8; 32],
+	tblock: u64,
+	state_root: Option<Vec<u8>>,
+}
+
+impl BlockAnchor {
+	fn new(block: &SourceBlockTransactions<SignatureType, ProofType>) -> Self {
+		Self {
+			parent_block_hash: block.context.parent_block_hash.0,
+			tblock: block.context.tblock.to_secs(),
+			state_root: block.state_root.clone(),
+		}
+	}
+}
+
+/// Wallets followed by `watch`, up to the last applied source block
+struct WatchState {
+	context: LedgerContext<DefaultDB>,
+	applied_blocks: usize,
+	last_block: Option<BlockAnchor>,
+}
+
+impl WatchState {
+	fn new(network_id: &str, seeds: &[WalletSeed], viewing_keys: &[ViewingKeyWallet]) -> Self {
+		let context = LedgerContext::new_from_wallet_seeds(network_id, seeds);
+		*context.viewing_keys.lock().expect("Error locking `LedgerContext` viewing keys") =
+			viewing_keys.to_vec();
+		Self { context, applied_blocks: 0, last_block: None }
+	}
+
+	/// Index of the first source block to fetch. The last applied block is fetched again to
+	/// check that the source still contains it
+	fn fetch_from(&self) -> usize {
+		self.applied_blocks.saturating_sub(1)
+	}
+
+	/// Applies the blocks following the applied ones, `blocks` starting at source index
+	/// `offset`. Returns `false` without applying anything if the source no longer contains the
+	/// last applied block, e.g. after a reorg
+	fn apply_blocks(
+		&mut self,
+		offset: usize,
+		blocks: Vec<SourceBlockTransactions<SignatureType, ProofType>>,
+	) -> bool {
+		if let Some(last_block) = &self.last_block {
+			let fetched = (self.applied_blocks - 1).checked_sub(offset).and_then(|i| blocks.get(i));
+			if fetched.map(BlockAnchor::new).as_ref() != Some(last_block) {
+				return false;
+			}
+		}
+
+		for block in blocks.into_iter().skip(self.applied_blocks.saturating_sub(offset)) {
+			self.last_block = Some(BlockAnchor::new(&block));
+			self.context
+				.update_from_block(block.transactions, block.context, block.state_root);
+			self.applied_blocks += 1;
+		}
+		true
+	}
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct WatchedWalletJson {
+	#[serde(flatten)]
+	pub info: WalletInfoJson,
+	pub dust_balance: Option<u128>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct WatchedViewingKeyJson {
+	/// Coins received by the wallet by commitment, including spent ones
+	pub received_coins: BTreeMap<String, CoinInfoSer>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct WatchJson {
+	/// Number of blocks applied to the wallet database
+	pub blocks: usize,
+	/// Watched wallets by unshielded address
+	pub wallets: BTreeMap<String, WatchedWalletJson>,
+	/// Watched viewing keys by `key_fingerprint`
+	pub viewing_keys: BTreeMap<String, WatchedViewingKeyJson>,
+}
+
+pub async fn execute(args: WalletArgs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+	match args.command {
+		WalletCommand::Watch(args) => watch(args).await,
+	}
+}
+
+async fn watch(args: WatchArgs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+	if args.seeds.is_empty() && args.viewing_keys.is_empty() && args.addresses.is_empty() {
+		return Err(WalletWatchError::NoWallets.into());
+	}
+	if args.source.dust_warp {
+		return Err(WalletWatchError::DustWarp.into());
+	}
+	if let Some(address) = args
+		.addresses
+		.iter()
+		.find(|a| a.human_readable_part().contains(HRP_CREDENTIAL_SHIELDED))
+	{
+		return Err(WalletWatchError::ShieldedAddress(address.to_bech32()).into());
+	}
+
+	let interval = args.interval.or(args.listen.map(|_| DEFAULT_POLL_INTERVAL_SECS));
+	let latest_json = Arc::new(RwLock::new(String::new()));
+	if let Some(listen) = args.listen {
+		let listener = TcpListener::bind(listen).await?;
+		println!("Serving wallet states on http://{listen}/wallets");
+		tokio::spawn(serve(listener, latest_json.clone()));
+	}
+
+	let src = TxGenerator::<SignatureType, ProofType>::source(args.source, false).await?;
+	let fingerprints = fingerprints(&args.seeds, &args.viewing_keys);
+
+	let mut watched: Option<(String, WatchState)> = None;
+	loop {
+		let fetch_from = watched.as_ref().map(|(_, state)| state.fetch_from()).unwrap_or(0);
+		let source_blocks = src.get_txs_from(fetch_from).await?;
+
+		if watched.is_none() {
+			let network_id = source_blocks.network().to_string();
+			let state =
+				load_db(&args.db, &network_id, &fingerprints, &args.seeds, &args.viewing_keys)?;
+			watched = Some((network_id, state));
+		}
+		let (network_id, state) = watched.as_mut().expect("wallet database is loaded");
+		let applied_blocks = state.applied_blocks;
+		if !state.apply_blocks(fetch_from, source_blocks.blocks) {
+			println!("The source no longer contains the last applied block, rebuilding");
+			*state = WatchState::new(network_id, &args.seeds, &args.viewing_keys);
+			continue;
+		}
+		// Nothing to write down if the source has no new blocks
+		if state.applied_blocks != applied_blocks {
+			save_db(&args.db, network_id, &fingerprints, state)?;
+		}
+
+		let json = watch_json(state, network_id, &args.seeds, &args.addresses);
+		let json = serde_json::to_string_pretty(&json)?;
+		if args.listen.is_some() {
+			*latest_json.write().await = json;
+		} else {
+			println!("{json}");
+		}
+
+		match interval {
+			Some(interval) => tokio::time::sleep(Duration::from_secs(interval)).await,
+			None => return Ok(()),
+		}
+	}
+}
+
+fn fingerprints(seeds: &[WalletSeed], viewing_keys: &[ViewingKeyWallet]) -> Vec<String> {
+	let seeds = seeds.iter().map(|seed| key_fingerprint(seed.as_bytes()));
+	let viewing_keys = viewing_keys.iter().map(|vk| key_fingerprint(vk.viewing_key.as_bytes()));
+	let mut fingerprints: Vec<String> = seeds.chain(viewing_keys).collect();
+	fingerprints.sort();
+	fingerprints
+}
+
+/// Restores the watched wallets from the wallet database, or starts anew if the database
+/// doesn't exist or was built for another network or set of wallets
+fn load_db(
+	path: &Path,
+	network_id: &str,
+	fingerprints: &[String],
+	seeds: &[WalletSeed],
+	viewing_keys: &[ViewingKeyWallet],
+) -> Result<WatchState, WalletWatchError> {
+	if path.exists() {
+		let db: WalletDb = bincode::deserialize(&std::fs::read(path)?)?;
+		if db.network_id == network_id && db.fingerprints == fingerprints {
+			let context = LedgerContext::from_snapshot(&db.context, seeds, viewing_keys)?;
+			return Ok(WatchState {
+				context,
+				applied_blocks: db.applied_blocks,
+				last_block: db.last_block,
+			});
+		}
+		println!(
+			"Wallet database {} doesn't match the watched wallets, rebuilding",
+			path.display()
+		);
+	}
+
+	Ok(WatchState::new(network_id, seeds, viewing_keys))
+}
+
+fn save_db(
+	path: &Path,
+	network_id: &str,
+	fingerprints: &[String],
+	state: &WatchState,
+) -> Result<(), WalletWatchError> {
+	let db = WalletDb {
+		network_id: network_id.to_string(),
+		fingerprints: fingerprints.to_vec(),
+		applied_blocks: state.applied_blocks,
+		last_block: state.last_block.clone(),
+		context: state.context.snapshot()?,
+	};
+
+	// Write to a temporary file next to the database and move it in place, so that an
+	// interrupted write never leaves a truncated database behind
+	let mut tmp_path = path.as_os_str().to_owned();
+	tmp_path.push(".tmp");
+	let tmp_path = PathBuf::from(tmp_path);
+	write_private(&tmp_path, &bincode::serialize(&db)?)?;
+	std::fs::rename(tmp_path, path)?;
+	Ok(())
+}
+
+/// Writes a new file only readable and writable by its owner and flushes it to disk
+fn write_private(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+	match std::fs::remove_file(path) {
+		Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+		_ => {},
+	}
+
+	let mut options = std::fs::OpenOptions::new();
+	options.write(true).create_new(true);
+	#[cfg(unix)]
+	std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+	let mut file = options.open(path)?;
+	file.write_all(bytes)?;
+	file.sync_all()
+}
+
+fn watch_json(
+	state: &WatchState,
+	network_id: &str,
+	seeds: &[WalletSeed],
+	addresses: &[WalletAddress],
+) -> WatchJson {
+	let context = &state.context;
+	let now = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.expect("Time went backwards")
+		.as_secs();
+	let timestamp = Timestamp::from_secs(now);
+
+	let mut wallets = BTreeMap::new();
+	context.with_ledger_state(|ledger_state| {
+		for seed in seeds {
+			context.with_wallet_from_seed(*seed, |wallet| {
+				let address = wallet.unshielded.address(network_id).to_bech32();
+				let dust_balance =
+					wallet.dust.dust_local_state.as_ref().map(|s| s.wallet_balance(timestamp));
+				let info = wallet_info_json(wallet, ledger_state);
+				wallets.insert(address, WatchedWalletJson { info, dust_balance });
+			});
+		}
+	});
+	for address in addresses {
+		let utxos = context.utxos(address.clone()).into_iter().map(|u| u.into()).collect();
+		let info =
+			WalletInfoJson { coins: Default::default(), utxos, dust_utxos: Default::default() };
+		wallets.insert(address.to_bech32(), WatchedWalletJson { info, dust_balance: None });
+	}
+
+	let viewing_keys = context
+		.viewing_keys
+		.lock()
+		.expect("Error locking `LedgerContext` viewing keys")
+		.iter()
+		.map(|viewing_key| {
+			let received_coins = viewing_key
+				.received_coins
+				.iter()
+				.map(|(commitment, coin)| (commitment.clone(), (*coin).into()))
+				.collect();
+			(
+				key_fingerprint(viewing_key.viewing_key.as_bytes()),
+				WatchedViewingKeyJson { received_coins },
+			)
+		})
+		.collect();
+
+	WatchJson { blocks: state.applied_blocks, wallets, viewing_keys }
+}
+
+/// Minimal HTTP server answering `GET /wallets` with the latest wallet states
+async fn serve(listener: TcpListener, latest_json: Arc<RwLock<String>>) {
+	loop {
+		let Ok((stream, _)) = listener.accept().await else {
+			continue;
+		};
+		// A slow or idle client must not hold up the others
+		tokio::spawn(respond(stream, latest_json.clone()));
+	}
+}
+
+async fn respond(mut stream: TcpStream, latest_json: Arc<RwLock<String>>) {
+	let mut request = [0u8; 1024];
+	let Ok(Ok(len)) = tokio::time::timeout(REQUEST_TIMEOUT, stream.read(&mut request)).await else {
+		return;
+	};
+	let request = String::from_utf8_lossy(&request[..len]);
+
+	let response = if request.starts_with("GET /wallets ") || request.starts_with("GET / ") {
+		let json = latest_json.read().await;
+		format!(
+			"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+			json.len(),
+			*json
+		)
+	} else {
+		"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+	};
+	let _ = tokio::time::timeout(REQUEST_TIMEOUT, stream.write_all(response.as_bytes())).await;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{HashOutput, ShieldedWallet};
+	use midnight_node_toolkit::{
+		serde_def::SourceTransactions,
+		tx_generator::source::{GetTxs, GetTxsFromFile},
+	};
+
+	fn td(filepath: &str) -> String {
+		[env!("CARGO_MANIFEST_DIR"), "/test-data/", filepath].concat()
+	}
+
+	fn seed() -> WalletSeed {
+		WalletSeed::try_from_hex_str(
+			"0000000000000000000000000000000000000000000000000000000000000001",
+		)
+		.unwrap()
+	}
+
+	fn watch_args(db_path: &Path, viewing_keys: Vec<ViewingKeyWallet>) -> WatchArgs {
+		WatchArgs {
+			source: Source {
+				src_url: None,
+				fetch_concurrency: 1,
+				src_files: Some(vec![td("genesis/genesis_block_undeployed.mn")]),
+				dust_warp: false,
+			},
+			seeds: vec![seed()],
+			viewing_keys,
+			addresses: vec![],
+			db: db_path.to_path_buf(),
+			interval: None,
+			listen: None,
+		}
+	}
+
+	async fn source_blocks() -> Vec<SourceBlockTransactions<SignatureType, ProofType>> {
+		let src: GetTxsFromFile<SignatureType, ProofType> = GetTxsFromFile::new(
+			vec![td("genesis/genesis_block_undeployed.mn")],
+			"mn".to_string(),
+			false,
+		);
+		src.get_txs_from(0).await.unwrap().blocks
+	}
+
+	#[tokio::test]
+	async fn should_resume_from_wallet_database() {
+		let db_dir = tempfile::tempdir().unwrap();
+		let db_path = db_dir.path().join("wallet_db");
+
+		watch(watch_args(&db_path, vec![])).await.unwrap();
+		let bytes = std::fs::read(&db_path).unwrap();
+		let db: WalletDb = bincode::deserialize(&bytes).unwrap();
+		assert!(db.applied_blocks > 0);
+		assert!(db.last_block.is_some());
+		// The seed is only stored as a fingerprint
+		let seed_hex = hex::encode(seed().as_bytes());
+		assert!(!bytes.windows(seed_hex.len()).any(|w| w == seed_hex.as_bytes()));
+		assert!(!bytes.windows(32).any(|w| w == seed().as_bytes()));
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			let mode = std::fs::metadata(&db_path).unwrap().permissions().mode();
+			assert_eq!(mode & 0o777, 0o600);
+		}
+
+		let fingerprints = fingerprints(&[seed()], &[]);
+		let state = load_db(&db_path, &db.network_id, &fingerprints, &[seed()], &[]).unwrap();
+		assert_eq!(state.applied_blocks, db.applied_blocks);
+		assert_eq!(state.last_block, db.last_block);
+
+		let json = watch_json(&state, &db.network_id, &[seed()], &[]);
+		let wallet = json.wallets.values().next().unwrap();
+		assert!(!wallet.info.utxos.is_empty());
+		assert!(!wallet.info.coins.is_empty());
+	}
+
+	#[tokio::test]
+	async fn should_only_apply_new_blocks_and_detect_a_changed_chain() {
+		let blocks = source_blocks().await;
+		let network_id = SourceTransactions { blocks: blocks.clone() }.network().to_string();
+		let mut state = WatchState::new(&network_id, &[seed()], &[]);
+
+		assert!(state.apply_blocks(0, blocks.clone()));
+		assert_eq!(state.applied_blocks, blocks.len());
+		assert_eq!(state.fetch_from(), blocks.len() - 1);
+
+		// Fetching again from the last applied block applies nothing new
+		assert!(state.apply_blocks(state.fetch_from(), blocks[state.fetch_from()..].to_vec()));
+		assert_eq!(state.applied_blocks, blocks.len());
+
+		// A source without the last applied block is rejected
+		let mut changed = blocks[state.fetch_from()..].to_vec();
+		changed[0].context.parent_block_hash = HashOutput([1; 32]);
+		assert!(!state.apply_blocks(state.fetch_from(), changed));
+		assert!(!state.apply_blocks(state.fetch_from(), vec![]));
+		assert_eq!(state.applied_blocks, blocks.len());
+	}
+
+	#[tokio::test]
+	async fn should_watch_received_coins_of_a_viewing_key() {
+		let db_dir = tempfile::tempdir().unwrap();
+		let db_path = db_dir.path().join("wallet_db");
+		let blocks = source_blocks().await;
+		let network_id = SourceTransactions { blocks }.network().to_string();
+		let viewing_key = ShieldedWallet::<DefaultDB>::default(seed()).viewing_key(&network_id);
+		let viewing_key = ViewingKeyWallet::from_viewing_key(&viewing_key).unwrap();
+
+		watch(watch_args(&db_path, vec![viewing_key.clone()])).await.unwrap();
+
+		let fingerprints = fingerprints(&[seed()], std::slice::from_ref(&viewing_key));
+		let state =
+			load_db(&db_path, &network_id, &fingerprints, &[seed()], &[viewing_key]).unwrap();
+		let json = watch_json(&state, &network_id, &[seed()], &[]);
+		let wallet = json.wallets.values().next().unwrap();
+		let watched_viewing_key = json.viewing_keys.values().next().unwrap();
+		assert_eq!(watched_viewing_key.received_coins.len(), wallet.info.coins.len());
+	}
+}
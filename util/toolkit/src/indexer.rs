@@ -250,6 +250,12 @@ where
 		s.blocks.clone().into()
 	}
 
+	/// Returns the indexed blocks from index `start` on
+	pub async fn get_blocks_from(&self, start: usize) -> Vec<SourceBlockTransactions<S, P>> {
+		let s = self.state.lock().await;
+		s.blocks.iter().skip(start).cloned().collect()
+	}
+
 	pub async fn start(self: &Arc<Self>) -> Result<IndexerHandle, IndexerError> {
 		if !self.looping.load(std::sync::atomic::Ordering::Relaxed) {
 			self.looping.store(true, std::sync::atomic::Ordering::Relaxed);
@@ -588,11 +594,17 @@ where
 				.inspect_err(|e| eprintln!("Sender dropped: {e}"));
 		}
 
+		let mut latest_number = latest_block.number();
 		loop {
 			select! {
 				// Check block subscriptions
 				Some(block) = blocks_sub.next() => {
 					let block = block?;
+					// The finalized blocks already indexed by the sync are skipped
+					if block.number() <= latest_number {
+						continue;
+					}
+					latest_number = block.number();
 					// Get midnight transactions
 					let mn_block = self.clone().fetch_midnight_block(block.hash()).await?;
 					let block = self.clone().process_block(&mn_block).await?;
@@ -26,6 +26,7 @@ use commands::{
 	show_transaction::{self, ShowTransactionArgs},
 	show_viewing_key::{self, ShowViewingKeyArgs},
 	show_wallet::{self, ShowWalletArgs, ShowWalletResult},
+	wallet::{self, WalletArgs},
 };
 use midnight_node_ledger_helpers::*;
 use std::{
@@ -83,6 +84,8 @@ enum Commands {
 	DustBalance(DustBalanceArgs),
 	/// Show the state of a wallet using it's seed
 	ShowWallet(ShowWalletArgs),
+	/// Wallet database commands
+	Wallet(WalletArgs),
 	/// Show the address of a wallet using it's seed
 	ShowAddress(ShowAddressArgs),
 	/// Show the seed of a wallet
@@ -219,6 +222,7 @@ pub(crate) async fn run_command(
 
 			Ok(())
 		},
+		Commands::Wallet(args) => wallet::execute(args).await,
 		Commands::ShowAddress(args) => {
 			let address = show_address::execute(args);
 			match address {
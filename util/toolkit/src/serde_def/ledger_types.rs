@@ -1,7 +1,7 @@
 use hex::ToHex as _;
 use midnight_node_ledger_helpers::{
-	DustGenerationInfo, InitialNonce, QualifiedDustOutput, QualifiedInfo, Timestamp, Utxo,
-	persistent_commit, serialize_untagged,
+	CoinInfo, DustGenerationInfo, InitialNonce, QualifiedDustOutput, QualifiedInfo, Timestamp,
+	Utxo, persistent_commit, serialize_untagged,
 };
 
 #[derive(Debug, serde::Serialize)]
@@ -80,6 +80,23 @@ impl From<QualifiedInfo> for QualifiedInfoSer {
 	}
 }
 
+#[derive(Debug, serde::Serialize)]
+pub struct CoinInfoSer {
+	pub nonce: String,
+	pub token_type: String,
+	pub value: u128,
+}
+
+impl From<CoinInfo> for CoinInfoSer {
+	fn from(info: CoinInfo) -> Self {
+		Self {
+			nonce: serialize_untagged(&info.nonce).unwrap().encode_hex(),
+			token_type: serialize_untagged(&info.type_).unwrap().encode_hex(),
+			value: info.value,
+		}
+	}
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct DustGenerationInfoSer {
 	pub value: u128,
@@ -21,6 +21,7 @@ mod do_nothing;
 mod register_dust_address;
 mod replace_initial_tx;
 pub mod single_tx;
+mod spec;
 
 pub use batches::*;
 pub use claim_rewards::*;
@@ -31,3 +32,4 @@ pub use contract_maintenance::*;
 pub use do_nothing::*;
 pub use register_dust_address::*;
 pub use replace_initial_tx::*;
+pub use spec::*;
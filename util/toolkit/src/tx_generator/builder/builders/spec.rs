@@ -0,0 +1,538 @@
+// This file is part of midnight-node.
+// Copyright (C) 2025 Midnight Foundation
+// SPDX-License-Identifier: Apache-2.0
+// Licensed under the Apache License, Version 2.0 (the "License");
+// You may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+	collections::{BTreeMap, HashMap, HashSet},
+	path::{Path, PathBuf},
+	str::FromStr,
+	sync::Arc,
+};
+
+use async_trait::async_trait;
+use midnight_node_ledger_helpers::{
+	BuildInput, BuildIntent, BuildOutput, BuildUtxoOutput, BuildUtxoSpend, DefaultDB,
+	FromContext as _, InputInfo, IntentInfo, LedgerContext, OfferInfo, OutputInfo, ProofProvider,
+	ShieldedTokenType, ShieldedWallet, StandardTrasactionInfo, TransactionWithContext,
+	UnshieldedOfferInfo, UnshieldedTokenType, UnshieldedWallet, UtxoOutputInfo, UtxoSpendInfo,
+	WalletAddress, WalletSeed,
+};
+use serde::Deserialize;
+
+use crate::{
+	ProofType, SignatureType, cli_parsers as cli,
+	progress::Spin,
+	serde_def::{DeserializedTransactionsWithContext, SourceTransactions},
+	tx_generator::builder::{BuildTxs, SpecArgs},
+};
+
+const ZERO_TOKEN_TYPE: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SpecBuilderError {
+	#[error("failed to read spec file")]
+	FailedReadingSpec(#[from] std::io::Error),
+	#[error("failed to parse JSON spec file")]
+	FailedParsingJsonSpec(#[from] serde_json::Error),
+	#[error("failed to parse TOML spec file")]
+	FailedParsingTomlSpec(#[from] toml::de::Error),
+	#[error("failed to parse YAML spec file")]
+	FailedParsingYamlSpec(#[from] serde_yaml::Error),
+	#[error("unsupported spec file extension {0:?}, expected `toml`, `yaml` or `json`")]
+	UnsupportedExtension(String),
+	#[error("invalid {field} {value:?}: {reason}")]
+	InvalidValue { field: &'static str, value: String, reason: String },
+	#[error("intents can't use segment 0, it is reserved for the guaranteed section")]
+	GuaranteedIntentSegment,
+	#[error("{0} is spent by more than one transfer, merge their outputs into one entry")]
+	DuplicateSpend(String),
+	#[error("transfer to {0} doesn't match the kind of address")]
+	AddressKindMismatch(String),
+	#[error("insufficient funds in source wallet for {0}")]
+	InsufficientFunds(String),
+	#[error("the spec doesn't contain any transfer")]
+	EmptySpec,
+	#[error("failed to prove tx")]
+	FailedProvingTx(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Declarative description of a transaction
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TxSpec {
+	/// Seed of the wallet funding every transfer
+	pub source_seed: String,
+	/// Seed for the random number generator, hex encoded. Defaults to entropy source
+	#[serde(default)]
+	pub rng_seed: Option<String>,
+	#[serde(default)]
+	pub shielded: Vec<ShieldedTransferSpec>,
+	#[serde(default)]
+	pub unshielded: Vec<UnshieldedTransferSpec>,
+}
+
+/// Shielded transfer, added to the Zswap offer of a segment
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShieldedTransferSpec {
+	/// 0 for the guaranteed offer, any other value for a fallible offer
+	#[serde(default)]
+	pub segment: u16,
+	#[serde(default = "zero_token_type")]
+	pub token_type: String,
+	pub outputs: Vec<OutputSpec>,
+}
+
+/// Unshielded transfer, added to the unshielded offer of an intent
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UnshieldedTransferSpec {
+	/// Segment of the intent, 1 or more
+	#[serde(default = "first_intent_segment")]
+	pub segment: u16,
+	/// Add the transfer to the fallible unshielded offer of the intent
+	#[serde(default)]
+	pub fallible: bool,
+	#[serde(default = "zero_token_type")]
+	pub token_type: String,
+	pub outputs: Vec<OutputSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputSpec {
+	/// Bech32 address of the destination
+	pub destination: String,
+	pub amount: u128,
+}
+
+fn zero_token_type() -> String {
+	ZERO_TOKEN_TYPE.to_string()
+}
+
+fn first_intent_segment() -> u16 {
+	1
+}
+
+impl TxSpec {
+	pub fn load(path: &Path) -> Result<Self, SpecBuilderError> {
+		let content = std::fs::read_to_string(path)?;
+		match path.extension().and_then(|ext| ext.to_str()).unwrap_or("") {
+			"json" => Ok(serde_json::from_str(&content)?),
+			"toml" => Ok(toml::from_str(&content)?),
+			"yaml" | "yml" => Ok(serde_yaml::from_str(&content)?),
+			ext => Err(SpecBuilderError::UnsupportedExtension(ext.to_string())),
+		}
+	}
+
+	/// Checks the spec is self-consistent, before syncing any ledger state
+	pub fn validate(&self) -> Result<(), SpecBuilderError> {
+		if self.shielded.is_empty() && self.unshielded.is_empty() {
+			return Err(SpecBuilderError::EmptySpec);
+		}
+		if self.unshielded.iter().any(|t| t.segment == 0) {
+			return Err(SpecBuilderError::GuaranteedIntentSegment);
+		}
+
+		// The coin or UTXO to spend is selected from the source wallet by token type, so each
+		// token type can only be spent by a single shielded and a single unshielded transfer
+		let mut spends = HashSet::new();
+		let shielded = self.shielded.iter().map(|t| ("shielded", &t.token_type));
+		let unshielded = self.unshielded.iter().map(|t| ("unshielded", &t.token_type));
+		for (kind, token_type) in shielded.chain(unshielded) {
+			if !spends.insert((kind, token_type)) {
+				return Err(SpecBuilderError::DuplicateSpend(format!("{kind} {token_type}")));
+			}
+		}
+
+		self.source_seed()?;
+		self.rng_seed()?;
+		Ok(())
+	}
+
+	fn source_seed(&self) -> Result<WalletSeed, SpecBuilderError> {
+		WalletSeed::from_str(&self.source_seed).map_err(|e| SpecBuilderError::InvalidValue {
+			field: "source_seed",
+			value: self.source_seed.clone(),
+			reason: e.to_string(),
+		})
+	}
+
+	fn rng_seed(&self) -> Result<Option<[u8; 32]>, SpecBuilderError> {
+		self.rng_seed
+			.as_deref()
+			.map(|seed| {
+				cli::hex_str_decode::<[u8; 32]>(seed).map_err(|e| SpecBuilderError::InvalidValue {
+					field: "rng_seed",
+					value: seed.to_string(),
+					reason: e.to_string(),
+				})
+			})
+			.transpose()
+	}
+}
+
+pub struct SpecBuilder {
+	file: PathBuf,
+}
+
+impl SpecBuilder {
+	pub fn new(args: SpecArgs) -> Self {
+		Self { file: args.file }
+	}
+}
+
+#[async_trait]
+impl BuildTxs for SpecBuilder {
+	type Error = SpecBuilderError;
+
+	async fn build_txs_from(
+		&self,
+		received_tx: SourceTransactions<SignatureType, ProofType>,
+		prover_arc: Arc<dyn ProofProvider<DefaultDB>>,
+	) -> Result<DeserializedTransactionsWithContext<SignatureType, ProofType>, Self::Error> {
+		let spec = TxSpec::load(&self.file)?;
+		spec.validate()?;
+
+		let spin = Spin::new("generating tx from spec...");
+
+		let source_seed = spec.source_seed()?;
+		let network_id = received_tx.network();
+		let context = LedgerContext::new_from_wallet_seeds(network_id, &[source_seed]);
+		for block in received_tx.blocks {
+			context.update_from_block(block.transactions, block.context, block.state_root.clone());
+		}
+		let context = Arc::new(context);
+
+		let mut tx_info = StandardTrasactionInfo::new_from_context(
+			context.clone(),
+			prover_arc.clone(),
+			spec.rng_seed()?,
+		);
+
+		// - Shielded offers, one per segment
+		let mut fallible_offers = HashMap::new();
+		for (segment, transfers) in group_by_segment(&spec.shielded, |t| t.segment) {
+			let offer = build_shielded_offer(&context, source_seed, &transfers)?;
+			if segment == 0 {
+				tx_info.set_guaranteed_offer(offer);
+			} else {
+				fallible_offers.insert(segment, offer);
+			}
+		}
+		if !fallible_offers.is_empty() {
+			tx_info.set_fallible_offers(fallible_offers);
+		}
+
+		// - Intents, one per segment
+		let mut intents: HashMap<u16, Box<dyn BuildIntent<DefaultDB>>> = HashMap::new();
+		for (segment, transfers) in group_by_segment(&spec.unshielded, |t| t.segment) {
+			let (fallible, guaranteed): (Vec<_>, Vec<_>) =
+				transfers.into_iter().partition(|t| t.fallible);
+			let intent_info = IntentInfo {
+				guaranteed_unshielded_offer: build_unshielded_offer(
+					&context,
+					source_seed,
+					&guaranteed,
+				)?,
+				fallible_unshielded_offer: build_unshielded_offer(
+					&context,
+					source_seed,
+					&fallible,
+				)?,
+				actions: vec![],
+			};
+			intents.insert(segment, Box::new(intent_info));
+		}
+		if !intents.is_empty() {
+			tx_info.set_intents(intents);
+		}
+
+		tx_info.set_wallet_seeds(vec![source_seed]);
+		tx_info.use_mock_proofs_for_fees(true);
+
+		#[cfg(not(feature = "erase-proof"))]
+		let tx = tx_info.prove().await.map_err(SpecBuilderError::FailedProvingTx)?;
+
+		#[cfg(feature = "erase-proof")]
+		let tx = tx_info.erase_proof().await.map_err(SpecBuilderError::FailedProvingTx)?;
+
+		let tx_with_context = TransactionWithContext::new(tx, None);
+
+		spin.finish("generated tx.");
+
+		Ok(DeserializedTransactionsWithContext { initial_tx: tx_with_context, batches: Vec::new() })
+	}
+}
+
+/// Groups transfers by segment, in ascending segment order for a deterministic output
+fn group_by_segment<T: Clone>(
+	transfers: &[T],
+	segment: impl Fn(&T) -> u16,
+) -> BTreeMap<u16, Vec<T>> {
+	let mut grouped: BTreeMap<u16, Vec<T>> = BTreeMap::new();
+	for transfer in transfers {
+		grouped.entry(segment(transfer)).or_default().push(transfer.clone());
+	}
+	grouped
+}
+
+fn parse_address(destination: &str) -> Result<WalletAddress, SpecBuilderError> {
+	WalletAddress::from_str(destination).map_err(|e| SpecBuilderError::InvalidValue {
+		field: "destination",
+		value: destination.to_string(),
+		reason: e.to_string(),
+	})
+}
+
+fn parse_token_type<T: cli::TokenDecode>(token_type: &str) -> Result<T, SpecBuilderError> {
+	cli::token_decode::<T>(token_type).map_err(|e| SpecBuilderError::InvalidValue {
+		field: "token_type",
+		value: token_type.to_string(),
+		reason: e.to_string(),
+	})
+}
+
+fn build_shielded_offer(
+	context: &Arc<LedgerContext<DefaultDB>>,
+	source_seed: WalletSeed,
+	transfers: &[ShieldedTransferSpec],
+) -> Result<OfferInfo<DefaultDB>, SpecBuilderError> {
+	let funding_wallet = context.wallet_from_seed(source_seed);
+
+	let mut inputs: Vec<Box<dyn BuildInput<DefaultDB>>> = Vec::new();
+	let mut outputs: Vec<Box<dyn BuildOutput<DefaultDB>>> = Vec::new();
+	for transfer in transfers {
+		let token_type: ShieldedTokenType = parse_token_type(&transfer.token_type)?;
+
+		let mut total_required = 0u128;
+		for output in &transfer.outputs {
+			let destination: ShieldedWallet<DefaultDB> = (&parse_address(&output.destination)?)
+				.try_into()
+				.map_err(|_| SpecBuilderError::AddressKindMismatch(output.destination.clone()))?;
+			outputs.push(Box::new(OutputInfo { destination, token_type, value: output.amount }));
+			total_required += output.amount;
+		}
+
+		let input_info = InputInfo { origin: source_seed, token_type, value: total_required };
+		let has_funds = funding_wallet
+			.shielded
+			.state
+			.coins
+			.iter()
+			.any(|(_, coin)| coin.type_ == token_type && coin.value >= total_required);
+		if !has_funds {
+			return Err(SpecBuilderError::InsufficientFunds(transfer.token_type.clone()));
+		}
+		let input_amount = input_info.min_match_coin(&funding_wallet.shielded.state).value;
+		inputs.push(Box::new(input_info));
+
+		// Send the remaining coins back to the source wallet
+		let remaining = input_amount - total_required;
+		if remaining > 0 {
+			outputs.push(Box::new(OutputInfo {
+				destination: source_seed,
+				token_type,
+				value: remaining,
+			}));
+		}
+	}
+
+	Ok(OfferInfo { inputs, outputs, transients: vec![] })
+}
+
+fn build_unshielded_offer(
+	context: &Arc<LedgerContext<DefaultDB>>,
+	source_seed: WalletSeed,
+	transfers: &[UnshieldedTransferSpec],
+) -> Result<Option<UnshieldedOfferInfo<DefaultDB>>, SpecBuilderError> {
+	if transfers.is_empty() {
+		return Ok(None);
+	}
+	let funding_wallet = context.wallet_from_seed(source_seed);
+	let funding_utxos =
+		context.with_ledger_state(|ledger_state| funding_wallet.unshielded_utxos(ledger_state));
+
+	let mut inputs: Vec<Box<dyn BuildUtxoSpend<DefaultDB>>> = Vec::new();
+	let mut outputs: Vec<Box<dyn BuildUtxoOutput<DefaultDB>>> = Vec::new();
+	for transfer in transfers {
+		let token_type: UnshieldedTokenType = parse_token_type(&transfer.token_type)?;
+
+		let mut total_required = 0u128;
+		for output in &transfer.outputs {
+			let owner: UnshieldedWallet = (&parse_address(&output.destination)?)
+				.try_into()
+				.map_err(|_| SpecBuilderError::AddressKindMismatch(output.destination.clone()))?;
+			outputs.push(Box::new(UtxoOutputInfo { value: output.amount, owner, token_type }));
+			total_required += output.amount;
+		}
+
+		if !funding_utxos.iter().any(|u| u.type_ == token_type && u.value >= total_required) {
+			return Err(SpecBuilderError::InsufficientFunds(transfer.token_type.clone()));
+		}
+		let utxo_spend_info = UtxoSpendInfo {
+			value: total_required,
+			owner: source_seed,
+			token_type,
+			intent_hash: None,
+			output_number: None,
+		};
+		let input_amount = utxo_spend_info.min_match_utxo(context.clone(), &funding_wallet).value;
+		inputs.push(Box::new(utxo_spend_info));
+
+		// Send the remaining tokens back to the source wallet
+		let remaining = input_amount - total_required;
+		if remaining > 0 {
+			outputs.push(Box::new(UtxoOutputInfo {
+				value: remaining,
+				owner: source_seed,
+				token_type,
+			}));
+		}
+	}
+
+	Ok(Some(UnshieldedOfferInfo { inputs, outputs }))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tx_generator::source::{GetTxs, GetTxsFromFile};
+	use midnight_node_ledger_helpers::IntoWalletAddress as _;
+
+	fn parse(toml: &str) -> TxSpec {
+		toml::from_str(toml).unwrap()
+	}
+
+	#[test]
+	fn should_parse_spec_with_defaults() {
+		let spec = parse(
+			r#"
+			source_seed = "0000000000000000000000000000000000000000000000000000000000000001"
+
+			[[shielded]]
+			segment = 1
+			outputs = [{ destination = "mn_shield-addr_undeployed1", amount = 100 }]
+
+			[[unshielded]]
+			fallible = true
+			outputs = [{ destination = "mn_addr_undeployed1", amount = 10 }]
+			"#,
+		);
+
+		assert_eq!(spec.shielded[0].token_type, ZERO_TOKEN_TYPE);
+		assert_eq!(spec.unshielded[0].segment, 1);
+		assert!(spec.validate().is_ok());
+	}
+
+	#[test]
+	fn should_reject_invalid_specs() {
+		let seed = r#"source_seed = "01""#;
+		assert!(matches!(parse(seed).validate(), Err(SpecBuilderError::EmptySpec)));
+
+		let guaranteed_intent =
+			parse(&format!("{seed}\n[[unshielded]]\nsegment = 0\noutputs = []"));
+		assert!(matches!(
+			guaranteed_intent.validate(),
+			Err(SpecBuilderError::GuaranteedIntentSegment)
+		));
+
+		let duplicate =
+			parse(&format!("{seed}\n[[shielded]]\noutputs = []\n[[shielded]]\noutputs = []"));
+		assert!(matches!(duplicate.validate(), Err(SpecBuilderError::DuplicateSpend(_))));
+
+		let invalid_seed = parse(
+			r#"source_seed = "not a seed"
+			[[shielded]]
+			outputs = []"#,
+		);
+		assert!(matches!(
+			invalid_seed.validate(),
+			Err(SpecBuilderError::InvalidValue { field: "source_seed", .. })
+		));
+
+		let mut invalid_rng_seed = parse(
+			r#"source_seed = "0000000000000000000000000000000000000000000000000000000000000001"
+			[[shielded]]
+			outputs = []"#,
+		);
+		invalid_rng_seed.rng_seed = Some("42".to_string());
+		assert!(matches!(
+			invalid_rng_seed.validate(),
+			Err(SpecBuilderError::InvalidValue { field: "rng_seed", .. })
+		));
+	}
+
+	#[test]
+	fn should_load_example_specs() {
+		let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data/spec");
+		let toml_spec = TxSpec::load(&dir.join("transfers.toml")).unwrap();
+		let yaml_spec = TxSpec::load(&dir.join("transfers.yaml")).unwrap();
+
+		for spec in [&toml_spec, &yaml_spec] {
+			assert!(spec.validate().is_ok());
+			assert!(spec.rng_seed().unwrap().is_some());
+			for output in spec.unshielded.iter().flat_map(|t| &t.outputs) {
+				assert!(parse_address(&output.destination).is_ok());
+			}
+		}
+		assert_eq!(format!("{toml_spec:?}"), format!("{yaml_spec:?}"));
+	}
+
+	#[tokio::test]
+	async fn should_not_add_an_empty_shielded_change_output() {
+		let genesis =
+			[env!("CARGO_MANIFEST_DIR"), "/test-data/genesis/genesis_block_undeployed.mn"].concat();
+		let src: GetTxsFromFile<SignatureType, ProofType> =
+			GetTxsFromFile::new(vec![genesis], "mn".to_string(), false);
+		let received_tx = src.get_txs().await.unwrap();
+
+		let source_seed = WalletSeed::from_str(
+			"0000000000000000000000000000000000000000000000000000000000000001",
+		)
+		.unwrap();
+		let network_id = received_tx.network().to_string();
+		let context = LedgerContext::new_from_wallet_seeds(&network_id, &[source_seed]);
+		for block in received_tx.blocks {
+			context.update_from_block(block.transactions, block.context, block.state_root);
+		}
+		let context = Arc::new(context);
+
+		let token_type: ShieldedTokenType = parse_token_type(ZERO_TOKEN_TYPE).unwrap();
+		let smallest_coin = context
+			.wallet_from_seed(source_seed)
+			.shielded
+			.state
+			.coins
+			.iter()
+			.filter(|(_, coin)| coin.type_ == token_type)
+			.map(|(_, coin)| coin.value)
+			.min()
+			.unwrap();
+		let destination = ShieldedWallet::<DefaultDB>::default(source_seed)
+			.address(&network_id)
+			.to_bech32();
+		let transfer = |amount| ShieldedTransferSpec {
+			segment: 0,
+			token_type: ZERO_TOKEN_TYPE.to_string(),
+			outputs: vec![OutputSpec { destination: destination.clone(), amount }],
+		};
+
+		let exact =
+			build_shielded_offer(&context, source_seed, &[transfer(smallest_coin)]).unwrap();
+		assert_eq!(exact.outputs.len(), 1);
+
+		let with_change =
+			build_shielded_offer(&context, source_seed, &[transfer(smallest_coin - 1)]).unwrap();
+		assert_eq!(with_change.outputs.len(), 2);
+	}
+}
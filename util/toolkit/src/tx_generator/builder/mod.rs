@@ -15,7 +15,7 @@ use async_trait::async_trait;
 use builders::{
 	BatchesBuilder, ClaimRewardsBuilder, ContractCallBuilder, ContractDeployBuilder,
 	ContractMaintenanceBuilder, CustomContractBuilder, DoNothingBuilder, ReplaceInitialTxBuilder,
-	single_tx::SingleTxBuilder,
+	SpecBuilder, single_tx::SingleTxBuilder,
 };
 use clap::{Args, Subcommand};
 use midnight_node_ledger_helpers::*;
@@ -247,6 +247,14 @@ pub struct SingleTxArgs {
     )]
 	pub rng_seed: Option<[u8; 32]>,
 }
+#[derive(Args, Clone, Debug)]
+pub struct SpecArgs {
+	/// Spec file (`.toml`, `.yaml` or `.json`) describing the shielded and unshielded transfers of the
+	/// transaction
+	#[arg(long)]
+	pub file: PathBuf,
+}
+
 #[derive(Args, Clone, Debug)]
 pub struct RegisterDustAddressArgs {
 	/// Seed for source wallet
@@ -290,6 +298,8 @@ pub enum Builder {
 	ClaimRewards(ClaimRewardsArgs),
 	/// Send single transaction with one-or-many outputs
 	SingleTx(SingleTxArgs),
+	/// Send single transaction built from a declarative spec file
+	Spec(SpecArgs),
 	RegisterDustAddress(RegisterDustAddressArgs),
 	/// Send is a no-op here (source is sent directly to destination)
 	Send,
@@ -363,6 +373,7 @@ impl Builder {
 			Builder::ContractCustom(args) => constr(CustomContractBuilder::new(args)),
 			Builder::ClaimRewards(args) => constr(ClaimRewardsBuilder::new(args)),
 			Builder::SingleTx(args) => constr(SingleTxBuilder::new(args)),
+			Builder::Spec(args) => constr(SpecBuilder::new(args)),
 			Builder::RegisterDustAddress(args) => constr(RegisterDustAddressBuilder::new(args)),
 			Builder::Send => constr(DoNothingBuilder::new()),
 			Builder::Migrate => constr(ReplaceInitialTxBuilder::new()),
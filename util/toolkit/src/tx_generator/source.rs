@@ -21,10 +21,11 @@ use std::{
 	time::{SystemTime, UNIX_EPOCH},
 };
 use thiserror::Error;
+use tokio::sync::Mutex;
 
 use crate::{
 	client::ClientError,
-	indexer::Indexer,
+	indexer::{Indexer, IndexerHandle},
 	serde_def::{SerializedTransactionsWithContext, SourceBlockTransactions, SourceTransactions},
 };
 
@@ -80,6 +81,13 @@ pub trait GetTxs<
 	async fn get_txs(
 		&self,
 	) -> Result<SourceTransactions<S, P>, Box<dyn std::error::Error + Send + Sync>>;
+
+	/// Returns the source blocks from index `start` on, without the `--dust-warp` block. Called
+	/// repeatedly to follow the source
+	async fn get_txs_from(
+		&self,
+		start: usize,
+	) -> Result<SourceTransactions<S, P>, Box<dyn std::error::Error + Send + Sync>>;
 }
 
 #[async_trait]
@@ -93,6 +101,13 @@ impl<
 	) -> Result<SourceTransactions<S, P>, Box<dyn std::error::Error + Send + Sync>> {
 		Ok(SourceTransactions { blocks: vec![] })
 	}
+
+	async fn get_txs_from(
+		&self,
+		_start: usize,
+	) -> Result<SourceTransactions<S, P>, Box<dyn std::error::Error + Send + Sync>> {
+		Ok(SourceTransactions { blocks: vec![] })
+	}
 }
 
 pub struct GetTxsFromFile<S, P> {
@@ -161,6 +176,15 @@ where
 		let txs = self.txs_from_files()?;
 		Ok(txs)
 	}
+
+	async fn get_txs_from(
+		&self,
+		start: usize,
+	) -> Result<SourceTransactions<S, P>, Box<dyn std::error::Error + Send + Sync>> {
+		let mut txs = self.txs_from_files()?;
+		txs.blocks.drain(..start.min(txs.blocks.len()));
+		Ok(txs)
+	}
 }
 
 pub struct GetTxsFromUrl<
@@ -171,6 +195,8 @@ pub struct GetTxsFromUrl<
 {
 	pub indexer: Arc<Indexer<S, P>>,
 	pub dust_warp: bool,
+	/// Indexer kept running by `get_txs_from`, to only fetch new blocks on later calls
+	following: Mutex<Option<IndexerHandle>>,
 }
 
 impl<S: SignatureKind<DefaultDB> + Tagged, P: ProofKind<DefaultDB> + Send + 'static>
@@ -182,7 +208,7 @@ where
 	Transaction<S, P, PureGeneratorPedersen, DefaultDB>: Tagged,
 {
 	pub fn new(indexer: Arc<Indexer<S, P>>, dust_warp: bool) -> Self {
-		Self { indexer, dust_warp }
+		Self { indexer, dust_warp, following: Mutex::new(None) }
 	}
 }
 
@@ -223,4 +249,17 @@ where
 
 		Ok(SourceTransactions { blocks })
 	}
+
+	async fn get_txs_from(
+		&self,
+		start: usize,
+	) -> Result<SourceTransactions<S, P>, Box<dyn std::error::Error + Send + Sync>> {
+		let mut following = self.following.lock().await;
+		if following.is_none() {
+			*following = Some(self.indexer.clone().start().await?);
+		}
+		let blocks = self.indexer.get_blocks_from(start).await;
+
+		Ok(SourceTransactions { blocks })
+	}
 }